@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::claude_state::{detect_state_explained_in, detect_state_explained_without, strip_ansi, UiLocale, RULES};
+use crate::elapsed::unix_now;
+use crate::paths;
+use crate::status::state_key;
+use crate::tmux;
+
+const AB_INTERVAL: Duration = Duration::from_secs(2);
+
+/// State keys a candidate rule can decide, as in `status.json`.
+const STATE_KEYS: &[&str] = &["running", "compacting", "approval", "answer", "blocked", "idle", "error"];
+
+/// A candidate rule set for `claudeye ab`, read from a TOML file: built-in
+/// rules to switch off and extra rules tried before the built-in ones.
+///
+/// ```toml
+/// disabled = ["waiting_text"]
+///
+/// [[rule]]
+/// name = "webfetch_dialog"
+/// state = "approval"
+/// pattern = 'Do you want to allow Claude to fetch'
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CandidateRules {
+    /// Names of built-in rules (see `claude_state::RULES`) that never match.
+    pub disabled: Vec<String>,
+    pub rule: Vec<CandidateRule>,
+}
+
+/// A regex that decides `state` when it matches one of the bottom `lines`
+/// non-empty lines of a pane.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawCandidateRule")]
+pub struct CandidateRule {
+    pub name: String,
+    pub state: String,
+    pub pattern: Regex,
+    pub lines: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawCandidateRule {
+    name: String,
+    state: String,
+    pattern: String,
+    #[serde(default = "default_lines")]
+    lines: usize,
+}
+
+fn default_lines() -> usize {
+    10
+}
+
+impl TryFrom<RawCandidateRule> for CandidateRule {
+    type Error = String;
+
+    fn try_from(raw: RawCandidateRule) -> Result<Self, Self::Error> {
+        if !STATE_KEYS.contains(&raw.state.as_str()) {
+            return Err(format!("unknown state {:?} in rule {:?}", raw.state, raw.name));
+        }
+        Ok(Self {
+            pattern: Regex::new(&raw.pattern).map_err(|e| e.to_string())?,
+            name: raw.name,
+            state: raw.state,
+            lines: raw.lines,
+        })
+    }
+}
+
+impl CandidateRules {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let rules: Self = toml::from_str(text).map_err(|e| e.to_string())?;
+        if let Some(unknown) = rules.disabled.iter().find(|name| !RULES.contains(&name.as_str())) {
+            return Err(format!("unknown rule {unknown:?} in disabled"));
+        }
+        Ok(rules)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        Self::parse(&text).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    /// The state key and deciding rule for `content` under this rule set.
+    pub fn detect(&self, content: &str, locale: UiLocale) -> (&'static str, String) {
+        let stripped = strip_ansi(content);
+        let lines: Vec<&str> = stripped.lines().filter(|l| !l.trim().is_empty()).collect();
+        for rule in &self.rule {
+            let bottom = &lines[lines.len().saturating_sub(rule.lines)..];
+            if bottom.iter().any(|l| rule.pattern.is_match(l)) {
+                let state = STATE_KEYS.iter().copied().find(|k| *k == rule.state).unwrap_or("idle");
+                return (state, rule.name.clone());
+            }
+        }
+        let (state, trace) = detect_state_explained_without(content, locale, &self.disabled);
+        (state_key(&state), trace.rule.to_string())
+    }
+}
+
+/// A capture the current and candidate rules detect differently.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub current: &'static str,
+    pub current_rule: String,
+    pub candidate: &'static str,
+    pub candidate_rule: String,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "current {} ({}), candidate {} ({})",
+            self.current, self.current_rule, self.candidate, self.candidate_rule
+        )
+    }
+}
+
+/// Run both rule sets on `content`, returning how they differ if they do.
+pub fn compare(candidate: &CandidateRules, content: &str, locale: UiLocale) -> Option<Divergence> {
+    let (state, trace) = detect_state_explained_in(content, locale);
+    let current = state_key(&state);
+    let (candidate, candidate_rule) = candidate.detect(content, locale);
+    (current != candidate).then(|| Divergence {
+        current,
+        current_rule: trace.rule.to_string(),
+        candidate,
+        candidate_rule,
+    })
+}
+
+/// File name of a saved capture, laid out so it can be moved into the
+/// fixture corpus under the state it should have been detected as.
+pub fn capture_name(pane_id: &str, divergence: &Divergence, now: u64) -> String {
+    let pane: String = pane_id.chars().filter(char::is_ascii_alphanumeric).collect();
+    format!("{now}-{pane}-{}-vs-{}.txt", divergence.current, divergence.candidate)
+}
+
+/// Poll every Claude pane, printing each new divergence between the current
+/// rules and `candidate` and saving its capture to `$XDG_STATE_HOME/claudeye/ab/`.
+/// A pane is reported again only once its divergence changes.
+pub fn run(candidate: &CandidateRules, locale: UiLocale) -> std::io::Result<()> {
+    let dir = paths::state_dir()
+        .map(|d| d.join("ab"))
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no state directory"))?;
+    std::fs::create_dir_all(&dir)?;
+    let mut last: HashMap<String, Divergence> = HashMap::new();
+    loop {
+        let panes = tmux::list_claude_panes();
+        last.retain(|id, _| panes.iter().any(|p| &p.id == id));
+        for pane in &panes {
+            let content = tmux::capture_pane(&pane.id);
+            let Some(divergence) = compare(candidate, &content, locale) else {
+                last.remove(&pane.id);
+                continue;
+            };
+            if last.get(&pane.id) == Some(&divergence) {
+                continue;
+            }
+            let path = dir.join(capture_name(&pane.id, &divergence, unix_now()));
+            std::fs::write(&path, &content)?;
+            println!("{}: {divergence}; saved {}", pane.id, path.display());
+            crate::log!("ab divergence in {}: {divergence}", pane.id);
+            last.insert(pane.id.clone(), divergence);
+        }
+        thread::sleep(AB_INTERVAL);
+    }
+}
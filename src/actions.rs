@@ -0,0 +1,136 @@
+use serde::Deserialize;
+
+use crate::claude_state::{detect_state, pending_approval, ApprovalKind, ClaudeState};
+use crate::files;
+use crate::guardrail::{self, Guardrail};
+use crate::elapsed;
+use crate::marks::{Marks, Snooze};
+use crate::monitor;
+use crate::status;
+use crate::tmux;
+
+/// What a mouse gesture on an overlay row does, from `[clicks]`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RowAction {
+    /// Switch the tmux client to the session's pane ([`switch`]).
+    Switch,
+    /// Accept a pending approval ([`approve`]), subject to guardrails.
+    Approve,
+    /// Reject a pending approval ([`deny`]).
+    Deny,
+    /// Snooze the session's alerts for `snooze_minutes`, or wake a snoozed
+    /// session.
+    Snooze,
+    /// Open the session's Claude transcript.
+    Transcript,
+}
+
+/// Row actions bound to mouse gestures on the overlay in `--interactive`
+/// mode; unbound gestures do nothing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClickConfig {
+    pub double: Option<RowAction>,
+    pub middle: Option<RowAction>,
+}
+
+/// Switch the tmux client to a pane, marking its current output as read.
+pub fn switch(pane_id: &str) -> Result<(), String> {
+    let content = tmux::capture_pane_range(pane_id, "0", "-")
+        .ok_or_else(|| format!("pane {pane_id} not found"))?;
+    let mut marks = Marks::load();
+    marks.mark_seen(pane_id, monitor::content_hash(&content));
+    marks.save();
+    tmux::switch_to_pane(pane_id);
+    Ok(())
+}
+
+/// Snooze a pane's alerts until its state changes, or for `minutes`; with
+/// `off`, cancel an active snooze instead.
+pub fn snooze(pane_id: &str, minutes: Option<u64>, off: bool) -> Result<(), String> {
+    let mut marks = Marks::load();
+    if off {
+        marks.unsnooze(pane_id);
+    } else {
+        let snooze = match minutes {
+            Some(minutes) => Snooze::Until(elapsed::unix_now() + minutes * 60),
+            None => {
+                let content = tmux::capture_pane_range(pane_id, "0", "-")
+                    .ok_or_else(|| format!("pane {pane_id} not found"))?;
+                Snooze::UntilChange(status::state_key(&detect_state(&content)).to_string())
+            }
+        };
+        marks.snooze(pane_id, snooze);
+    }
+    marks.save();
+    Ok(())
+}
+
+/// Accept the pending approval prompt of a pane by pressing Enter on its
+/// default ("Yes") option. The pane is re-captured first so that a prompt
+/// which was already answered never receives a stray keypress, and a prompt
+/// tripping a guardrail is refused unless `force` is set.
+pub fn approve(pane_id: &str, guardrails: &[Guardrail], force: bool) -> Result<(), String> {
+    let content = approval_prompt(pane_id)?;
+    let hits = guardrail::check(
+        guardrails,
+        &ClaudeState::WaitingForApproval,
+        &content,
+        &files::touched_files(&content),
+    );
+    if !hits.is_empty() && !force {
+        return Err(format!(
+            "pane {pane_id} trips guardrail {}; approve it manually or pass --force",
+            hits.join(", ")
+        ));
+    }
+    if !tmux::send_keys(pane_id, &["Enter"]) {
+        return Err(format!("failed to send keys to pane {pane_id}"));
+    }
+    Ok(())
+}
+
+/// Reject the pending approval prompt of a pane by pressing Esc, which
+/// tells Claude not to go ahead and waits for directions. The pane is
+/// re-captured first, like [`approve`]. Folder trust prompts are refused,
+/// since Esc there quits Claude.
+pub fn deny(pane_id: &str) -> Result<(), String> {
+    let content = approval_prompt(pane_id)?;
+    if pending_approval(&content).is_some_and(|p| p.kind == ApprovalKind::TrustDirectory) {
+        return Err(format!("pane {pane_id} asks to trust its folder; answer it from the pane"));
+    }
+    if !tmux::send_keys(pane_id, &["Escape"]) {
+        return Err(format!("failed to send keys to pane {pane_id}"));
+    }
+    Ok(())
+}
+
+/// Stop a working session by pressing Esc, as in the pane, which makes
+/// Claude drop the current step and wait for directions. The pane is
+/// re-captured first so that a session that already finished is left alone.
+pub fn interrupt(pane_id: &str) -> Result<(), String> {
+    let content = tmux::capture_pane_range(pane_id, "0", "-")
+        .ok_or_else(|| format!("pane {pane_id} not found"))?;
+    if detect_state(&content) != ClaudeState::Working {
+        return Err(format!("pane {pane_id} is not working"));
+    }
+    if !tmux::send_keys(pane_id, &["Escape"]) {
+        return Err(format!("failed to send keys to pane {pane_id}"));
+    }
+    Ok(())
+}
+
+/// The content of a pane showing an approval prompt that can be answered
+/// from outside it.
+fn approval_prompt(pane_id: &str) -> Result<String, String> {
+    let content = tmux::capture_pane_range(pane_id, "0", "-")
+        .ok_or_else(|| format!("pane {pane_id} not found"))?;
+    if detect_state(&content) != ClaudeState::WaitingForApproval {
+        return Err(format!("pane {pane_id} is not waiting for approval"));
+    }
+    if pending_approval(&content).is_some_and(|p| p.is_login()) {
+        return Err(format!("pane {pane_id} is waiting for a login; sign in from the pane"));
+    }
+    Ok(content)
+}
@@ -0,0 +1,363 @@
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock};
+
+use regex::Regex;
+
+use crate::claude_state::{
+    detect_status_in, strip_ansi, ApprovalKind, ClaudeState, PendingApproval, StatusInfo, UiLocale, RULES,
+};
+use crate::filter;
+use crate::health;
+use crate::i18n::Strings;
+use crate::kitty;
+use crate::monitor::MonitorConfig;
+use crate::process::{self, ProcessTree};
+use crate::remote;
+use crate::screen;
+use crate::tmux::{self, PaneInfo, PaneLabels};
+
+/// Tool name of Claude Code sessions, which are shown without a tag.
+pub const CLAUDE: &str = "claude";
+
+/// How many non-empty lines at the bottom of a pane the other agents'
+/// detectors look at.
+const BOTTOM_LINES_COUNT: usize = 10;
+
+/// Recognizes the panes of one agent CLI and parses their state.
+pub trait Detector: Sync {
+    /// Tool name, as used in the `agents` setting and shown next to the
+    /// project.
+    fn name(&self) -> &'static str;
+
+    /// Whether a pane whose foreground command (`#{pane_current_command}`)
+    /// is `command` runs this tool.
+    fn matches_command(&self, command: &str) -> bool;
+
+    /// The state of a pane running this tool, from its captured content.
+    fn detect(&self, content: &str, locale: UiLocale) -> StatusInfo;
+
+    /// Every rule name [`StatusInfo::rule`] can take for this tool.
+    fn rules(&self) -> &'static [&'static str];
+}
+
+pub struct Claude;
+pub struct Aider;
+pub struct Codex;
+pub struct Gemini;
+
+/// Every detector claudeye ships, in the order panes are matched.
+pub static BUILTIN: &[&dyn Detector] = &[&Claude, &Aider, &Codex, &Gemini];
+
+/// Names of every built-in detector, the default of the `agents` setting.
+pub fn builtin_names() -> Vec<String> {
+    BUILTIN.iter().map(|d| d.name().to_string()).collect()
+}
+
+/// The built-in detectors named in `names`, skipping unknown ones.
+pub fn enabled(names: &[String]) -> Vec<&'static dyn Detector> {
+    names
+        .iter()
+        .filter_map(|name| {
+            let found = BUILTIN.iter().copied().find(|d| d.name() == name);
+            if found.is_none() {
+                crate::log!("ignoring unknown agent {name:?}");
+            }
+            found
+        })
+        .collect()
+}
+
+/// The first of `detectors` that recognizes `command`.
+pub fn detector_for(detectors: &[&'static dyn Detector], command: &str) -> Option<&'static dyn Detector> {
+    detectors.iter().copied().find(|d| d.matches_command(command))
+}
+
+/// Panes running one of the enabled agents, each with its detector, named
+/// as `[monitor.naming]` says. GNU screen and kitty windows count as panes
+/// when their backend is on, and so do the panes of `remote_hosts`, whose
+/// names end in their host (`api@devbox`). Panes left out by the
+/// `include` and `exclude` rules are skipped.
+pub fn list_panes(config: &MonitorConfig) -> Vec<(PaneInfo, &'static dyn Detector)> {
+    let detectors = enabled(&config.agents);
+    let screen_windows = if config.screen { screen::list_windows() } else { vec![] };
+    let kitty_windows = if config.kitty { kitty::list_windows() } else { vec![] };
+    let panes: Vec<_> = tmux::list_panes_with_commands()
+        .into_iter()
+        .chain(screen_windows)
+        .chain(kitty_windows)
+        .chain(remote::list_panes(&config.remote_hosts))
+        .collect();
+    let listing = Listing::of(&panes, &detectors);
+    *last_listing_slot().lock().unwrap_or_else(|e| e.into_inner()) = Some(listing);
+    let claude = detector_for(&detectors, CLAUDE);
+    let mut tree = None;
+    panes
+        .into_iter()
+        .filter(|(pane, _, _)| filter::is_monitored(pane, &config.include, &config.exclude))
+        .filter_map(|(mut pane, command, labels)| {
+            let detector = detector_for(&detectors, &command).or_else(|| {
+                // Claude under a wrapper; only a local pane's processes can be looked at.
+                let claude = claude?;
+                if !process::WRAPPERS.contains(&command.as_str()) || remote::parse_pane_id(&pane.id).is_some() {
+                    return None;
+                }
+                tree.get_or_insert_with(ProcessTree::snapshot).find_claude(pane.pid).map(|_| claude)
+            })?;
+            pane.project_name = config.naming.display_name(&pane, &labels, &command);
+            if let Some((host, _)) = remote::parse_pane_id(&pane.id) {
+                pane.project_name = format!("{}@{host}", pane.project_name);
+            }
+            Some((pane, detector))
+        })
+        .collect()
+}
+
+/// What the last [`list_panes`] call saw, to tell why it found nothing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Listing {
+    /// Panes of every source, whatever they run.
+    pub panes: usize,
+    /// Sessions those panes belong to.
+    pub sessions: usize,
+    /// Panes running a built-in agent left out of the `agents` setting.
+    pub filtered: usize,
+}
+
+impl Listing {
+    pub fn of(panes: &[(PaneInfo, String, PaneLabels)], detectors: &[&'static dyn Detector]) -> Self {
+        let sessions: HashSet<&str> = panes.iter().map(|(pane, _, _)| pane.session_name.as_str()).collect();
+        let filtered = panes
+            .iter()
+            .filter(|(_, command, _)| {
+                detector_for(detectors, command).is_none() && detector_for(BUILTIN, command).is_some()
+            })
+            .count();
+        Self {
+            panes: panes.len(),
+            sessions: sessions.len(),
+            filtered,
+        }
+    }
+}
+
+fn last_listing_slot() -> &'static Mutex<Option<Listing>> {
+    static LISTING: OnceLock<Mutex<Option<Listing>>> = OnceLock::new();
+    LISTING.get_or_init(|| Mutex::new(None))
+}
+
+/// What the last [`list_panes`] call in this process saw, if any.
+pub fn last_listing() -> Option<Listing> {
+    last_listing_slot().lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Why no sessions are shown.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EmptyReason {
+    /// No tmux server runs, or tmux is not installed.
+    NoTmux,
+    /// tmux answered with an error.
+    TmuxFailed(String),
+    /// tmux runs but none of its panes runs an agent.
+    NoAgentPanes { sessions: usize },
+    /// Agent panes exist, but run tools left out of `agents`.
+    Filtered(usize),
+}
+
+impl EmptyReason {
+    /// The reason for an empty session list, from the last listing and the
+    /// error of the last `tmux list-panes`.
+    pub fn of(listing: &Listing, tmux_error: Option<&str>) -> Self {
+        if listing.filtered > 0 {
+            return EmptyReason::Filtered(listing.filtered);
+        }
+        match tmux_error {
+            Some(error) if listing.panes == 0 && !tmux::is_no_server(error) => EmptyReason::TmuxFailed(error.to_string()),
+            // A tmux server exits with its last session, so no panes at
+            // all means none runs.
+            _ if listing.panes == 0 => EmptyReason::NoTmux,
+            _ => EmptyReason::NoAgentPanes { sessions: listing.sessions },
+        }
+    }
+
+    /// The reason for the current empty session list, `None` before the
+    /// first listing.
+    pub fn current() -> Option<Self> {
+        Some(Self::of(&last_listing()?, health::source_error("tmux").as_deref()))
+    }
+
+    pub fn label(&self, strings: &Strings) -> String {
+        match self {
+            EmptyReason::NoTmux => strings.no_tmux.to_string(),
+            EmptyReason::TmuxFailed(error) => strings.tmux_lost_label(error),
+            EmptyReason::NoAgentPanes { sessions } => strings.no_agent_panes_label(*sessions),
+            EmptyReason::Filtered(panes) => strings.agents_filtered_label(*panes),
+        }
+    }
+}
+
+impl Detector for Claude {
+    fn name(&self) -> &'static str {
+        CLAUDE
+    }
+
+    fn matches_command(&self, command: &str) -> bool {
+        tmux::is_claude_command(command)
+    }
+
+    fn detect(&self, content: &str, locale: UiLocale) -> StatusInfo {
+        detect_status_in(content, locale)
+    }
+
+    fn rules(&self) -> &'static [&'static str] {
+        RULES
+    }
+}
+
+/// aider prompts with `> ` (or `architect> `, `ask> ` in other chat modes)
+/// when idle and asks `(Y)es/(N)o` questions before adding files or running
+/// commands; anything else means it is talking to the model.
+impl Detector for Aider {
+    fn name(&self) -> &'static str {
+        "aider"
+    }
+
+    fn matches_command(&self, command: &str) -> bool {
+        command == "aider"
+    }
+
+    fn detect(&self, content: &str, _locale: UiLocale) -> StatusInfo {
+        let content = strip_ansi(content);
+        let lines = bottom_lines(&content, BOTTOM_LINES_COUNT);
+        let Some(last) = lines.last() else {
+            return decided(ClaudeState::Idle, "aider:empty");
+        };
+        if let Some(caps) = aider_question_pattern().captures(last) {
+            return approval(self.name(), caps[1].trim(), "aider:question");
+        }
+        if aider_prompt_pattern().is_match(last) {
+            return decided(ClaudeState::Idle, "aider:prompt");
+        }
+        decided(ClaudeState::Working, "aider:output")
+    }
+
+    fn rules(&self) -> &'static [&'static str] {
+        &["aider:empty", "aider:question", "aider:prompt", "aider:output"]
+    }
+}
+
+/// Codex CLI shows `Working (12s • esc to interrupt)` while busy and a
+/// dialog offering `Yes, proceed` before running commands or editing files.
+impl Detector for Codex {
+    fn name(&self) -> &'static str {
+        "codex"
+    }
+
+    fn matches_command(&self, command: &str) -> bool {
+        command == "codex"
+    }
+
+    fn detect(&self, content: &str, _locale: UiLocale) -> StatusInfo {
+        let content = strip_ansi(content);
+        let lines = bottom_lines(&content, BOTTOM_LINES_COUNT);
+        if let Some(question) = lines.iter().find(|l| codex_approval_pattern().is_match(l)) {
+            return approval(self.name(), question.trim(), "codex:approval");
+        }
+        if let Some(caps) = lines.iter().rev().find_map(|l| codex_working_pattern().captures(l)) {
+            let mut info = decided(ClaudeState::Working, "codex:working");
+            info.activity = Some(caps[1].trim().to_string());
+            return info;
+        }
+        decided(ClaudeState::Idle, "codex:fallback")
+    }
+
+    fn rules(&self) -> &'static [&'static str] {
+        &["codex:approval", "codex:working", "codex:fallback"]
+    }
+}
+
+/// Gemini CLI shows `(esc to cancel, 5s)` after its spinner while busy and
+/// asks `Allow execution?` or `Apply this change?` before acting.
+impl Detector for Gemini {
+    fn name(&self) -> &'static str {
+        "gemini"
+    }
+
+    fn matches_command(&self, command: &str) -> bool {
+        command == "gemini"
+    }
+
+    fn detect(&self, content: &str, _locale: UiLocale) -> StatusInfo {
+        let content = strip_ansi(content);
+        let lines = bottom_lines(&content, BOTTOM_LINES_COUNT);
+        if let Some(question) = lines.iter().find(|l| gemini_approval_pattern().is_match(l)) {
+            return approval(self.name(), question.trim().trim_matches(['│', ' ']), "gemini:approval");
+        }
+        if lines.iter().any(|l| l.contains("(esc to cancel")) {
+            return decided(ClaudeState::Working, "gemini:working");
+        }
+        decided(ClaudeState::Idle, "gemini:fallback")
+    }
+
+    fn rules(&self) -> &'static [&'static str] {
+        &["gemini:approval", "gemini:working", "gemini:fallback"]
+    }
+}
+
+fn decided(state: ClaudeState, rule: &'static str) -> StatusInfo {
+    StatusInfo { rule, ..StatusInfo::new(state) }
+}
+
+fn approval(tool: &str, question: &str, rule: &'static str) -> StatusInfo {
+    let mut info = decided(ClaudeState::WaitingForApproval, rule);
+    info.pending = Some(PendingApproval {
+        tool: tool.to_string(),
+        detail: question.to_string(),
+        kind: question_kind(question),
+    });
+    info
+}
+
+/// What the other agents' approval questions ask for, from their wording.
+fn question_kind(question: &str) -> ApprovalKind {
+    let question = question.to_lowercase();
+    if ["command", "execution"].iter().any(|word| question.contains(word)) {
+        ApprovalKind::BashCommand
+    } else if ["edit", "change", "create new file"].iter().any(|word| question.contains(word)) {
+        ApprovalKind::FileEdit
+    } else {
+        ApprovalKind::Other
+    }
+}
+
+fn bottom_lines(content: &str, count: usize) -> Vec<&str> {
+    let mut lines: Vec<&str> = content.lines().rev().filter(|l| !l.trim().is_empty()).take(count).collect();
+    lines.reverse();
+    lines
+}
+
+fn aider_question_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^(.*?)\s*\(Y\)es/\(N\)o.*\[(?:Yes|No)\]:\s*$").unwrap())
+}
+
+fn aider_prompt_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^[\w ]*>\s*$").unwrap())
+}
+
+fn codex_approval_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| {
+        Regex::new(r"Allow command\?|Would you like to (?:run the following command|make the following edits)\?").unwrap()
+    })
+}
+
+fn codex_working_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^\W*(\w[^(]*?)\s*\([^)]*esc to interrupt\)").unwrap())
+}
+
+fn gemini_approval_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"Allow execution\??|Apply this change\?|Do you want to proceed\?").unwrap())
+}
@@ -0,0 +1,135 @@
+use std::process::Command;
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+
+use crate::claude_state::{ApprovalKind, ClaudeState};
+use crate::monitor::Transition;
+
+/// How chatty spoken announcements are.
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum Verbosity {
+    /// Only announce that some session needs approval
+    Minimal,
+    /// Announce approvals and finished runs, naming the project
+    #[default]
+    Normal,
+    /// Announce every state transition with its duration
+    Verbose,
+}
+
+/// Text-to-speech commands tried in order; the first one found on PATH is used.
+const TTS_COMMANDS: &[&str] = &["say", "spd-say", "espeak-ng", "espeak"];
+
+/// Speaks state transitions through the OS text-to-speech command.
+///
+/// Utterances are queued to a dedicated thread so that speech never blocks
+/// the polling loop and consecutive announcements do not talk over each other.
+pub struct Announcer {
+    verbosity: Verbosity,
+    queue: Sender<String>,
+}
+
+impl Announcer {
+    /// Returns `None` when no supported TTS command is installed.
+    pub fn new(verbosity: Verbosity) -> Option<Self> {
+        let Some(command) = find_tts_command() else {
+            crate::log!("no text-to-speech command found (tried {})", TTS_COMMANDS.join(", "));
+            return None;
+        };
+        let (queue, rx) = mpsc::channel::<String>();
+        thread::spawn(move || {
+            for text in rx {
+                if let Err(e) = Command::new(command).arg(&text).status() {
+                    crate::log!("{command} failed: {e}");
+                }
+            }
+        });
+        Some(Self { verbosity, queue })
+    }
+
+    pub fn announce(&self, transition: &Transition) {
+        if let Some(text) = announcement(transition, self.verbosity) {
+            let _ = self.queue.send(text);
+        }
+    }
+}
+
+fn find_tts_command() -> Option<&'static str> {
+    TTS_COMMANDS.iter().copied().find(|cmd| {
+        Command::new("which")
+            .arg(cmd)
+            .output()
+            .is_ok_and(|out| out.status.success())
+    })
+}
+
+/// Build the sentence to speak for a transition, or `None` if the
+/// verbosity level does not cover it.
+pub fn announcement(transition: &Transition, verbosity: Verbosity) -> Option<String> {
+    let project = &transition.pane.project_name;
+    match verbosity {
+        Verbosity::Minimal => match transition.to {
+            ClaudeState::WaitingForApproval => Some("Claude needs approval".to_string()),
+            ClaudeState::WaitingForAnswer => Some("Claude has a question".to_string()),
+            ClaudeState::Error => Some("Claude hit an error".to_string()),
+            ClaudeState::Blocked => Some("Claude needs input".to_string()),
+            _ => None,
+        },
+        Verbosity::Normal => match (&transition.from, &transition.to) {
+            (_, ClaudeState::WaitingForApproval) => {
+                Some(format!("{project} needs approval{}", transition.approval.map_or("", spoken_approval)))
+            }
+            (_, ClaudeState::WaitingForAnswer) => Some(format!("{project} has a question")),
+            (_, ClaudeState::Error) => Some(format!("{project} hit an error")),
+            (_, ClaudeState::Blocked) => Some(format!("{project} needs input")),
+            (ClaudeState::Working, ClaudeState::Idle) => Some(format!("{project} finished")),
+            (_, ClaudeState::NotRunning) => Some(format!("{project} stopped")),
+            _ => None,
+        },
+        Verbosity::Verbose => Some(format!(
+            "{project}: {} to {} after {}",
+            spoken_state(&transition.from),
+            spoken_state(&transition.to),
+            spoken_duration(transition.elapsed.as_secs()),
+        )),
+    }
+}
+
+fn spoken_state(state: &ClaudeState) -> &'static str {
+    match state {
+        ClaudeState::Working => "running",
+        ClaudeState::Compacting => "compacting",
+        ClaudeState::WaitingForApproval => "waiting for approval",
+        ClaudeState::WaitingForAnswer => "waiting for an answer",
+        ClaudeState::Blocked => "blocked on input",
+        ClaudeState::Idle => "idle",
+        ClaudeState::Error => "error",
+        ClaudeState::NotRunning => "not running",
+    }
+}
+
+fn spoken_approval(kind: ApprovalKind) -> &'static str {
+    match kind {
+        ApprovalKind::BashCommand => " to run a command",
+        ApprovalKind::FileEdit => " to edit a file",
+        ApprovalKind::McpTool => " to use an MCP tool",
+        ApprovalKind::TrustDirectory => " to trust a folder",
+        ApprovalKind::PlanApproval => " for its plan",
+        ApprovalKind::Other => "",
+    }
+}
+
+fn spoken_duration(secs: u64) -> String {
+    let plural = |n: u64, unit: &str| {
+        if n == 1 {
+            format!("1 {unit}")
+        } else {
+            format!("{n} {unit}s")
+        }
+    };
+    if secs < 60 {
+        plural(secs, "second")
+    } else {
+        plural(secs / 60, "minute")
+    }
+}
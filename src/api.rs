@@ -0,0 +1,328 @@
+use std::io::Read;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::mpsc::Receiver;
+
+use serde::Deserialize;
+use serde_json::json;
+use tiny_http::{Header, Request, Response, Server, SslConfig};
+
+use crate::actions;
+use crate::discovery;
+use crate::guardrail::Guardrail;
+use crate::health::Health;
+use crate::monitor::{ClaudeSession, SessionEvent, SessionList};
+use crate::rule_stats::RuleStats;
+use crate::status::StatusSnapshot;
+use crate::team::{Report, TeamBoard};
+
+/// The `[api]` config table for `claudeye serve`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ApiConfig {
+    /// Address to listen on; anything but loopback requires a `token`.
+    pub bind: String,
+    /// Bearer token every request must carry.
+    pub token: Option<String>,
+    /// Token team members report with; it authorizes the team endpoints
+    /// and nothing else.
+    pub team_token: Option<String>,
+    /// PEM certificate chain; serves HTTPS together with `tls_key`.
+    pub tls_cert: Option<PathBuf>,
+    /// PEM private key for `tls_cert`.
+    pub tls_key: Option<PathBuf>,
+    /// Serve status only; every action endpoint answers 403.
+    pub read_only: bool,
+    /// Announce the API over mDNS when it listens beyond loopback, for
+    /// `claudeye discover` on other machines.
+    pub advertise: bool,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            bind: "127.0.0.1:7890".to_string(),
+            token: None,
+            team_token: None,
+            tls_cert: None,
+            tls_key: None,
+            read_only: false,
+            advertise: true,
+        }
+    }
+}
+
+impl ApiConfig {
+    /// Refuse settings that would expose the API unprotected: a
+    /// non-loopback address without a token, or half a TLS key pair.
+    pub fn validate(&self) -> Result<SocketAddr, String> {
+        let addr: SocketAddr = self
+            .bind
+            .parse()
+            .map_err(|e| format!("invalid api bind address {:?}: {e}", self.bind))?;
+        if self.token.as_deref().is_none_or(str::is_empty) && !is_loopback(addr.ip()) {
+            return Err(format!("refusing to serve on {addr} without an api token"));
+        }
+        if self.tls_cert.is_some() != self.tls_key.is_some() {
+            return Err("api tls_cert and tls_key must be set together".to_string());
+        }
+        Ok(addr)
+    }
+
+    /// Whether a request to `route` with this `Authorization` header may
+    /// proceed: anything with the token, the team endpoints with the team
+    /// token.
+    pub fn allows(&self, route: &Route, authorization: Option<&str>) -> bool {
+        let team_token = self.team_token.as_deref().filter(|t| !t.is_empty());
+        authorized(self.token.as_deref(), authorization)
+            || (matches!(route, Route::Team | Route::TeamReport)
+                && team_token.is_some_and(|token| authorized(Some(token), authorization)))
+    }
+}
+
+fn is_loopback(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => v4.is_loopback(),
+        IpAddr::V6(v6) => v6.is_loopback() || v6.to_ipv4_mapped().is_some_and(|v4| v4.is_loopback()),
+    }
+}
+
+/// Whether an `Authorization` header value carries the expected token.
+/// Without a configured token every request is allowed.
+pub fn authorized(token: Option<&str>, authorization: Option<&str>) -> bool {
+    let Some(token) = token.filter(|t| !t.is_empty()) else {
+        return true;
+    };
+    authorization
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| constant_time_eq(given.trim().as_bytes(), token.as_bytes()))
+}
+
+/// Whether an action request may come from a web page rather than a
+/// client of the API. Browsers send `Origin` with every POST, and a page
+/// reaching loopback through DNS rebinding does so under its own `Host`;
+/// the latter only matters without a token, which a page cannot send.
+pub fn from_browser(token: Option<&str>, origin: Option<&str>, host: Option<&str>) -> bool {
+    let no_token = token.is_none_or(str::is_empty);
+    origin.is_some() || (no_token && host.is_some_and(|host| !is_loopback_host(host)))
+}
+
+/// Whether a `Host` header value names this machine, port or not.
+fn is_loopback_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next().unwrap_or_default(),
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+    };
+    name.eq_ignore_ascii_case("localhost") || name.parse().is_ok_and(is_loopback)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Route {
+    /// `GET /api/status`
+    Status,
+    /// `GET /healthz`: freshness of the last poll and status of its sources
+    Health,
+    /// `GET /api/metrics`: detection rule counters in the Prometheus format
+    Metrics,
+    /// `POST /api/panes/<pane>/approve`
+    Approve(String),
+    /// `POST /api/panes/<pane>/deny`
+    Deny(String),
+    /// `GET /api/team`: member totals of team reports
+    Team,
+    /// `POST /api/team/reports`: a report from a member's claudeye
+    TeamReport,
+    MethodNotAllowed,
+    NotFound,
+}
+
+impl Route {
+    /// Whether the endpoint acts on a session rather than just reading.
+    pub fn is_action(&self) -> bool {
+        matches!(self, Route::Approve(_) | Route::Deny(_))
+    }
+}
+
+/// Map a method and request URL (query string ignored) to an endpoint.
+/// Pane ids are percent-decoded so session names with spaces work.
+pub fn route(method: &str, url: &str) -> Route {
+    let path = url.split('?').next().unwrap_or_default();
+    let (route, expected) = if path == "/api/status" {
+        (Route::Status, "GET")
+    } else if path == "/healthz" {
+        (Route::Health, "GET")
+    } else if path == "/api/metrics" {
+        (Route::Metrics, "GET")
+    } else if path == "/api/team" {
+        (Route::Team, "GET")
+    } else if path == "/api/team/reports" {
+        (Route::TeamReport, "POST")
+    } else if let Some(pane) = path
+        .strip_prefix("/api/panes/")
+        .and_then(|rest| rest.strip_suffix("/approve"))
+        .filter(|pane| !pane.is_empty())
+    {
+        (Route::Approve(percent_decode(pane)), "POST")
+    } else if let Some(pane) = path
+        .strip_prefix("/api/panes/")
+        .and_then(|rest| rest.strip_suffix("/deny"))
+        .filter(|pane| !pane.is_empty())
+    {
+        (Route::Deny(percent_decode(pane)), "POST")
+    } else {
+        return Route::NotFound;
+    };
+    if method == expected { route } else { Route::MethodNotAllowed }
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Serve the API on the current thread until the process exits.
+pub fn serve(
+    config: &ApiConfig,
+    events: Receiver<SessionEvent>,
+    guardrails: Vec<Guardrail>,
+) -> Result<(), String> {
+    let addr = config.validate()?;
+    let server = match (&config.tls_cert, &config.tls_key) {
+        (Some(cert), Some(key)) => {
+            let read = |path: &PathBuf| {
+                std::fs::read(path).map_err(|e| format!("failed to read {}: {e}", path.display()))
+            };
+            let ssl = SslConfig {
+                certificate: read(cert)?,
+                private_key: read(key)?,
+            };
+            Server::https(addr, ssl)
+        }
+        _ => Server::http(addr),
+    }
+    .map_err(|e| format!("failed to listen on {addr}: {e}"))?;
+    let scheme = if config.tls_cert.is_some() { "https" } else { "http" };
+    let mode = if config.read_only { " (read-only)" } else { "" };
+    crate::log!("api listening on {scheme}://{addr}{mode}");
+    // Held for the life of the server; dropping it stops the announcements.
+    let _mdns = (config.advertise && !is_loopback(addr.ip()))
+        .then(|| discovery::advertise(config, addr))
+        .flatten();
+    let team = Mutex::new(TeamBoard::default());
+    // Requests are handled one at a time, so the list is caught up with the
+    // monitor before each rather than shared with it.
+    let mut sessions = SessionList::default();
+    for request in server.incoming_requests() {
+        sessions.catch_up(&events);
+        handle(request, config, sessions.sessions(), &guardrails, &team);
+    }
+    Ok(())
+}
+
+fn handle(
+    mut request: Request,
+    config: &ApiConfig,
+    sessions: &[ClaudeSession],
+    guardrails: &[Guardrail],
+    team: &Mutex<TeamBoard>,
+) {
+    let header = |name: &'static str| {
+        request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv(name))
+            .map(|h| h.value.as_str().to_string())
+    };
+    let (authorization, origin, host) = (header("Authorization"), header("Origin"), header("Host"));
+    let route = route(request.method().as_str(), request.url());
+    let is_metrics = route == Route::Metrics;
+    let (status, body) = if !config.allows(&route, authorization.as_deref()) {
+        (401, json!({ "error": "unauthorized" }))
+    } else if route.is_action() && from_browser(config.token.as_deref(), origin.as_deref(), host.as_deref()) {
+        (403, json!({ "error": "cross-origin request" }))
+    } else if config.read_only && route.is_action() {
+        (403, json!({ "error": "read-only" }))
+    } else {
+        match route {
+            Route::Status => (200, json!(StatusSnapshot::from_sessions(sessions))),
+            Route::Health => {
+                let health = Health::current();
+                (if health.ok { 200 } else { 503 }, json!(health))
+            }
+            Route::Metrics => (200, json!(RuleStats::load().prometheus())),
+            Route::Approve(pane) => match actions::approve(&pane, guardrails, false) {
+                Ok(()) => (200, json!({ "ok": true })),
+                Err(e) => (409, json!({ "error": e })),
+            },
+            Route::Deny(pane) => match actions::deny(&pane) {
+                Ok(()) => (200, json!({ "ok": true })),
+                Err(e) => (409, json!({ "error": e })),
+            },
+            Route::Team => {
+                let team = team.lock().unwrap_or_else(|e| e.into_inner());
+                (200, json!({ "members": team.members() }))
+            }
+            Route::TeamReport => match read_report(&mut request) {
+                Ok(report) => {
+                    team.lock().unwrap_or_else(|e| e.into_inner()).add(report);
+                    (200, json!({ "ok": true }))
+                }
+                Err(e) => (400, json!({ "error": e })),
+            },
+            Route::MethodNotAllowed => (405, json!({ "error": "method not allowed" })),
+            Route::NotFound => (404, json!({ "error": "not found" })),
+        }
+    };
+    // Metrics are plain text for Prometheus; errors stay JSON like elsewhere.
+    let (content_type, body) = match body {
+        serde_json::Value::String(text) if is_metrics => ("text/plain; version=0.0.4", text),
+        body => ("application/json", body.to_string()),
+    };
+    let mut response = Response::from_string(body).with_status_code(status);
+    if let Ok(header) = Header::from_bytes("Content-Type", content_type) {
+        response = response.with_header(header);
+    }
+    if status == 401 && let Ok(header) = Header::from_bytes("WWW-Authenticate", "Bearer") {
+        response = response.with_header(header);
+    }
+    if let Err(e) = request.respond(response) {
+        crate::log!("api response failed: {e}");
+    }
+}
+
+/// Longest team report body accepted.
+const MAX_REPORT_BYTES: u64 = 64 * 1024;
+
+fn read_report(request: &mut Request) -> Result<Report, String> {
+    let mut body = String::new();
+    request
+        .as_reader()
+        .take(MAX_REPORT_BYTES)
+        .read_to_string(&mut body)
+        .map_err(|e| e.to_string())?;
+    let report: Report = serde_json::from_str(&body).map_err(|e| format!("invalid report: {e}"))?;
+    if report.member.is_empty() {
+        return Err("invalid report: empty member".to_string());
+    }
+    Ok(report)
+}
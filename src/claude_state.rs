@@ -1,89 +1,893 @@
 use regex::Regex;
+use serde::Deserialize;
+use std::borrow::Cow;
 use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::text;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum ClaudeState {
     Working,
+    /// Claude is compacting its conversation: busy, but not progressing on
+    /// the task.
+    Compacting,
     WaitingForApproval,
+    /// Claude asked a question that needs typed input rather than a yes/no
+    /// decision (AskUserQuestion interviews, "What should Claude do instead?").
+    WaitingForAnswer,
+    /// A command Claude ran is itself waiting for input (a `sudo` password,
+    /// an SSH host key confirmation), which Claude cannot answer.
+    Blocked,
     Idle,
+    /// The last request failed: an API error, a rate or usage limit, or no
+    /// connection. Nothing proceeds until it is retried.
+    Error,
+    /// The claude process exited (crashed or quit) but its pane is still open.
+    NotRunning,
+}
+
+/// Display language of the Claude Code UI, which decides the prompt texts
+/// that detection looks for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UiLocale {
+    /// Recognise the prompts of every supported language.
+    #[default]
+    Auto,
+    En,
+    Ja,
+}
+
+impl UiLocale {
+    fn pattern_sets(self) -> &'static [&'static LocalePatterns] {
+        match self {
+            UiLocale::Auto => &[&EN_PATTERNS, &JA_PATTERNS],
+            UiLocale::En => &[&EN_PATTERNS],
+            UiLocale::Ja => &[&JA_PATTERNS],
+        }
+    }
+}
+
+/// A state plus what Claude's status line says it is doing.
+#[derive(Debug, PartialEq, Clone)]
+pub struct StatusInfo {
+    pub state: ClaudeState,
+    /// Spinner text while working, without the trailing ellipsis: a verb
+    /// such as "Thinking" or "Compacting", or the current action
+    /// ("Adding handler types").
+    pub activity: Option<String>,
+    /// Claude's own timer for the current turn (`1m 45s`).
+    pub timer: Option<Duration>,
+    /// Tokens streamed so far this turn (`↓ 1.2k tokens`).
+    pub tokens: Option<u64>,
+    /// What a session waiting for approval asks to do.
+    pub pending: Option<PendingApproval>,
+    /// The error line of a session in [`ClaudeState::Error`].
+    pub error: Option<String>,
+    /// The prompt a [`ClaudeState::Blocked`] session's command waits at
+    /// (`[sudo] password for me:`).
+    pub input_prompt: Option<String>,
+    /// Subagents still running while working.
+    pub subagents: Option<Subagents>,
+    /// Completed and total items of the latest todo list shown.
+    pub todo_progress: Option<(u32, u32)>,
+    /// Permission mode from the footer.
+    pub permission_mode: PermissionMode,
+    /// Permission checks are bypassed (`--dangerously-skip-permissions`),
+    /// from the footer or the warning shown at startup.
+    pub skips_permissions: bool,
+    /// Model named in the footer (`Opus 4.6`).
+    pub model: Option<String>,
+    /// Percentage of the context window left, from the footer.
+    pub context_left: Option<u8>,
+    /// An Idle session was stopped with Esc mid-turn and waits to be told
+    /// what to do instead, rather than having finished.
+    pub interrupted: bool,
+    /// Messages typed while working, queued until the turn ends.
+    pub queued_messages: u32,
+    /// How long the turn an Idle session finished took, from Claude's
+    /// summary line (`✻ Cooked for 43s`).
+    pub last_turn: Option<Duration>,
+    /// Name of the detection rule that decided the state (see [`RULES`]).
+    pub rule: &'static str,
+}
+
+impl StatusInfo {
+    /// `state` with nothing else known about it.
+    pub fn new(state: ClaudeState) -> Self {
+        Self {
+            state,
+            activity: None,
+            timer: None,
+            tokens: None,
+            pending: None,
+            error: None,
+            input_prompt: None,
+            subagents: None,
+            todo_progress: None,
+            permission_mode: PermissionMode::Default,
+            skips_permissions: false,
+            model: None,
+            context_left: None,
+            interrupted: false,
+            last_turn: None,
+            queued_messages: 0,
+            rule: "fallback",
+        }
+    }
+}
+
+/// Which permission mode a session is in, cycled with shift+tab and shown
+/// in the footer (`⏸ plan mode on`, `⏵⏵ accept edits on`).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PermissionMode {
+    /// Nothing shown in the footer: every edit and command asks first.
+    #[default]
+    Default,
+    /// Claude only plans and reads until the plan is accepted.
+    Plan,
+    /// File edits are applied without asking.
+    AcceptEdits,
+    /// Nothing asks for approval (`--dangerously-skip-permissions`).
+    BypassPermissions,
+}
+
+/// Subagents (`Task`, `Explore`, ...) a working session is waiting on.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Subagents {
+    /// How many have not finished yet.
+    pub running: usize,
+    /// The bottom-most running one, e.g. `Explore: Find the config loader`.
+    pub active: String,
+}
+
+/// The tool call awaiting approval.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PendingApproval {
+    /// Tool name: `Bash`, `Edit`, `Create`, whatever the tool call line
+    /// shows (`WebFetch`, an MCP tool, ...), or [`LOGIN`] for a sign-in
+    /// screen.
+    pub tool: String,
+    /// The command, file, or tool arguments.
+    pub detail: String,
+    pub kind: ApprovalKind,
+}
+
+/// What an approval is for, to tell a harmless command from an edit or a
+/// trust decision at a glance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalKind {
+    /// A `Bash command` dialog.
+    BashCommand,
+    /// Creating, editing, or overwriting a file.
+    FileEdit,
+    /// A tool of an MCP server (`github - create_issue (MCP)`).
+    McpTool,
+    /// Whether to trust the files of the folder claude was started in.
+    TrustDirectory,
+    /// Whether to start coding on the plan made in plan mode.
+    PlanApproval,
+    /// Web fetches, sign-in screens, and every other tool.
+    Other,
+}
+
+impl ApprovalKind {
+    /// Key used in `status.json` and webhook payloads.
+    pub fn key(self) -> &'static str {
+        match self {
+            ApprovalKind::BashCommand => "bash",
+            ApprovalKind::FileEdit => "file_edit",
+            ApprovalKind::McpTool => "mcp",
+            ApprovalKind::TrustDirectory => "trust",
+            ApprovalKind::PlanApproval => "plan",
+            ApprovalKind::Other => "other",
+        }
+    }
+
+    /// The kind of a tool call line's tool (`Bash`, `Write`, `mcp__github__create_issue`).
+    pub fn of_tool(tool: &str) -> Self {
+        match tool {
+            "Bash" => ApprovalKind::BashCommand,
+            "Edit" | "MultiEdit" | "Write" | "Create" | "Update" | "NotebookEdit" => ApprovalKind::FileEdit,
+            _ if tool.starts_with("mcp__") => ApprovalKind::McpTool,
+            _ => ApprovalKind::Other,
+        }
+    }
+}
+
+/// [`PendingApproval::tool`] of a session waiting for the user to sign in
+/// again (`/login`, an OAuth URL prompt).
+pub const LOGIN: &str = "Login";
+
+impl PendingApproval {
+    /// Whether this is a sign-in screen rather than a tool call, which only
+    /// the user can get past.
+    pub fn is_login(&self) -> bool {
+        self.tool == LOGIN
+    }
+
+    /// One-line summary such as `Bash: grep --help | head`, shortened to
+    /// `max_width` columns.
+    pub fn summary(&self, max_width: usize) -> String {
+        let text = format!("{}: {}", self.tool, self.detail.lines().next().unwrap_or_default());
+        text::truncate(&text, max_width).into_owned()
+    }
+}
+
+/// How many non-empty lines at the bottom of a pane the main checks look
+/// at, unless `[monitor] detection_lines` says otherwise.
+pub const LAST_LINES_COUNT: usize = 30;
+
+static DETECTION_LINES: OnceLock<usize> = OnceLock::new();
+
+/// Look at the bottom `lines` non-empty lines instead of
+/// [`LAST_LINES_COUNT`], as `[monitor] detection_lines` asks. Only the first
+/// call has an effect.
+pub fn set_detection_lines(lines: usize) {
+    let _ = DETECTION_LINES.set(lines.max(1));
+}
+
+fn last_lines_count() -> usize {
+    DETECTION_LINES.get().copied().unwrap_or(LAST_LINES_COUNT)
+}
+/// Free-form question prompts, error banners, login prompts, and the exit
+/// hint sit at the very bottom, so only that is searched to avoid matching
+/// text that scrolled up.
+const QUESTION_LINES_COUNT: usize = 6;
+/// A command's own prompt sits above the spinner, the input box, and the
+/// footer, so a little more than the question area is searched.
+const INPUT_PROMPT_LINES_COUNT: usize = 10;
+/// Longest error or login summary kept, in characters.
+const ERROR_SUMMARY_WIDTH: usize = 120;
+
+/// `content` without ANSI escape sequences (colors, cursor movement, OSC
+/// titles and hyperlinks), which `capture-pane -e` and stray output leave
+/// in the text and which would keep the patterns from matching.
+pub fn strip_ansi(content: &str) -> Cow<'_, str> {
+    if !content.contains('\x1b') {
+        return Cow::Borrowed(content);
+    }
+    ansi_pattern().replace_all(content, "")
+}
+
+/// Detect the state, plus what the spinner line says while working and
+/// what needs approval while waiting for it.
+pub fn detect_status(content: &str) -> StatusInfo {
+    detect_status_in(content, UiLocale::Auto)
+}
+
+/// [`detect_status`] for a UI shown in `locale`.
+pub fn detect_status_in(content: &str, locale: UiLocale) -> StatusInfo {
+    let content = &*strip_ansi(content);
+    let (state, trace) = detect_state_explained_in(content, locale);
+    let mut info = StatusInfo {
+        todo_progress: todo_progress(content),
+        permission_mode: permission_mode(content),
+        rule: trace.rule,
+        ..StatusInfo::new(state)
+    };
+    info.skips_permissions =
+        info.permission_mode == PermissionMode::BypassPermissions || bypass_banner_pattern().is_match(content);
+    (info.model, info.context_left) = model_context(content);
+    match info.state {
+        ClaudeState::Working => {
+            read_spinner(&mut info, content);
+            info.subagents = running_subagents(content);
+            info.queued_messages = queued_messages(content);
+        }
+        ClaudeState::Compacting => {
+            read_spinner(&mut info, content);
+            info.queued_messages = queued_messages(content);
+        }
+        ClaudeState::WaitingForApproval => info.pending = pending_approval(content),
+        ClaudeState::Error => info.error = error_line(content).map(summarize_line),
+        ClaudeState::Blocked => info.input_prompt = input_prompt_line(content).map(summarize_line),
+        ClaudeState::Idle => {
+            info.interrupted = was_interrupted(content);
+            info.last_turn = last_turn_duration(content);
+        }
+        _ => {}
+    }
+    info
+}
+
+/// The prompt of a command still waiting for input, e.g.
+/// `⎿  [sudo] password for me:`. Once Claude has written anything after
+/// the command's output the prompt is stale.
+fn input_prompt_line(content: &str) -> Option<&str> {
+    let lines: Vec<&str> = content.split('\n').collect();
+    for line in last_non_empty_lines(&lines, INPUT_PROMPT_LINES_COUNT).into_iter().rev() {
+        if input_prompt_pattern().is_match(line) {
+            return Some(line);
+        }
+        if line.trim_start().starts_with('⏺') {
+            return None;
+        }
+    }
+    None
+}
+
+/// Whether the last turn ended with `⎿  Interrupted · What should Claude do
+/// instead?` rather than with Claude's reply.
+fn was_interrupted(content: &str) -> bool {
+    let lines: Vec<&str> = content.split('\n').collect();
+    for line in last_non_empty_lines(&lines, INPUT_PROMPT_LINES_COUNT).into_iter().rev() {
+        if interrupted_pattern().is_match(line) {
+            return true;
+        }
+        if line.trim_start().starts_with('⏺') {
+            return false;
+        }
+    }
+    false
 }
 
-const LAST_LINES_COUNT: usize = 30;
+/// The duration in the summary Claude prints below its reply when a turn
+/// ends (`✻ Churned for 3m 5s`). A summary above Claude's last reply
+/// belongs to an earlier turn.
+fn last_turn_duration(content: &str) -> Option<Duration> {
+    let lines: Vec<&str> = content.split('\n').collect();
+    for line in last_non_empty_lines(&lines, INPUT_PROMPT_LINES_COUNT).into_iter().rev() {
+        if let Some(caps) = turn_summary_pattern().captures(line) {
+            return parse_timer(&caps[1]);
+        }
+        if line.trim_start().starts_with('⏺') {
+            return None;
+        }
+    }
+    None
+}
+
+/// The bottom-most error banner line, e.g. `⎿  API Error: 529 ...`.
+fn error_line(content: &str) -> Option<&str> {
+    let lines: Vec<&str> = content.split('\n').collect();
+    last_non_empty_lines(&lines, QUESTION_LINES_COUNT)
+        .into_iter()
+        .rev()
+        .find(|line| error_pattern().is_match(line))
+}
+
+/// The bottom-most line asking the user to sign in again.
+fn login_line(content: &str) -> Option<&str> {
+    let lines: Vec<&str> = content.split('\n').collect();
+    last_non_empty_lines(&lines, QUESTION_LINES_COUNT)
+        .into_iter()
+        .rev()
+        .find(|line| login_pattern().is_match(line))
+}
+
+/// A dialog line without the box drawn around it.
+fn dialog_text(line: &str) -> &str {
+    line.trim_matches(['│', ' '])
+}
+
+fn summarize_line(line: &str) -> String {
+    let text = line.trim().trim_start_matches(['⎿', '⏺', '●']).trim();
+    text::truncate(text, ERROR_SUMMARY_WIDTH).into_owned()
+}
+
+/// The live spinner line, which is the bottom-most one.
+fn bottom_spinner(content: &str) -> Option<regex::Captures<'_>> {
+    let lines: Vec<&str> = content.split('\n').collect();
+    last_non_empty_lines(&lines, last_lines_count())
+        .into_iter()
+        .rev()
+        .find_map(|line| spinner_pattern().captures(line))
+}
+
+fn read_spinner(info: &mut StatusInfo, content: &str) {
+    let Some(caps) = bottom_spinner(content) else {
+        return;
+    };
+    info.activity = Some(caps[1].trim().to_string()).filter(|text| !text.is_empty());
+    // `(esc to interrupt · 1m 45s · ↓ 1.2k tokens · thinking)`: the parts
+    // come in varying order, so each is recognised on its own.
+    for part in caps.get(2).map_or("", |m| m.as_str()).split('·').map(str::trim) {
+        if let Some(timer) = parse_timer(part) {
+            info.timer.get_or_insert(timer);
+        } else if let Some(tokens) = parse_tokens(part) {
+            info.tokens.get_or_insert(tokens);
+        }
+    }
+}
+
+/// Messages typed while Claude works, which are listed between the spinner
+/// and the input box (`> also update the docs`) until the turn ends.
+pub fn queued_messages(content: &str) -> u32 {
+    let lines: Vec<&str> = content.split('\n').collect();
+    let Some(spinner) = lines.iter().rposition(|l| spinner_pattern().is_match(l)) else {
+        return 0;
+    };
+    let queued = lines[spinner + 1..]
+        .iter()
+        .map(|l| l.trim())
+        .take_while(|l| l.is_empty() || !is_separator_line(l))
+        .filter(|l| queued_message_pattern().is_match(l))
+        .count();
+    queued as u32
+}
+
+/// Extract what an approval dialog asks for: a login prompt, the command
+/// of a `Bash command` dialog, the file of an edit or create dialog, or
+/// else the arguments of the last tool call line (`⏺ WebFetch(https://...)`).
+pub fn pending_approval(content: &str) -> Option<PendingApproval> {
+    let content = &*strip_ansi(content);
+    if let Some(line) = login_line(content) {
+        return Some(PendingApproval {
+            tool: LOGIN.to_string(),
+            detail: summarize_line(line),
+            kind: ApprovalKind::Other,
+        });
+    }
+
+    let lines: Vec<&str> = content.split('\n').collect();
+    let count = last_lines_count();
+    let bottom = last_non_empty_lines(&lines, count);
+    // Keep blank lines here: they separate a command from its description.
+    let end = lines.iter().rposition(|l| !l.trim().is_empty()).map_or(0, |i| i + 1);
+    let dialog = &lines[end.saturating_sub(count)..end];
+
+    if let Some(question) = dialog.iter().position(|l| trust_dialog_pattern().is_match(l)) {
+        // The folder is shown on its own line below the question.
+        let folder = dialog[question + 1..]
+            .iter()
+            .map(|l| dialog_text(l))
+            .find(|l| l.starts_with('/') || l.starts_with('~'))
+            .unwrap_or_default();
+        return Some(PendingApproval {
+            tool: "Trust".to_string(),
+            detail: folder.to_string(),
+            kind: ApprovalKind::TrustDirectory,
+        });
+    }
+
+    if let Some(header) = dialog.iter().position(|l| l.contains("Here is Claude's plan:")) {
+        // The plan's first line is usually its title.
+        let title = dialog[header + 1..]
+            .iter()
+            .map(|l| dialog_text(l))
+            .find(|l| !l.is_empty() && !is_separator_line(l))
+            .unwrap_or_default();
+        return Some(PendingApproval {
+            tool: "Plan".to_string(),
+            detail: title.to_string(),
+            kind: ApprovalKind::PlanApproval,
+        });
+    }
+
+    if let Some(header) = dialog.iter().rposition(|l| l.trim() == "Bash command") {
+        // `Bash command`, a blank line, the command (possibly spanning
+        // several lines), then a one-line description.
+        let block: Vec<&str> = dialog[header + 1..]
+            .iter()
+            .skip_while(|l| l.trim().is_empty())
+            .take_while(|l| !l.trim().is_empty())
+            .map(|l| l.trim())
+            .collect();
+        let command = match block.len() {
+            0 => None,
+            1 => Some(block[0].to_string()),
+            n => Some(block[..n - 1].join("\n")),
+        };
+        if let Some(command) = command {
+            return Some(PendingApproval {
+                tool: "Bash".to_string(),
+                detail: command,
+                kind: ApprovalKind::BashCommand,
+            });
+        }
+    }
+
+    for line in bottom.iter().rev() {
+        if let Some(caps) = file_question_pattern().captures(line) {
+            let tool = if &caps[1] == "create" { "Create" } else { "Edit" };
+            return Some(PendingApproval {
+                tool: tool.to_string(),
+                detail: caps[2].to_string(),
+                kind: ApprovalKind::FileEdit,
+            });
+        }
+    }
+
+    bottom.iter().rev().find_map(|line| {
+        if let Some(caps) = mcp_call_pattern().captures(line) {
+            return Some(PendingApproval {
+                tool: caps[1].to_string(),
+                detail: caps[2].to_string(),
+                kind: ApprovalKind::McpTool,
+            });
+        }
+        let caps = tool_call_pattern().captures(line)?;
+        Some(PendingApproval {
+            tool: caps[1].to_string(),
+            detail: caps[2].to_string(),
+            kind: ApprovalKind::of_tool(&caps[1]),
+        })
+    })
+}
+
+/// Subagent blocks without a `Done (...)` result yet. A block is a tool
+/// call line at the left margin (`⏺ Explore(Find the config loader)`) and
+/// the indented lines below it; it is a subagent when the tool is a known
+/// agent type or the block reports nested tool uses.
+pub fn running_subagents(content: &str) -> Option<Subagents> {
+    let lines: Vec<&str> = content.split('\n').collect();
+    let mut running = 0;
+    let mut active = None;
+    for (i, line) in lines.iter().enumerate() {
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let Some(caps) = tool_call_pattern().captures(line) else {
+            continue;
+        };
+        let body: Vec<&str> = lines[i + 1..]
+            .iter()
+            .take_while(|l| l.is_empty() || l.starts_with(char::is_whitespace))
+            .copied()
+            .collect();
+        let is_agent = SUBAGENT_TOOLS.contains(&&caps[1])
+            || body.iter().any(|l| subagent_progress_pattern().is_match(l));
+        let finished = body.iter().any(|l| subagent_result_pattern().is_match(l));
+        if is_agent && !finished {
+            running += 1;
+            active = Some(match &caps[1] {
+                "Task" | "Agent" => caps[2].to_string(),
+                tool => format!("{tool}: {}", &caps[2]),
+            });
+        }
+    }
+    active.map(|active| Subagents { running, active })
+}
+
+/// Completed and total items of the bottom-most todo list (`☒ Add types`,
+/// `☐ Write tests`, or `✔`/`◻` in the ctrl+t view).
+pub fn todo_progress(content: &str) -> Option<(u32, u32)> {
+    let lines: Vec<&str> = content.split('\n').collect();
+    let last = lines.iter().rposition(|l| todo_item_pattern().is_match(l))?;
+    let (mut done, mut total) = (0, 0);
+    for line in lines[..=last].iter().rev() {
+        let Some(caps) = todo_item_pattern().captures(line) else {
+            break;
+        };
+        total += 1;
+        if matches!(&caps[1], "☒" | "✔" | "✓") {
+            done += 1;
+        }
+    }
+    Some((done, total))
+}
+
+/// The permission mode shown in the footer. The mode line is matched by
+/// its glyph so that localized footers are recognized too.
+pub fn permission_mode(content: &str) -> PermissionMode {
+    let lines: Vec<&str> = content.split('\n').collect();
+    last_non_empty_lines(&lines, QUESTION_LINES_COUNT)
+        .into_iter()
+        .rev()
+        .find_map(|line| {
+            let caps = permission_mode_pattern().captures(line)?;
+            Some(match &caps[1] {
+                "⏸" => PermissionMode::Plan,
+                _ if caps[2].contains("bypass") => PermissionMode::BypassPermissions,
+                _ => PermissionMode::AcceptEdits,
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// The model and the percentage of context left, from a footer such as
+/// `[Opus 4.6] Context: 37%` or Claude's own `Context left until
+/// auto-compact: 12%`.
+pub fn model_context(content: &str) -> (Option<String>, Option<u8>) {
+    let lines: Vec<&str> = content.split('\n').collect();
+    let (mut model, mut context) = (None, None);
+    for line in last_non_empty_lines(&lines, QUESTION_LINES_COUNT) {
+        let Some(caps) = context_pattern().captures(line) else {
+            continue;
+        };
+        if model.is_none() {
+            model = caps.get(1).map(|m| m.as_str().trim().to_string());
+        }
+        if context.is_none() {
+            context = caps.get(2).or(caps.get(3)).and_then(|m| m.as_str().parse::<u8>().ok()).map(|p| p.min(100));
+        }
+    }
+    (model, context)
+}
+
+/// `1m 45s`, `2h 3m`, `30s`.
+fn parse_timer(part: &str) -> Option<Duration> {
+    let mut secs = 0;
+    let mut units = 0;
+    for unit in part.split_whitespace() {
+        let (value, multiplier) = match unit.as_bytes().last()? {
+            b'h' => (&unit[..unit.len() - 1], 3600),
+            b'm' => (&unit[..unit.len() - 1], 60),
+            b's' => (&unit[..unit.len() - 1], 1),
+            _ => return None,
+        };
+        secs += value.parse::<u64>().ok()? * multiplier;
+        units += 1;
+    }
+    (units > 0).then(|| Duration::from_secs(secs))
+}
+
+/// `↓ 1.2k tokens`, `↑ 500 tokens`.
+fn parse_tokens(part: &str) -> Option<u64> {
+    let caps = tokens_pattern().captures(part)?;
+    let value: f64 = caps[1].parse().ok()?;
+    let multiplier = match caps.get(2).map(|m| m.as_str()) {
+        Some("k") => 1_000.0,
+        Some("m") => 1_000_000.0,
+        _ => 1.0,
+    };
+    Some((value * multiplier).round() as u64)
+}
 
-/// Ported from tcmux parseClaudeStatus.
 pub fn detect_state(content: &str) -> ClaudeState {
+    detect_state_in(content, UiLocale::Auto)
+}
+
+/// [`detect_state`] for a UI shown in `locale`.
+pub fn detect_state_in(content: &str, locale: UiLocale) -> ClaudeState {
+    detect_state_explained_in(content, locale).0
+}
+
+/// Every rule [`DetectionTrace::rule`] can name, in the order they are
+/// checked.
+pub const RULES: &[&str] = &[
+    "exit_hint",
+    "input_prompt",
+    "running",
+    "running_time_first",
+    "running_fallback",
+    "esc_to_interrupt_end",
+    "running_generic",
+    "interview",
+    "free_form_question",
+    "login",
+    "error",
+    "prompt_line",
+    "waiting_text",
+    "selection_menu",
+    "idle_pattern",
+    "fallback",
+];
+
+/// Why [`detect_state_explained`] settled on a state, for debugging
+/// misdetections.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DetectionTrace {
+    /// The check that decided, in the order they run: `exit_hint`,
+    /// `input_prompt`, `running`, `running_time_first`, `running_fallback`,
+    /// `esc_to_interrupt_end`, `running_generic`, `interview`,
+    /// `free_form_question`, `login`, `error`, `prompt_line`,
+    /// `waiting_text`, `selection_menu`, `idle_pattern`, or `fallback` when
+    /// none matched.
+    pub rule: &'static str,
+    /// The text the deciding check matched.
+    pub matched: Option<String>,
+    /// Checks that ran before it without matching.
+    pub skipped: Vec<&'static str>,
+    /// ANSI escape sequences were removed before matching.
+    pub stripped_ansi: bool,
+    /// The spinner text turned a Working state into Compacting.
+    pub compacting: bool,
+}
+
+impl DetectionTrace {
+    /// Multi-line explanation printed by `claudeye detect --explain`.
+    pub fn render(&self) -> String {
+        let mut out = format!("rule: {}\n", self.rule);
+        if let Some(matched) = &self.matched {
+            out.push_str(&format!("matched: {matched}\n"));
+        }
+        if !self.skipped.is_empty() {
+            out.push_str(&format!("checked first: {}\n", self.skipped.join(", ")));
+        }
+        if self.stripped_ansi {
+            out.push_str("note: ANSI escape sequences were stripped before matching\n");
+        }
+        if self.compacting {
+            out.push_str("note: the spinner says Compacting, so Working became Compacting\n");
+        }
+        out
+    }
+}
+
+/// [`detect_state`] along with the check that decided it.
+pub fn detect_state_explained(content: &str) -> (ClaudeState, DetectionTrace) {
+    detect_state_explained_in(content, UiLocale::Auto)
+}
+
+/// [`detect_state_explained`] for a UI shown in `locale`.
+pub fn detect_state_explained_in(content: &str, locale: UiLocale) -> (ClaudeState, DetectionTrace) {
+    detect_state_explained_without(content, locale, &[])
+}
+
+/// [`detect_state_explained_in`] with the named [`RULES`] switched off, for
+/// trying out rule changes.
+pub fn detect_state_explained_without(
+    content: &str,
+    locale: UiLocale,
+    disabled: &[String],
+) -> (ClaudeState, DetectionTrace) {
+    let stripped = strip_ansi(content);
+    let content = &*stripped;
+    let mut trace = DetectionTrace {
+        rule: "fallback",
+        matched: None,
+        skipped: vec![],
+        stripped_ansi: matches!(stripped, Cow::Owned(_)),
+        compacting: false,
+    };
+    let state = match detect_base_state(content, locale, disabled, &mut trace) {
+        // "✻ Compacting conversation… (esc to interrupt · 41s)"
+        ClaudeState::Working
+            if bottom_spinner(content).is_some_and(|caps| caps[1].trim_start().starts_with("Compacting")) =>
+        {
+            trace.compacting = true;
+            ClaudeState::Compacting
+        }
+        state => state,
+    };
+    (state, trace)
+}
+
+/// Ported from tcmux parseClaudeStatus. Records the deciding check in
+/// `trace`.
+fn detect_base_state(content: &str, locale: UiLocale, disabled: &[String], trace: &mut DetectionTrace) -> ClaudeState {
     let lines: Vec<&str> = content.split('\n').collect();
-    let last_lines = last_non_empty_lines(&lines, LAST_LINES_COUNT);
+    let last_lines = last_non_empty_lines(&lines, last_lines_count());
     let combined = last_lines.join("\n");
+    let bottom_lines = last_non_empty_lines(&lines, QUESTION_LINES_COUNT);
+
+    // Each check reports the text it matched, if it did.
+    let mut check = |rule: &'static str, matched: Option<&str>| match matched.filter(|_| !disabled.iter().any(|d| d == rule)) {
+        Some(text) => {
+            trace.rule = rule;
+            trace.matched = Some(text.trim().to_string());
+            true
+        }
+        None => {
+            trace.skipped.push(rule);
+            false
+        }
+    };
+    let find = |pattern: &Regex| pattern.find(&combined).map(|m| m.as_str());
+
+    // On exit claude prints how to resume the session, followed by the
+    // shell prompt, in place of its own input box.
+    let bottom = bottom_lines.join("\n");
+    if check("exit_hint", exit_hint_pattern().find(&bottom).map(|m| m.as_str())) {
+        return ClaudeState::NotRunning;
+    }
+
+    // A command waiting on its own prompt keeps the spinner going, but
+    // nothing happens until someone types into the pane.
+    if check("input_prompt", input_prompt_line(content)) {
+        return ClaudeState::Blocked;
+    }
 
     // Running check (highest priority)
     // Format 1: (esc to interrupt · 1m 45s · ...) — time after middle dot
-    if running_pattern().is_match(&combined) {
+    if check("running", find(running_pattern())) {
         return ClaudeState::Working;
     }
 
     // Format 2: (1m 52s · ...) — time at beginning of parentheses
-    if running_pattern_time_first().is_match(&combined) {
+    if check("running_time_first", find(running_pattern_time_first())) {
         return ClaudeState::Working;
     }
 
     // Fallback: "(esc to interrupt)" or "(ctrl+c to interrupt)" — no time
-    if running_fallback_pattern().is_match(&combined) {
+    if check("running_fallback", find(running_fallback_pattern())) {
         return ClaudeState::Working;
     }
 
-    if esc_to_interrupt_end_pattern().is_match(&combined) {
+    if check("esc_to_interrupt_end", find(esc_to_interrupt_end_pattern())) {
         return ClaudeState::Working;
     }
 
     // Catches the initial thinking phase before a timer appears (e.g., "(thinking)")
-    if running_generic_pattern().is_match(&combined) {
+    if check("running_generic", find(running_generic_pattern())) {
         return ClaudeState::Working;
     }
 
-    if is_claude_prompt_line(&lines) {
-        return ClaudeState::Idle;
+    if check("interview", find(interview_pattern())) {
+        return ClaudeState::WaitingForAnswer;
     }
 
-    for &pattern in WAITING_PATTERNS.iter() {
-        if combined.contains(pattern) {
-            return ClaudeState::WaitingForApproval;
-        }
+    // "Interrupted · What should Claude do instead?" merely echoes the user's
+    // own Esc and leaves an ordinary prompt, so it does not count.
+    let question = bottom_lines
+        .iter()
+        .find(|line| free_form_question_pattern().is_match(line) && !line.contains("Interrupted"));
+    if check("free_form_question", question.copied()) {
+        return ClaudeState::WaitingForAnswer;
     }
 
-    if interview_pattern().is_match(&combined) {
+    // An expired or revoked login blocks everything until the user signs in
+    // again; "API Error: 401 ... Please run /login" is that, not an error.
+    if check("login", login_line(content)) {
         return ClaudeState::WaitingForApproval;
     }
 
-    if selection_menu_pattern().is_match(&combined) {
+    // A failed request leaves its error above an ordinary prompt; while
+    // Claude retries, the spinner above has already returned Working.
+    if check("error", error_line(content)) {
+        return ClaudeState::Error;
+    }
+
+    if check("prompt_line", claude_prompt_line(&lines, locale)) {
+        return ClaudeState::Idle;
+    }
+
+    let localized = locale.pattern_sets().iter().flat_map(|set| set.waiting);
+    let waiting = WAITING_PATTERNS.iter().chain(localized).find(|&&pattern| combined.contains(pattern));
+    if check("waiting_text", waiting.copied()) {
         return ClaudeState::WaitingForApproval;
     }
 
-    if idle_pattern().is_match(&combined) {
+    if check("selection_menu", find(selection_menu_pattern())) {
+        return ClaudeState::WaitingForApproval;
+    }
+
+    if check("idle_pattern", find(idle_pattern())) {
         return ClaudeState::Idle;
     }
 
     ClaudeState::Idle // no Unknown state
 }
 
-static WAITING_PATTERNS: &[&str] = &[
-    "Yes, allow once",
-    "Yes, allow always",
-    "Allow once",
-    "Allow always",
-    "❯ Yes",
-    "❯ No",
-    "Do you trust",
-    "Run this command?",
-    "Allow this MCP server",
-    "Continue?",
-    "Proceed?",
-    "Do you want to proceed?",
-    "(Y/n)",
-    "(y/N)",
-    "[Y/n]",
-    "[y/N]",
-];
+/// Confirmation prompts that read the same in every language.
+static WAITING_PATTERNS: &[&str] = &["(Y/n)", "(y/N)", "[Y/n]", "[y/N]"];
+
+/// Prompt texts of one Claude Code display language.
+struct LocalePatterns {
+    /// Approval dialogs and confirmations.
+    waiting: &'static [&'static str],
+    /// Status bar hints shown below the input prompt.
+    status_hints: &'static [&'static str],
+}
+
+const EN_PATTERNS: LocalePatterns = LocalePatterns {
+    waiting: &[
+        "Yes, allow once",
+        "Yes, allow always",
+        "Allow once",
+        "Allow always",
+        "❯ Yes",
+        "❯ No",
+        "Do you trust",
+        "Run this command?",
+        "Allow this MCP server",
+        "Continue?",
+        "Proceed?",
+        "Do you want to proceed?",
+    ],
+    status_hints: &["? for shortcuts"],
+};
+
+const JA_PATTERNS: LocalePatterns = LocalePatterns {
+    waiting: &[
+        "はい、許可する",
+        "はい、常に許可する",
+        "今回のみ許可",
+        "常に許可",
+        "❯ はい",
+        "❯ いいえ",
+        "信頼しますか",
+        "このコマンドを実行しますか？",
+        "このMCPサーバーを許可しますか",
+        "続行しますか？",
+        "実行しますか？",
+        "進めますか？",
+    ],
+    status_hints: &["? でショートカット", "? ショートカット"],
+};
 
 fn running_pattern() -> &'static Regex {
     static P: OnceLock<Regex> = OnceLock::new();
@@ -123,6 +927,161 @@ fn running_generic_pattern() -> &'static Regex {
     })
 }
 
+fn spinner_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^\s*[✢✽✶✻·]\s+([^…]+?)…\s*(?:\(([^)]*)\))?").unwrap())
+}
+
+fn file_question_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| {
+        Regex::new(r"Do you want to (make this edit to|create) (.+?)\?").unwrap()
+    })
+}
+
+/// A tool call of an MCP server: `⏺ github - create_issue (MCP)(title: "x")`.
+fn mcp_call_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^\s*[⏺●]\s+(.+?) \(MCP\)\((.*)\)\s*$").unwrap())
+}
+
+fn trust_dialog_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"Do you trust the files in this folder\?|Is this a project you created or one you trust").unwrap())
+}
+
+fn tool_call_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^\s*[⏺●]\s+([\w:-]+)\((.+)\)\s*$").unwrap())
+}
+
+fn error_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| {
+        Regex::new(concat!(
+            r"^\s*(?:[⎿⏺●]\s*)?(?:",
+            r"API Error\b",
+            r"|Claude (?:AI )?usage limit reached",
+            r"|\d+-hour limit reached",
+            r"|(?:Weekly|Opus) limit reached",
+            r"|Rate limited\b",
+            r"|Unable to connect to API",
+            r"|Connection error\.",
+            r"|Request timed out\.",
+            r")",
+        ))
+        .unwrap()
+    })
+}
+
+fn login_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| {
+        Regex::new(concat!(
+            r"Please run /login\b",
+            r"|Select login method:",
+            r"|Browser didn't open\? Use the url below to sign in",
+            r"|Paste code here if prompted",
+        ))
+        .unwrap()
+    })
+}
+
+/// Tool names of subagent calls; custom agents are recognised by their
+/// nested tool uses instead.
+static SUBAGENT_TOOLS: &[&str] = &["Task", "Agent", "Explore", "Plan", "general-purpose"];
+
+/// A nested tool call or the `+3 more tool uses` line of a subagent block.
+fn subagent_progress_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| {
+        Regex::new(r"^\s+(?:⎿\s+)?(?:[\w:-]+\(.*\)\s*$|\+\d+ more tool uses?\b)").unwrap()
+    })
+}
+
+fn queued_message_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^[>❯]\s+\S").unwrap())
+}
+
+fn turn_summary_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^[✻✳✶✢✽·*]\s+\p{L}+ for ((?:\d+[hms]\s*)+)$").unwrap())
+}
+
+fn interrupted_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^\s*⎿\s+Interrupted\b").unwrap())
+}
+
+/// The last line of a finished or stopped subagent block.
+fn subagent_result_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^\s+⎿\s+(?:Done \(|Interrupted\b|Error\b)").unwrap())
+}
+
+/// A todo item: a done (`☒`, `✔`, `✓`) or open (`☐`, `◻`, `◼`, `□`)
+/// marker, possibly after the `⎿` of an `Update Todos` call.
+fn todo_item_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^\s*(?:⎿\s*)?([☒✔✓☐◻◼□])\s+\S").unwrap())
+}
+
+/// The warning `claude --dangerously-skip-permissions` shows at startup.
+fn bypass_banner_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"(?m)running in Bypass Permissions mode|^\W*Bypassing Permissions\W*$").unwrap())
+}
+
+/// `⏸ plan mode on (shift+tab to cycle)`, `⏵⏵ accept edits on`,
+/// `⏵⏵ bypass permissions on`.
+fn permission_mode_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^\s*(⏸|⏵⏵)\s*(.*)$").unwrap())
+}
+
+/// Prompts of commands that read from the terminal, at the start of a
+/// tool output line: `sudo` and `su` passwords, SSH passphrases and host
+/// key confirmations, git credentials, and "press a key" pauses.
+fn input_prompt_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| {
+        Regex::new(concat!(
+            r"^\s*(?:⎿\s*)?(?:",
+            r"\[sudo\] password for [^:]+:",
+            r"|(?:[Pp]assword|Passphrase)(?: for [^:]+)?:\s*$",
+            r"|Enter passphrase for (?:key )?'[^']*':",
+            r"|Are you sure you want to continue connecting \(yes/no",
+            r"|Username for 'https?://[^']*':",
+            r"|Press (?:any key|ENTER|RETURN|Enter|Return) to continue",
+            r")",
+        ))
+        .unwrap()
+    })
+}
+
+/// CSI (`ESC [ 1;32 m`), OSC (`ESC ] 8;;url BEL`, terminated by BEL or
+/// `ESC \`), charset selection (`ESC ( B`), and other two-byte escapes.
+fn ansi_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| {
+        Regex::new(r"\x1b(?:\[[0-?]*[ -/]*[@-~]|\][^\x07\x1b]*(?:\x07|\x1b\\)?|[()][0-9A-Za-z]|[@-Z\\-_])").unwrap()
+    })
+}
+
+/// `[Opus 4.6] Context: 37%`, `Context left until auto-compact: 12%`.
+fn context_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| {
+        Regex::new(r"\[([^\[\]]+)\]\s*Context:\s*(\d+)%|Context left until auto-compact:\s*(\d+)%").unwrap()
+    })
+}
+
+fn tokens_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^[↓↑]\s*(\d+(?:\.\d+)?)([km])?\s+tokens$").unwrap())
+}
+
 fn selection_menu_pattern() -> &'static Regex {
     static P: OnceLock<Regex> = OnceLock::new();
     P.get_or_init(|| {
@@ -151,6 +1110,20 @@ fn interview_pattern() -> &'static Regex {
     })
 }
 
+fn exit_hint_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| {
+        Regex::new(r"(?m)^\s*claude --resume [0-9a-f-]{8,}\s*$").unwrap()
+    })
+}
+
+fn free_form_question_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| {
+        Regex::new(r"What should Claude do instead\?|Tell Claude what to do differently").unwrap()
+    })
+}
+
 fn last_non_empty_lines<'a>(lines: &[&'a str], n: usize) -> Vec<&'a str> {
     let mut result = Vec::new();
     for &line in lines.iter().rev() {
@@ -175,9 +1148,11 @@ fn is_separator_line(line: &str) -> bool {
     line.chars().all(|c| ('\u{2500}'..='\u{257F}').contains(&c))
 }
 
-fn is_claude_prompt_line(lines: &[&str]) -> bool {
+/// Claude's own `❯` input line, looking past the status bar below it.
+fn claude_prompt_line<'a>(lines: &[&'a str], locale: UiLocale) -> Option<&'a str> {
     let sel = selection_menu_pattern();
     let file_changes = file_changes_pattern();
+    let sets = locale.pattern_sets();
 
     for &line in lines.iter().rev() {
         let trimmed = line.trim();
@@ -192,7 +1167,7 @@ fn is_claude_prompt_line(lines: &[&str]) -> bool {
         // Skip status bar lines that appear below the ❯ prompt.
         // These are not user content and should be ignored when searching
         // upward for the prompt line.
-        if trimmed.contains("? for shortcuts")
+        if sets.iter().flat_map(|set| set.status_hints).any(|hint| trimmed.contains(hint))
             || trimmed.contains("ctrl+")
             || trimmed.contains("shift+")
             || file_changes.is_match(trimmed)
@@ -206,21 +1181,21 @@ fn is_claude_prompt_line(lines: &[&str]) -> bool {
 
         if trimmed.starts_with('❯') {
             if sel.is_match(trimmed) {
-                return false; // "❯ 1. Yes" style selection menu
+                return None; // "❯ 1. Yes" style selection menu
             }
             // ❯-prefixed waiting patterns (e.g., "❯ Yes", "❯ No") are not prompts
-            for &pattern in WAITING_PATTERNS.iter() {
+            for &pattern in sets.iter().flat_map(|set| set.waiting) {
                 if pattern.starts_with('❯') && trimmed == pattern {
-                    return false;
+                    return None;
                 }
             }
-            return true;
+            return Some(line);
         }
 
-        return false;
+        return None;
     }
 
-    false
+    None
 }
 
 #[cfg(test)]
@@ -0,0 +1,110 @@
+use std::io::IsTerminal;
+
+/// Whether CLI output is colored, from `--color`.
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when writing to a terminal that supports it and `NO_COLOR` is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Whether to color output going to a terminal (`is_terminal`), with the
+/// environment read through `var`. `NO_COLOR` (any non-empty value) and
+/// `TERM=dumb` turn automatic color off; `--color always` wins over both.
+pub fn enabled(choice: ColorChoice, is_terminal: bool, var: impl Fn(&str) -> Option<String>) -> bool {
+    match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            is_terminal
+                && var("NO_COLOR").is_none_or(|v| v.is_empty())
+                && var("TERM").is_none_or(|term| term != "dumb")
+        }
+    }
+}
+
+/// Whether to color what this process writes to stdout.
+pub fn stdout_enabled(choice: ColorChoice) -> bool {
+    enabled(choice, std::io::stdout().is_terminal(), |name| std::env::var(name).ok())
+}
+
+/// SGR code of a state, by its [`crate::status::state_key`] (or `unread`),
+/// in the hues of the overlay; `None` for idle, which stays uncolored.
+fn state_code(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "running" => "32",
+        "compacting" => "35",
+        "approval" => "33",
+        "answer" => "36",
+        "blocked" => "95",
+        "error" => "31",
+        "unread" => "34",
+        "not_running" => "2",
+        _ => return None,
+    })
+}
+
+/// The same hues as tmux colour names, for `#[fg=...]`.
+fn tmux_colour(key: &str) -> Option<&'static str> {
+    Some(match key {
+        "running" => "green",
+        "compacting" => "magenta",
+        "approval" => "yellow",
+        "answer" => "cyan",
+        "blocked" => "brightmagenta",
+        "error" => "red",
+        "unread" => "blue",
+        "not_running" => "colour244",
+        _ => return None,
+    })
+}
+
+pub fn paint(text: &str, code: &str) -> String {
+    format!("\x1b[{code}m{text}\x1b[0m")
+}
+
+/// `text` in the color of the state `key`.
+pub fn state(text: &str, key: &str) -> String {
+    match state_code(key) {
+        Some(code) => paint(text, code),
+        None => text.to_string(),
+    }
+}
+
+/// A [`crate::status::summary_line`] with each count in its state's color.
+pub fn summary(line: &str) -> String {
+    map_segments(line, state)
+}
+
+/// A [`crate::status::summary_line`] with tmux style codes coloring each
+/// count, for `status-right "#(claudeye status --tmux)"`.
+pub fn tmux_summary(line: &str) -> String {
+    map_segments(line, |segment, key| match tmux_colour(key) {
+        Some(colour) => format!("#[fg={colour}]{segment}#[fg=default]"),
+        None => segment.to_string(),
+    })
+}
+
+/// Each count of a summary line through `paint`, with the state key (or
+/// `unread`) its symbol stands for.
+fn map_segments(line: &str, paint: impl Fn(&str, &str) -> String) -> String {
+    line.split(' ')
+        .map(|segment| {
+            let key = match segment.chars().next() {
+                Some('!') => "error",
+                Some('⌨') => "blocked",
+                Some('⚠') => "approval",
+                Some('?') => "answer",
+                Some('▶') => "running",
+                Some('◌') => "compacting",
+                Some('●') => "unread",
+                Some('✕') => "not_running",
+                _ => "",
+            };
+            paint(segment, key)
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,226 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::actions::ClickConfig;
+use crate::api::ApiConfig;
+use crate::elapsed::ElapsedFormat;
+use crate::forge::PullRequestConfig;
+use crate::guardrail::Guardrail;
+use crate::i18n::{LabelOverrides, Language, Strings};
+use crate::journal::TransitionLogConfig;
+use crate::monitor::{MonitorConfig, NameFormat};
+use crate::notify::NotifyConfig;
+use crate::paths;
+use crate::watch::WatchRule;
+use crate::redact::RedactionConfig;
+use crate::rename::TmuxRename;
+use crate::stats::StatsConfig;
+use crate::team::TeamConfig;
+use crate::webhook::Webhook;
+
+/// User settings read from `$XDG_CONFIG_HOME/claudeye/config.toml`
+/// (falling back to `~/.config/claudeye/config.toml`).
+///
+/// Every field is optional; a missing file yields the defaults.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Language of UI labels (`en` or `ja`).
+    pub language: Language,
+    /// Overlay position (e.g. `bottom-right`) used when `--position` is not
+    /// given.
+    pub position: Option<String>,
+    /// Send desktop notifications for watch rules and guardrails.
+    pub notifications: bool,
+    /// How repeated and simultaneous notifications are held back.
+    pub notify: NotifyConfig,
+    /// Custom text shown for each state instead of the language default.
+    pub labels: LabelOverrides,
+    /// When and how precisely elapsed time is shown.
+    pub elapsed: ElapsedFormat,
+    /// Shrink reviewed Idle sessions to a minimal row in the overlay.
+    pub collapse_reviewed: bool,
+    /// Show each session's total runtime next to the state elapsed time.
+    pub show_age: bool,
+    /// How sessions are named: `project` (`myapp`) or `tmux`
+    /// (`work/api ▸ myapp`).
+    pub name_format: NameFormat,
+    /// Show the model and context left in the overlay as well as the picker.
+    pub show_context: bool,
+    /// Wrap overlay bubbles onto two lines within `narrow_width` pixels, for
+    /// a thin strip along a screen edge.
+    pub narrow: bool,
+    /// Overlay width in narrow mode.
+    pub narrow_width: u32,
+    /// Show a single traffic light for all sessions instead of rows.
+    pub light: bool,
+    /// Screen edge (`top`, `bottom`, `left`, `right`) to dock the overlay to
+    /// as a thin strip of state colors, used when `--strip` is not given.
+    pub strip: Option<String>,
+    /// Length of a timed snooze (picker `Z`, notification button).
+    pub snooze_minutes: u64,
+    /// Row actions run by double- and middle-clicking an overlay row in
+    /// `--interactive` mode.
+    pub clicks: ClickConfig,
+    /// Zoom a pane (`resize-pane -Z`) when jumping to it, as `--zoom` does.
+    pub zoom_on_jump: bool,
+    /// Ring the bell of a pane that starts waiting for approval or input,
+    /// so tmux highlights its window in the status bar.
+    pub tmux_bell: bool,
+    /// Append a state mark to the window name or pane title of each tmux
+    /// session (`myapp ✅`): `off`, `window`, or `pane`.
+    pub tmux_rename: TmuxRename,
+    /// Write `status.json`/`status.txt` to the state directory on every
+    /// change, for editor statuslines and shell prompts.
+    pub status_file: bool,
+    /// Polling behaviour.
+    pub monitor: MonitorConfig,
+    /// Show the open PR/MR of each session's branch.
+    pub pull_requests: PullRequestConfig,
+    /// Regex rules raising a badge (and optionally a notification) when
+    /// they match a pane's content.
+    pub watch: Vec<WatchRule>,
+    /// Patterns over pending approval prompts and touched paths that raise
+    /// an alarm and block `approve`.
+    pub guardrail: Vec<Guardrail>,
+    /// Shell command run on every state transition, with `{pane}`,
+    /// `{project}`, `{from}`, `{to}`, ... filled in.
+    pub on_transition: Option<String>,
+    /// Endpoints receiving every state transition.
+    pub webhook: Vec<Webhook>,
+    /// Append-only log of state transitions in the data directory.
+    pub transition_log: TransitionLogConfig,
+    /// Transitions and turns recorded for `claudeye stats`.
+    pub stats: StatsConfig,
+    /// Masking of secrets and personal data in everything sent out.
+    pub redaction: RedactionConfig,
+    /// Opt-in anonymized statistics sent to a team's `claudeye serve`.
+    pub team: TeamConfig,
+    /// HTTP API served by `claudeye serve`.
+    pub api: ApiConfig,
+    /// Named sets of settings applied over the rest with `--profile`.
+    pub profiles: BTreeMap<String, toml::Table>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Invalid(toml::de::Error),
+    UnknownProfile(String),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Invalid(e) => write!(f, "{e}"),
+            ConfigError::UnknownProfile(name) => write!(f, "unknown profile {name:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            language: Language::default(),
+            position: None,
+            notifications: true,
+            notify: NotifyConfig::default(),
+            labels: LabelOverrides::default(),
+            elapsed: ElapsedFormat::default(),
+            collapse_reviewed: false,
+            show_age: false,
+            name_format: NameFormat::default(),
+            show_context: false,
+            narrow: false,
+            narrow_width: 240,
+            light: false,
+            strip: None,
+            snooze_minutes: 30,
+            clicks: ClickConfig::default(),
+            zoom_on_jump: false,
+            tmux_bell: false,
+            tmux_rename: TmuxRename::Off,
+            status_file: false,
+            monitor: MonitorConfig::default(),
+            pull_requests: PullRequestConfig::default(),
+            watch: vec![],
+            guardrail: vec![],
+            on_transition: None,
+            webhook: vec![],
+            transition_log: TransitionLogConfig::default(),
+            stats: StatsConfig::default(),
+            redaction: RedactionConfig::default(),
+            team: TeamConfig::default(),
+            api: ApiConfig::default(),
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+impl Config {
+    pub fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    /// Parse `text` with the settings of `[profiles.<profile>]` merged over
+    /// it: tables merge key by key, anything else is replaced.
+    pub fn parse_with_profile(text: &str, profile: Option<&str>) -> Result<Self, ConfigError> {
+        let Some(name) = profile else {
+            return Self::parse(text).map_err(ConfigError::Invalid);
+        };
+        let mut table: toml::Table = toml::from_str(text).map_err(ConfigError::Invalid)?;
+        let overrides = table
+            .get("profiles")
+            .and_then(|profiles| profiles.get(name))
+            .and_then(toml::Value::as_table)
+            .cloned()
+            .ok_or_else(|| ConfigError::UnknownProfile(name.to_string()))?;
+        merge(&mut table, overrides);
+        toml::Value::Table(table).try_into().map_err(ConfigError::Invalid)
+    }
+
+    /// UI strings for the configured language with label overrides applied.
+    pub fn strings(&self) -> Strings {
+        self.language.strings().clone().with_overrides(&self.labels)
+    }
+}
+
+fn merge(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(value)) => merge(base, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    Some(paths::config_dir()?.join("config.toml"))
+}
+
+/// Load the config file with `profile` applied, falling back to defaults
+/// when it is missing. A malformed file is reported on stderr rather than
+/// aborting, so a typo never takes the overlay down; only an unknown
+/// profile is an error.
+pub fn load(profile: Option<&str>) -> Result<Config, ConfigError> {
+    let text = config_path().and_then(|path| Some((std::fs::read_to_string(&path).ok()?, path)));
+    let Some((text, path)) = text else {
+        return match profile {
+            Some(name) => Err(ConfigError::UnknownProfile(name.to_string())),
+            None => Ok(Config::default()),
+        };
+    };
+    match Config::parse_with_profile(&text, profile) {
+        Ok(config) => Ok(config),
+        Err(e @ ConfigError::UnknownProfile(_)) => Err(e),
+        Err(e) => {
+            crate::log!("ignoring invalid config {}: {e}", path.display());
+            Ok(Config::default())
+        }
+    }
+}
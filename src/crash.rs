@@ -0,0 +1,149 @@
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::elapsed;
+use crate::monitor::ClaudeSession;
+use crate::paths;
+use crate::redact::redact;
+use crate::status::state_key;
+
+/// Log lines kept for the crash report.
+pub const LOG_CAPACITY: usize = 50;
+
+static LOG: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+static SESSIONS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Print a `[claudeye]` message to stderr and keep it for crash reports.
+#[macro_export]
+macro_rules! log {
+    ($($arg:tt)*) => {
+        $crate::crash::record_log(format!($($arg)*))
+    };
+}
+
+/// What [`log!`] expands to.
+pub fn record_log(message: String) {
+    let message = redact(&message);
+    eprintln!("[claudeye] {message}");
+    let mut log = lock(&LOG);
+    if log.len() == LOG_CAPACITY {
+        log.pop_front();
+    }
+    log.push_back(format!("{} {message}", elapsed::unix_now()));
+}
+
+/// The kept log lines, oldest first.
+pub fn recent_log() -> Vec<String> {
+    lock(&LOG).iter().cloned().collect()
+}
+
+/// Remember the latest poll result for crash reports.
+pub fn record_sessions(sessions: &[ClaudeSession]) {
+    *lock(&SESSIONS) = sessions.iter().map(session_line).collect();
+}
+
+/// One session in a crash report: pane, state, and the last component of
+/// its directory. Pending commands and errors are left out since they can
+/// carry arbitrary paths.
+pub fn session_line(session: &ClaudeSession) -> String {
+    format!(
+        "{}\t{}\t{}",
+        session.pane.id,
+        state_key(&session.state),
+        redact_path(&session.pane.cwd)
+    )
+}
+
+/// `/home/me/src/api` -> `…/api`.
+pub fn redact_path(path: &str) -> String {
+    match path.trim_end_matches('/').rsplit_once('/') {
+        Some((_, last)) => format!("…/{last}"),
+        None => path.to_string(),
+    }
+}
+
+/// `text` with the home directory replaced by `~`.
+pub fn redact_home(text: &str, home: &str) -> String {
+    if home.len() <= 1 {
+        return text.to_string();
+    }
+    text.replace(home, "~")
+}
+
+pub fn render_report(panic: &str, backtrace: &str, log: &[String], sessions: &[String]) -> String {
+    let mut report = format!(
+        "claudeye {} crash report\n\n{panic}\n\nbacktrace:\n{backtrace}\n\nrecent log:\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    for line in log {
+        report.push_str(line);
+        report.push('\n');
+    }
+    report.push_str("\nsessions:\n");
+    for line in sessions {
+        report.push_str(line);
+        report.push('\n');
+    }
+    report
+}
+
+/// Where the last crash report is written.
+pub fn report_path() -> Option<PathBuf> {
+    Some(paths::state_dir()?.join("crash-report.txt"))
+}
+
+/// Write a crash report on panic, after the default message, and say where
+/// it went.
+pub fn install_hook() {
+    let default = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        default(info);
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .copied()
+            .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str))
+            .unwrap_or("(no message)");
+        let location = info.location().map_or_else(String::new, |l| format!(" at {l}"));
+        let thread = std::thread::current();
+        let panic = format!(
+            "thread '{}' panicked{location}:\n{message}",
+            thread.name().unwrap_or("<unnamed>")
+        );
+        let report = render_report(
+            &panic,
+            &Backtrace::force_capture().to_string(),
+            &recent_log(),
+            &lock(&SESSIONS),
+        );
+        let home = std::env::var("HOME").unwrap_or_default();
+        let Some(path) = report_path() else {
+            return;
+        };
+        let written = path
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(&path, redact(&redact_home(&report, &home)).as_bytes()));
+        match written {
+            Ok(()) => eprintln!(
+                "[claudeye] crash report written to {}; print it with `claudeye report-crash`",
+                path.display()
+            ),
+            Err(e) => eprintln!("[claudeye] failed to write crash report to {}: {e}", path.display()),
+        }
+    }));
+}
+
+/// Print the last crash report.
+pub fn print_report() -> Result<(), String> {
+    let path = report_path().ok_or("cannot locate the state directory (HOME unset)")?;
+    let report = std::fs::read_to_string(&path).map_err(|_| format!("no crash report at {}", path.display()))?;
+    print!("{report}");
+    Ok(())
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
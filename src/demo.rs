@@ -0,0 +1,123 @@
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::claude_state::{ApprovalKind, ClaudeState, PendingApproval, Subagents};
+use crate::monitor::ClaudeSession;
+use crate::tmux::PaneInfo;
+
+/// Projects the demo sessions pretend to work on.
+const PROJECTS: &[&str] = &["webshop", "api-gateway", "ml-pipeline", "docs", "mobile-app", "infra", "billing", "search"];
+
+/// One part of the turn every demo session plays over and over.
+struct Step {
+    state: ClaudeState,
+    secs: u64,
+    activity: Option<&'static str>,
+}
+
+const fn step(state: ClaudeState, secs: u64, activity: Option<&'static str>) -> Step {
+    Step { state, secs, activity }
+}
+
+const SCRIPT: &[Step] = &[
+    step(ClaudeState::Working, 12, Some("Thinking")),
+    step(ClaudeState::WaitingForApproval, 8, None),
+    step(ClaudeState::Working, 10, Some("Editing files")),
+    step(ClaudeState::Compacting, 4, None),
+    step(ClaudeState::Working, 6, Some("Running tests")),
+    step(ClaudeState::WaitingForAnswer, 6, None),
+    step(ClaudeState::Working, 8, Some("Writing summary")),
+    step(ClaudeState::Idle, 15, None),
+];
+
+/// What the demo sessions ask approval for, in turn.
+const APPROVALS: &[(&str, &str, ApprovalKind)] = &[
+    ("Bash", "npm test -- --watch=false", ApprovalKind::BashCommand),
+    ("Edit", "src/handlers/cart.rs", ApprovalKind::FileEdit),
+    ("github - create_issue (MCP)", "title: \"Flaky checkout test\"", ApprovalKind::McpTool),
+    ("Bash", "git push origin feature/search", ApprovalKind::BashCommand),
+];
+
+/// Every fourth turn of a session goes wrong instead of waiting for an
+/// answer: an API error or a command stuck at a password prompt.
+const MISHAPS: &[ClaudeState] = &[ClaudeState::Error, ClaudeState::Blocked];
+
+static DEMO: OnceLock<(usize, Instant)> = OnceLock::new();
+
+/// Show `count` made-up sessions instead of polling tmux from now on, for
+/// `claudeye demo`.
+pub fn start(count: usize) {
+    let _ = DEMO.set((count, Instant::now()));
+}
+
+/// The demo sessions as of now, or `None` outside `claudeye demo`.
+pub fn current() -> Option<Vec<ClaudeSession>> {
+    let (count, started) = DEMO.get()?;
+    Some(sessions(*count, *started, Instant::now()))
+}
+
+/// `count` sessions `now`, each playing [`SCRIPT`] since `started` at its
+/// own pace and from its own point, so they rarely change state together.
+pub fn sessions(count: usize, started: Instant, now: Instant) -> Vec<ClaudeSession> {
+    (0..count).map(|i| session(i, started, now)).collect()
+}
+
+fn session(i: usize, started: Instant, now: Instant) -> ClaudeSession {
+    // Tenths of a second, so that paces stay whole numbers.
+    let pace = 10 + (i as u64 % 4) * 2;
+    let turn: u64 = SCRIPT.iter().map(|s| s.secs * pace).sum();
+    let t = now.saturating_duration_since(started).as_millis() as u64 / 100 + i as u64 * 70;
+    let round = t / turn;
+    let mut into = t % turn;
+    let mut n = 0;
+    while into >= SCRIPT[n].secs * pace {
+        into -= SCRIPT[n].secs * pace;
+        n += 1;
+    }
+    let current = &SCRIPT[n];
+    let in_step = Duration::from_millis(into * 100);
+    let state = match current.state {
+        ClaudeState::WaitingForAnswer if round % 4 == 3 => MISHAPS[(i + round as usize) % MISHAPS.len()].clone(),
+        ref state => state.clone(),
+    };
+    let project = PROJECTS[i % PROJECTS.len()];
+    let cwd = format!("/home/demo/{project}");
+    let pane = PaneInfo {
+        id: format!("demo:{}.0", i + 1),
+        pid: 0,
+        cwd,
+        project_name: if i < PROJECTS.len() { project.to_string() } else { format!("{project}-{}", i / PROJECTS.len() + 1) },
+        session_name: "demo".to_string(),
+        window_name: project.to_string(),
+    };
+    let changed_at = now.checked_sub(in_step).unwrap_or(started).max(started);
+    let mut session = ClaudeSession::new(pane, state.clone(), changed_at);
+    session.first_seen = started;
+    session.started_at = started;
+    session.model = Some(if i.is_multiple_of(3) { "Opus" } else { "Sonnet" }.to_string());
+    session.context_left = Some(90u8.saturating_sub(((round * 8 + n as u64 * 2) % 80) as u8));
+    session.content_hash = round * SCRIPT.len() as u64 + n as u64;
+    match state {
+        ClaudeState::Working => {
+            session.activity = current.activity.map(String::from);
+            session.timer = Some(in_step);
+            session.tokens = Some(in_step.as_secs() * 45);
+            session.todo_progress = Some((n as u32 / 2, 4));
+            if i % 2 == 1 && n == 2 {
+                session.subagents = Some(Subagents { running: 2, active: "Explore: Find the config loader".to_string() });
+            }
+        }
+        ClaudeState::WaitingForApproval => {
+            let (tool, detail, kind) = APPROVALS[(i + round as usize) % APPROVALS.len()];
+            session.pending = Some(PendingApproval { tool: tool.to_string(), detail: detail.to_string(), kind });
+        }
+        ClaudeState::Error => session.error = Some("API Error: 529 Overloaded".to_string()),
+        ClaudeState::Blocked => session.input_prompt = Some("[sudo] password for demo:".to_string()),
+        ClaudeState::Idle => {
+            session.unread = true;
+            session.last_turn_duration = Some(Duration::from_secs(SCRIPT[..n].iter().map(|s| s.secs * pace / 10).sum()));
+        }
+        _ => {}
+    }
+    session
+}
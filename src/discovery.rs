@@ -0,0 +1,103 @@
+use std::net::{IpAddr, SocketAddr};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+
+use crate::api::ApiConfig;
+
+/// DNS-SD service type advertised by `claudeye serve`.
+pub const SERVICE_TYPE: &str = "_claudeye._tcp.local.";
+
+/// A `claudeye serve` instance found on the local network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Instance {
+    /// Instance name, the advertising machine's host name.
+    pub name: String,
+    pub addresses: Vec<IpAddr>,
+    pub port: u16,
+    pub tls: bool,
+    pub read_only: bool,
+}
+
+impl Instance {
+    /// Base URL of the API, preferring an IPv4 address.
+    pub fn url(&self) -> Option<String> {
+        let scheme = if self.tls { "https" } else { "http" };
+        let addr = self.addresses.iter().find(|a| a.is_ipv4()).or(self.addresses.first())?;
+        Some(format!("{scheme}://{}", SocketAddr::new(*addr, self.port)))
+    }
+}
+
+/// TXT record of an advertised instance: whether it speaks TLS and whether
+/// its action endpoints are disabled. The token is never advertised.
+pub fn txt_properties(config: &ApiConfig) -> Vec<(&'static str, String)> {
+    let flag = |on: bool| if on { "1" } else { "0" }.to_string();
+    vec![
+        ("version", env!("CARGO_PKG_VERSION").to_string()),
+        ("tls", flag(config.tls_cert.is_some())),
+        ("ro", flag(config.read_only)),
+    ]
+}
+
+/// Announce the API on the local network. Returns the daemon, which keeps
+/// answering queries until dropped, or `None` when mDNS is unavailable.
+pub fn advertise(config: &ApiConfig, addr: SocketAddr) -> Option<ServiceDaemon> {
+    let daemon = ServiceDaemon::new()
+        .map_err(|e| crate::log!("mDNS unavailable: {e}"))
+        .ok()?;
+    let name = host_name();
+    let host = format!("{name}.local.");
+    let info = if addr.ip().is_unspecified() {
+        ServiceInfo::new(SERVICE_TYPE, &name, &host, "", addr.port(), txt_properties(config).as_slice())
+            .map(ServiceInfo::enable_addr_auto)
+    } else {
+        ServiceInfo::new(SERVICE_TYPE, &name, &host, addr.ip(), addr.port(), txt_properties(config).as_slice())
+    };
+    match info.and_then(|info| daemon.register(info)) {
+        Ok(()) => Some(daemon),
+        Err(e) => {
+            crate::log!("mDNS advertisement failed: {e}");
+            None
+        }
+    }
+}
+
+/// Browse the local network for `timeout` and return the instances found.
+pub fn discover(timeout: Duration) -> Result<Vec<Instance>, String> {
+    let daemon = ServiceDaemon::new().map_err(|e| format!("mDNS unavailable: {e}"))?;
+    let events = daemon.browse(SERVICE_TYPE).map_err(|e| format!("mDNS browse failed: {e}"))?;
+    let deadline = Instant::now() + timeout;
+    let mut found: Vec<Instance> = vec![];
+    while let Some(left) = deadline.checked_duration_since(Instant::now()) {
+        let Ok(event) = events.recv_timeout(left) else {
+            break;
+        };
+        if let ServiceEvent::ServiceResolved(service) = event {
+            let name = service.fullname.trim_end_matches(SERVICE_TYPE).trim_end_matches('.');
+            let instance = Instance {
+                name: name.to_string(),
+                addresses: service.addresses.iter().map(|a| a.to_ip_addr()).collect(),
+                port: service.port,
+                tls: service.txt_properties.get_property_val_str("tls") == Some("1"),
+                read_only: service.txt_properties.get_property_val_str("ro") == Some("1"),
+            };
+            found.retain(|i| i.name != instance.name);
+            found.push(instance);
+        }
+    }
+    let _ = daemon.shutdown();
+    found.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(found)
+}
+
+fn host_name() -> String {
+    Command::new("hostname")
+        .arg("-s")
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| String::from_utf8_lossy(&out.stdout).trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "claudeye".to_string())
+}
@@ -0,0 +1,66 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Deserialize;
+
+/// Thresholds controlling how a session's elapsed time is rendered, from the
+/// `[elapsed]` config table. The defaults keep the plain `33s` display.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ElapsedFormat {
+    /// Hide the elapsed time entirely while it is below this many seconds.
+    pub hide_below_secs: u64,
+    /// Show whole minutes (`12m`) from this many seconds on.
+    pub minutes_from_secs: Option<u64>,
+    /// Show hours and minutes (`3h 12m`) from this many seconds on.
+    pub hours_from_secs: Option<u64>,
+}
+
+impl ElapsedFormat {
+    /// Returns `None` when the elapsed time should not be shown at all.
+    pub fn format(&self, secs: u64) -> Option<String> {
+        if secs < self.hide_below_secs {
+            return None;
+        }
+        if self.hours_from_secs.is_some_and(|t| secs >= t) {
+            return Some(format!("{}h {}m", secs / 3600, secs % 3600 / 60));
+        }
+        if self.minutes_from_secs.is_some_and(|t| secs >= t) {
+            return Some(format!("{}m", secs / 60));
+        }
+        Some(format!("{secs}s"))
+    }
+
+    /// Whether the rendered value changes every second at `secs`, i.e. the
+    /// display needs a once-per-second repaint to stay accurate.
+    pub fn ticks_every_second(&self, secs: u64) -> bool {
+        self.format(secs).is_some_and(|text| text.ends_with('s'))
+    }
+
+    /// Widest value this format can produce, used to keep the overlay width stable.
+    pub fn placeholder(&self) -> &'static str {
+        if self.hours_from_secs.is_some() {
+            "99h 59m"
+        } else {
+            "9999s"
+        }
+    }
+}
+
+/// Compact human duration such as `42s`, `12m`, or `3h 5m`.
+pub fn compact(secs: u64) -> String {
+    if secs < 60 {
+        format!("{secs}s")
+    } else if secs < 3600 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}h {}m", secs / 3600, secs % 3600 / 60)
+    }
+}
+
+/// Seconds since the Unix epoch, for timestamps shared between processes.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
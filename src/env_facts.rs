@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::git;
+
+/// How long collected facts are reused; `git status` on a large tree is
+/// too slow to run on every redraw.
+const CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// Facts about a session's working directory that help decide whether to
+/// approve what it asks for.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EnvFacts {
+    /// `node --version` (`v20.11.0`) for a Node project.
+    pub node: Option<String>,
+    /// Python version (`3.12.1`) for a Python project, preferring the
+    /// project's `.venv`.
+    pub python: Option<String>,
+    /// Files with uncommitted changes; `None` outside a git repository.
+    pub dirty_files: Option<usize>,
+}
+
+impl EnvFacts {
+    pub fn has_uncommitted_changes(&self) -> bool {
+        self.dirty_files.is_some_and(|n| n > 0)
+    }
+}
+
+/// Which runtimes the project at `dir` uses, judging by its marker files.
+pub fn runtimes(dir: &Path) -> (bool, bool) {
+    let any = |names: &[&str]| names.iter().any(|name| dir.join(name).exists());
+    let node = any(&["package.json", ".nvmrc", ".node-version"]);
+    let python = any(&["pyproject.toml", "requirements.txt", "setup.py", ".python-version"]);
+    (node, python)
+}
+
+/// The version in `node --version` or `python --version` output.
+pub fn parse_version(output: &str) -> Option<String> {
+    let version = output.trim().trim_start_matches("Python").trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+fn version(program: &Path, cwd: &str) -> Option<String> {
+    // Run in the project so version managers (nvm, pyenv, asdf) pick the
+    // version it pins.
+    let output = Command::new(program).arg("--version").current_dir(cwd).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    // Python 2 printed its version to stderr.
+    let text = if output.stdout.is_empty() { &output.stderr } else { &output.stdout };
+    parse_version(&String::from_utf8_lossy(text))
+}
+
+fn env_cache() -> &'static Mutex<HashMap<String, (Instant, EnvFacts)>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, (Instant, EnvFacts)>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Facts for `cwd`, reused for a few seconds.
+pub fn collect(cwd: &str) -> EnvFacts {
+    if let Ok(cache) = env_cache().lock()
+        && let Some((at, facts)) = cache.get(cwd)
+        && at.elapsed() < CACHE_TTL
+    {
+        return facts.clone();
+    }
+    let dir = Path::new(cwd);
+    let (node, python) = runtimes(dir);
+    let venv_python = dir.join(".venv/bin/python");
+    let facts = EnvFacts {
+        node: node.then(|| version(Path::new("node"), cwd)).flatten(),
+        python: python
+            .then(|| {
+                let program = if venv_python.exists() { venv_python.as_path() } else { Path::new("python3") };
+                version(program, cwd)
+            })
+            .flatten(),
+        dirty_files: git::dirty_file_count(cwd),
+    };
+    if let Ok(mut cache) = env_cache().lock() {
+        cache.insert(cwd.to_string(), (Instant::now(), facts.clone()));
+    }
+    facts
+}
@@ -0,0 +1,66 @@
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+/// Most files remembered per session.
+pub const MAX_TOUCHED_FILES: usize = 20;
+
+fn edit_tool_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| {
+        // Tool call headers such as "⏺ Update(src/main.rs)" or "● Write(/tmp/x)"
+        Regex::new(r"(?m)^\s*[⏺●]\s*(?:Edit|MultiEdit|Write|Update|Create|NotebookEdit)\(([^)\n]+)\)").unwrap()
+    })
+}
+
+/// Paths of files edited or written by tool calls in `content`, oldest
+/// first, each listed once at its most recent position.
+pub fn touched_files(content: &str) -> Vec<String> {
+    let seen: Vec<String> = edit_tool_pattern()
+        .captures_iter(content)
+        .map(|c| c[1].trim().to_string())
+        .collect();
+    merge_touched(&[], &seen)
+}
+
+/// Append `seen` (oldest first) to `prev`, moving files seen again to the
+/// end and keeping at most [`MAX_TOUCHED_FILES`] of the most recent.
+pub fn merge_touched(prev: &[String], seen: &[String]) -> Vec<String> {
+    let mut merged: Vec<String> = prev.to_vec();
+    for file in seen {
+        merged.retain(|f| f != file);
+        merged.push(file.clone());
+    }
+    let excess = merged.len().saturating_sub(MAX_TOUCHED_FILES);
+    merged.drain(..excess);
+    merged
+}
+
+/// Whether `path`, as written by the agent, points outside the session's
+/// working directory (absolute elsewhere, `~`, or escaping via `..`).
+pub fn is_outside(cwd: &str, path: &str) -> bool {
+    let expanded = match path.strip_prefix("~/") {
+        Some(rest) => match std::env::var_os("HOME") {
+            Some(home) => PathBuf::from(home).join(rest),
+            None => return true,
+        },
+        None => PathBuf::from(path),
+    };
+    !normalize(&Path::new(cwd).join(expanded)).starts_with(normalize(Path::new(cwd)))
+}
+
+/// Resolve `.` and `..` lexically, without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                result.pop();
+            }
+            Component::CurDir => {}
+            other => result.push(other),
+        }
+    }
+    result
+}
@@ -0,0 +1,64 @@
+use serde::Deserialize;
+
+use crate::tmux::PaneInfo;
+
+/// One `[[monitor.include]]` or `[[monitor.exclude]]` rule, matching a pane
+/// when every field it sets matches. Fields are globs: `*` and `?` stay
+/// within a path segment, `**` crosses them.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PaneRule {
+    /// tmux session name (`scratch*`).
+    pub session: Option<String>,
+    /// Working directory (`~/src/noisy`), which also covers the directories
+    /// below it.
+    pub path: Option<String>,
+    /// Pane id as claudeye shows it (`main:0.1`, `main:2.*`).
+    pub pane: Option<String>,
+}
+
+impl PaneRule {
+    pub fn matches(&self, pane: &PaneInfo) -> bool {
+        self.session.as_deref().is_none_or(|session| glob_match(session, &pane.session_name))
+            && self.path.as_deref().is_none_or(|path| path_matches(path, &pane.cwd))
+            && self.pane.as_deref().is_none_or(|id| glob_match(id, &pane.id))
+    }
+}
+
+/// Whether `pane` is monitored: it matches one of `include` (when there are
+/// any) and none of `exclude`.
+pub fn is_monitored(pane: &PaneInfo, include: &[PaneRule], exclude: &[PaneRule]) -> bool {
+    (include.is_empty() || include.iter().any(|rule| rule.matches(pane)))
+        && !exclude.iter().any(|rule| rule.matches(pane))
+}
+
+/// Whether `cwd` is a directory matching `pattern` or below one; a leading
+/// `~/` stands for the home directory.
+pub fn path_matches(pattern: &str, cwd: &str) -> bool {
+    let pattern = match (pattern.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{}/{rest}", home.trim_end_matches('/')),
+        _ => pattern.to_string(),
+    };
+    let pattern = pattern.trim_end_matches('/');
+    glob_match(pattern, cwd) || glob_match(&format!("{pattern}/**"), cwd)
+}
+
+/// Whether all of `text` matches the glob `pattern`.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    glob_chars(&pattern, &text)
+}
+
+fn glob_chars(pattern: &[char], text: &[char]) -> bool {
+    match pattern {
+        [] => text.is_empty(),
+        ['*', '*', rest @ ..] => (0..=text.len()).any(|i| glob_chars(rest, &text[i..])),
+        ['*', rest @ ..] => {
+            let segment = text.iter().position(|&c| c == '/').unwrap_or(text.len());
+            (0..=segment).any(|i| glob_chars(rest, &text[i..]))
+        }
+        ['?', rest @ ..] => text.first().is_some_and(|&c| c != '/') && glob_chars(rest, &text[1..]),
+        [c, rest @ ..] => text.first() == Some(c) && glob_chars(rest, &text[1..]),
+    }
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::claude_state::detect_state_explained;
+use crate::status::state_key;
+
+/// A captured pane and the state it should be detected as. A corpus
+/// directory holds one subdirectory per state key (as in `status.json`),
+/// each with `.txt` captures: `approval/bash-dialog.txt`.
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    pub path: PathBuf,
+    /// State key taken from the subdirectory name.
+    pub expected: String,
+    pub content: String,
+}
+
+/// A fixture detected as another state than expected.
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub path: PathBuf,
+    pub expected: String,
+    pub detected: &'static str,
+    /// Which rule decided, as printed by `claudeye detect --explain`.
+    pub trace: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}: expected {}, detected {}", self.path.display(), self.expected, self.detected)?;
+        write!(f, "{}", self.trace.trim_end())
+    }
+}
+
+/// Every fixture under `dir`, sorted by path.
+pub fn load(dir: &Path) -> io::Result<Vec<Fixture>> {
+    let mut fixtures = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let state_dir = entry?.path();
+        if !state_dir.is_dir() {
+            continue;
+        }
+        let Some(expected) = state_dir.file_name().and_then(|n| n.to_str()).map(str::to_string) else {
+            continue;
+        };
+        for file in std::fs::read_dir(&state_dir)? {
+            let path = file?.path();
+            if path.extension().is_some_and(|ext| ext == "txt") {
+                let content = std::fs::read_to_string(&path)?;
+                fixtures.push(Fixture { path, expected: expected.clone(), content });
+            }
+        }
+    }
+    fixtures.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(fixtures)
+}
+
+/// Run detection on a fixture, returning what went wrong if anything.
+pub fn check(fixture: &Fixture) -> Option<Mismatch> {
+    let (state, trace) = detect_state_explained(&fixture.content);
+    let detected = state_key(&state);
+    (detected != fixture.expected).then(|| Mismatch {
+        path: fixture.path.clone(),
+        expected: fixture.expected.clone(),
+        detected,
+        trace: trace.render(),
+    })
+}
+
+/// Check every fixture under `dir`.
+pub fn check_dir(dir: &Path) -> io::Result<Vec<Mismatch>> {
+    Ok(load(dir)?.iter().filter_map(check).collect())
+}
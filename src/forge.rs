@@ -0,0 +1,312 @@
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::mpsc::Sender;
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::claude_state::ClaudeState;
+use crate::elapsed;
+use crate::monitor::{Transition, TransitionHook};
+use crate::notify::Alert;
+use crate::redact::redact;
+
+/// Settings from the `[pull_requests]` config table.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PullRequestConfig {
+    /// Look up the open PR/MR of each session's branch via `gh` or `glab`.
+    pub enabled: bool,
+    /// How long a lookup result is reused before querying again.
+    pub refresh_secs: u64,
+    /// What to do when a running session finishes on a branch with an open PR.
+    pub on_finish: OnFinish,
+}
+
+impl Default for PullRequestConfig {
+    fn default() -> Self {
+        Self { enabled: false, refresh_secs: 120, on_finish: OnFinish::Off }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OnFinish {
+    #[default]
+    Off,
+    /// Desktop notification with the PR link.
+    Notify,
+    /// Post a short status comment on the PR.
+    Comment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChecksStatus {
+    /// No CI checks reported.
+    None,
+    Pending,
+    Passing,
+    Failing,
+}
+
+/// The open pull request (GitHub) or merge request (GitLab) of a branch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PullRequest {
+    pub number: u64,
+    pub url: String,
+    pub checks: ChecksStatus,
+}
+
+impl PullRequest {
+    /// Short label such as `#123 ✓`.
+    pub fn label(&self) -> String {
+        let symbol = match self.checks {
+            ChecksStatus::None => "",
+            ChecksStatus::Pending => " ●",
+            ChecksStatus::Passing => " ✓",
+            ChecksStatus::Failing => " ✗",
+        };
+        format!("#{}{symbol}", self.number)
+    }
+}
+
+/// Parse `gh pr view --json number,url,state,statusCheckRollup` output.
+/// Closed and merged PRs are ignored.
+pub fn parse_gh_pr(json: &str) -> Option<PullRequest> {
+    let v: Value = serde_json::from_str(json).ok()?;
+    if v["state"].as_str()? != "OPEN" {
+        return None;
+    }
+    let mut checks = ChecksStatus::None;
+    for check in v["statusCheckRollup"].as_array().into_iter().flatten() {
+        // CheckRun entries report `status`/`conclusion`, StatusContext entries `state`.
+        let result = check["conclusion"]
+            .as_str()
+            .filter(|c| !c.is_empty())
+            .or_else(|| check["state"].as_str());
+        let status = match result {
+            Some("SUCCESS" | "NEUTRAL" | "SKIPPED") => ChecksStatus::Passing,
+            Some("PENDING" | "EXPECTED") | None => ChecksStatus::Pending,
+            Some(_) => ChecksStatus::Failing,
+        };
+        checks = combine(checks, status);
+    }
+    Some(PullRequest {
+        number: v["number"].as_u64()?,
+        url: v["url"].as_str()?.to_string(),
+        checks,
+    })
+}
+
+/// Parse `glab mr view --output json` output. Closed and merged MRs are
+/// ignored.
+pub fn parse_glab_mr(json: &str) -> Option<PullRequest> {
+    let v: Value = serde_json::from_str(json).ok()?;
+    if v["state"].as_str()? != "opened" {
+        return None;
+    }
+    let pipeline = if v["head_pipeline"].is_object() { &v["head_pipeline"] } else { &v["pipeline"] };
+    let checks = match pipeline["status"].as_str() {
+        None => ChecksStatus::None,
+        Some("success" | "skipped") => ChecksStatus::Passing,
+        Some("failed" | "canceled") => ChecksStatus::Failing,
+        Some(_) => ChecksStatus::Pending,
+    };
+    Some(PullRequest {
+        number: v["iid"].as_u64()?,
+        url: v["web_url"].as_str()?.to_string(),
+        checks,
+    })
+}
+
+/// Any failure wins, then anything still running; passing needs all to pass.
+fn combine(a: ChecksStatus, b: ChecksStatus) -> ChecksStatus {
+    use ChecksStatus::*;
+    match (a, b) {
+        (Failing, _) | (_, Failing) => Failing,
+        (Pending, _) | (_, Pending) => Pending,
+        _ => Passing,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Forge {
+    GitHub,
+    GitLab,
+}
+
+/// The forge hosting the repository at `cwd`, judged from its `origin`
+/// remote: GitLab when the URL mentions it, GitHub otherwise.
+fn forge_for(cwd: &str) -> Option<Forge> {
+    let remote = Command::new("git")
+        .args(["-C", cwd, "remote", "get-url", "origin"])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    if String::from_utf8_lossy(&remote.stdout).contains("gitlab") {
+        Some(Forge::GitLab)
+    } else {
+        Some(Forge::GitHub)
+    }
+}
+
+/// Query the forge CLI (`gh` or `glab`) for the open PR of the branch checked
+/// out at `cwd`. Blocks for the duration of a network request.
+pub fn lookup(cwd: &str) -> Option<PullRequest> {
+    match forge_for(cwd)? {
+        Forge::GitLab => {
+            let output = Command::new("glab")
+                .args(["mr", "view", "--output", "json"])
+                .current_dir(cwd)
+                .output()
+                .ok()?;
+            parse_glab_mr(&String::from_utf8_lossy(&output.stdout))
+        }
+        Forge::GitHub => {
+            let output = Command::new("gh")
+                .args(["pr", "view", "--json", "number,url,state,statusCheckRollup"])
+                .current_dir(cwd)
+                .output()
+                .ok()?;
+            parse_gh_pr(&String::from_utf8_lossy(&output.stdout))
+        }
+    }
+}
+
+/// Post `body` as a comment on the PR/MR `number` of the repository at `cwd`.
+fn post_comment(cwd: &str, number: u64, body: &str) -> Result<(), String> {
+    let number = number.to_string();
+    let mut command = match forge_for(cwd).ok_or("not a git repository with an origin")? {
+        Forge::GitHub => {
+            let mut c = Command::new("gh");
+            c.args(["pr", "comment", &number, "--body", body]);
+            c
+        }
+        Forge::GitLab => {
+            let mut c = Command::new("glab");
+            c.args(["mr", "note", &number, "--message", body]);
+            c
+        }
+    };
+    let output = command.current_dir(cwd).output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+struct CacheEntry {
+    fetched_at: Option<Instant>,
+    in_flight: bool,
+    pr: Option<PullRequest>,
+}
+
+fn pr_cache() -> &'static Mutex<HashMap<String, CacheEntry>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, CacheEntry>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Last known PR for `cwd`, refreshing it on a background thread once it is
+/// older than `refresh` so that polling never waits on the network. Returns
+/// `None` until the first lookup finishes.
+pub fn cached(cwd: &str, refresh: Duration) -> Option<PullRequest> {
+    let mut cache = pr_cache().lock().ok()?;
+    let entry = cache.entry(cwd.to_string()).or_insert(CacheEntry {
+        fetched_at: None,
+        in_flight: false,
+        pr: None,
+    });
+    let stale = entry.fetched_at.is_none_or(|t| t.elapsed() >= refresh);
+    if stale && !entry.in_flight {
+        entry.in_flight = true;
+        let cwd = cwd.to_string();
+        thread::spawn(move || {
+            let pr = lookup(&cwd);
+            if let Ok(mut cache) = pr_cache().lock()
+                && let Some(entry) = cache.get_mut(&cwd)
+            {
+                entry.pr = pr;
+                entry.fetched_at = Some(Instant::now());
+                entry.in_flight = false;
+            }
+        });
+    }
+    entry.pr.clone()
+}
+
+/// Look up several repositories at once, in parallel, for one-shot commands
+/// such as the picker that cannot wait for the background cache to fill.
+pub fn lookup_all<'a>(cwds: impl IntoIterator<Item = &'a str>) -> HashMap<String, PullRequest> {
+    let mut unique: Vec<&str> = cwds.into_iter().collect();
+    unique.sort_unstable();
+    unique.dedup();
+    thread::scope(|scope| {
+        let handles: Vec<_> = unique
+            .into_iter()
+            .map(|cwd| (cwd, scope.spawn(move || lookup(cwd))))
+            .collect();
+        handles
+            .into_iter()
+            .filter_map(|(cwd, h)| Some((cwd.to_string(), h.join().ok()??)))
+            .collect()
+    })
+}
+
+/// Text of the comment posted when a session finishes.
+pub fn finish_comment(transition: &Transition) -> String {
+    format!(
+        "Claude session in `{}` finished after {} (posted by claudeye).",
+        transition.pane.project_name,
+        elapsed::compact(transition.elapsed.as_secs())
+    )
+}
+
+/// The `on_finish = "notify"` alert for `pr`, whose session finished.
+pub fn finish_alert(transition: &Transition, pr: &PullRequest) -> Alert {
+    Alert {
+        pane: Some(transition.pane.id.clone()),
+        project: transition.pane.project_name.clone(),
+        kind: "pr_finished".to_string(),
+        title: format!("{} finished", transition.pane.project_name),
+        body: format!("{} {}", pr.label(), pr.url),
+        urgent: false,
+        snooze: false,
+    }
+}
+
+/// Transition hook implementing `on_finish`: when a Working run ends in Idle
+/// on a branch with an open PR, send an alert with its link to `alerts` or
+/// comment on it. The lookup runs on its own thread so polling is not held
+/// up by the network.
+pub fn finish_hook(config: &PullRequestConfig, alerts: Sender<Alert>) -> Option<TransitionHook> {
+    let mode = config.on_finish;
+    if mode == OnFinish::Off {
+        return None;
+    }
+    Some(Box::new(move |t: &Transition| {
+        if t.from != ClaudeState::Working || t.to != ClaudeState::Idle {
+            return;
+        }
+        let t = t.clone();
+        let alerts = alerts.clone();
+        thread::spawn(move || {
+            let Some(pr) = lookup(&t.pane.cwd) else {
+                return;
+            };
+            match mode {
+                OnFinish::Notify => {
+                    let _ = alerts.send(finish_alert(&t, &pr));
+                }
+                OnFinish::Comment => {
+                    if let Err(e) = post_comment(&t.pane.cwd, pr.number, &redact(&finish_comment(&t))) {
+                        crate::log!("failed to comment on {}: {e}", pr.url);
+                    }
+                }
+                OnFinish::Off => {}
+            }
+        });
+    }))
+}
@@ -0,0 +1,222 @@
+use std::str::FromStr;
+
+use serde_json::Value;
+use unicode_width::UnicodeWidthStr;
+
+/// How a data-producing subcommand (`status`, `list`, `stats`) prints what it
+/// found, from `--format`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Format {
+    /// The subcommand's own human-readable output
+    Plain,
+    Json,
+    Yaml,
+    /// One aligned column per field of a row
+    Table,
+    /// One line per row, with `{field}` replaced by the row's value
+    Template(String),
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(Format::Plain),
+            "json" => Ok(Format::Json),
+            "yaml" => Ok(Format::Yaml),
+            "table" => Ok(Format::Table),
+            _ if s.contains('{') => Ok(Format::Template(s.to_string())),
+            _ => Err(format!(
+                "unknown format `{s}`; use plain, json, yaml, table, or a template such as '{{pane}} {{state}}'"
+            )),
+        }
+    }
+}
+
+impl Format {
+    /// `document` as JSON or YAML, or its `rows` (objects with the same keys)
+    /// as a table or through the template; `plain` is only called for
+    /// [`Format::Plain`].
+    pub fn render(&self, document: &Value, rows: &[Value], plain: impl FnOnce() -> String) -> Result<String, String> {
+        Ok(match self {
+            Format::Plain => plain(),
+            Format::Json => format!("{}\n", serde_json::to_string_pretty(document).map_err(|e| e.to_string())?),
+            Format::Yaml => yaml(document),
+            Format::Table => table(rows),
+            Format::Template(template) => {
+                let mut out = String::new();
+                for row in rows {
+                    out.push_str(&fill(template, row)?);
+                    out.push('\n');
+                }
+                out
+            }
+        })
+    }
+}
+
+/// `value` as a YAML document, in block style.
+pub fn yaml(value: &Value) -> String {
+    if is_block(value) {
+        let mut out = String::new();
+        write_block(&mut out, value, 0);
+        out
+    } else {
+        format!("{}\n", yaml_scalar(value))
+    }
+}
+
+/// Arrays and objects with something in them, which take lines of their own.
+fn is_block(value: &Value) -> bool {
+    match value {
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(fields) => !fields.is_empty(),
+        _ => false,
+    }
+}
+
+fn write_block(out: &mut String, value: &Value, indent: usize) {
+    let pad = " ".repeat(indent);
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                out.push_str(&format!("{pad}{}:", yaml_string(key)));
+                write_child(out, field, indent);
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                out.push_str(&format!("{pad}-"));
+                if is_block(item) {
+                    // The item's first line goes after the dash, so it is
+                    // written as if indented past it and then pulled up.
+                    let mut nested = String::new();
+                    write_block(&mut nested, item, indent + 2);
+                    out.push(' ');
+                    out.push_str(&nested[indent + 2..]);
+                } else {
+                    out.push_str(&format!(" {}\n", yaml_scalar(item)));
+                }
+            }
+        }
+        _ => out.push_str(&format!("{pad}{}\n", yaml_scalar(value))),
+    }
+}
+
+fn write_child(out: &mut String, value: &Value, indent: usize) {
+    if is_block(value) {
+        out.push('\n');
+        write_block(out, value, indent + 2);
+    } else {
+        out.push_str(&format!(" {}\n", yaml_scalar(value)));
+    }
+}
+
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "null".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => yaml_string(s),
+        Value::Array(_) => "[]".to_string(),
+        Value::Object(_) => "{}".to_string(),
+    }
+}
+
+/// `s` bare when YAML would read it back as the same string, otherwise
+/// double-quoted (JSON's escapes are valid YAML).
+fn yaml_string(s: &str) -> String {
+    let plain = s.chars().next().is_some_and(|c| c.is_alphanumeric() || "_./".contains(c))
+        && s.chars().all(|c| c.is_alphanumeric() || " _./@:-".contains(c))
+        && !s.ends_with([' ', ':'])
+        && !s.contains(": ")
+        && !matches!(
+            s.to_ascii_lowercase().as_str(),
+            "true" | "false" | "null" | "yes" | "no" | "on" | "off" | "y" | "n"
+        )
+        && s.parse::<f64>().is_err();
+    if plain { s.to_string() } else { Value::String(s.to_string()).to_string() }
+}
+
+/// A value as one table cell or template field: strings unquoted, null
+/// empty, and lists comma-separated.
+pub fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(cell).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+/// `rows` under a header of the first row's keys, columns padded to their
+/// widest cell and numbers aligned right.
+pub fn table(rows: &[Value]) -> String {
+    let Some(Value::Object(first)) = rows.first() else {
+        return String::new();
+    };
+    let keys: Vec<&String> = first.keys().collect();
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| keys.iter().map(|key| row.get(key.as_str()).map(cell).unwrap_or_default()).collect())
+        .collect();
+    let widths: Vec<usize> = keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| cells.iter().map(|row| row[i].width()).chain([key.width()]).max().unwrap_or(0))
+        .collect();
+    let numeric: Vec<bool> = keys
+        .iter()
+        .map(|key| rows.iter().all(|row| row.get(key.as_str()).is_some_and(Value::is_number)))
+        .collect();
+    let header: Vec<String> = keys.iter().map(|key| key.to_string()).collect();
+    std::iter::once(&header)
+        .chain(&cells)
+        .map(|line| {
+            let padded: Vec<String> = line
+                .iter()
+                .enumerate()
+                .map(|(i, text)| {
+                    let fill = " ".repeat(widths[i] - text.width());
+                    if numeric[i] { format!("{fill}{text}") } else { format!("{text}{fill}") }
+                })
+                .collect();
+            format!("{}\n", padded.join("  ").trim_end())
+        })
+        .collect()
+}
+
+/// `template` with each `{field}` replaced by that field of `row`; `{{` and
+/// `}}` stand for literal braces.
+pub fn fill(template: &str, row: &Value) -> Result<String, String> {
+    let mut out = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '{' => {
+                let name: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                match row.get(name.trim()) {
+                    Some(value) => out.push_str(&cell(value)),
+                    None => {
+                        let fields = row.as_object().map(|o| o.keys().cloned().collect::<Vec<_>>().join(", "));
+                        return Err(format!(
+                            "unknown field `{name}` in the template; fields are {}",
+                            fields.unwrap_or_default()
+                        ));
+                    }
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    Ok(out)
+}
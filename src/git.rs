@@ -0,0 +1,83 @@
+use std::path::Path;
+use std::process::Command;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+/// Summary of uncommitted changes in the repository at `cwd`, as printed by
+/// `git diff --shortstat HEAD` (e.g. "3 files changed, 20 insertions(+)").
+///
+/// Returns an empty string for a clean tree and `None` when `cwd` is not
+/// inside a git repository or git is unavailable.
+pub fn diff_shortstat(cwd: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["-C", cwd, "diff", "--shortstat", "HEAD"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Number of files with uncommitted changes (including untracked ones) in
+/// the repository at `cwd`, from `git status --porcelain`.
+///
+/// Returns `None` when `cwd` is not inside a git repository or git is
+/// unavailable.
+pub fn dirty_file_count(cwd: &str) -> Option<usize> {
+    let output = Command::new("git")
+        .args(["-C", cwd, "status", "--porcelain"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(porcelain_count(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Entries in `git status --porcelain` output.
+pub fn porcelain_count(output: &str) -> usize {
+    output.lines().filter(|line| !line.trim().is_empty()).count()
+}
+
+/// The git subcommand in `command` that can throw away uncommitted work
+/// (`checkout`, `switch`, `reset`, `rebase`, `restore`, `clean`), if any.
+pub fn discarding_operation(command: &str) -> Option<&str> {
+    discarding_pattern().captures(command).and_then(|caps| caps.get(1)).map(|m| m.as_str())
+}
+
+fn discarding_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| {
+        Regex::new(r"\bgit\s+(?:-C\s+\S+\s+)?(checkout|switch|reset|rebase|restore|clean)\b").unwrap()
+    })
+}
+
+/// The branch checked out in the repository at `cwd`, or the short commit
+/// when detached. Read from `HEAD` without running git, so that it is cheap
+/// enough to look up on every capture.
+pub fn branch(cwd: &str) -> Option<String> {
+    let git = Path::new(cwd).ancestors().map(|dir| dir.join(".git")).find(|path| path.exists())?;
+    // Worktrees and submodules have a `.git` file pointing at the real one.
+    let dir = if git.is_file() {
+        let text = std::fs::read_to_string(&git).ok()?;
+        git.parent()?.join(text.strip_prefix("gitdir:")?.trim())
+    } else {
+        git
+    };
+    parse_head(&std::fs::read_to_string(dir.join("HEAD")).ok()?)
+}
+
+/// The branch named by the contents of a `HEAD` file, or its first seven
+/// hex digits when it holds a commit.
+pub fn parse_head(text: &str) -> Option<String> {
+    let text = text.trim();
+    match text.strip_prefix("ref:") {
+        Some(name) => {
+            let name = name.trim();
+            Some(name.strip_prefix("refs/heads/").unwrap_or(name).to_string())
+        }
+        None => text.get(..7).filter(|hash| hash.chars().all(|c| c.is_ascii_hexdigit())).map(String::from),
+    }
+}
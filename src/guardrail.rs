@@ -0,0 +1,92 @@
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::claude_state::ClaudeState;
+use crate::monitor::ClaudeSession;
+use crate::tmux::PaneInfo;
+
+/// Lines from the bottom of the pane searched for the pending approval
+/// prompt, matching how far state detection looks.
+const PROMPT_LINES: usize = 30;
+
+/// What a guardrail pattern is matched against.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Target {
+    /// The approval prompt of a session waiting for approval.
+    Command,
+    /// Paths of files the session edited or wrote.
+    Path,
+    /// Both of the above.
+    #[default]
+    Any,
+}
+
+/// A `[[guardrail]]` rule. A match raises an alarm that `approve` refuses
+/// to act on without `--force`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawGuardrail")]
+pub struct Guardrail {
+    pub name: String,
+    pub pattern: Regex,
+    pub target: Target,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawGuardrail {
+    name: String,
+    pattern: String,
+    #[serde(default)]
+    target: Target,
+}
+
+impl TryFrom<RawGuardrail> for Guardrail {
+    type Error = regex::Error;
+
+    fn try_from(raw: RawGuardrail) -> Result<Self, Self::Error> {
+        Ok(Self {
+            pattern: Regex::new(&raw.pattern)?,
+            name: raw.name,
+            target: raw.target,
+        })
+    }
+}
+
+/// The bottom of the pane, where a pending approval prompt shows the
+/// command or edit awaiting approval.
+pub fn pending_prompt(content: &str) -> String {
+    let lines: Vec<&str> = content.trim_end().lines().collect();
+    lines[lines.len().saturating_sub(PROMPT_LINES)..].join("\n")
+}
+
+/// Names of the guardrails triggered by a session, in rule order. Command
+/// rules only apply while the session is waiting for approval.
+pub fn check(rules: &[Guardrail], state: &ClaudeState, content: &str, touched_files: &[String]) -> Vec<String> {
+    let prompt = (*state == ClaudeState::WaitingForApproval).then(|| pending_prompt(content));
+    rules
+        .iter()
+        .filter(|rule| {
+            let command_hit = rule.target != Target::Path
+                && prompt.as_deref().is_some_and(|p| rule.pattern.is_match(p));
+            let path_hit =
+                rule.target != Target::Command && touched_files.iter().any(|f| rule.pattern.is_match(f));
+            command_hit || path_hit
+        })
+        .map(|rule| rule.name.clone())
+        .collect()
+}
+
+/// Guardrail hits present in `updated` but not for the same pane in `prev`.
+pub fn new_hits(prev: &[ClaudeSession], updated: &[ClaudeSession]) -> Vec<(PaneInfo, String)> {
+    let mut result = vec![];
+    for session in updated.iter().filter(|s| s.is_live()) {
+        let old = prev.iter().find(|p| p.pane.id == session.pane.id);
+        for name in &session.guardrail_hits {
+            if !old.is_some_and(|o| o.guardrail_hits.contains(name)) {
+                result.push((session.pane.clone(), name.clone()));
+            }
+        }
+    }
+    result
+}
@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+use serde::{Deserialize, Serialize};
+
+use crate::api::ApiConfig;
+use crate::elapsed;
+
+/// A poll older than this means the polling loop is wedged: several missed
+/// intervals, with room for a slow `tmux` or `git` call.
+pub const STALE_AFTER_SECS: u64 = 30;
+
+/// A source failing for this long is lost rather than hiccuping, e.g. a
+/// tmux server restarting between two polls.
+pub const LOST_AFTER_SECS: u64 = 10;
+
+/// How long after a restart of the polling loop the overlay says so.
+pub const RESTART_NOTICE_SECS: u64 = 60;
+
+/// What the polling loop last reported about itself.
+#[derive(Debug, Default)]
+struct Heartbeat {
+    last_poll: Option<u64>,
+    sessions: usize,
+    /// Times a poll panicked and polling was restarted.
+    restarts: u32,
+    last_restart: Option<u64>,
+    /// Error of each source's latest call, `None` when it succeeded.
+    sources: BTreeMap<String, Option<String>>,
+    /// Unix time each failing source started failing.
+    failing_since: BTreeMap<String, u64>,
+}
+
+fn heartbeat() -> &'static Mutex<Heartbeat> {
+    static HEARTBEAT: OnceLock<Mutex<Heartbeat>> = OnceLock::new();
+    HEARTBEAT.get_or_init(|| Mutex::new(Heartbeat::default()))
+}
+
+/// Note a finished poll that saw `sessions` sessions.
+pub fn record_poll(sessions: usize, now: u64) {
+    let mut heartbeat = heartbeat().lock().unwrap_or_else(|e| e.into_inner());
+    heartbeat.last_poll = Some(now);
+    heartbeat.sessions = sessions;
+}
+
+/// Note that a poll panicked and polling starts over.
+pub fn record_restart(now: u64) {
+    let mut heartbeat = heartbeat().lock().unwrap_or_else(|e| e.into_inner());
+    heartbeat.restarts += 1;
+    heartbeat.last_restart = Some(now);
+}
+
+/// Whether polling was restarted within [`RESTART_NOTICE_SECS`] of `now`.
+pub fn restarted_recently(now: u64) -> bool {
+    let heartbeat = heartbeat().lock().unwrap_or_else(|e| e.into_inner());
+    heartbeat.last_restart.is_some_and(|at| now.saturating_sub(at) < RESTART_NOTICE_SECS)
+}
+
+/// Note the outcome of a call to `source` (`tmux`, `ssh devbox`).
+pub fn record_source(source: &str, result: Result<(), String>) {
+    let mut heartbeat = heartbeat().lock().unwrap_or_else(|e| e.into_inner());
+    match &result {
+        Ok(()) => {
+            heartbeat.failing_since.remove(source);
+        }
+        Err(_) => {
+            heartbeat.failing_since.entry(source.to_string()).or_insert_with(elapsed::unix_now);
+        }
+    }
+    heartbeat.sources.insert(source.to_string(), result.err());
+}
+
+/// The error of the latest call to `source`, `None` when it succeeded or
+/// was never made.
+pub fn source_error(source: &str) -> Option<String> {
+    let heartbeat = heartbeat().lock().unwrap_or_else(|e| e.into_inner());
+    heartbeat.sources.get(source).cloned().flatten()
+}
+
+/// The error of `source` when it has failed every call for at least
+/// [`LOST_AFTER_SECS`] up to `now`.
+pub fn lost(source: &str, now: u64) -> Option<String> {
+    let heartbeat = heartbeat().lock().unwrap_or_else(|e| e.into_inner());
+    let since = *heartbeat.failing_since.get(source)?;
+    if now.saturating_sub(since) < LOST_AFTER_SECS {
+        return None;
+    }
+    heartbeat.sources.get(source).cloned().flatten()
+}
+
+/// The `GET /healthz` body and `claudeye ping` result.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Health {
+    /// A poll finished within [`STALE_AFTER_SECS`] and every source answered.
+    pub ok: bool,
+    /// Unix time of the last finished poll; none before the first.
+    pub last_poll: Option<u64>,
+    pub poll_age_secs: Option<u64>,
+    /// Live sessions seen by the last poll.
+    pub sessions: usize,
+    pub sources: BTreeMap<String, SourceStatus>,
+    /// Times polling was restarted after a poll panicked.
+    #[serde(default)]
+    pub restarts: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceStatus {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Health {
+    pub fn new(last_poll: Option<u64>, sessions: usize, sources: BTreeMap<String, SourceStatus>, now: u64) -> Self {
+        let poll_age_secs = last_poll.map(|at| now.saturating_sub(at));
+        Self {
+            ok: poll_age_secs.is_some_and(|age| age <= STALE_AFTER_SECS) && sources.values().all(|s| s.ok),
+            last_poll,
+            poll_age_secs,
+            sessions,
+            sources,
+            restarts: 0,
+        }
+    }
+
+    /// Health of the polling loop in this process.
+    pub fn current() -> Self {
+        let heartbeat = heartbeat().lock().unwrap_or_else(|e| e.into_inner());
+        let sources = heartbeat
+            .sources
+            .iter()
+            .map(|(name, error)| (name.to_string(), SourceStatus { ok: error.is_none(), error: error.clone() }))
+            .collect();
+        let health = Self::new(heartbeat.last_poll, heartbeat.sessions, sources, elapsed::unix_now());
+        Self { restarts: heartbeat.restarts, ..health }
+    }
+
+    /// One line for `claudeye ping`, e.g. `ok: polled 2s ago, 3 sessions, tmux ok`.
+    pub fn summary(&self) -> String {
+        let polled = match self.poll_age_secs {
+            Some(age) => format!("polled {} ago", elapsed::compact(age)),
+            None => "not polled yet".to_string(),
+        };
+        let mut parts = vec![polled, format!("{} sessions", self.sessions)];
+        match self.restarts {
+            0 => {}
+            1 => parts.push("restarted once".to_string()),
+            n => parts.push(format!("restarted {n} times")),
+        }
+        for (name, source) in &self.sources {
+            match &source.error {
+                Some(error) => parts.push(format!("{name}: {error}")),
+                None => parts.push(format!("{name} ok")),
+            }
+        }
+        format!("{}: {}", if self.ok { "ok" } else { "unhealthy" }, parts.join(", "))
+    }
+}
+
+/// `/healthz` of the API configured by `config`, reached over loopback when
+/// it listens on every address.
+pub fn healthz_url(config: &ApiConfig) -> Result<String, String> {
+    api_url(config, "/healthz")
+}
+
+/// `path` on the API configured by `config`, as for [`healthz_url`].
+pub fn api_url(config: &ApiConfig, path: &str) -> Result<String, String> {
+    let mut addr: SocketAddr = config
+        .bind
+        .parse()
+        .map_err(|e| format!("invalid api bind address {:?}: {e}", config.bind))?;
+    if addr.ip().is_unspecified() {
+        addr.set_ip(match addr.ip() {
+            IpAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+            IpAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+        });
+    }
+    let scheme = if config.tls_cert.is_some() { "https" } else { "http" };
+    Ok(format!("{scheme}://{addr}{path}"))
+}
+
+/// Ask a running `claudeye serve` for its health.
+pub fn ping(url: &str, token: Option<&str>) -> Result<Health, String> {
+    let mut command = Command::new("curl");
+    command.args(["-sS", "--max-time", "5"]);
+    if let Some(token) = token.filter(|t| !t.is_empty()) {
+        command.args(["-H", &format!("Authorization: Bearer {token}")]);
+    }
+    let output = command.arg(url).output().map_err(|e| format!("curl: {e}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    parse_response(&output.stdout)
+}
+
+/// A `/healthz` body, or the error the API answered with instead.
+pub fn parse_response(body: &[u8]) -> Result<Health, String> {
+    if let Ok(health) = serde_json::from_slice::<Health>(body) {
+        return Ok(health);
+    }
+    #[derive(Deserialize)]
+    struct Error {
+        error: String,
+    }
+    match serde_json::from_slice::<Error>(body) {
+        Ok(body) => Err(body.error),
+        Err(_) => Err(format!("unexpected response: {}", String::from_utf8_lossy(body).trim())),
+    }
+}
@@ -0,0 +1,352 @@
+use std::borrow::Cow;
+
+use serde::Deserialize;
+
+use crate::claude_state::{ApprovalKind, ClaudeState, PermissionMode};
+use crate::elapsed;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    #[default]
+    En,
+    Ja,
+}
+
+/// User-facing strings shown by the overlay and the picker.
+#[derive(Debug, Clone)]
+pub struct Strings {
+    pub running: Cow<'static, str>,
+    pub compacting: Cow<'static, str>,
+    pub approval: Cow<'static, str>,
+    pub answer: Cow<'static, str>,
+    pub blocked: Cow<'static, str>,
+    pub idle: Cow<'static, str>,
+    /// Shown instead of `idle` for a session stopped with Esc mid-turn.
+    pub interrupted: Cow<'static, str>,
+    pub error: Cow<'static, str>,
+    pub not_running: Cow<'static, str>,
+    pub no_sessions: Cow<'static, str>,
+    /// Overlay banner while tmux keeps failing; `{error}` is replaced with
+    /// its message.
+    pub tmux_lost: Cow<'static, str>,
+    pub monitoring_restarted: Cow<'static, str>,
+    /// Picker notice after approving or denying; `{pane}` is replaced with
+    /// the pane id.
+    pub approved: Cow<'static, str>,
+    pub denied: Cow<'static, str>,
+    /// Picker notices of `x`; `{pane}` is replaced with the pane id.
+    pub interrupt_confirm: Cow<'static, str>,
+    pub interrupt_sent: Cow<'static, str>,
+    /// Shown instead of `no_sessions` when no tmux server runs.
+    pub no_tmux: Cow<'static, str>,
+    /// Shown instead of `no_sessions` when tmux has no agent panes; `{n}`
+    /// is replaced with its number of sessions.
+    pub no_agent_panes: Cow<'static, str>,
+    /// Shown instead of `no_sessions` when every agent pane runs a tool
+    /// left out of `agents`; `{n}` is replaced with their number.
+    pub agents_filtered: Cow<'static, str>,
+    pub picker_help: Cow<'static, str>,
+    /// Tombstone row text; `{ago}` and `{ran}` are replaced with durations.
+    pub ended: Cow<'static, str>,
+    /// Session age; `{age}` is replaced with a duration.
+    pub age: Cow<'static, str>,
+    /// Length of an Idle session's last turn; `{time}` is replaced with it.
+    pub last_turn: Cow<'static, str>,
+    pub compare_help: Cow<'static, str>,
+    pub no_changes: Cow<'static, str>,
+    pub not_a_repo: Cow<'static, str>,
+    pub touched_files: Cow<'static, str>,
+    pub timeline: Cow<'static, str>,
+    pub time_in_states: Cow<'static, str>,
+    pub no_touched_files: Cow<'static, str>,
+    /// Marker after a touched file outside the session's directory.
+    pub outside_cwd: Cow<'static, str>,
+    /// Running subagent count; `{n}` is replaced with the number.
+    pub subagents: Cow<'static, str>,
+    /// Todo list progress; `{done}` and `{total}` are replaced with counts.
+    pub todos: Cow<'static, str>,
+    /// Messages queued while working; `{n}` is replaced with the count.
+    pub queued: Cow<'static, str>,
+    /// Dirty file count in the detail view; `{n}` is replaced with it.
+    pub uncommitted: Cow<'static, str>,
+    /// Context window left; `{pct}` is replaced with the percentage.
+    pub context: Cow<'static, str>,
+    /// Permission mode badges; the default mode has none.
+    pub plan_mode: Cow<'static, str>,
+    pub accept_edits: Cow<'static, str>,
+    pub bypass_permissions: Cow<'static, str>,
+    /// Warning badge of a session that never asks for approval.
+    pub skips_permissions: Cow<'static, str>,
+    /// Approval kind badges; other approvals have none.
+    pub bash_command: Cow<'static, str>,
+    pub file_edit: Cow<'static, str>,
+    pub mcp_tool: Cow<'static, str>,
+    pub trust_directory: Cow<'static, str>,
+    pub plan_approval: Cow<'static, str>,
+}
+
+/// Per-state label overrides from the `[labels]` config table.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LabelOverrides {
+    pub running: Option<String>,
+    pub compacting: Option<String>,
+    pub approval: Option<String>,
+    pub answer: Option<String>,
+    pub blocked: Option<String>,
+    pub idle: Option<String>,
+    pub interrupted: Option<String>,
+    pub error: Option<String>,
+    pub not_running: Option<String>,
+}
+
+pub static EN: Strings = Strings {
+    running: Cow::Borrowed("Running"),
+    compacting: Cow::Borrowed("Compacting"),
+    approval: Cow::Borrowed("Approval"),
+    answer: Cow::Borrowed("Question"),
+    blocked: Cow::Borrowed("Blocked"),
+    idle: Cow::Borrowed("Idle"),
+    interrupted: Cow::Borrowed("Interrupted"),
+    error: Cow::Borrowed("Error"),
+    not_running: Cow::Borrowed("Exited"),
+    no_sessions: Cow::Borrowed("No Claude sessions found"),
+    tmux_lost: Cow::Borrowed("Cannot reach tmux: {error}"),
+    monitoring_restarted: Cow::Borrowed("Monitoring restarted after an error"),
+    approved: Cow::Borrowed("Approved {pane}"),
+    denied: Cow::Borrowed("Denied {pane}"),
+    interrupt_confirm: Cow::Borrowed("Interrupt {pane}? Press x again to confirm"),
+    interrupt_sent: Cow::Borrowed("Interrupted {pane}"),
+    no_tmux: Cow::Borrowed("tmux is not running — start claude inside tmux"),
+    no_agent_panes: Cow::Borrowed("No Claude panes in tmux ({n} sessions) — run claude in a pane"),
+    agents_filtered: Cow::Borrowed("{n} agent panes hidden — add their tool to `agents` in the config"),
+    picker_help: Cow::Borrowed("1-9: jump  j/k: move  Enter: switch  r: mark reviewed  y/n: approve/deny  x: interrupt  z/Z: snooze  p: pin  c: compare pinned  i: details  q: quit"),
+    ended: Cow::Borrowed("ended {ago} ago · ran {ran}"),
+    age: Cow::Borrowed("up {age}"),
+    last_turn: Cow::Borrowed("took {time}"),
+    compare_help: Cow::Borrowed("c/Esc: back to list"),
+    no_changes: Cow::Borrowed("no uncommitted changes"),
+    not_a_repo: Cow::Borrowed("not a git repository"),
+    touched_files: Cow::Borrowed("Recently edited files (newest first)"),
+    timeline: Cow::Borrowed("Recent states (newest first)"),
+    time_in_states: Cow::Borrowed("Time in each state, in total"),
+    no_touched_files: Cow::Borrowed("no edits seen"),
+    outside_cwd: Cow::Borrowed("outside project"),
+    subagents: Cow::Borrowed("subagents: {n}"),
+    todos: Cow::Borrowed("{done}/{total} tasks"),
+    queued: Cow::Borrowed("{n} queued"),
+    uncommitted: Cow::Borrowed("{n} uncommitted files"),
+    context: Cow::Borrowed("{pct}% context left"),
+    plan_mode: Cow::Borrowed("⏸ plan"),
+    accept_edits: Cow::Borrowed("⏵⏵ accept edits"),
+    bypass_permissions: Cow::Borrowed("⏵⏵ bypass"),
+    skips_permissions: Cow::Borrowed("⚠ permissions bypassed"),
+    bash_command: Cow::Borrowed("command"),
+    file_edit: Cow::Borrowed("file edit"),
+    mcp_tool: Cow::Borrowed("MCP tool"),
+    trust_directory: Cow::Borrowed("trust folder"),
+    plan_approval: Cow::Borrowed("plan review"),
+};
+
+pub static JA: Strings = Strings {
+    running: Cow::Borrowed("実行中"),
+    compacting: Cow::Borrowed("圧縮中"),
+    approval: Cow::Borrowed("承認待ち"),
+    answer: Cow::Borrowed("回答待ち"),
+    blocked: Cow::Borrowed("入力待ち"),
+    idle: Cow::Borrowed("待機中"),
+    interrupted: Cow::Borrowed("中断"),
+    error: Cow::Borrowed("エラー"),
+    not_running: Cow::Borrowed("終了"),
+    no_sessions: Cow::Borrowed("Claude セッションが見つかりません"),
+    tmux_lost: Cow::Borrowed("tmux に接続できません: {error}"),
+    monitoring_restarted: Cow::Borrowed("エラーのため監視を再起動しました"),
+    approved: Cow::Borrowed("{pane} を承認しました"),
+    denied: Cow::Borrowed("{pane} を拒否しました"),
+    interrupt_confirm: Cow::Borrowed("{pane} を中断しますか？もう一度 x で確定"),
+    interrupt_sent: Cow::Borrowed("{pane} を中断しました"),
+    no_tmux: Cow::Borrowed("tmux が起動していません — tmux の中で claude を起動してください"),
+    no_agent_panes: Cow::Borrowed("tmux に Claude のペインがありません（{n} セッション）— ペインで claude を起動してください"),
+    agents_filtered: Cow::Borrowed("エージェントのペイン {n} 件が非表示です — 設定の `agents` に追加してください"),
+    picker_help: Cow::Borrowed("1-9: ジャンプ  j/k: 移動  Enter: 切替  r: 確認済み  y/n: 承認/拒否  x: 中断  z/Z: スヌーズ  p: ピン留め  c: ピン留めを比較  i: 詳細  q: 終了"),
+    ended: Cow::Borrowed("{ago}前に終了 · 実行時間 {ran}"),
+    age: Cow::Borrowed("稼働 {age}"),
+    last_turn: Cow::Borrowed("所要 {time}"),
+    compare_help: Cow::Borrowed("c/Esc: 一覧に戻る"),
+    no_changes: Cow::Borrowed("未コミットの変更なし"),
+    not_a_repo: Cow::Borrowed("git リポジトリではありません"),
+    touched_files: Cow::Borrowed("最近編集したファイル（新しい順）"),
+    timeline: Cow::Borrowed("最近の状態（新しい順）"),
+    time_in_states: Cow::Borrowed("状態ごとの合計時間"),
+    no_touched_files: Cow::Borrowed("編集なし"),
+    outside_cwd: Cow::Borrowed("プロジェクト外"),
+    subagents: Cow::Borrowed("サブエージェント: {n}"),
+    todos: Cow::Borrowed("タスク {done}/{total}"),
+    queued: Cow::Borrowed("送信待ち {n}件"),
+    uncommitted: Cow::Borrowed("未コミット {n} ファイル"),
+    context: Cow::Borrowed("コンテキスト残り {pct}%"),
+    plan_mode: Cow::Borrowed("⏸ プラン"),
+    accept_edits: Cow::Borrowed("⏵⏵ 編集自動承認"),
+    bypass_permissions: Cow::Borrowed("⏵⏵ 権限スキップ"),
+    skips_permissions: Cow::Borrowed("⚠ 権限チェック無効"),
+    bash_command: Cow::Borrowed("コマンド"),
+    file_edit: Cow::Borrowed("ファイル編集"),
+    mcp_tool: Cow::Borrowed("MCP ツール"),
+    trust_directory: Cow::Borrowed("フォルダ信頼"),
+    plan_approval: Cow::Borrowed("プラン確認"),
+};
+
+impl Language {
+    pub fn strings(self) -> &'static Strings {
+        match self {
+            Language::En => &EN,
+            Language::Ja => &JA,
+        }
+    }
+}
+
+impl Strings {
+    pub fn state_label(&self, state: &ClaudeState) -> &str {
+        match state {
+            ClaudeState::Working => &self.running,
+            ClaudeState::Compacting => &self.compacting,
+            ClaudeState::WaitingForApproval => &self.approval,
+            ClaudeState::WaitingForAnswer => &self.answer,
+            ClaudeState::Blocked => &self.blocked,
+            ClaudeState::Idle => &self.idle,
+            ClaudeState::Error => &self.error,
+            ClaudeState::NotRunning => &self.not_running,
+        }
+    }
+
+    /// Every label a session can show for its state, for width measurement.
+    pub fn state_labels(&self) -> [&str; 9] {
+        [
+            &self.running,
+            &self.compacting,
+            &self.approval,
+            &self.answer,
+            &self.blocked,
+            &self.idle,
+            &self.interrupted,
+            &self.error,
+            &self.not_running,
+        ]
+    }
+
+    pub fn ended_label(&self, ago_secs: u64, ran_secs: u64) -> String {
+        self.ended
+            .replace("{ago}", &elapsed::compact(ago_secs))
+            .replace("{ran}", &elapsed::compact(ran_secs))
+    }
+
+    pub fn age_label(&self, age_secs: u64) -> String {
+        self.age.replace("{age}", &elapsed::compact(age_secs))
+    }
+
+    pub fn last_turn_label(&self, secs: u64) -> String {
+        self.last_turn.replace("{time}", &elapsed::compact(secs))
+    }
+
+    pub fn tmux_lost_label(&self, error: &str) -> String {
+        self.tmux_lost.replace("{error}", error)
+    }
+
+    pub fn approved_label(&self, pane: &str) -> String {
+        self.approved.replace("{pane}", pane)
+    }
+
+    pub fn denied_label(&self, pane: &str) -> String {
+        self.denied.replace("{pane}", pane)
+    }
+
+    pub fn interrupt_confirm_label(&self, pane: &str) -> String {
+        self.interrupt_confirm.replace("{pane}", pane)
+    }
+
+    pub fn interrupt_sent_label(&self, pane: &str) -> String {
+        self.interrupt_sent.replace("{pane}", pane)
+    }
+
+    pub fn no_agent_panes_label(&self, sessions: usize) -> String {
+        self.no_agent_panes.replace("{n}", &sessions.to_string())
+    }
+
+    pub fn agents_filtered_label(&self, panes: usize) -> String {
+        self.agents_filtered.replace("{n}", &panes.to_string())
+    }
+
+    pub fn subagents_label(&self, running: usize) -> String {
+        self.subagents.replace("{n}", &running.to_string())
+    }
+
+    pub fn todos_label(&self, done: u32, total: u32) -> String {
+        self.todos.replace("{done}", &done.to_string()).replace("{total}", &total.to_string())
+    }
+
+    pub fn queued_label(&self, messages: u32) -> String {
+        self.queued.replace("{n}", &messages.to_string())
+    }
+
+    pub fn uncommitted_label(&self, files: usize) -> String {
+        self.uncommitted.replace("{n}", &files.to_string())
+    }
+
+    pub fn context_label(&self, percent: u8) -> String {
+        self.context.replace("{pct}", &percent.to_string())
+    }
+
+    /// Badge for a session's permission mode; `None` in the default mode.
+    pub fn permission_mode_badge(&self, mode: PermissionMode) -> Option<&str> {
+        match mode {
+            PermissionMode::Default => None,
+            PermissionMode::Plan => Some(&self.plan_mode),
+            PermissionMode::AcceptEdits => Some(&self.accept_edits),
+            PermissionMode::BypassPermissions => Some(&self.bypass_permissions),
+        }
+    }
+
+    pub fn approval_kind_badge(&self, kind: ApprovalKind) -> Option<&str> {
+        match kind {
+            ApprovalKind::BashCommand => Some(&self.bash_command),
+            ApprovalKind::FileEdit => Some(&self.file_edit),
+            ApprovalKind::McpTool => Some(&self.mcp_tool),
+            ApprovalKind::TrustDirectory => Some(&self.trust_directory),
+            ApprovalKind::PlanApproval => Some(&self.plan_approval),
+            ApprovalKind::Other => None,
+        }
+    }
+
+    /// Replace state labels with the user's overrides, keeping the rest.
+    pub fn with_overrides(mut self, overrides: &LabelOverrides) -> Self {
+        if let Some(label) = &overrides.running {
+            self.running = Cow::Owned(label.clone());
+        }
+        if let Some(label) = &overrides.compacting {
+            self.compacting = Cow::Owned(label.clone());
+        }
+        if let Some(label) = &overrides.approval {
+            self.approval = Cow::Owned(label.clone());
+        }
+        if let Some(label) = &overrides.answer {
+            self.answer = Cow::Owned(label.clone());
+        }
+        if let Some(label) = &overrides.blocked {
+            self.blocked = Cow::Owned(label.clone());
+        }
+        if let Some(label) = &overrides.idle {
+            self.idle = Cow::Owned(label.clone());
+        }
+        if let Some(label) = &overrides.interrupted {
+            self.interrupted = Cow::Owned(label.clone());
+        }
+        if let Some(label) = &overrides.error {
+            self.error = Cow::Owned(label.clone());
+        }
+        if let Some(label) = &overrides.not_running {
+            self.not_running = Cow::Owned(label.clone());
+        }
+        self
+    }
+}
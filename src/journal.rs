@@ -0,0 +1,98 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::elapsed::unix_now;
+use crate::monitor::{Transition, TransitionHook};
+use crate::paths;
+use crate::redact::redact;
+use crate::status::state_key;
+
+/// Settings from the `[transition_log]` config table.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransitionLogConfig {
+    /// Append every state transition to `transitions.jsonl` in the data
+    /// directory.
+    pub enabled: bool,
+    /// Size at which the log is rotated to `transitions.jsonl.1`.
+    pub max_mb: u64,
+    /// Rotated logs kept besides the current one.
+    pub keep: u32,
+}
+
+impl Default for TransitionLogConfig {
+    fn default() -> Self {
+        Self { enabled: false, max_mb: 10, keep: 5 }
+    }
+}
+
+/// `transitions.jsonl` in the data directory.
+pub fn log_path() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join("transitions.jsonl"))
+}
+
+/// One line of the log: when the transition was seen, the pane and its
+/// project, and how long the session stayed in `from`.
+pub fn entry(transition: &Transition, timestamp: u64) -> Value {
+    let mut entry = json!({
+        "timestamp": timestamp,
+        "pane": transition.pane.id,
+        "project": redact(&transition.pane.project_name),
+        "cwd": redact(&transition.pane.cwd),
+        "from": state_key(&transition.from),
+        "to": state_key(&transition.to),
+        "duration_secs": transition.elapsed.as_secs(),
+    });
+    if let Some(kind) = transition.approval {
+        entry["approval"] = json!(kind.key());
+    }
+    entry
+}
+
+/// Append `line` to `path`, first rotating `path` to `path.1` (and `path.1`
+/// to `path.2`, ...) once it has reached `max_bytes`. Rotation keeps `keep`
+/// old logs and drops the oldest.
+pub fn append(path: &Path, line: &str, max_bytes: u64, keep: u32) -> io::Result<()> {
+    if fs::metadata(path).is_ok_and(|meta| meta.len() >= max_bytes) {
+        rotate(path, keep)?;
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{line}")
+}
+
+fn rotate(path: &Path, keep: u32) -> io::Result<()> {
+    let numbered = |n: u32| PathBuf::from(format!("{}.{n}", path.display()));
+    if keep == 0 {
+        return fs::remove_file(path);
+    }
+    // The oldest is overwritten by the one before it.
+    for n in (1..keep).rev() {
+        let from = numbered(n);
+        if from.exists() {
+            fs::rename(&from, numbered(n + 1))?;
+        }
+    }
+    fs::rename(path, numbered(1))
+}
+
+/// Transition hook implementing `[transition_log]`.
+pub fn hook(config: &TransitionLogConfig) -> Option<TransitionHook> {
+    if !config.enabled {
+        return None;
+    }
+    let path = log_path()?;
+    let max_bytes = config.max_mb.saturating_mul(1024 * 1024);
+    let keep = config.keep;
+    Some(Box::new(move |t: &Transition| {
+        if let Err(e) = append(&path, &entry(t, unix_now()).to_string(), max_bytes, keep) {
+            crate::log!("failed to write {}: {e}", path.display());
+        }
+    }))
+}
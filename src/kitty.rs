@@ -0,0 +1,166 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Command;
+
+use serde::Deserialize;
+
+use crate::tmux::{PaneInfo, PaneLabels};
+
+/// Prefix of kitty pane ids, followed by the window id (`kitty:12`). tmux
+/// pane ids always have a `.` after the window and kitty ids never do.
+const ID_PREFIX: &str = "kitty:";
+
+/// An OS window in `kitty @ ls` output.
+#[derive(Debug, Deserialize)]
+struct OsWindow {
+    #[serde(default)]
+    tabs: Vec<Tab>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Tab {
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    windows: Vec<Window>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Window {
+    id: u32,
+    #[serde(default)]
+    title: String,
+    pid: u32,
+    #[serde(default)]
+    cwd: String,
+    #[serde(default)]
+    foreground_processes: Vec<ForegroundProcess>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ForegroundProcess {
+    #[serde(default)]
+    cmdline: Vec<String>,
+    #[serde(default)]
+    cwd: String,
+}
+
+pub fn window_id(id: u32) -> String {
+    format!("{ID_PREFIX}{id}")
+}
+
+/// The kitty window id of a pane id, or `None` for other panes.
+pub fn parse_window_id(id: &str) -> Option<&str> {
+    id.strip_prefix(ID_PREFIX)
+        .filter(|rest| !rest.is_empty() && rest.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Windows in `kitty @ ls` output, with the command running in their
+/// foreground and their labels, in the shape of
+/// [`crate::tmux::list_panes_with_commands`]. Tabs take the place of tmux
+/// windows; all of kitty is one session.
+pub fn parse_ls(json: &str) -> Result<Vec<(PaneInfo, String, PaneLabels)>, String> {
+    let os_windows: Vec<OsWindow> = serde_json::from_str(json).map_err(|e| format!("invalid kitty @ ls output: {e}"))?;
+    let mut windows = vec![];
+    for tab in os_windows.iter().flat_map(|w| &w.tabs) {
+        for window in &tab.windows {
+            let foreground = window.foreground_processes.first();
+            let command = foreground
+                .and_then(|p| p.cmdline.first())
+                .and_then(|arg0| Path::new(arg0).file_name())
+                .and_then(|name| name.to_str())
+                .unwrap_or_default()
+                .trim_start_matches('-') // login shells run as "-zsh"
+                .to_string();
+            let cwd = foreground
+                .map(|p| p.cwd.clone())
+                .filter(|cwd| !cwd.is_empty())
+                .unwrap_or_else(|| window.cwd.clone());
+            let project_name = Path::new(&cwd)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+            let pane = PaneInfo {
+                id: window_id(window.id),
+                pid: window.pid,
+                cwd,
+                project_name,
+                session_name: "kitty".to_string(),
+                window_name: tab.title.clone(),
+            };
+            let labels = PaneLabels {
+                title: window.title.clone(),
+                host: String::new(),
+            };
+            windows.push((pane, command, labels));
+        }
+    }
+    Ok(windows)
+}
+
+/// Every kitty window, or none when kitty's remote control is unreachable
+/// (`allow_remote_control` off, or no `$KITTY_LISTEN_ON` outside kitty).
+pub fn list_windows() -> Vec<(PaneInfo, String, PaneLabels)> {
+    let output = match Command::new("kitty").args(["@", "ls"]).output() {
+        Ok(out) if out.status.success() => out,
+        Ok(out) => {
+            crate::log!("kitty @ ls failed: {}", String::from_utf8_lossy(&out.stderr).trim());
+            return vec![];
+        }
+        Err(e) => {
+            crate::log!("kitty @ ls failed: {e}");
+            return vec![];
+        }
+    };
+    parse_ls(&String::from_utf8_lossy(&output.stdout)).unwrap_or_else(|e| {
+        crate::log!("{e}");
+        vec![]
+    })
+}
+
+/// Pane ids of every open kitty window.
+pub fn all_window_ids() -> HashSet<String> {
+    list_windows().into_iter().map(|(pane, _, _)| pane.id).collect()
+}
+
+/// The visible content of a window.
+pub fn capture(window: &str) -> Option<String> {
+    let output = Command::new("kitty")
+        .args(["@", "get-text", "--match", &format!("id:{window}")])
+        .output();
+    match output {
+        Ok(out) if out.status.success() => Some(String::from_utf8_lossy(&out.stdout).into_owned()),
+        Ok(_) => None,
+        Err(e) => {
+            crate::log!("kitty @ get-text failed for window {window}: {e}");
+            None
+        }
+    }
+}
+
+/// Press `keys` (tmux key names such as `Enter`) in a window.
+pub fn send_keys(window: &str, keys: &[&str]) -> bool {
+    let keys: Vec<String> = keys.iter().map(|key| key.to_lowercase()).collect();
+    let result = Command::new("kitty")
+        .args(["@", "send-key", "--match", &format!("id:{window}")])
+        .args(&keys)
+        .output();
+    match result {
+        Ok(out) => out.status.success(),
+        Err(e) => {
+            crate::log!("kitty @ send-key failed for window {window}: {e}");
+            false
+        }
+    }
+}
+
+/// Bring a window to the front.
+pub fn focus_window(window: &str) {
+    let result = Command::new("kitty")
+        .args(["@", "focus-window", "--match", &format!("id:{window}")])
+        .output();
+    if let Err(e) = result {
+        crate::log!("kitty @ focus-window failed for window {window}: {e}");
+    }
+}
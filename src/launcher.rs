@@ -0,0 +1,121 @@
+use serde_json::{json, Value};
+
+use crate::claude_state::ClaudeState;
+use crate::color;
+use crate::elapsed;
+use crate::i18n::Strings;
+use crate::monitor::ClaudeSession;
+use crate::status::state_key;
+
+/// Subcommand a launcher runs when a session is chosen: approving is the
+/// useful action for a pending prompt, switching for everything else
+/// (including prompts that trip a guardrail).
+fn primary_action(session: &ClaudeSession) -> &'static str {
+    match session.state {
+        ClaudeState::WaitingForApproval if !session.tripped_guardrail() => "approve",
+        _ => "switch",
+    }
+}
+
+fn subtitle(session: &ClaudeSession, strings: &Strings) -> String {
+    format!(
+        "{} · {} {}",
+        session.pane.id,
+        strings.state_label(&session.state),
+        elapsed::compact(session.state_changed_at.elapsed().as_secs())
+    )
+}
+
+/// Tab-separated `pane  project  state  elapsed-secs` lines for scripts,
+/// with the state in its color when `color` is set.
+pub fn plain(sessions: &[ClaudeSession], color: bool) -> String {
+    sessions
+        .iter()
+        .filter(|s| s.is_live())
+        .map(|s| {
+            let state = state_key(&s.state);
+            let state = if color { color::state(state, state) } else { state.to_string() };
+            format!(
+                "{}\t{}\t{}\t{}\n",
+                s.pane.id,
+                s.pane.project_name,
+                state,
+                s.state_changed_at.elapsed().as_secs()
+            )
+        })
+        .collect()
+}
+
+/// The lines of [`plain`] for `claudeye list --format`: `pane`, `project`,
+/// `state`, and `elapsed` in seconds.
+pub fn rows(sessions: &[ClaudeSession]) -> Vec<Value> {
+    sessions
+        .iter()
+        .filter(|s| s.is_live())
+        .map(|s| {
+            json!({
+                "pane": s.pane.id,
+                "project": s.pane.project_name,
+                "state": state_key(&s.state),
+                "elapsed": s.state_changed_at.elapsed().as_secs(),
+            })
+        })
+        .collect()
+}
+
+/// Alfred script filter JSON. The workflow's Run Script action receives the
+/// subcommand and pane as the `action` and `pane` variables; holding ⌘
+/// switches instead of approving.
+pub fn alfred(sessions: &[ClaudeSession], strings: &Strings) -> Value {
+    let items: Vec<Value> = sessions
+        .iter()
+        .filter(|s| s.is_live())
+        .map(|s| {
+            json!({
+                "uid": s.pane.id,
+                "title": s.pane.project_name,
+                "subtitle": subtitle(s, strings),
+                "arg": s.pane.id,
+                "variables": { "action": primary_action(s), "pane": s.pane.id },
+                "mods": {
+                    "cmd": {
+                        "subtitle": format!("Switch to {}", s.pane.id),
+                        "arg": s.pane.id,
+                        "variables": { "action": "switch", "pane": s.pane.id },
+                    }
+                },
+            })
+        })
+        .collect();
+    json!({ "items": items })
+}
+
+/// JSON for a Raycast script command or extension: one item per session with
+/// the full command line of each available action, primary action first.
+pub fn raycast(sessions: &[ClaudeSession], strings: &Strings, exe: &str) -> Value {
+    let items: Vec<Value> = sessions
+        .iter()
+        .filter(|s| s.is_live())
+        .map(|s| {
+            let mut actions = vec![json!({
+                "title": "Switch",
+                "command": [exe, "switch", s.pane.id],
+            })];
+            if primary_action(s) == "approve" {
+                actions.insert(0, json!({
+                    "title": "Approve",
+                    "command": [exe, "approve", s.pane.id],
+                }));
+            }
+            json!({
+                "id": s.pane.id,
+                "title": s.pane.project_name,
+                "subtitle": subtitle(s, strings),
+                "state": state_key(&s.state),
+                "unread": s.shows_unread(),
+                "actions": actions,
+            })
+        })
+        .collect();
+    json!({ "items": items })
+}
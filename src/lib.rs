@@ -1,3 +1,50 @@
+pub mod ab;
+pub mod actions;
+pub mod agents;
+pub mod announce;
+pub mod api;
 pub mod claude_state;
+pub mod color;
+pub mod config;
+pub mod crash;
+pub mod demo;
+pub mod discovery;
+pub mod elapsed;
+pub mod env_facts;
+pub mod files;
+pub mod filter;
+pub mod fixtures;
+pub mod forge;
+pub mod format;
+pub mod git;
+pub mod guardrail;
+pub mod journal;
+pub mod health;
+pub mod i18n;
+pub mod kitty;
+pub mod launcher;
+pub mod marks;
+pub mod paths;
+pub mod poke;
+pub mod process;
+pub mod query;
+pub mod record;
+pub mod redact;
+pub mod remote;
+pub mod rename;
+pub mod rule_stats;
+pub mod screen;
+pub mod service;
+pub mod setup;
+pub mod stats;
+pub mod status;
+pub mod team;
+pub mod text;
 pub mod tmux;
+pub mod transcript;
+pub mod watch;
+pub mod webhook;
+pub mod xbar;
 pub mod monitor;
+pub mod notify;
+pub mod picker;
@@ -1,13 +1,25 @@
-mod claude_state;
-mod monitor;
-mod picker;
-mod tmux;
-
 use clap::{Parser, Subcommand};
+use claudeye::announce::{Announcer, Verbosity};
+use claudeye::claude_state::{self, detect_state_explained_in, ApprovalKind, ClaudeState, PermissionMode};
+use claudeye::color::{self, ColorChoice};
+use claudeye::config::{self, Config};
+use claudeye::elapsed::{self, ElapsedFormat};
+use claudeye::format::Format;
+use claudeye::i18n::Strings;
+use claudeye::monitor::{self, ClaudeSession, Hooks, Monitor, NameFormat, SessionEvent, SessionList, Transition, TransitionHook, poll_once, start_polling};
+use claudeye::query::Query;
+use claudeye::redact::{self, Redactor};
+use claudeye::rule_stats::RuleStats;
+use claudeye::stats::Period;
+use claudeye::agents::EmptyReason;
+use claudeye::guardrail::Guardrail;
+use claudeye::actions::{ClickConfig, RowAction};
+use claudeye::{ab, actions, agents, api, crash, demo, discovery, forge, health, journal, launcher, picker, poke, record, service, setup, stats, status, team, text, tmux, transcript, webhook, xbar};
 use eframe::egui::{self, Color32, RichText, Ui, Vec2};
-use monitor::{ClaudeSession, start_polling};
-use claude_state::ClaudeState;
-use std::sync::{Arc, Mutex};
+use std::io::IsTerminal;
+use std::sync::Arc;
+use std::sync::mpsc::Receiver;
+use std::time::Instant;
 
 #[derive(Parser)]
 #[command(about = "Claude session monitor overlay", version)]
@@ -16,14 +28,46 @@ struct Args {
     #[arg(long)]
     compact: bool,
 
-    /// Overlay window position on screen
-    #[arg(long, short, default_value = "top-center", value_enum)]
-    position: Position,
+    /// Overlay window position on screen [default: top-center, or `position` from the config]
+    #[arg(long, short, value_enum)]
+    position: Option<Position>,
 
     /// Move overlay to screen center when any session stays in Approval/Idle for over 10 seconds
     #[arg(long)]
     center_on_stale: bool,
 
+    /// Wrap each bubble onto two lines in a fixed-width overlay (see `narrow_width`)
+    #[arg(long)]
+    narrow: bool,
+
+    /// Show one traffic light colored by the most urgent session, with counts on hover
+    #[arg(long, conflicts_with_all = ["compact", "narrow", "strip"])]
+    light: bool,
+
+    /// Dock the overlay to a screen edge as a thin strip of state colors, expanding to full rows on hover
+    #[arg(long, value_enum)]
+    strip: Option<Edge>,
+
+    /// Accept mouse input instead of passing clicks through (enables row actions such as dismissing ended sessions)
+    #[arg(long)]
+    interactive: bool,
+
+    /// Speak state transitions aloud via the OS text-to-speech command
+    #[arg(long, value_enum, num_args = 0..=1, default_missing_value = "normal")]
+    announce: Option<Verbosity>,
+
+    /// Apply the settings of `[profiles.<PROFILE>]` from the config file
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    /// Zoom the pane (`resize-pane -Z`) when jumping to a session
+    #[arg(long, global = true)]
+    zoom: bool,
+
+    /// When to color `list`, `prompt`, and `ping` output
+    #[arg(long, value_enum, global = true, default_value_t)]
+    color: ColorChoice,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -32,6 +76,200 @@ struct Args {
 enum Commands {
     /// Interactive TUI session picker
     Picker,
+    /// Speak state transitions aloud without showing the overlay
+    Speak {
+        /// Which transitions to announce
+        #[arg(long, value_enum, default_value = "normal")]
+        verbosity: Verbosity,
+    },
+    /// Append a pane's output to a file until the pane closes
+    Record {
+        /// tmux pane target (e.g. `main:0.1` or `%3`)
+        pane: String,
+        /// Log file to append to
+        file: std::path::PathBuf,
+    },
+    /// Print a short summary such as `⚠1 ▶2` for shell prompts; exits 1
+    /// when there is nothing to show
+    Prompt,
+    /// Switch the tmux client to a pane
+    Switch {
+        /// tmux pane target (e.g. `main:0.1` or `%3`)
+        pane: String,
+    },
+    /// Accept the pending approval prompt of a pane
+    Approve {
+        /// tmux pane target (e.g. `main:0.1` or `%3`)
+        pane: String,
+        /// Approve even when the prompt trips a guardrail
+        #[arg(long)]
+        force: bool,
+    },
+    /// Reject the pending approval prompt of a pane (presses Esc)
+    Deny {
+        /// tmux pane target (e.g. `main:0.1` or `%3`)
+        pane: String,
+    },
+    /// Stop a working session (presses Esc), after asking for confirmation
+    Interrupt {
+        /// tmux pane target (e.g. `main:0.1` or `%3`)
+        pane: String,
+        /// Do not ask for confirmation
+        #[arg(long, short)]
+        yes: bool,
+    },
+    /// Silence a session's alerts until its state changes (or for a while)
+    Snooze {
+        /// tmux pane target (e.g. `main:0.1` or `%3`)
+        pane: String,
+        /// Snooze for this many minutes instead of until the next state change
+        #[arg(long, conflicts_with = "off")]
+        minutes: Option<u64>,
+        /// Cancel an active snooze
+        #[arg(long)]
+        off: bool,
+    },
+    /// Print the status of every session as JSON, or what `--query` selects.
+    /// Exits 0 when all sessions are idle, 1 when one is working, 2 when one
+    /// waits on you, and 3 when one hit an error
+    Status {
+        /// Filter and print fields, e.g. `state==approval | count` or
+        /// `project=="myapp".elapsed` (see the README); exits 0 on success
+        #[arg(long)]
+        query: Option<String>,
+        /// Print nothing, only set the exit status
+        #[arg(long, short, conflicts_with = "query")]
+        quiet: bool,
+        /// Print the counts colored with tmux style codes, for
+        /// `status-right "#(claudeye status --tmux)"`; exits 0
+        #[arg(long, conflicts_with_all = ["query", "quiet", "format"])]
+        tmux: bool,
+        /// Print as plain, json (default), yaml, table, or a template such as
+        /// '{pane} {state}'
+        #[arg(long, conflicts_with_all = ["query", "quiet"])]
+        format: Option<Format>,
+    },
+    /// Print sessions in the xbar/SwiftBar plugin format
+    Xbar,
+    /// List sessions, tab-separated or as launcher JSON
+    List {
+        /// Alfred script filter JSON
+        #[arg(long, conflicts_with = "raycast")]
+        alfred: bool,
+        /// JSON items with approve/switch command lines for Raycast
+        #[arg(long)]
+        raycast: bool,
+        /// Print as plain (default), json, yaml, table, or a template such as
+        /// '{pane} {state}'
+        #[arg(long, conflicts_with_all = ["alfred", "raycast"])]
+        format: Option<Format>,
+    },
+    /// Serve session status and actions over HTTP(S) (see `[api]` in the config)
+    Serve {
+        /// Address to listen on, overriding `api.bind`
+        #[arg(long)]
+        bind: Option<String>,
+        /// Disable action endpoints such as approve, for shared dashboards
+        #[arg(long)]
+        read_only: bool,
+    },
+    /// Check that a running `claudeye serve` is still polling; exits 1 when it is not
+    Ping {
+        /// Health endpoint to ask, instead of the one `api.bind` points at
+        #[arg(long)]
+        url: Option<String>,
+    },
+    /// List `claudeye serve` instances advertised on the local network
+    Discover {
+        /// Seconds to listen for announcements
+        #[arg(long, default_value_t = 3)]
+        timeout: u64,
+    },
+    /// Interactive first-run setup that writes the config file
+    Init,
+    /// Add a picker popup keybinding and a status-right summary to the tmux config
+    InstallTmuxBinding {
+        /// Key to press after the tmux prefix
+        #[arg(long, default_value = "C-g")]
+        key: String,
+        /// Remove what an earlier install added
+        #[arg(long)]
+        uninstall: bool,
+    },
+    /// Have running monitors capture a pane again now (run by the tmux hooks)
+    Poke {
+        /// tmux pane target (e.g. `main:0.1`)
+        pane: String,
+    },
+    /// Show made-up sessions cycling through every state, in the overlay
+    /// (with its usual options) or the picker, for screenshots and UI work
+    Demo {
+        /// How many sessions to make up
+        #[arg(long, default_value_t = 5)]
+        sessions: usize,
+        /// Open the picker instead of the overlay
+        #[arg(long)]
+        picker: bool,
+    },
+    /// Print per-project totals recorded while monitoring (working time,
+    /// approvals, waits, turns), or detection rule counters
+    Stats {
+        /// Span to total: today (default) or this week; needs `[stats]`
+        /// enabled in the config
+        #[arg(long, value_enum, conflicts_with_all = ["rules", "reset"])]
+        period: Option<Period>,
+        /// How often each detection rule decided a state, overall and per session
+        #[arg(long)]
+        rules: bool,
+        /// Reset the counters
+        #[arg(long)]
+        reset: bool,
+        /// Print as plain (default), json, yaml, table, or a template such as
+        /// '{rule} {hits}'
+        #[arg(long, conflicts_with = "reset")]
+        format: Option<Format>,
+    },
+    /// Print the state detected in a pane, or in text read from stdin
+    Detect {
+        /// tmux pane target (e.g. `main:0.1` or `%3`); stdin when omitted
+        pane: Option<String>,
+        /// Also print which detection rule decided the state
+        #[arg(long)]
+        explain: bool,
+    },
+    /// Run a candidate rule set beside the current one on live panes and
+    /// save the captures they disagree on
+    Ab {
+        /// TOML file with `disabled` built-in rules and `[[rule]]` additions
+        rules: std::path::PathBuf,
+    },
+    /// Run `claudeye serve` in the background as a systemd user service or
+    /// launchd agent
+    Service {
+        #[command(subcommand)]
+        action: ServiceAction,
+    },
+    /// Print the report written by the last crash, for attaching to bug reports
+    ReportCrash,
+    /// Show per-member agent utilization collected by the `[team]` endpoint
+    TeamReport,
+}
+
+#[derive(Subcommand)]
+enum ServiceAction {
+    /// Write the unit or plist with this shell's PATH and tmux socket, then
+    /// enable and start it
+    Install {
+        /// Print the unit or plist instead of installing it
+        #[arg(long)]
+        print: bool,
+    },
+    /// Stop the service and remove its unit or plist
+    Uninstall,
+    /// Start the service now and at every login
+    Enable,
+    /// Stop the service and keep it from starting at login
+    Disable,
 }
 
 #[derive(Clone, Copy, Default, clap::ValueEnum)]
@@ -72,34 +310,456 @@ impl Position {
     }
 }
 
+/// Screen edge the overlay docks to in strip mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+enum Edge {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl Edge {
+    /// Where the strip and the rows it expands to are placed.
+    fn position(self) -> Position {
+        match self {
+            Edge::Top => Position::TopCenter,
+            Edge::Bottom => Position::BottomCenter,
+            Edge::Left => Position::MiddleLeft,
+            Edge::Right => Position::MiddleRight,
+        }
+    }
+
+    fn is_vertical(self) -> bool {
+        matches!(self, Edge::Left | Edge::Right)
+    }
+
+    /// Window size of a strip of `count` segments (at least one, so an
+    /// empty strip can still be hovered).
+    fn strip_size(self, count: usize) -> Vec2 {
+        let length = count.max(1) as f32 * (STRIP_SEGMENT_LENGTH + STRIP_GAP) - STRIP_GAP;
+        if self.is_vertical() {
+            Vec2::new(STRIP_THICKNESS, length)
+        } else {
+            Vec2::new(length, STRIP_THICKNESS)
+        }
+    }
+
+    /// Rectangle of the `index`th segment within a strip starting at `origin`.
+    fn segment(self, origin: egui::Pos2, index: usize) -> egui::Rect {
+        let offset = index as f32 * (STRIP_SEGMENT_LENGTH + STRIP_GAP);
+        let (min, size) = if self.is_vertical() {
+            (origin + Vec2::new(0.0, offset), Vec2::new(STRIP_THICKNESS, STRIP_SEGMENT_LENGTH))
+        } else {
+            (origin + Vec2::new(offset, 0.0), Vec2::new(STRIP_SEGMENT_LENGTH, STRIP_THICKNESS))
+        };
+        egui::Rect::from_min_size(min, size)
+    }
+}
+
 const REPAINT_INTERVAL_SECS: u64 = 2;
 const STALE_THRESHOLD_SECS: u64 = 10;
 const MIN_WINDOW_WIDTH: f32 = 180.0;
 const WINDOW_EMPTY_HEIGHT: f32 = 40.0;
 const ROW_HEIGHT: f32 = 22.0;
+/// Height of a session row whose bubble wraps onto two lines.
+const NARROW_ROW_HEIGHT: f32 = 34.0;
 const WINDOW_PADDING: f32 = 8.0;
 const MARGIN: f32 = 2.0;
+/// Thickness of the strip docked to a screen edge.
+const STRIP_THICKNESS: f32 = 6.0;
+/// Length of one session's segment along the strip; kept below the row
+/// height so the pointer stays inside the rows the strip expands to.
+const STRIP_SEGMENT_LENGTH: f32 = 20.0;
+const STRIP_GAP: f32 = 3.0;
+/// Diameter of the traffic light, which is also the window height.
+const LIGHT_SIZE: f32 = 18.0;
+const _: () = assert!(STRIP_SEGMENT_LENGTH + STRIP_GAP <= ROW_HEIGHT + 4.0);
+/// Dot shown in the bubble of a finished session with output not yet looked at.
+const UNREAD_COLOR: Color32 = Color32::from_rgb(90, 160, 255);
+const WATCH_COLOR: Color32 = Color32::from_rgb(220, 110, 220);
+/// Shown after the bubble of a session whose alerts are snoozed.
+const SNOOZE_BADGE: &str = "zz";
+/// Widest token label, so the bubble does not resize as the count grows.
+const TOKENS_PLACEHOLDER: &str = " · 999.9k tok · 9999/s";
+/// Longest pending approval or error summary shown in a bubble.
+const PENDING_MAX_WIDTH: usize = 40;
+const APPROVE_BUTTON: &str = "✓";
+const DENY_BUTTON: &str = "✗";
+/// Longest tmux error shown in the banner raised when tmux is lost.
+const TMUX_ERROR_MAX_WIDTH: usize = 80;
+/// Badge color of a session asking to run a git command that can discard
+/// uncommitted changes.
+const WARNING_COLOR: Color32 = Color32::from_rgb(230, 170, 60);
+/// Bubble and badge color of a session that tripped a guardrail.
+const ALARM_COLOR: Color32 = Color32::from_rgb(255, 60, 60);
+/// Width reserved for the unread dot so rows don't resize when it appears.
+const UNREAD_DOT_WIDTH: f32 = 10.0;
 /// Horizontal overhead per session row (panel margin + robot art + spacing + bubble padding + buffer).
 const ROW_HORIZONTAL_OVERHEAD: f32 = 82.0;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    crash::install_hook();
     let args = Args::parse();
+    let config = config::load(args.profile.as_deref())?;
+    let home = std::env::var("HOME").ok();
+    redact::install(Redactor::new(&config.redaction, home.as_deref()));
+    if let Some(path) = &config.monitor.tmux_path {
+        tmux::set_program(path.clone());
+    }
+    tmux::set_capture_lines(config.monitor.capture_lines);
+    tmux::set_zoom(args.zoom || config.zoom_on_jump);
+    claude_state::set_detection_lines(config.monitor.detection_lines);
+    let color = color::stdout_enabled(args.color);
+    // Only the monitoring processes record stats.
+    if matches!(args.command, None | Some(Commands::Speak { .. } | Commands::Serve { .. } | Commands::Demo { .. })) {
+        config.stats.validate()?;
+    }
     match args.command {
-        Some(Commands::Picker) => picker::run_picker()?,
-        None => run_gui(args.compact, args.position, args.center_on_stale)?,
+        Some(Commands::Picker) => picker::run_picker(&config)?,
+        Some(Commands::Speak { verbosity }) => run_speak(verbosity, config)?,
+        Some(Commands::Record { ref pane, ref file }) => record::run_record(pane, file)?,
+        Some(Commands::Prompt) => run_prompt(&config, color),
+        Some(Commands::Switch { ref pane }) => actions::switch(pane)?,
+        Some(Commands::Approve { ref pane, force }) => actions::approve(pane, &config.guardrail, force)?,
+        Some(Commands::Deny { ref pane }) => actions::deny(pane)?,
+        Some(Commands::Interrupt { ref pane, yes }) => run_interrupt(pane, yes)?,
+        Some(Commands::Snooze { ref pane, minutes, off }) => actions::snooze(pane, minutes, off)?,
+        Some(Commands::Status { tmux: true, .. }) => {
+            println!("{}", color::tmux_summary(&status::prompt_summary(&config)))
+        }
+        Some(Commands::Status { ref query, quiet, ref format, .. }) => {
+            run_status(&config, query.as_deref(), quiet, format.clone().unwrap_or(Format::Json), color)?
+        }
+        Some(Commands::Xbar) => run_xbar(&config)?,
+        Some(Commands::List { alfred, raycast, ref format }) => {
+            run_list(&config, alfred, raycast, format.clone().unwrap_or(Format::Plain), color)?
+        }
+        Some(Commands::Serve { ref bind, read_only }) => run_serve(bind.clone(), read_only, config)?,
+        Some(Commands::Ping { ref url }) => run_ping(&config, url.as_deref(), color)?,
+        Some(Commands::Discover { timeout }) => run_discover(timeout)?,
+        Some(Commands::InstallTmuxBinding { key, uninstall }) => run_install_tmux_binding(&key, uninstall)?,
+        Some(Commands::Detect { ref pane, explain }) => run_detect(&config, pane.as_deref(), explain)?,
+        Some(Commands::Ab { ref rules }) => ab::run(&ab::CandidateRules::load(rules)?, config.monitor.ui_locale)?,
+        Some(Commands::Demo { sessions, picker }) => {
+            demo::start(sessions);
+            if picker {
+                picker::run_picker(&config)?;
+            } else {
+                run_gui(&args, config)?;
+            }
+        }
+        Some(Commands::Poke { ref pane }) => {
+            poke::send(pane);
+        }
+        Some(Commands::Stats { period, rules, reset, ref format }) => {
+            run_stats(&config, period, rules, reset, format.clone().unwrap_or(Format::Plain))?
+        }
+        Some(Commands::Service { ref action }) => run_service(action, args.profile.as_deref())?,
+        Some(Commands::ReportCrash) => crash::print_report()?,
+        Some(Commands::TeamReport) => print!("{}", team::render_table(&team::fetch(&config.team)?, elapsed::unix_now())),
+        Some(Commands::Init) => setup::run_init(&mut std::io::stdin().lock(), &mut std::io::stdout())?,
+        None => run_gui(&args, config)?,
+    }
+    Ok(())
+}
+
+fn run_install_tmux_binding(key: &str, uninstall: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let conf = setup::tmux_conf_path().ok_or("cannot locate the tmux config (HOME unset)")?;
+    if uninstall {
+        if setup::remove_tmux_block(&conf)? {
+            println!("Removed the claudeye block from {}", conf.display());
+        } else {
+            println!("No claudeye block in {}", conf.display());
+        }
+        return Ok(());
+    }
+    let exe = std::env::current_exe()?;
+    let exe = exe.to_string_lossy();
+    let mut lines = vec![setup::popup_binding(&exe, key), setup::status_snippet(&exe)];
+    lines.extend(setup::poke_hooks(&exe));
+    setup::write_tmux_block(&conf, &lines)?;
+    println!("Updated {}; reload with: tmux source-file {}", conf.display(), conf.display());
+    Ok(())
+}
+
+/// Transition hooks configured independently of announcements.
+fn integration_hooks(config: &Config) -> Hooks {
+    let mut hooks = Hooks::default();
+    hooks.alerting.extend(forge::finish_hook(&config.pull_requests, hooks.alerts()));
+    hooks.alerting.extend(tmux::bell_hook(config.tmux_bell));
+    hooks.recording.extend(
+        webhook::hook(&config.webhook)
+            .into_iter()
+            .chain(journal::hook(&config.transition_log))
+            .chain(stats::hook(&config.stats))
+            .chain(monitor::command_hook(config.on_transition.as_deref())),
+    );
+    hooks
+}
+
+fn announce_hooks(verbosity: Option<Verbosity>) -> Vec<TransitionHook> {
+    let Some(announcer) = verbosity.and_then(Announcer::new) else {
+        return vec![];
+    };
+    vec![Box::new(move |t: &Transition| announcer.announce(t))]
+}
+
+fn run_speak(verbosity: Verbosity, config: Config) -> Result<(), Box<dyn std::error::Error>> {
+    let announce = announce_hooks(Some(verbosity));
+    if announce.is_empty() {
+        return Err("no text-to-speech command available".into());
+    }
+    let mut hooks = integration_hooks(&config);
+    hooks.alerting.extend(announce);
+    start_polling(hooks, config);
+    loop {
+        std::thread::park();
+    }
+}
+
+fn run_serve(
+    bind: Option<String>,
+    read_only: bool,
+    mut config: Config,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(bind) = bind {
+        config.api.bind = bind;
+    }
+    config.api.read_only |= read_only;
+    config.api.validate()?;
+    let monitor = start_polling(integration_hooks(&config), config.clone());
+    api::serve(&config.api, monitor.subscribe(), config.guardrail)?;
+    Ok(())
+}
+
+fn run_service(action: &ServiceAction, profile: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let manager = service::Manager::current();
+    match action {
+        ServiceAction::Install { print } => {
+            let exe = std::env::current_exe()?;
+            let spec = service::ServiceSpec::serve(&exe.to_string_lossy(), profile, |name| std::env::var(name).ok());
+            if *print {
+                print!("{}", spec.render(manager));
+                return Ok(());
+            }
+            let path = service::install(manager, &spec)?;
+            println!("Installed {} and started it", path.display());
+        }
+        ServiceAction::Uninstall => match service::uninstall(manager)? {
+            Some(path) => println!("Stopped and removed {}", path.display()),
+            None => println!("The service is not installed"),
+        },
+        ServiceAction::Enable => service::enable(manager)?,
+        ServiceAction::Disable => service::disable(manager)?,
+    }
+    Ok(())
+}
+
+fn run_ping(config: &Config, url: Option<&str>, color: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let url = match url {
+        Some(url) => url.to_string(),
+        None => health::healthz_url(&config.api)?,
+    };
+    let health = health::ping(&url, config.api.token.as_deref())?;
+    let summary = health.summary();
+    match summary.split_once(':') {
+        Some((verdict, rest)) if color => println!("{}:{rest}", color::paint(verdict, if health.ok { "32" } else { "31" })),
+        _ => println!("{summary}"),
+    }
+    if !health.ok {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn run_discover(timeout: u64) -> Result<(), Box<dyn std::error::Error>> {
+    for instance in discovery::discover(std::time::Duration::from_secs(timeout))? {
+        let mut flags = vec![];
+        if instance.read_only {
+            flags.push("read-only");
+        }
+        println!(
+            "{}\t{}\t{}",
+            instance.name,
+            instance.url().unwrap_or_default(),
+            flags.join(",")
+        );
     }
     Ok(())
 }
 
-fn run_gui(compact: bool, position: Position, center_on_stale: bool) -> eframe::Result<()> {
-    let sessions: Arc<Mutex<Vec<ClaudeSession>>> = Arc::new(Mutex::new(vec![]));
-    start_polling(Arc::clone(&sessions));
+fn run_prompt(config: &Config, color: bool) {
+    let summary = status::prompt_summary(config);
+    if summary.is_empty() {
+        std::process::exit(1);
+    }
+    println!("{}", if color { color::summary(&summary) } else { summary });
+}
+
+fn run_detect(config: &Config, pane: Option<&str>, explain: bool) -> std::io::Result<()> {
+    let content = match pane {
+        Some(pane) => tmux::capture_pane(pane),
+        None => std::io::read_to_string(std::io::stdin())?,
+    };
+    let (state, trace) = detect_state_explained_in(&content, config.monitor.ui_locale);
+    println!("{}", status::state_key(&state));
+    if explain {
+        print!("{}", trace.render());
+    }
+    Ok(())
+}
+
+fn run_xbar(config: &Config) -> std::io::Result<()> {
+    let exe = std::env::current_exe()?;
+    let sessions = poll_once(&[], config);
+    print!("{}", xbar::render(&sessions, &config.strings(), config.name_format, &exe.to_string_lossy()));
+    Ok(())
+}
+
+fn run_stats(
+    config: &Config,
+    period: Option<Period>,
+    rules: bool,
+    reset: bool,
+    format: Format,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if reset {
+        RuleStats::default().save();
+        return Ok(());
+    }
+    if !rules {
+        let period = period.unwrap_or_default();
+        let db = stats::db_path().filter(|db| db.exists()).ok_or(
+            "no stats recorded yet; enable them with `[stats] enabled = true` in the config, or try `claudeye stats --rules`",
+        )?;
+        stats::check_sqlite()?;
+        let rows = stats::report(&db, period)?;
+        print!("{}", format.render(&stats::document(&rows, period), &rows, || stats::render_table(&rows, period))?);
+        return Ok(());
+    }
+    let known: Vec<&str> = agents::enabled(&config.monitor.agents)
+        .iter()
+        .flat_map(|detector| detector.rules().iter().copied())
+        .collect();
+    let stats = RuleStats::load();
+    let rows = stats.rows();
+    let document = serde_json::json!({ "rules": rows, "never_matched": stats.unused(&known) });
+    print!("{}", format.render(&document, &rows, || stats.render_table(&known))?);
+    Ok(())
+}
+
+fn run_status(
+    config: &Config,
+    query: Option<&str>,
+    quiet: bool,
+    format: Format,
+    color: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Parsed before polling so that a typo fails fast.
+    let query = query.map(Query::parse).transpose()?;
+    let sessions = poll_once(&[], config);
+    let snapshot = status::StatusSnapshot::from_sessions(&sessions);
+    if let Some(query) = query {
+        print!("{}", query.run(&snapshot.sessions, elapsed::unix_now())?);
+        return Ok(());
+    }
+    if !quiet {
+        let document = serde_json::to_value(&snapshot)?;
+        let rows = document["sessions"].as_array().cloned().unwrap_or_default();
+        print!(
+            "{}",
+            format.render(&document, &rows, || {
+                let summary = status::summary_line(&snapshot.counts);
+                let summary = if color { color::summary(&summary) } else { summary };
+                format!("{summary}\n{}", launcher::plain(&sessions, color))
+            })?
+        );
+    }
+    std::process::exit(status::exit_code(&snapshot.counts));
+}
+
+fn run_interrupt(pane: &str, yes: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if !yes {
+        if !std::io::stdin().is_terminal() {
+            return Err("refusing to interrupt without confirmation; pass --yes".into());
+        }
+        eprint!("Interrupt the session in {pane}? [y/N] ");
+        let mut answer = String::new();
+        std::io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            return Ok(());
+        }
+    }
+    actions::interrupt(pane)?;
+    Ok(())
+}
+
+fn run_list(
+    config: &Config,
+    alfred: bool,
+    raycast: bool,
+    format: Format,
+    color: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let sessions = poll_once(&[], config);
+    let strings = config.strings();
+    if alfred {
+        println!("{}", launcher::alfred(&sessions, &strings));
+    } else if raycast {
+        let exe = std::env::current_exe()?;
+        println!("{}", launcher::raycast(&sessions, &strings, &exe.to_string_lossy()));
+    } else {
+        let rows = launcher::rows(&sessions);
+        print!("{}", format.render(&serde_json::Value::Array(rows.clone()), &rows, || launcher::plain(&sessions, color))?);
+    }
+    Ok(())
+}
+
+/// Fonts with Japanese, Chinese, and Korean glyphs, which egui's built-in
+/// fonts lack. The first one found is used as a fallback.
+const CJK_FONTS: &[&str] = &[
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/droid/DroidSansFallbackFull.ttf",
+    "/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+];
+
+/// Add the first of [`CJK_FONTS`] found so that Japanese project names and
+/// spinner text are drawn, and measured, with real glyphs.
+fn install_cjk_font(ctx: &egui::Context) {
+    let Some(data) = CJK_FONTS.iter().find_map(|path| std::fs::read(path).ok()) else {
+        claudeye::log!("no CJK font found; Japanese text will show as boxes");
+        return;
+    };
+    let mut fonts = egui::FontDefinitions::default();
+    fonts.font_data.insert("cjk".to_string(), Arc::new(egui::FontData::from_owned(data)));
+    for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+        fonts.families.entry(family).or_default().push("cjk".to_string());
+    }
+    ctx.set_fonts(fonts);
+}
+
+fn run_gui(args: &Args, config: Config) -> eframe::Result<()> {
+    let mut hooks = integration_hooks(&config);
+    hooks.alerting.extend(announce_hooks(args.announce));
+    let monitor = start_polling(hooks, config.clone());
+    let events = monitor.subscribe();
 
+    let light = args.light || config.light;
+    let strip = args.strip.or_else(|| config_strip(&config)).filter(|_| !light);
+    let position = match strip {
+        Some(edge) => edge.position(),
+        None if light && args.position.is_none() && config.position.is_none() => Position::TopRight,
+        None => args.position.unwrap_or_else(|| config_position(&config)),
+    };
     let options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_decorations(false)
             .with_always_on_top()
-            .with_mouse_passthrough(true)
+            .with_mouse_passthrough(!args.interactive && strip.is_none() && !light)
             .with_inner_size([MIN_WINDOW_WIDTH, WINDOW_EMPTY_HEIGHT])
             .with_transparent(true),
         ..Default::default()
@@ -108,15 +768,121 @@ fn run_gui(compact: bool, position: Position, center_on_stale: bool) -> eframe::
     eframe::run_native(
         "claudeye",
         options,
-        Box::new(|_cc| Ok(Box::new(CcMonitorApp { sessions, compact, position, center_on_stale }))),
+        Box::new(|cc| {
+            install_cjk_font(&cc.egui_ctx);
+            Ok(Box::new(CcMonitorApp {
+                sessions: SessionList::default(),
+                events,
+                monitor,
+                compact: args.compact,
+                position,
+                center_on_stale: args.center_on_stale,
+                interactive: args.interactive,
+                light,
+                strip,
+                strings: config.strings(),
+                elapsed: config.elapsed,
+                collapse_reviewed: config.collapse_reviewed,
+                row: RowOptions {
+                    show_age: config.show_age,
+                    show_context: config.show_context,
+                    narrow: args.narrow || config.narrow,
+                    name_format: config.name_format,
+                    answer_buttons: args.interactive,
+                    tooltips: args.interactive || strip.is_some(),
+                    clicks: if args.interactive { config.clicks } else { ClickConfig::default() },
+                },
+                guardrails: config.guardrail.clone(),
+                snooze_minutes: config.snooze_minutes,
+                narrow_width: config.narrow_width as f32,
+            }))
+        }),
     )
 }
 
+/// The `position` config setting, or the default when unset or invalid.
+fn config_position(config: &Config) -> Position {
+    let Some(name) = config.position.as_deref() else {
+        return Position::default();
+    };
+    clap::ValueEnum::from_str(name, true).unwrap_or_else(|_| {
+        claudeye::log!("ignoring unknown position {name:?}");
+        Position::default()
+    })
+}
+
+/// The `strip` config setting, or none when unset or invalid.
+fn config_strip(config: &Config) -> Option<Edge> {
+    let name = config.strip.as_deref()?;
+    clap::ValueEnum::from_str(name, true)
+        .inspect_err(|_| claudeye::log!("ignoring unknown strip edge {name:?}"))
+        .ok()
+}
+
 struct CcMonitorApp {
-    sessions: Arc<Mutex<Vec<ClaudeSession>>>,
+    /// The overlay's copy of the session list, brought up to date from
+    /// `events` every frame.
+    sessions: SessionList,
+    events: Receiver<SessionEvent>,
+    /// Told about rows dismissed from the overlay.
+    monitor: Monitor,
     compact: bool,
     position: Position,
     center_on_stale: bool,
+    interactive: bool,
+    /// Show only the traffic light.
+    light: bool,
+    /// Edge the overlay docks to as a strip until hovered.
+    strip: Option<Edge>,
+    strings: Strings,
+    elapsed: ElapsedFormat,
+    collapse_reviewed: bool,
+    row: RowOptions,
+    /// Checked before approving from the overlay.
+    guardrails: Vec<Guardrail>,
+    /// Length of a snooze from a row click.
+    snooze_minutes: u64,
+    /// Fixed overlay width in narrow mode.
+    narrow_width: f32,
+}
+
+/// Optional parts and layout of session rows.
+#[derive(Clone, Copy)]
+struct RowOptions {
+    show_age: bool,
+    show_context: bool,
+    /// Project on the first line, state and details on the second.
+    narrow: bool,
+    name_format: NameFormat,
+    /// Approve and deny buttons on sessions waiting for approval.
+    answer_buttons: bool,
+    /// Full details of a session when its bubble is hovered, which needs
+    /// the pointer (interactive or strip mode).
+    tooltips: bool,
+    /// Row actions of mouse gestures on a bubble (interactive mode).
+    clicks: ClickConfig,
+}
+
+impl CcMonitorApp {
+    fn row_height(&self, session: &ClaudeSession) -> f32 {
+        let full_row = session.is_live() && !(self.collapse_reviewed && session.is_reviewed());
+        if self.row.narrow && full_row { NARROW_ROW_HEIGHT } else { ROW_HEIGHT }
+    }
+
+    /// Run a row action clicked on the overlay; failures are only logged.
+    fn run_row_action(&self, session: &ClaudeSession, action: RowAction) {
+        let pane_id = &session.pane.id;
+        let result = match action {
+            RowAction::Switch => actions::switch(pane_id),
+            RowAction::Approve => actions::approve(pane_id, &self.guardrails, false),
+            RowAction::Deny => actions::deny(pane_id),
+            RowAction::Snooze => actions::snooze(pane_id, Some(self.snooze_minutes), session.snoozed),
+            RowAction::Transcript => transcript::open(&session.pane.cwd),
+        };
+        if let Err(e) = result {
+            claudeye::log!("{e}");
+        }
+    }
 }
 
 impl eframe::App for CcMonitorApp {
@@ -130,18 +896,34 @@ impl eframe::App for CcMonitorApp {
         ctx.set_visuals(visuals);
 
         ctx.send_viewport_cmd(egui::ViewportCommand::WindowLevel(egui::WindowLevel::AlwaysOnTop));
-        ctx.send_viewport_cmd(egui::ViewportCommand::MousePassthrough(true));
+        // The strip and the light need the pointer to notice hovering
+        ctx.send_viewport_cmd(egui::ViewportCommand::MousePassthrough(
+            !self.interactive && self.strip.is_none() && !self.light,
+        ));
 
-        let sessions = match self.sessions.lock() {
-            Ok(guard) => guard.clone(),
-            Err(_) => return, // poisoned mutex: polling thread panicked
-        };
+        self.sessions.catch_up(&self.events);
+        let sessions = self.sessions.sessions();
 
-        let needs_fast_repaint = sessions.iter().any(|s| matches!(s.state, ClaudeState::Working | ClaudeState::WaitingForApproval));
+        let needs_fast_repaint = sessions
+            .iter()
+            .any(|s| {
+                s.is_live()
+                    && matches!(
+                        s.state,
+                        ClaudeState::Working
+                            | ClaudeState::Compacting
+                            | ClaudeState::WaitingForApproval
+                            | ClaudeState::WaitingForAnswer
+                            | ClaudeState::Blocked
+                            | ClaudeState::Error
+                    )
+            });
         if needs_fast_repaint || self.compact {
             ctx.request_repaint_after(std::time::Duration::from_millis(100));
-        } else if !sessions.is_empty() {
-            // Repaint every second to keep elapsed time display up to date
+        } else if sessions.iter().any(|s| {
+            !s.is_live() || self.elapsed.ticks_every_second(s.state_changed_at.elapsed().as_secs())
+        }) {
+            // Repaint every second to keep elapsed and "ended ago" times up to date
             ctx.request_repaint_after(std::time::Duration::from_secs(1));
         } else {
             ctx.request_repaint_after(std::time::Duration::from_secs(REPAINT_INTERVAL_SECS));
@@ -149,6 +931,18 @@ impl eframe::App for CcMonitorApp {
 
         let time = ctx.input(|i| i.time);
 
+        if self.light {
+            show_light(ctx, sessions, self.position);
+            return;
+        }
+
+        if let Some(edge) = self.strip
+            && ctx.input(|i| i.pointer.hover_pos().is_none())
+        {
+            show_strip(ctx, edge, sessions, time);
+            return;
+        }
+
         // In compact mode, show one session at a time cycling every second
         let display_sessions: Vec<&ClaudeSession> = if self.compact && !sessions.is_empty() {
             let idx = (time as usize) % sessions.len();
@@ -157,22 +951,50 @@ impl eframe::App for CcMonitorApp {
             sessions.iter().collect()
         };
 
+        // Shown in place of "no sessions", or above the sessions of other
+        // sources, so that losing tmux doesn't pass for having no sessions.
+        // A restart of polling after a panic is shown there for a while too.
+        let now = elapsed::unix_now();
+        let banner = health::lost("tmux", now)
+            .map(|error| (self.strings.tmux_lost_label(&text::truncate(&error, TMUX_ERROR_MAX_WIDTH)), ALARM_COLOR))
+            .or_else(|| {
+                health::restarted_recently(now).then(|| (self.strings.monitoring_restarted.to_string(), WARNING_COLOR))
+            });
+        let empty_label = (display_sessions.is_empty() && banner.is_none()).then(|| {
+            EmptyReason::current().map_or_else(|| self.strings.no_sessions.to_string(), |r| r.label(&self.strings))
+        });
+        let banner_width = ctx.fonts(|fonts| {
+            banner
+                .iter()
+                .map(|(text, _)| text)
+                .chain(&empty_label)
+                .map(|text| {
+                    let font_id = egui::FontId::proportional(12.0);
+                    fonts.layout_no_wrap(text.clone(), font_id, Color32::WHITE).size().x + 16.0
+                })
+                .fold(0.0_f32, f32::max)
+        });
+
         let n = display_sessions.len() as f32;
         let window_height = if display_sessions.is_empty() {
             WINDOW_EMPTY_HEIGHT
         } else {
-            // ROW_HEIGHT per row + 4px item_spacing between rows + top/bottom padding
-            n * ROW_HEIGHT + (n - 1.0) * 4.0 + WINDOW_PADDING * 2.0
+            // Row heights + 4px item_spacing between rows + top/bottom padding
+            let rows: f32 = display_sessions.iter().map(|s| self.row_height(s)).sum();
+            let banner = if banner.is_some() { ROW_HEIGHT + 4.0 } else { 0.0 };
+            rows + banner + (n - 1.0) * 4.0 + WINDOW_PADDING * 2.0
         };
 
         let window_width = if display_sessions.is_empty() {
-            MIN_WINDOW_WIDTH
+            MIN_WINDOW_WIDTH.max(banner_width)
+        } else if self.row.narrow {
+            self.narrow_width.max(MIN_WINDOW_WIDTH)
         } else {
             let max_text = display_sessions
                 .iter()
-                .map(|s| measure_session_text_width(ctx, s))
+                .map(|s| measure_session_text_width(ctx, s, &self.strings, &self.elapsed, self.row))
                 .fold(0.0_f32, f32::max);
-            (max_text + ROW_HORIZONTAL_OVERHEAD).max(MIN_WINDOW_WIDTH)
+            (max_text + UNREAD_DOT_WIDTH + ROW_HORIZONTAL_OVERHEAD).max(MIN_WINDOW_WIDTH).max(banner_width)
         };
 
         ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(Vec2::new(
@@ -181,7 +1003,7 @@ impl eframe::App for CcMonitorApp {
         )));
 
         if let Some(monitor_size) = ctx.input(|i| i.viewport().monitor_size) {
-            let effective_position = if self.center_on_stale && has_stale_session(&sessions) {
+            let effective_position = if self.center_on_stale && has_stale_session(sessions) {
                 Position::MiddleCenter
             } else {
                 self.position
@@ -190,6 +1012,8 @@ impl eframe::App for CcMonitorApp {
             ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(pos));
         }
 
+        let mut dismissed = None;
+        let mut acted = None;
         egui::CentralPanel::default()
             .frame(
                 egui::Frame::none()
@@ -197,81 +1021,389 @@ impl eframe::App for CcMonitorApp {
                     .inner_margin(egui::Margin::symmetric(8.0, WINDOW_PADDING)),
             )
             .show(ctx, |ui| {
-                if display_sessions.is_empty() {
-                    ui.label(
-                        RichText::new("No Claude sessions found")
-                            .color(Color32::from_gray(120))
-                            .size(12.0),
-                    );
+                if let Some((banner, color)) = &banner {
+                    ui.label(RichText::new(banner).color(*color).size(12.0));
+                }
+                if let Some(empty_label) = &empty_label {
+                    ui.label(RichText::new(empty_label).color(Color32::from_gray(120)).size(12.0));
                 } else {
                     for session in &display_sessions {
-                        render_session_row(ui, session, time);
+                        if !session.is_live() {
+                            if render_ended_row(ui, session, &self.strings, self.row.name_format, self.interactive) {
+                                dismissed = Some(session.pane.id.clone());
+                            }
+                        } else if self.collapse_reviewed && session.is_reviewed() {
+                            render_collapsed_row(ui, session, self.row.name_format);
+                        } else {
+                            if let Some(action) =
+                                render_session_row(ui, session, time, &self.strings, &self.elapsed, self.row)
+                            {
+                                acted = Some(((*session).clone(), action));
+                            }
+                        }
                     }
                 }
             });
+
+        if let Some(pane_id) = dismissed {
+            self.sessions.dismiss(&pane_id);
+            self.monitor.dismiss(&pane_id);
+        }
+        if let Some((session, action)) = acted {
+            self.run_row_action(&session, action);
+        }
     }
 }
 
 /// Measure the rendered text width of a session row using the egui font system.
 ///
-/// State label is fixed to the longest value ("Approval") and elapsed to a
-/// wide placeholder ("9999s") to prevent jitter from state transitions or
+/// State label is fixed to the widest configured label and elapsed to a
+/// wide placeholder (e.g. "9999s") to prevent jitter from state transitions or
 /// ticking seconds.
-fn measure_session_text_width(ctx: &egui::Context, session: &ClaudeSession) -> f32 {
-    let text = format!(
-        "{}  {}  [{}] {}",
-        session.pane.id, session.pane.project_name, "Approval", "9999s"
-    );
+fn measure_session_text_width(
+    ctx: &egui::Context,
+    session: &ClaudeSession,
+    strings: &Strings,
+    elapsed: &ElapsedFormat,
+    options: RowOptions,
+) -> f32 {
     let font_id = egui::FontId::proportional(11.0);
+    let age = if options.show_age {
+        format!(" · {}", strings.age_label(99 * 3600 + 59 * 60))
+    } else {
+        String::new()
+    };
+    let context = if options.show_context {
+        session.context_label(strings).map(|label| format!(" · {label}")).unwrap_or_default()
+    } else {
+        String::new()
+    };
     ctx.fonts(|fonts| {
-        let galley = fonts.layout_no_wrap(text, font_id, Color32::WHITE);
-        galley.size().x
+        let mut badges = badges_width(fonts, session, strings);
+        if options.answer_buttons {
+            badges += answer_buttons_width(fonts, session);
+        }
+        // The spinner text is measured as well since it can outgrow the labels.
+        let activity = session.state_label(strings);
+        let tokens = if session.tokens.is_some() { TOKENS_PLACEHOLDER } else { "" };
+        let todos = session
+            .todo_progress
+            .map(|(done, total)| format!(" · {}", strings.todos_label(done, total)))
+            .unwrap_or_default();
+        let queued = match session.queued_messages {
+            0 => String::new(),
+            n => format!(" · {}", strings.queued_label(n)),
+        };
+        let last_turn = session.last_turn_label(strings).map(|label| format!(" · {label}")).unwrap_or_default();
+        let pending = session
+            .pending
+            .as_ref()
+            .map(|p| format!(" {}", p.summary(PENDING_MAX_WIDTH)))
+            .or_else(|| session.error.as_ref().map(|e| format!(" {}", text::truncate(e, PENDING_MAX_WIDTH))))
+            .or_else(|| session.input_prompt.as_ref().map(|p| format!(" {}", text::truncate(p, PENDING_MAX_WIDTH))))
+            .or_else(|| session.subagents_label(strings, PENDING_MAX_WIDTH).map(|s| format!(" {s}")))
+            .unwrap_or_default();
+        strings
+            .state_labels()
+            .into_iter()
+            .chain(session.activity.is_some().then_some(activity.as_ref()))
+            .map(|label| {
+                let text = format!(
+                    "{}  {}{}  [{}]{} {}{}{}{}{}{}{}",
+                    session.pane.id,
+                    session.display_name(options.name_format),
+                    pr_suffix(session),
+                    label,
+                    pending,
+                    elapsed.placeholder(),
+                    tokens,
+                    todos,
+                    queued,
+                    last_turn,
+                    context,
+                    age
+                );
+                fonts.layout_no_wrap(text, font_id.clone(), Color32::WHITE).size().x + badges
+            })
+            .fold(0.0_f32, f32::max)
     })
 }
 
+/// Width of the badges drawn after a session's bubble.
+fn badges_width(fonts: &egui::epaint::Fonts, session: &ClaudeSession, strings: &Strings) -> f32 {
+    let snooze = if session.snoozed { SNOOZE_BADGE.to_string() } else { String::new() };
+    let mode = session.mode_badge(strings).unwrap_or_default().to_string();
+    let bypass = if session.skips_permissions { strings.skips_permissions.to_string() } else { String::new() };
+    let approval = approval_badge(session, strings).map(|(badge, _)| badge.to_string()).unwrap_or_default();
+    let at_risk = at_risk_badge(session, strings).unwrap_or_default();
+    [bypass, mode, approval, at_risk, watch_badges(session), guardrail_badges(session), snooze]
+        .into_iter()
+        .filter(|text| !text.is_empty())
+        .map(|text| {
+            let font_id = egui::FontId::proportional(10.0);
+            fonts.layout_no_wrap(text, font_id, Color32::WHITE).size().x + 4.0
+        })
+        .sum()
+}
+
 fn calc_stroke_width(state: &ClaudeState, time: f64) -> f32 {
     match state {
-        ClaudeState::WaitingForApproval => {
+        ClaudeState::WaitingForApproval
+        | ClaudeState::WaitingForAnswer
+        | ClaudeState::Blocked
+        | ClaudeState::Error => {
             let pulse = ((time * 16.0).sin() as f32 + 1.0) / 2.0;
             1.0 + pulse * 2.0
         }
-        ClaudeState::Working | ClaudeState::Idle => 1.0,
+        ClaudeState::Working | ClaudeState::Compacting | ClaudeState::Idle | ClaudeState::NotRunning => 1.0,
+    }
+}
+
+fn state_color(state: &ClaudeState) -> Color32 {
+    match state {
+        ClaudeState::Working => Color32::from_rgb(80, 200, 80),
+        ClaudeState::Compacting => Color32::from_rgb(150, 120, 230),
+        ClaudeState::WaitingForApproval => Color32::from_rgb(220, 180, 0),
+        ClaudeState::WaitingForAnswer => Color32::from_rgb(60, 200, 200),
+        ClaudeState::Blocked => Color32::from_rgb(240, 90, 170),
+        ClaudeState::Idle => Color32::from_gray(160),
+        ClaudeState::Error => Color32::from_rgb(235, 95, 50),
+        ClaudeState::NotRunning => Color32::from_rgb(180, 80, 80),
+    }
+}
+
+/// Collapsed strip mode: one segment per session along `edge`, colored by
+/// state, with attention states pulsing as the bubble stroke does.
+fn show_strip(ctx: &egui::Context, edge: Edge, sessions: &[ClaudeSession], time: f64) {
+    let size = edge.strip_size(sessions.len());
+    ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(size));
+    if let Some(monitor_size) = ctx.input(|i| i.viewport().monitor_size) {
+        let pos = edge.position().compute(monitor_size, size);
+        ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(pos));
+    }
+    egui::CentralPanel::default()
+        .frame(egui::Frame::none().fill(Color32::TRANSPARENT))
+        .show(ctx, |ui| {
+            let origin = ui.max_rect().min;
+            let painter = ui.painter();
+            if sessions.is_empty() {
+                painter.rect_filled(edge.segment(origin, 0), 2.0, Color32::from_gray(70));
+            }
+            for (index, session) in sessions.iter().enumerate() {
+                painter.rect_filled(edge.segment(origin, index), 2.0, strip_color(session, time));
+            }
+        });
+}
+
+/// What the traffic light shows: the most urgent state across live sessions.
+#[derive(Debug, PartialEq)]
+enum Light {
+    /// No sessions.
+    Off,
+    /// Every session is working.
+    Green,
+    /// Some session is done or exited and waits for a new prompt.
+    Yellow,
+    /// Some session needs you: approval, an answer, a stuck command, an
+    /// error, or a guardrail alarm.
+    Red,
+}
+
+impl Light {
+    fn of(sessions: &[ClaudeSession]) -> Self {
+        let live: Vec<&ClaudeSession> = sessions.iter().filter(|s| s.is_live()).collect();
+        if live.is_empty() {
+            return Light::Off;
+        }
+        let needs_you = |s: &&ClaudeSession| {
+            s.tripped_guardrail()
+                || s.state == ClaudeState::Blocked
+                || (!s.snoozed
+                    && matches!(
+                        s.state,
+                        ClaudeState::WaitingForApproval | ClaudeState::WaitingForAnswer | ClaudeState::Error
+                    ))
+        };
+        if live.iter().any(needs_you) {
+            Light::Red
+        } else if live.iter().all(|s| matches!(s.state, ClaudeState::Working | ClaudeState::Compacting)) {
+            Light::Green
+        } else {
+            Light::Yellow
+        }
+    }
+
+    fn color(&self) -> Color32 {
+        match self {
+            Light::Off => Color32::from_gray(90),
+            Light::Green => state_color(&ClaudeState::Working),
+            Light::Yellow => state_color(&ClaudeState::WaitingForApproval),
+            Light::Red => ALARM_COLOR,
+        }
+    }
+}
+
+/// Traffic light mode: one circle in the `position` corner, with the
+/// session counts next to it while hovered.
+fn show_light(ctx: &egui::Context, sessions: &[ClaudeSession], position: Position) {
+    let hovered = ctx.input(|i| i.pointer.hover_pos().is_some());
+    let live = sessions.iter().filter(|s| s.is_live()).count();
+    let counts = status::StatusSnapshot::from_sessions(sessions).counts;
+    let text = format!("{live}  {}", status::summary_line(&counts)).trim_end().to_string();
+    let font_id = egui::FontId::proportional(11.0);
+    let text_width = if hovered {
+        ctx.fonts(|fonts| fonts.layout_no_wrap(text.clone(), font_id.clone(), Color32::WHITE).size().x) + 8.0
+    } else {
+        0.0
+    };
+    let size = Vec2::new(LIGHT_SIZE + text_width, LIGHT_SIZE);
+    ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(size));
+    // Grow away from the screen edge so the circle stays put
+    let on_right = matches!(position, Position::TopRight | Position::MiddleRight | Position::BottomRight);
+    if let Some(monitor_size) = ctx.input(|i| i.viewport().monitor_size) {
+        let mut pos = position.compute(monitor_size, Vec2::splat(LIGHT_SIZE));
+        if on_right {
+            pos.x -= text_width;
+        }
+        ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(pos));
+    }
+    egui::CentralPanel::default()
+        .frame(egui::Frame::none().fill(Color32::TRANSPARENT))
+        .show(ctx, |ui| {
+            let rect = ui.max_rect();
+            let painter = ui.painter();
+            let background = Color32::from_rgba_unmultiplied(30, 30, 45, 220);
+            painter.rect_filled(rect, LIGHT_SIZE / 2.0, background);
+            let (circle_x, text_x) = if on_right {
+                (rect.right() - LIGHT_SIZE / 2.0, rect.left() + 6.0)
+            } else {
+                (rect.left() + LIGHT_SIZE / 2.0, rect.left() + LIGHT_SIZE + 2.0)
+            };
+            let center = egui::pos2(circle_x, rect.center().y);
+            painter.circle_filled(center, LIGHT_SIZE / 2.0 - 3.0, Light::of(sessions).color());
+            if hovered {
+                painter.text(
+                    egui::pos2(text_x, rect.center().y),
+                    egui::Align2::LEFT_CENTER,
+                    text,
+                    font_id,
+                    Color32::from_gray(220),
+                );
+            }
+        });
+}
+
+fn strip_color(session: &ClaudeSession, time: f64) -> Color32 {
+    if !session.is_live() {
+        return Color32::from_gray(70);
     }
+    let (color, stroke_width) = if session.tripped_guardrail() {
+        (ALARM_COLOR, calc_stroke_width(&ClaudeState::WaitingForApproval, time))
+    } else if session.snoozed {
+        (state_color(&session.state), 1.0)
+    } else {
+        (state_color(&session.state), calc_stroke_width(&session.state, time))
+    };
+    // Stroke width pulses between 1 and 3; dim the segment in step with it
+    color.gamma_multiply(1.0 - (stroke_width - 1.0) * 0.2)
 }
 
-fn render_session_row(ui: &mut Ui, session: &ClaudeSession, time: f64) {
-    let (state_color, label) = match &session.state {
-        ClaudeState::Working => (Color32::from_rgb(80, 200, 80), "Running"),
-        ClaudeState::WaitingForApproval => (Color32::from_rgb(220, 180, 0), "Approval"),
-        ClaudeState::Idle => (Color32::from_gray(160), "Idle"),
+/// Mini robot art (fixed-width column, center-aligned) with the head in `head_color`.
+fn render_robot(ui: &mut Ui, head_color: Color32) {
+    ui.allocate_ui(egui::Vec2::new(40.0, ROW_HEIGHT), |ui| {
+        ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
+            ui.spacing_mut().item_spacing.y = 0.0;
+            let o = Color32::from_rgb(210, 110, 30);  // orange
+            let lines: [(&str, Color32); 4] = [
+                ("▟█▙", head_color),
+                ("▐▛███▜▌", o),
+                ("▝▜█████▛▘", o),
+                ("▘▘ ▝▝", o),
+            ];
+            for (text, color) in lines {
+                ui.label(RichText::new(text).size(5.0).color(color).monospace());
+            }
+        });
+    });
+}
+
+/// Minimal row for a reviewed Idle session: robot and dimmed project name only.
+fn render_collapsed_row(ui: &mut Ui, session: &ClaudeSession, name_format: NameFormat) {
+    ui.horizontal(|ui| {
+        ui.spacing_mut().item_spacing.x = 2.0;
+        render_robot(ui, state_color(&session.state));
+        ui.label(
+            RichText::new(session.display_name(name_format))
+                .color(Color32::from_gray(110))
+                .size(9.0),
+        );
+    });
+}
+
+/// Tombstone row for a pane that disappeared. Returns true when the user
+/// clicked its dismiss button (only shown in interactive mode).
+fn render_ended_row(ui: &mut Ui, session: &ClaudeSession, strings: &Strings, name_format: NameFormat, interactive: bool) -> bool {
+    let Some(ended_at) = session.ended_at else {
+        return false;
     };
+    let ran = session.age().as_secs();
+    let text = format!(
+        "{}  {}  {}",
+        session.pane.id,
+        session.display_name(name_format),
+        strings.ended_label(ended_at.elapsed().as_secs(), ran)
+    );
+    let mut clicked = false;
+    ui.horizontal(|ui| {
+        ui.spacing_mut().item_spacing.x = 2.0;
+        render_robot(ui, Color32::from_gray(70));
+        ui.label(RichText::new(text).color(Color32::from_gray(110)).size(11.0));
+        if interactive {
+            clicked = ui
+                .add(egui::Button::new(RichText::new("✕").size(10.0)).frame(false))
+                .clicked();
+        }
+    });
+    clicked
+}
 
-    let stroke_width = calc_stroke_width(&session.state, time);
+fn render_session_row(
+    ui: &mut Ui,
+    session: &ClaudeSession,
+    time: f64,
+    strings: &Strings,
+    elapsed_format: &ElapsedFormat,
+    options: RowOptions,
+) -> Option<RowAction> {
+    let label = session.state_label(strings);
+    let (state_color, stroke_width) = if session.tripped_guardrail() {
+        (ALARM_COLOR, calc_stroke_width(&ClaudeState::WaitingForApproval, time))
+    } else if session.snoozed {
+        (state_color(&session.state), 1.0)
+    } else {
+        (state_color(&session.state), calc_stroke_width(&session.state, time))
+    };
 
+    let mut action = None;
     ui.horizontal(|ui| {
         ui.spacing_mut().item_spacing.x = 2.0;
-        // Mini robot art or spinner (fixed-width column, center-aligned)
-        ui.allocate_ui(egui::Vec2::new(40.0, ROW_HEIGHT), |ui| {
-            ui.with_layout(egui::Layout::top_down(egui::Align::Center), |ui| {
-                ui.spacing_mut().item_spacing.y = 0.0;
-                let o = Color32::from_rgb(210, 110, 30);  // orange
-                let lines: [(&str, Color32); 4] = [
-                    ("▟█▙", state_color),
-                    ("▐▛███▜▌", o),
-                    ("▝▜█████▛▘", o),
-                    ("▘▘ ▝▝", o),
-                ];
-                for (text, color) in lines {
-                    ui.label(RichText::new(text).size(5.0).color(color).monospace());
-                }
-            });
-        });
+        render_robot(ui, state_color);
 
         // Speech bubble with tail pointing left toward robot
         ui.add_space(2.0); // space for the tail triangle
 
-        // Clamp bubble width to remaining available space (minus inner padding + stroke)
-        let max_label_width = (ui.available_width() - 14.0).max(0.0);
+        // Clamp bubble width to remaining available space (minus inner padding + stroke),
+        // leaving room for the badges when the overlay width is fixed
+        let reserved = if options.narrow {
+            ui.fonts(|fonts| {
+                badges_width(fonts, session, strings)
+                    + if options.answer_buttons { answer_buttons_width(fonts, session) } else { 0.0 }
+            })
+                + if session.shows_unread() { UNREAD_DOT_WIDTH } else { 0.0 }
+        } else {
+            0.0
+        };
+        let max_label_width = (ui.available_width() - 14.0 - reserved).max(0.0);
 
         let bubble_fill = Color32::from_rgba_unmultiplied(30, 30, 45, 220);
         let inner = egui::Frame::none()
@@ -281,17 +1413,83 @@ fn render_session_row(ui: &mut Ui, session: &ClaudeSession, time: f64) {
             .inner_margin(egui::Margin::symmetric(6.0, 2.0))
             .show(ui, |ui: &mut Ui| {
                 ui.set_max_width(max_label_width);
-                let elapsed = session.state_changed_at.elapsed().as_secs();
-                ui.label(
-                    RichText::new(format!(
-                        "{}  {}  [{}] {}s",
-                        session.pane.id, session.pane.project_name, label, elapsed
-                    ))
-                    .color(state_color)
-                    .size(11.0),
-                );
+                let head = format!("{}  {}{}", session.pane.id, session.display_name(options.name_format), pr_suffix(session));
+                let mut text = format!("[{label}]");
+                if let Some(pending) = &session.pending {
+                    text.push(' ');
+                    text.push_str(&pending.summary(PENDING_MAX_WIDTH));
+                }
+                if let Some(error) = &session.error {
+                    text.push(' ');
+                    text.push_str(&text::truncate(error, PENDING_MAX_WIDTH));
+                }
+                if let Some(prompt) = &session.input_prompt {
+                    text.push(' ');
+                    text.push_str(&text::truncate(prompt, PENDING_MAX_WIDTH));
+                }
+                if let Some(subagents) = session.subagents_label(strings, PENDING_MAX_WIDTH) {
+                    text.push(' ');
+                    text.push_str(&subagents);
+                }
+                if let Some(elapsed) = elapsed_format.format(session.elapsed_secs()) {
+                    text.push(' ');
+                    text.push_str(&elapsed);
+                }
+                if let Some(tokens) = session.tokens_label() {
+                    text.push_str(" · ");
+                    text.push_str(&tokens);
+                }
+                if let Some((done, total)) = session.todo_progress {
+                    text.push_str(" · ");
+                    text.push_str(&strings.todos_label(done, total));
+                }
+                if session.queued_messages > 0 {
+                    text.push_str(" · ");
+                    text.push_str(&strings.queued_label(session.queued_messages));
+                }
+                if let Some(last_turn) = session.last_turn_label(strings) {
+                    text.push_str(" · ");
+                    text.push_str(&last_turn);
+                }
+                if options.show_context && let Some(context) = session.context_label(strings) {
+                    text.push_str(" · ");
+                    text.push_str(&context);
+                }
+                if options.show_age {
+                    text.push_str(" · ");
+                    text.push_str(&strings.age_label(session.age().as_secs()));
+                }
+                if options.narrow {
+                    ui.vertical(|ui| {
+                        ui.spacing_mut().item_spacing.y = 0.0;
+                        ui.add(egui::Label::new(RichText::new(head).color(state_color).size(11.0)).truncate());
+                        ui.add(egui::Label::new(RichText::new(text).color(state_color).size(11.0)).truncate());
+                    });
+                } else {
+                    ui.label(RichText::new(format!("{head}  {text}")).color(state_color).size(11.0));
+                }
             });
 
+        // The bubble senses clicks only when a gesture is bound to it.
+        let bubble = if options.clicks == ClickConfig::default() {
+            inner.response.clone()
+        } else {
+            ui.interact(inner.response.rect, ui.id().with(("bubble", &session.pane.id)), egui::Sense::click())
+        };
+        if bubble.double_clicked() {
+            action = action.or(options.clicks.double);
+        } else if bubble.middle_clicked() {
+            action = action.or(options.clicks.middle);
+        }
+
+        if options.tooltips {
+            bubble.on_hover_ui(|ui| {
+                for line in session.detail_lines(strings, session.branch.as_deref(), Instant::now()) {
+                    ui.label(RichText::new(line).size(11.0));
+                }
+            });
+        }
+
         // Draw tail triangle pointing left toward the robot
         let rect = inner.response.rect;
         let mid_y = rect.center().y;
@@ -306,22 +1504,157 @@ fn render_session_row(ui: &mut Ui, session: &ClaudeSession, time: f64) {
         ));
         painter.line_segment([tail_tip, tail_top], egui::Stroke::new(stroke_width, state_color));
         painter.line_segment([tail_tip, tail_bot], egui::Stroke::new(stroke_width, state_color));
+
+        if session.shows_unread() {
+            ui.add_space(2.0);
+            ui.label(RichText::new("●").color(UNREAD_COLOR).size(8.0));
+        }
+        if session.skips_permissions {
+            ui.add_space(2.0);
+            ui.label(RichText::new(&*strings.skips_permissions).color(ALARM_COLOR).size(10.0).strong());
+        }
+        if let Some(badge) = session.mode_badge(strings) {
+            ui.add_space(2.0);
+            ui.label(RichText::new(badge).color(mode_color(session.permission_mode)).size(10.0));
+        }
+        if let Some((badge, kind)) = approval_badge(session, strings) {
+            ui.add_space(2.0);
+            ui.label(RichText::new(badge).color(approval_kind_color(kind)).size(10.0));
+        }
+        if let Some(badge) = at_risk_badge(session, strings) {
+            ui.add_space(2.0);
+            ui.label(RichText::new(badge).color(WARNING_COLOR).size(10.0).strong());
+        }
+        if !session.watch_hits.is_empty() {
+            ui.add_space(2.0);
+            ui.label(RichText::new(watch_badges(session)).color(WATCH_COLOR).size(10.0));
+        }
+        if session.tripped_guardrail() {
+            ui.add_space(2.0);
+            ui.label(RichText::new(guardrail_badges(session)).color(ALARM_COLOR).size(10.0).strong());
+        }
+        if session.snoozed {
+            ui.add_space(2.0);
+            ui.label(RichText::new(SNOOZE_BADGE).color(Color32::from_gray(120)).size(10.0));
+        }
+        if options.answer_buttons {
+            action = action.or(render_answer_buttons(ui, session));
+        }
     });
+    action
+}
+
+/// Approve (`✓`) and deny (`✗`) buttons of a session waiting for approval,
+/// each shown only when the action may be taken from outside the pane.
+fn render_answer_buttons(ui: &mut Ui, session: &ClaudeSession) -> Option<RowAction> {
+    let mut answer = None;
+    for (button, color, allowed, action) in [
+        (APPROVE_BUTTON, Color32::from_rgb(80, 200, 80), session.approvable(), RowAction::Approve),
+        (DENY_BUTTON, Color32::from_rgb(235, 95, 50), session.deniable(), RowAction::Deny),
+    ] {
+        if allowed {
+            ui.add_space(2.0);
+            let text = RichText::new(button).color(color).size(11.0).strong();
+            if ui.add(egui::Button::new(text).frame(false)).clicked() {
+                answer = Some(action);
+            }
+        }
+    }
+    answer
+}
+
+/// Width of the answer buttons a session row shows.
+fn answer_buttons_width(fonts: &egui::epaint::Fonts, session: &ClaudeSession) -> f32 {
+    [(APPROVE_BUTTON, session.approvable()), (DENY_BUTTON, session.deniable())]
+        .into_iter()
+        .filter(|(_, allowed)| *allowed)
+        .map(|(button, _)| {
+            let font_id = egui::FontId::proportional(11.0);
+            fonts.layout_no_wrap(button.to_string(), font_id, Color32::WHITE).size().x + 6.0
+        })
+        .sum()
+}
+
+/// Badge text for tripped guardrails, e.g. `⛔ rm -rf`.
+fn guardrail_badges(session: &ClaudeSession) -> String {
+    session
+        .guardrail_hits
+        .iter()
+        .map(|hit| format!("⛔ {hit}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `⚠ 3 uncommitted files` while a pending git command could discard them.
+fn at_risk_badge(session: &ClaudeSession, strings: &Strings) -> Option<String> {
+    session.at_risk_files.map(|files| format!("⚠ {}", strings.uncommitted_label(files)))
+}
+
+/// What kind of approval the session waits for, unless it is none of the
+/// recognized kinds.
+fn approval_badge<'a>(session: &ClaudeSession, strings: &'a Strings) -> Option<(&'a str, ApprovalKind)> {
+    let kind = session.pending.as_ref()?.kind;
+    Some((strings.approval_kind_badge(kind)?, kind))
+}
+
+/// Commands in gray, edits and trust decisions in amber.
+fn approval_kind_color(kind: ApprovalKind) -> Color32 {
+    match kind {
+        ApprovalKind::FileEdit | ApprovalKind::TrustDirectory => WARNING_COLOR,
+        ApprovalKind::McpTool => WATCH_COLOR,
+        ApprovalKind::PlanApproval => Color32::from_rgb(80, 200, 200),
+        ApprovalKind::BashCommand | ApprovalKind::Other => Color32::from_gray(170),
+    }
+}
+
+/// Plan mode in teal, auto-approving modes in amber and red.
+fn mode_color(mode: PermissionMode) -> Color32 {
+    match mode {
+        PermissionMode::Plan => Color32::from_rgb(80, 200, 200),
+        PermissionMode::AcceptEdits => WARNING_COLOR,
+        PermissionMode::BypassPermissions => ALARM_COLOR,
+        PermissionMode::Default => Color32::from_gray(120),
+    }
+}
+
+/// ` #123 ✓` after the project name when the session's branch has an open PR.
+fn pr_suffix(session: &ClaudeSession) -> String {
+    session.pr.as_ref().map(|pr| format!(" {}", pr.label())).unwrap_or_default()
+}
+
+/// Badge text for the `[[watch]]` rules matching a session, e.g. `⚑ FAILED`.
+fn watch_badges(session: &ClaudeSession) -> String {
+    session
+        .watch_hits
+        .iter()
+        .map(|hit| format!("⚑ {hit}"))
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 fn has_stale_session(sessions: &[ClaudeSession]) -> bool {
-    sessions.iter().any(|s| match s.state {
-        ClaudeState::WaitingForApproval => true,
-        ClaudeState::Idle => s.state_changed_at.elapsed().as_secs() >= STALE_THRESHOLD_SECS,
-        _ => false,
+    sessions.iter().filter(|s| s.is_live()).any(|s| {
+        // Guardrail alarms and commands stuck on a prompt are escalations
+        // and ignore snoozing.
+        if s.tripped_guardrail() || s.state == ClaudeState::Blocked {
+            return true;
+        }
+        !s.snoozed
+            && match s.state {
+                ClaudeState::WaitingForApproval | ClaudeState::WaitingForAnswer | ClaudeState::Error => true,
+                ClaudeState::Idle => s.state_changed_at.elapsed().as_secs() >= STALE_THRESHOLD_SECS,
+                _ => false,
+            }
     })
 }
 
 #[cfg(test)]
+// The layout tests check their constants and ranges the plain way.
+#[allow(clippy::assertions_on_constants, clippy::manual_range_contains)]
 mod tests {
     use super::*;
     use std::time::{Duration, Instant};
-    use crate::tmux::PaneInfo;
+    use claudeye::tmux::PaneInfo;
 
     #[test]
     fn stroke_width_working_is_always_one() {
@@ -362,6 +1695,16 @@ mod tests {
         assert_eq!(pos.y, (1080.0 - 40.0) / 2.0);
     }
 
+    #[test]
+    fn strip_runs_along_its_edge() {
+        assert_eq!(Edge::Right.strip_size(3), Vec2::new(STRIP_THICKNESS, 3.0 * STRIP_SEGMENT_LENGTH + 2.0 * STRIP_GAP));
+        assert_eq!(Edge::Top.strip_size(1), Vec2::new(STRIP_SEGMENT_LENGTH, STRIP_THICKNESS));
+        assert_eq!(Edge::Bottom.strip_size(0), Edge::Bottom.strip_size(1));
+        let segment = Edge::Left.segment(egui::pos2(0.0, 0.0), 1);
+        assert_eq!(segment.min, egui::pos2(0.0, STRIP_SEGMENT_LENGTH + STRIP_GAP));
+        assert_eq!(segment.height(), STRIP_SEGMENT_LENGTH);
+    }
+
     #[test]
     fn stroke_width_approval_always_pulses_strongly() {
         let mut saw_peak = false;
@@ -388,16 +1731,18 @@ mod tests {
     }
 
     fn make_session(state: ClaudeState, elapsed: Duration) -> ClaudeSession {
-        ClaudeSession {
-            pane: PaneInfo {
+        ClaudeSession::new(
+            PaneInfo {
                 id: "test".to_string(),
                 pid: 1,
                 cwd: "/tmp".to_string(),
                 project_name: "test-project".to_string(),
+                session_name: String::new(),
+                window_name: String::new(),
             },
             state,
-            state_changed_at: Instant::now() - elapsed,
-        }
+            Instant::now() - elapsed,
+        )
     }
 
     #[test]
@@ -441,6 +1786,29 @@ mod tests {
         assert!(has_stale_session(&sessions));
     }
 
+    #[test]
+    fn has_stale_session_guardrail_hit_while_working() {
+        let mut session = make_session(ClaudeState::Working, Duration::from_secs(1));
+        session.guardrail_hits = vec!["ssh keys".to_string()];
+        assert!(has_stale_session(&[session]));
+    }
+
+    #[test]
+    fn has_stale_session_ignores_snoozed_approval() {
+        let mut session = make_session(ClaudeState::WaitingForApproval, Duration::from_secs(1));
+        session.snoozed = true;
+        assert!(!has_stale_session(std::slice::from_ref(&session)));
+        session.guardrail_hits = vec!["rm -rf".to_string()];
+        assert!(has_stale_session(&[session]));
+    }
+
+    #[test]
+    fn has_stale_session_ignores_ended_sessions() {
+        let mut ended = make_session(ClaudeState::WaitingForApproval, Duration::from_secs(1));
+        ended.ended_at = Some(Instant::now());
+        assert!(!has_stale_session(&[ended]));
+    }
+
     #[test]
     fn has_stale_session_mixed_working_and_approval() {
         let sessions = vec![
@@ -449,4 +1817,59 @@ mod tests {
         ];
         assert!(has_stale_session(&sessions));
     }
+
+    #[test]
+    fn light_shows_the_most_urgent_state() {
+        assert_eq!(Light::of(&[]), Light::Off);
+        let working = make_session(ClaudeState::Working, Duration::from_secs(5));
+        let idle = make_session(ClaudeState::Idle, Duration::from_secs(5));
+        let approval = make_session(ClaudeState::WaitingForApproval, Duration::from_secs(5));
+        assert_eq!(Light::of(std::slice::from_ref(&working)), Light::Green);
+        assert_eq!(Light::of(&[working.clone(), idle.clone()]), Light::Yellow);
+        assert_eq!(Light::of(&[working, idle, approval.clone()]), Light::Red);
+
+        let mut snoozed = approval;
+        snoozed.snoozed = true;
+        assert_eq!(Light::of(std::slice::from_ref(&snoozed)), Light::Yellow);
+        snoozed.ended_at = Some(Instant::now());
+        assert_eq!(Light::of(&[snoozed]), Light::Off);
+    }
+
+    #[test]
+    fn narrow_rows_are_taller_except_ended_and_collapsed_ones() {
+        let monitor = Monitor::default();
+        let app = CcMonitorApp {
+            sessions: SessionList::default(),
+            events: monitor.subscribe(),
+            monitor,
+            compact: false,
+            position: Position::default(),
+            center_on_stale: false,
+            interactive: false,
+            light: false,
+            strip: None,
+            strings: claudeye::i18n::Language::En.strings().clone(),
+            elapsed: ElapsedFormat::default(),
+            collapse_reviewed: true,
+            row: RowOptions {
+                show_age: false,
+                show_context: false,
+                narrow: true,
+                name_format: NameFormat::Project,
+                answer_buttons: false,
+                tooltips: false,
+                clicks: ClickConfig::default(),
+            },
+            guardrails: vec![],
+            snooze_minutes: 30,
+            narrow_width: 240.0,
+        };
+        let working = make_session(ClaudeState::Working, Duration::from_secs(1));
+        assert_eq!(app.row_height(&working), NARROW_ROW_HEIGHT);
+        let reviewed = make_session(ClaudeState::Idle, Duration::from_secs(1));
+        assert_eq!(app.row_height(&reviewed), ROW_HEIGHT);
+        let mut ended = make_session(ClaudeState::Working, Duration::from_secs(1));
+        ended.ended_at = Some(Instant::now());
+        assert_eq!(app.row_height(&ended), ROW_HEIGHT);
+    }
 }
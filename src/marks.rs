@@ -0,0 +1,167 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::paths;
+
+/// What the user has already looked at in a pane.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaneMark {
+    /// Content hash of the pane the last time it was focused.
+    pub seen_hash: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snooze: Option<Snooze>,
+}
+
+/// Alerts of a pane silenced by the user.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Snooze {
+    /// Until the session leaves this state (a `status::state_key`).
+    UntilChange(String),
+    /// Until this Unix time.
+    Until(u64),
+}
+
+/// Per-pane marks persisted to `$XDG_STATE_HOME/claudeye/marks.json` so that
+/// the overlay and the picker (separate processes) share the same view of
+/// which sessions have unread output.
+#[derive(Debug, Default)]
+pub struct Marks {
+    panes: HashMap<String, PaneMark>,
+    /// The marks as loaded, to tell what changed here from what another
+    /// process saved in the meantime.
+    base: HashMap<String, PaneMark>,
+    dirty: bool,
+}
+
+impl Marks {
+    pub fn load() -> Self {
+        marks_path().map(|p| Self::load_from(&p)).unwrap_or_default()
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        let panes: HashMap<String, PaneMark> = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Self { base: panes.clone(), panes, dirty: false }
+    }
+
+    /// Write the marks back if anything changed since loading.
+    pub fn save(&mut self) {
+        if !self.dirty {
+            return;
+        }
+        if let Some(path) = marks_path()
+            && let Err(e) = self.save_to(&path)
+        {
+            crate::log!("failed to save {}: {e}", path.display());
+        }
+    }
+
+    /// Save the changes made since loading over the marks in `path`, field
+    /// by field, so that marks another process saved in the meantime (a
+    /// snooze from the picker while the overlay polls) are kept.
+    pub fn save_to(&mut self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut panes = Self::load_from(path).panes;
+        panes.retain(|id, _| self.panes.contains_key(id) || !self.base.contains_key(id));
+        for (id, mark) in &self.panes {
+            let base = self.base.get(id);
+            let saved = panes.entry(id.clone()).or_default();
+            if base.map(|b| b.seen_hash) != Some(mark.seen_hash) {
+                saved.seen_hash = mark.seen_hash;
+            }
+            if base.map(|b| &b.snooze) != Some(&mark.snooze) {
+                saved.snooze = mark.snooze.clone();
+            }
+        }
+        // Write to a sibling file and rename so a concurrent reader never
+        // sees a half-written file.
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string(&panes)?)?;
+        std::fs::rename(&tmp, path)?;
+        self.base = panes.clone();
+        self.panes = panes;
+        self.dirty = false;
+        Ok(())
+    }
+
+    pub fn get(&self, pane_id: &str) -> Option<&PaneMark> {
+        self.panes.get(pane_id)
+    }
+
+    /// Record the current content of a pane and report whether it has
+    /// output the user has not seen yet.
+    ///
+    /// A pane seen for the first time, or one that is currently focused in
+    /// an attached tmux client, counts as read.
+    pub fn observe(&mut self, pane_id: &str, content_hash: u64, focused: bool) -> bool {
+        let mark = self.panes.entry(pane_id.to_string()).or_default();
+        if focused || mark.seen_hash.is_none() {
+            if mark.seen_hash != Some(content_hash) {
+                mark.seen_hash = Some(content_hash);
+                self.dirty = true;
+            }
+            return false;
+        }
+        mark.seen_hash != Some(content_hash)
+    }
+
+    /// Mark a pane's current content as read, e.g. right before switching to it.
+    pub fn mark_seen(&mut self, pane_id: &str, content_hash: u64) {
+        let mark = self.panes.entry(pane_id.to_string()).or_default();
+        if mark.seen_hash != Some(content_hash) {
+            mark.seen_hash = Some(content_hash);
+            self.dirty = true;
+        }
+    }
+
+    pub fn snooze(&mut self, pane_id: &str, snooze: Snooze) {
+        self.panes.entry(pane_id.to_string()).or_default().snooze = Some(snooze);
+        self.dirty = true;
+    }
+
+    pub fn unsnooze(&mut self, pane_id: &str) {
+        if let Some(mark) = self.panes.get_mut(pane_id)
+            && mark.snooze.take().is_some()
+        {
+            self.dirty = true;
+        }
+    }
+
+    /// Whether the pane's alerts are snoozed while it is in `state`, dropping
+    /// a snooze that ended because the state changed or its time ran out.
+    pub fn is_snoozed(&mut self, pane_id: &str, state: &str, now: u64) -> bool {
+        let Some(mark) = self.panes.get_mut(pane_id) else {
+            return false;
+        };
+        let active = match &mark.snooze {
+            None => return false,
+            Some(Snooze::UntilChange(snoozed_state)) => snoozed_state == state,
+            Some(Snooze::Until(until)) => now < *until,
+        };
+        if !active {
+            mark.snooze = None;
+            self.dirty = true;
+        }
+        active
+    }
+
+    /// Forget panes that no longer exist; tmux reuses pane ids.
+    pub fn retain_panes(&mut self, live: &HashSet<&str>) {
+        let before = self.panes.len();
+        self.panes.retain(|id, _| live.contains(id.as_str()));
+        if self.panes.len() != before {
+            self.dirty = true;
+        }
+    }
+}
+
+fn marks_path() -> Option<PathBuf> {
+    Some(paths::state_dir()?.join("marks.json"))
+}
@@ -1,42 +1,1410 @@
-use std::sync::{Arc, Mutex};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
 use std::thread;
 use std::time::{Duration, Instant};
 
+/// How often panes are listed, to find new ones, whatever the
+/// [`PollIntervals`] of the known ones.
 const POLL_INTERVAL_SECS: u64 = 2;
+/// How often the polling thread adds its rule hits to `rules.json`.
+const RULE_STATS_SAVE_SECS: u64 = 30;
+/// Context left, in percent, at or below which a session is about to be
+/// compacted.
+pub const CONTEXT_LOW_PERCENT: u8 = 20;
+/// Earlier states kept per session, for the overlay's row tooltip, the
+/// picker's detail panel, and `status.json`.
+const HISTORY_LEN: usize = 20;
+/// Earlier states the overlay's row tooltip shows.
+const TOOLTIP_STATES: usize = 4;
 
-use crate::claude_state::{detect_state, ClaudeState};
-use crate::tmux::{self, PaneInfo};
+use serde::Deserialize;
+use serde_json::{json, Value};
 
-#[derive(Debug, Clone)]
+use crate::agents;
+use crate::claude_state::{self, ApprovalKind, ClaudeState, PendingApproval, PermissionMode, Subagents, UiLocale};
+use crate::config::Config;
+use crate::crash;
+use crate::demo;
+use crate::env_facts;
+use crate::files;
+use crate::format;
+use crate::filter::PaneRule;
+use crate::forge::{self, PullRequest};
+use crate::git;
+use crate::guardrail;
+use crate::health;
+use crate::i18n::Strings;
+use crate::kitty;
+use crate::elapsed;
+use crate::marks::{Marks, Snooze};
+use crate::notify::{self, Alert, Notifier};
+use crate::poke;
+use crate::process::{self, ProcessInfo};
+use crate::rule_stats::RuleStats;
+use crate::remote;
+use crate::rename::Renamer;
+use crate::screen;
+use crate::status;
+use crate::status::StatusWriter;
+use crate::team::TeamReporter;
+use crate::text;
+use crate::tmux::{self, NamingConfig, PaneInfo};
+use crate::watch;
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct ClaudeSession {
     pub pane: PaneInfo,
+    /// Agent CLI running in the pane (`claude`, `aider`, ...).
+    pub tool: &'static str,
     pub state: ClaudeState,
     pub state_changed_at: Instant,
+    /// Spinner text while working ("Thinking", "Adding handler types").
+    pub activity: Option<String>,
+    /// Claude's own timer for the current turn, as of the last poll.
+    pub timer: Option<Duration>,
+    /// Tokens streamed this turn, as of the last poll.
+    pub tokens: Option<u64>,
+    /// The command or file edit awaiting approval.
+    pub pending: Option<PendingApproval>,
+    /// The error banner of a session in the Error state.
+    pub error: Option<String>,
+    /// The prompt a Blocked session's command waits at.
+    pub input_prompt: Option<String>,
+    /// Subagents still running on the session's behalf.
+    pub subagents: Option<Subagents>,
+    /// Completed and total items of the session's todo list.
+    pub todo_progress: Option<(u32, u32)>,
+    /// Plan mode, accept edits, or neither.
+    pub permission_mode: PermissionMode,
+    /// The session was seen bypassing permission checks, so it never asks
+    /// for approval. Kept for the session's lifetime once seen.
+    pub skips_permissions: bool,
+    /// Model shown in the footer.
+    pub model: Option<String>,
+    /// Percentage of the context window left.
+    pub context_left: Option<u8>,
+    /// Idle after being stopped with Esc, waiting for redirection.
+    pub interrupted: bool,
+    /// Messages typed while working, waiting to be sent.
+    pub queued_messages: u32,
+    /// How long the last turn of an Idle session took.
+    pub last_turn_duration: Option<Duration>,
+    /// Hash of the last captured pane content.
+    pub content_hash: u64,
+    /// The pane produced output since the user last focused it.
+    pub unread: bool,
+    /// When claudeye first saw this pane running claude.
+    pub first_seen: Instant,
+    /// When the claude process started, falling back to `first_seen` when
+    /// the process start time cannot be determined.
+    pub started_at: Instant,
+    /// Set once the pane has disappeared; the session is kept as an
+    /// "ended" row until the tombstone grace period runs out.
+    pub ended_at: Option<Instant>,
+    /// Names of `[[watch]]` rules matching the current pane content.
+    pub watch_hits: Vec<String>,
+    /// Branch checked out in the pane's directory, for the row tooltip;
+    /// `None` outside a repository and for panes on other machines.
+    pub branch: Option<String>,
+    /// Open PR/MR of the pane's branch, when `[pull_requests]` is enabled.
+    pub pr: Option<PullRequest>,
+    /// Files edited or written by the agent, oldest first, as seen in the
+    /// pane since claudeye started watching it.
+    pub touched_files: Vec<String>,
+    /// Names of `[[guardrail]]` rules the session currently trips.
+    pub guardrail_hits: Vec<String>,
+    /// The user silenced this session's alerts.
+    pub snoozed: bool,
+    /// Uncommitted files in the repository while the session asks to run
+    /// a git command that can discard them (`git checkout`, `reset`, ...).
+    pub at_risk_files: Option<usize>,
+    /// A newly detected state still waiting out its debounce rule.
+    pub unconfirmed: Option<Candidate>,
+    /// The last [`HISTORY_LEN`] states the session was in before the current
+    /// one, with when each was entered, oldest first.
+    pub history: Vec<(ClaudeState, Instant)>,
+    /// Time spent in states the session has since left, over its whole
+    /// life rather than just [`ClaudeSession::history`].
+    pub totals: StateTotals,
 }
 
-pub fn start_polling(sessions: Arc<Mutex<Vec<ClaudeSession>>>) {
-    thread::spawn(move || loop {
-        let panes = tmux::list_claude_panes();
-        let prev = sessions.lock().ok().map(|g| g.clone()).unwrap_or_default();
-        let now = Instant::now();
-        let updated: Vec<ClaudeSession> = panes
+/// How long a session has been working, waiting for approval, and idle,
+/// summed over every stretch in each state.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StateTotals {
+    pub working: Duration,
+    pub approval: Duration,
+    pub idle: Duration,
+}
+
+impl StateTotals {
+    /// These totals with a stretch of `lasted` in `state` added; other
+    /// states are not counted.
+    pub fn with(mut self, state: &ClaudeState, lasted: Duration) -> Self {
+        match state {
+            ClaudeState::Working => self.working += lasted,
+            ClaudeState::WaitingForApproval => self.approval += lasted,
+            ClaudeState::Idle => self.idle += lasted,
+            _ => {}
+        }
+        self
+    }
+}
+
+/// Settings from the `[monitor]` config table.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MonitorConfig {
+    /// How long a vanished pane stays listed as an "ended" row (0 disables).
+    pub tombstone_grace_secs: u64,
+    /// Display language of Claude Code, whose prompts detection looks for.
+    pub ui_locale: UiLocale,
+    /// How long a new state must persist before it is shown.
+    pub debounce: DebounceConfig,
+    /// Agent CLIs to monitor: `claude`, `aider`, `codex`, `gemini`.
+    pub agents: Vec<String>,
+    /// How often each pane is captured again, by its state.
+    pub poll_intervals: PollIntervals,
+    /// Where session names come from.
+    pub naming: NamingConfig,
+    /// Also monitor the windows of GNU screen sessions (Linux).
+    pub screen: bool,
+    /// Also monitor kitty windows through its remote control.
+    pub kitty: bool,
+    /// SSH hosts whose tmux panes are monitored too (`devbox`, `me@gpu1`).
+    pub remote_hosts: Vec<String>,
+    /// tmux binary to run instead of the one on `PATH`.
+    pub tmux_path: Option<PathBuf>,
+    /// Lines of scrollback captured above the visible screen (0: none).
+    pub capture_lines: usize,
+    /// Non-empty lines at the bottom of a capture that detection looks at.
+    pub detection_lines: usize,
+    /// When not empty, only panes matching one of these are monitored.
+    pub include: Vec<PaneRule>,
+    /// Panes matching any of these are never monitored.
+    pub exclude: Vec<PaneRule>,
+}
+
+impl Default for MonitorConfig {
+    fn default() -> Self {
+        Self {
+            tombstone_grace_secs: 60,
+            ui_locale: UiLocale::Auto,
+            debounce: DebounceConfig::default(),
+            poll_intervals: PollIntervals::default(),
+            agents: agents::builtin_names(),
+            naming: NamingConfig::default(),
+            screen: false,
+            kitty: false,
+            remote_hosts: vec![],
+            tmux_path: None,
+            capture_lines: 0,
+            detection_lines: claude_state::LAST_LINES_COUNT,
+            include: vec![],
+            exclude: vec![],
+        }
+    }
+}
+
+/// How sessions are named in the overlay, picker, and menu bar.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NameFormat {
+    /// Project name only (`myapp`).
+    #[default]
+    Project,
+    /// tmux session and window before the project (`work/api ▸ myapp`).
+    Tmux,
+}
+
+/// How long a newly detected state must persist before it replaces the
+/// current one, so that a pane caught mid-redraw does not flicker.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Debounce {
+    /// Consecutive polls that must detect the state.
+    pub polls: u32,
+    /// Seconds that must pass since it was first detected.
+    pub min_secs: u64,
+}
+
+impl Default for Debounce {
+    fn default() -> Self {
+        Self { polls: 1, min_secs: 0 }
+    }
+}
+
+/// The `[monitor.debounce]` table: a [`Debounce`] per state entered, keyed
+/// like `status.json` states.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DebounceConfig {
+    pub running: Debounce,
+    pub compacting: Debounce,
+    pub approval: Debounce,
+    pub answer: Debounce,
+    pub blocked: Debounce,
+    pub idle: Debounce,
+    pub error: Debounce,
+    pub not_running: Debounce,
+}
+
+impl Default for DebounceConfig {
+    fn default() -> Self {
+        Self {
+            running: Debounce::default(),
+            compacting: Debounce::default(),
+            approval: Debounce::default(),
+            answer: Debounce::default(),
+            blocked: Debounce::default(),
+            // A working pane briefly looks idle while the spinner redraws.
+            idle: Debounce { polls: 2, min_secs: 0 },
+            error: Debounce::default(),
+            not_running: Debounce::default(),
+        }
+    }
+}
+
+impl DebounceConfig {
+    pub fn for_state(&self, state: &ClaudeState) -> Debounce {
+        match state {
+            ClaudeState::Working => self.running,
+            ClaudeState::Compacting => self.compacting,
+            ClaudeState::WaitingForApproval => self.approval,
+            ClaudeState::WaitingForAnswer => self.answer,
+            ClaudeState::Blocked => self.blocked,
+            ClaudeState::Idle => self.idle,
+            ClaudeState::Error => self.error,
+            ClaudeState::NotRunning => self.not_running,
+        }
+    }
+}
+
+/// The `[monitor.poll_intervals]` table: milliseconds between captures of a
+/// pane per state it is in, keyed like `status.json` states. Panes that may
+/// need you soon are looked at more often than those at rest.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PollIntervals {
+    pub running: u64,
+    pub compacting: u64,
+    pub approval: u64,
+    pub answer: u64,
+    pub blocked: u64,
+    pub idle: u64,
+    pub error: u64,
+    pub not_running: u64,
+}
+
+impl Default for PollIntervals {
+    fn default() -> Self {
+        Self {
+            // A running session may stop at an approval any moment.
+            running: 500,
+            compacting: 2000,
+            approval: 500,
+            answer: 2000,
+            blocked: 2000,
+            idle: 5000,
+            error: 2000,
+            not_running: 5000,
+        }
+    }
+}
+
+impl PollIntervals {
+    pub fn for_state(&self, state: &ClaudeState) -> Duration {
+        let millis = match state {
+            ClaudeState::Working => self.running,
+            ClaudeState::Compacting => self.compacting,
+            ClaudeState::WaitingForApproval => self.approval,
+            ClaudeState::WaitingForAnswer => self.answer,
+            ClaudeState::Blocked => self.blocked,
+            ClaudeState::Idle => self.idle,
+            ClaudeState::Error => self.error,
+            ClaudeState::NotRunning => self.not_running,
+        };
+        Duration::from_millis(millis)
+    }
+
+    /// When `session`, captured at `captured_at`, is to be captured again.
+    /// A state still being confirmed by the debounce is polled at its own
+    /// pace if that is faster.
+    pub fn due_at(&self, session: &ClaudeSession, captured_at: Instant) -> Instant {
+        let interval = match &session.unconfirmed {
+            Some(candidate) => self.for_state(&session.state).min(self.for_state(&candidate.state)),
+            None => self.for_state(&session.state),
+        };
+        captured_at + interval
+    }
+}
+
+/// A detected state that differs from the shown one but has not yet
+/// persisted long enough to replace it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub state: ClaudeState,
+    /// Consecutive polls that detected it.
+    pub polls: u32,
+    /// When it was first detected.
+    pub since: Instant,
+}
+
+/// Apply `rule` to a newly `detected` state: the state to show, when it
+/// was entered if it just changed, and the candidate still waiting.
+pub fn debounce(
+    shown: &ClaudeState,
+    candidate: Option<&Candidate>,
+    detected: ClaudeState,
+    rule: Debounce,
+    now: Instant,
+) -> (ClaudeState, Option<Instant>, Option<Candidate>) {
+    if detected == *shown {
+        return (detected, None, None);
+    }
+    let (polls, since) = match candidate {
+        Some(c) if c.state == detected => (c.polls + 1, c.since),
+        _ => (1, now),
+    };
+    if polls >= rule.polls && now.saturating_duration_since(since) >= Duration::from_secs(rule.min_secs) {
+        return (detected, Some(since), None);
+    }
+    (shown.clone(), None, Some(Candidate { state: detected, polls, since }))
+}
+
+impl ClaudeSession {
+    pub fn new(pane: PaneInfo, state: ClaudeState, state_changed_at: Instant) -> Self {
+        Self {
+            pane,
+            tool: agents::CLAUDE,
+            state,
+            state_changed_at,
+            activity: None,
+            timer: None,
+            tokens: None,
+            pending: None,
+            error: None,
+            input_prompt: None,
+            subagents: None,
+            todo_progress: None,
+            permission_mode: PermissionMode::Default,
+            skips_permissions: false,
+            model: None,
+            context_left: None,
+            interrupted: false,
+            queued_messages: 0,
+            last_turn_duration: None,
+            content_hash: 0,
+            unread: false,
+            first_seen: state_changed_at,
+            started_at: state_changed_at,
+            ended_at: None,
+            watch_hits: vec![],
+            branch: None,
+            pr: None,
+            touched_files: vec![],
+            guardrail_hits: vec![],
+            snoozed: false,
+            at_risk_files: None,
+            unconfirmed: None,
+            history: vec![],
+            totals: StateTotals::default(),
+        }
+    }
+
+    /// Project name, tagged with the tool unless the session is claude's
+    /// (`api (aider)`), and led by its tmux session and window in
+    /// [`NameFormat::Tmux`] (`work/api ▸ myapp`).
+    pub fn display_name(&self, format: NameFormat) -> Cow<'_, str> {
+        let name = if self.tool == agents::CLAUDE {
+            Cow::Borrowed(self.pane.project_name.as_str())
+        } else {
+            Cow::Owned(format!("{} ({})", self.pane.project_name, self.tool))
+        };
+        match format {
+            NameFormat::Project => name,
+            NameFormat::Tmux => Cow::Owned(format!("{}/{} ▸ {name}", self.pane.session_name, self.pane.window_name)),
+        }
+    }
+
+    pub fn is_live(&self) -> bool {
+        self.ended_at.is_none()
+    }
+
+    /// Total runtime of the session, up to its end for ended sessions.
+    pub fn age(&self) -> Duration {
+        self.ended_at
+            .unwrap_or_else(Instant::now)
+            .saturating_duration_since(self.started_at)
+    }
+
+    /// Whether to show the unread indicator: only finished sessions count,
+    /// since a running one is expected to keep producing output.
+    pub fn shows_unread(&self) -> bool {
+        self.unread && self.state == ClaudeState::Idle
+    }
+
+    /// The session matches a `[[guardrail]]` and must not be auto-approved.
+    pub fn tripped_guardrail(&self) -> bool {
+        !self.guardrail_hits.is_empty()
+    }
+
+    /// Whether the session's approval prompt may be accepted without
+    /// switching to it, as [`crate::actions::approve`] does.
+    pub fn approvable(&self) -> bool {
+        self.answerable() && !self.tripped_guardrail()
+    }
+
+    /// Whether the session's approval prompt may be rejected without
+    /// switching to it, as [`crate::actions::deny`] does.
+    pub fn deniable(&self) -> bool {
+        self.answerable() && self.pending.as_ref().is_none_or(|p| p.kind != ApprovalKind::TrustDirectory)
+    }
+
+    fn answerable(&self) -> bool {
+        self.is_live()
+            && self.state == ClaudeState::WaitingForApproval
+            && !self.pending.as_ref().is_some_and(|p| p.is_login())
+    }
+
+    /// Label for the session's state: the spinner text while working
+    /// (`Thinking…`), `Interrupted` for an Idle session stopped with Esc,
+    /// the configured state label otherwise.
+    pub fn state_label<'a>(&'a self, strings: &'a Strings) -> Cow<'a, str> {
+        match &self.activity {
+            Some(activity) => Cow::Owned(format!("{activity}…")),
+            None if self.interrupted && self.state == ClaudeState::Idle => Cow::Borrowed(&strings.interrupted),
+            None => Cow::Borrowed(strings.state_label(&self.state)),
+        }
+    }
+
+    /// Seconds to show as the state's elapsed time: Claude's own turn timer
+    /// while it is known, our wall clock since the state change otherwise.
+    pub fn elapsed_secs(&self) -> u64 {
+        match (&self.state, self.timer) {
+            (ClaudeState::Working | ClaudeState::Compacting, Some(timer)) => timer.as_secs(),
+            _ => self.state_changed_at.elapsed().as_secs(),
+        }
+    }
+
+    /// Token count and throughput of the current turn, e.g. `1.2k tok · 11/s`.
+    pub fn tokens_label(&self) -> Option<String> {
+        let tokens = self.tokens?;
+        let count = if tokens >= 1000 {
+            format!("{:.1}k", tokens as f64 / 1000.0)
+        } else {
+            tokens.to_string()
+        };
+        match self.timer.map(|t| t.as_secs()).filter(|&secs| secs > 0) {
+            Some(secs) => Some(format!("{count} tok · {}/s", tokens / secs)),
+            None => Some(format!("{count} tok")),
+        }
+    }
+
+    /// Running subagents and the active one, e.g.
+    /// `subagents: 2 · Explore: Find the config loader`, with the active
+    /// one shortened to `max_width` columns.
+    pub fn subagents_label(&self, strings: &Strings, max_width: usize) -> Option<String> {
+        let subagents = self.subagents.as_ref()?;
+        let active = text::truncate(&subagents.active, max_width);
+        Some(format!("{} · {active}", strings.subagents_label(subagents.running)))
+    }
+
+    /// How long the turn an Idle session finished took, e.g. `took 43s`.
+    pub fn last_turn_label(&self, strings: &Strings) -> Option<String> {
+        let duration = self.last_turn_duration.filter(|_| self.state == ClaudeState::Idle)?;
+        Some(strings.last_turn_label(duration.as_secs()))
+    }
+
+    /// Model and context left, e.g. `Opus 4.6 · 37% context left`.
+    pub fn context_label(&self, strings: &Strings) -> Option<String> {
+        let context = self.context_left.map(|percent| strings.context_label(percent));
+        match (&self.model, context) {
+            (Some(model), Some(context)) => Some(format!("{model} · {context}")),
+            (Some(model), None) => Some(model.clone()),
+            (None, context) => context,
+        }
+    }
+
+    /// The current state and the earlier ones in [`ClaudeSession::history`],
+    /// latest first, each with how long it lasted (the current one so far).
+    pub fn timeline(&self, now: Instant) -> Vec<(&ClaudeState, Duration)> {
+        let ends = self.history.iter().skip(1).map(|(_, at)| *at).chain([self.state_changed_at]);
+        let earlier: Vec<_> =
+            self.history.iter().zip(ends).map(|((state, entered), left)| (state, left.saturating_duration_since(*entered))).collect();
+        std::iter::once((&self.state, now.saturating_duration_since(self.state_changed_at)))
+            .chain(earlier.into_iter().rev())
+            .collect()
+    }
+
+    /// [`ClaudeSession::totals`] with the current stretch counted up to `now`.
+    pub fn time_in_states(&self, now: Instant) -> StateTotals {
+        self.totals.with(&self.state, now.saturating_duration_since(self.state_changed_at))
+    }
+
+    /// Details for the overlay's row tooltip: the working directory, pane and
+    /// `branch`, the recent states with how long each lasted (latest first),
+    /// and the whole pending command, error, or input prompt.
+    pub fn detail_lines(&self, strings: &Strings, branch: Option<&str>, now: Instant) -> Vec<String> {
+        let mut lines = vec![self.pane.cwd.clone()];
+        lines.push(match branch {
+            Some(branch) => format!("{} · ⎇ {branch}", self.pane.id),
+            None => self.pane.id.clone(),
+        });
+        let states: Vec<String> = self
+            .timeline(now)
             .into_iter()
-            .map(|pane| {
-                let content = tmux::capture_pane(&pane.id);
-                let state = detect_state(&content);
-                let state_changed_at = prev
-                    .iter()
-                    .find(|s| s.pane.id == pane.id && s.state == state)
-                    .map(|s| s.state_changed_at)
-                    .unwrap_or(now);
-                ClaudeSession { pane, state, state_changed_at }
-            })
+            .take(1 + TOOLTIP_STATES)
+            .map(|(state, lasted)| format!("{} {}", strings.state_label(state), elapsed::compact(lasted.as_secs())))
             .collect();
+        lines.push(states.join(" ← "));
+        if let Some(pending) = &self.pending {
+            lines.push(format!("{}: {}", pending.tool, pending.detail));
+        }
+        lines.extend(self.error.iter().chain(&self.input_prompt).cloned());
+        lines
+    }
 
-        if let Ok(mut lock) = sessions.lock() {
-            *lock = updated;
+    /// The permission mode badge, unless the bypass warning replaces it.
+    pub fn mode_badge<'a>(&self, strings: &'a Strings) -> Option<&'a str> {
+        if self.skips_permissions && self.permission_mode == PermissionMode::BypassPermissions {
+            return None;
         }
+        strings.permission_mode_badge(self.permission_mode)
+    }
+
+    /// Little context is left, so the session will compact soon.
+    pub fn context_low(&self) -> bool {
+        self.context_left.is_some_and(|percent| percent <= CONTEXT_LOW_PERCENT)
+    }
+
+    /// A finished session whose output has been looked at or marked reviewed.
+    pub fn is_reviewed(&self) -> bool {
+        !self.unread && self.state == ClaudeState::Idle
+    }
+}
+
+/// A state change of a pane observed between two consecutive polls.
+#[derive(Debug, Clone)]
+pub struct Transition {
+    pub pane: PaneInfo,
+    pub from: ClaudeState,
+    pub to: ClaudeState,
+    /// How long the pane stayed in `from` before the change.
+    pub elapsed: Duration,
+    /// What a session entering WaitingForApproval asks approval for.
+    pub approval: Option<ApprovalKind>,
+}
+
+/// Callback invoked on the polling thread for every observed transition.
+pub type TransitionHook = Box<dyn FnMut(&Transition) + Send>;
+
+/// The transition hooks of [`start_polling`]. Alerting hooks tell someone
+/// about a transition and keep quiet while its session is snoozed;
+/// recording hooks keep track of every transition.
+pub struct Hooks {
+    pub alerting: Vec<TransitionHook>,
+    pub recording: Vec<TransitionHook>,
+    /// Alerts hooks raise later from their own threads, shown at the next
+    /// poll through the same [`Notifier`] as the monitor's.
+    alerts: (Sender<Alert>, Receiver<Alert>),
+}
+
+impl Default for Hooks {
+    fn default() -> Self {
+        Self { alerting: vec![], recording: vec![], alerts: mpsc::channel() }
+    }
+}
+
+impl Hooks {
+    /// Where a hook sends the alerts it raises.
+    pub fn alerts(&self) -> Sender<Alert> {
+        self.alerts.0.clone()
+    }
+
+    /// Run the hooks for `transition`. A hook that panics is logged and
+    /// the others still run, so the poll goes through and the transition
+    /// is not seen (and recorded) again by the next one.
+    pub fn run(&mut self, transition: &Transition, snoozed: bool) {
+        let alerting = if snoozed { &mut [][..] } else { &mut self.alerting[..] };
+        for hook in self.recording.iter_mut().chain(alerting) {
+            if panic::catch_unwind(AssertUnwindSafe(|| hook(transition))).is_err() {
+                crate::log!(
+                    "a transition hook panicked on {} ({} -> {})",
+                    transition.pane.id,
+                    status::state_key(&transition.from),
+                    status::state_key(&transition.to)
+                );
+            }
+        }
+    }
+}
+
+/// A change to the session list, as sent to the subscribers of a
+/// [`Monitor`]. Applied in order to a [`SessionList`], the events of a poll
+/// turn the previous list into the new one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SessionEvent {
+    /// A session appeared, at `index` in the list.
+    Added { index: usize, session: ClaudeSession },
+    /// The session of `pane` left the list: its tombstone expired or it
+    /// was dismissed.
+    Removed { pane: String },
+    /// The session now at `index` went from state `from` to another.
+    StateChanged { index: usize, from: ClaudeState, session: ClaudeSession },
+    /// The session now at `index` changed in anything but its state, or
+    /// moved there.
+    Updated { index: usize, session: ClaudeSession },
+}
+
+impl SessionEvent {
+    fn pane(&self) -> &str {
+        match self {
+            SessionEvent::Removed { pane } => pane,
+            SessionEvent::Added { session, .. }
+            | SessionEvent::StateChanged { session, .. }
+            | SessionEvent::Updated { session, .. } => &session.pane.id,
+        }
+    }
+}
 
-        thread::sleep(Duration::from_secs(POLL_INTERVAL_SECS));
+/// The events turning `prev` into `updated`: removals first, then, in list
+/// order, every session that is new, changed, or out of place.
+pub fn session_events(prev: &[ClaudeSession], updated: &[ClaudeSession]) -> Vec<SessionEvent> {
+    let in_updated = |pane: &str| updated.iter().any(|s| s.pane.id == pane);
+    let mut events: Vec<SessionEvent> = prev
+        .iter()
+        .filter(|p| !in_updated(&p.pane.id))
+        .map(|p| SessionEvent::Removed { pane: p.pane.id.clone() })
+        .collect();
+    // The panes in the order a subscriber has them so far.
+    let mut order: Vec<&str> = prev.iter().map(|p| p.pane.id.as_str()).filter(|pane| in_updated(pane)).collect();
+    for (index, session) in updated.iter().enumerate() {
+        let pane = session.pane.id.as_str();
+        let event = match prev.iter().find(|p| p.pane.id == pane) {
+            None => SessionEvent::Added { index, session: session.clone() },
+            Some(old) if old.state != session.state => {
+                SessionEvent::StateChanged { index, from: old.state.clone(), session: session.clone() }
+            }
+            Some(old) if old != session || order.get(index) != Some(&pane) => {
+                SessionEvent::Updated { index, session: session.clone() }
+            }
+            Some(_) => continue,
+        };
+        order.retain(|p| *p != pane);
+        order.insert(index.min(order.len()), pane);
+        events.push(event);
+    }
+    events
+}
+
+/// A subscriber's copy of the session list, brought up to date by
+/// applying the [`SessionEvent`]s of a [`Monitor`].
+#[derive(Debug, Default)]
+pub struct SessionList {
+    sessions: Vec<ClaudeSession>,
+    /// Panes dismissed here whose removal has not come back as an event
+    /// yet; events about their ended session still queued are dropped.
+    dismissed: HashSet<String>,
+}
+
+impl SessionList {
+    pub fn sessions(&self) -> &[ClaudeSession] {
+        &self.sessions
+    }
+
+    pub fn apply(&mut self, event: SessionEvent) {
+        let stale = match &event {
+            SessionEvent::Removed { pane } => {
+                self.dismissed.remove(pane);
+                false
+            }
+            SessionEvent::Added { session, .. }
+            | SessionEvent::StateChanged { session, .. }
+            | SessionEvent::Updated { session, .. } => {
+                !session.is_live() && self.dismissed.contains(&session.pane.id)
+            }
+        };
+        if stale {
+            return;
+        }
+        self.dismissed.remove(event.pane());
+        self.sessions.retain(|s| s.pane.id != event.pane());
+        match event {
+            SessionEvent::Removed { .. } => {}
+            SessionEvent::Added { index, session }
+            | SessionEvent::StateChanged { index, session, .. }
+            | SessionEvent::Updated { index, session } => {
+                self.sessions.insert(index.min(self.sessions.len()), session);
+            }
+        }
+    }
+
+    /// Apply the events waiting on `events`, without blocking. Returns
+    /// whether there were any.
+    pub fn catch_up(&mut self, events: &Receiver<SessionEvent>) -> bool {
+        let mut any = false;
+        while let Ok(event) = events.try_recv() {
+            self.apply(event);
+            any = true;
+        }
+        any
+    }
+
+    /// Drop the ended session of `pane`; see [`Monitor::dismiss`].
+    pub fn dismiss(&mut self, pane: &str) {
+        let before = self.sessions.len();
+        self.sessions.retain(|s| s.is_live() || s.pane.id != pane);
+        if self.sessions.len() != before {
+            self.dismissed.insert(pane.to_string());
+        }
+    }
+}
+
+/// Requests to the polling thread.
+enum Control {
+    Subscribe(Sender<SessionEvent>),
+    Dismiss(String),
+    /// Capture this pane again now (`claudeye poke`).
+    Poke(String),
+}
+
+/// Answer a request that does not wait for a poll.
+fn answer(request: Control, sessions: &mut Vec<ClaudeSession>, subscribers: &mut Vec<Sender<SessionEvent>>) {
+    match request {
+        Control::Subscribe(subscriber) => {
+            for event in session_events(&[], sessions) {
+                let _ = subscriber.send(event);
+            }
+            subscribers.push(subscriber);
+        }
+        Control::Dismiss(pane) => {
+            let before = sessions.clone();
+            sessions.retain(|s| s.is_live() || s.pane.id != pane);
+            publish(subscribers, &session_events(&before, sessions));
+        }
+        Control::Poke(_) => {}
+    }
+}
+
+/// Handle on the polling thread of [`start_polling`]. Requests are taken
+/// up at the start of the next poll. The default handle has no thread
+/// behind it: its subscriptions never receive anything.
+#[derive(Clone)]
+pub struct Monitor {
+    control: Sender<Control>,
+}
+
+impl Monitor {
+    /// Events for every change to the session list from now on, starting
+    /// with an [`SessionEvent::Added`] for each current session.
+    pub fn subscribe(&self) -> Receiver<SessionEvent> {
+        let (events, receiver) = mpsc::channel();
+        let _ = self.control.send(Control::Subscribe(events));
+        receiver
+    }
+
+    /// Drop the ended session of `pane` before its tombstone expires.
+    pub fn dismiss(&self, pane: &str) {
+        let _ = self.control.send(Control::Dismiss(pane.to_string()));
+    }
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        Monitor { control: mpsc::channel().0 }
+    }
+}
+
+/// Send `events` to every subscriber, forgetting those that went away.
+fn publish(subscribers: &mut Vec<Sender<SessionEvent>>, events: &[SessionEvent]) {
+    subscribers.retain(|subscriber| events.iter().all(|event| subscriber.send(event.clone()).is_ok()));
+}
+
+pub fn start_polling(mut hooks: Hooks, config: Config) -> Monitor {
+    let (control, requests) = mpsc::channel();
+    let mut subscribers: Vec<Sender<SessionEvent>> = vec![];
+    // The session list of the last poll; subscribers keep copies of it.
+    let mut sessions: Vec<ClaudeSession> = vec![];
+    let mut status = config.status_file.then(StatusWriter::default);
+    let mut renamer = Renamer::new(config.tmux_rename);
+    let mut team = TeamReporter::new(&config.team);
+    let mut notifier = Notifier::new(&config.notify);
+    let mut tmux_lost = false;
+    // tmux hooks poke about a pane between polls (`install-tmux-binding`).
+    // Pokes join the requests, so one wait between polls serves both.
+    if let Some(pokes) = poke::listen() {
+        let control = control.clone();
+        thread::spawn(move || {
+            for pane in pokes {
+                if control.send(Control::Poke(pane)).is_err() {
+                    break;
+                }
+            }
+        });
+    }
+    let mut poked: Option<String> = None;
+    // When each pane was last captured, to capture it again once due.
+    let mut captured: HashMap<String, Instant> = HashMap::new();
+    // Rule hits counted since they were last added to `rules.json`.
+    let mut rule_hits = RuleStats::default();
+    let mut rule_hits_saved = Instant::now();
+    // Consecutive polls that panicked, to back off before trying again.
+    let mut panics = 0;
+    thread::spawn(move || loop {
+        // A poll that panics (on tmux output nobody anticipated)
+        // is logged and retried instead of stopping monitoring for good.
+        let polled = panic::catch_unwind(AssertUnwindSafe(|| {
+            let prev = sessions.clone();
+            let started = Instant::now();
+            let intervals = &config.monitor.poll_intervals;
+            let kept: HashSet<String> = prev
+                .iter()
+                .filter(|s| s.is_live())
+                .filter(|s| match &poked {
+                    Some(pane) => s.pane.id != *pane,
+                    None => captured.get(&s.pane.id).is_some_and(|at| intervals.due_at(s, *at) > started),
+                })
+                .map(|s| s.pane.id.clone())
+                .collect();
+            let updated = poll(&prev, &config, |pane| kept.contains(pane), Some(&mut rule_hits));
+            if rule_hits_saved.elapsed() >= Duration::from_secs(RULE_STATS_SAVE_SECS) {
+                let live: HashSet<&str> = updated.iter().map(|s| s.pane.id.as_str()).collect();
+                let mut rule_stats = RuleStats::load();
+                rule_stats.add(&rule_hits, &live);
+                rule_stats.save();
+                rule_hits = RuleStats::default();
+                rule_hits_saved = Instant::now();
+            }
+            captured.retain(|pane, _| updated.iter().any(|s| s.pane.id == *pane));
+            for session in updated.iter().filter(|s| s.is_live() && !kept.contains(&s.pane.id)) {
+                captured.insert(session.pane.id.clone(), started);
+            }
+            let next_poll = updated
+                .iter()
+                .filter(|s| s.is_live())
+                .filter_map(|s| Some(intervals.due_at(s, *captured.get(&s.pane.id)?)))
+                .fold(started + Duration::from_secs(POLL_INTERVAL_SECS), Instant::min);
+
+            for transition in detect_transitions(&prev, &updated) {
+                hooks.run(&transition, is_snoozed(&updated, &transition.pane.id));
+            }
+
+            let mut alerts = vec![];
+            if config.notifications {
+                for (pane, rule) in watch::new_matches(&config.watch, &prev, &updated) {
+                    if is_snoozed(&updated, &pane.id) {
+                        continue;
+                    }
+                    let body = format!("{} ({})", pane.project_name, pane.id);
+                    alerts.push(Alert { snooze: true, ..alert(&pane, &format!("watch:{rule}"), &format!("claudeye: {rule}"), body) });
+                }
+
+                // Failed requests stall a session until retried, and a command
+                // waiting on its prompt until someone types into the pane, so both
+                // notify urgently and regardless of snoozing.
+                for session in new_errors(&prev, &updated) {
+                    let body = format!(
+                        "{} ({}): {}",
+                        session.pane.project_name,
+                        session.pane.id,
+                        session.error.as_deref().unwrap_or_default()
+                    );
+                    alerts.push(Alert { urgent: true, ..alert(&session.pane, "error", "claudeye: error", body) });
+                }
+                for session in new_blocked(&prev, &updated) {
+                    let body = format!(
+                        "{} ({}) needs input: {}",
+                        session.pane.project_name,
+                        session.pane.id,
+                        session.input_prompt.as_deref().unwrap_or_default()
+                    );
+                    alerts.push(Alert { urgent: true, ..alert(&session.pane, "blocked", "claudeye: blocked", body) });
+                }
+
+                for (pane, rule) in guardrail::new_hits(&prev, &updated) {
+                    let body = format!("{} ({}) tripped guardrail \"{rule}\"", pane.project_name, pane.id);
+                    alerts.push(alert(&pane, &format!("guardrail:{rule}"), "claudeye: guardrail", body));
+                }
+
+                // Approving these loses work, so like guardrails they ignore snoozing.
+                for session in new_git_risks(&prev, &updated) {
+                    let body = format!(
+                        "{} ({}): {} with {} uncommitted files",
+                        session.pane.project_name,
+                        session.pane.id,
+                        session.pending.as_ref().map(|p| p.summary(80)).unwrap_or_default(),
+                        session.at_risk_files.unwrap_or_default()
+                    );
+                    alerts.push(Alert { urgent: true, ..alert(&session.pane, "git", "claudeye: uncommitted changes", body) });
+                }
+            }
+            alerts.extend(hooks.alerts.1.try_iter());
+            for alert in notifier.filter(alerts, elapsed::unix_now()) {
+                show(alert, config.snooze_minutes);
+            }
+
+            if let Some(status) = status.as_mut() {
+                status.update(&updated);
+            }
+            if let Some(team) = team.as_mut() {
+                team.update(&updated);
+            }
+            if let Some(renamer) = renamer.as_mut() {
+                renamer.update(&updated);
+            }
+
+            health::record_poll(updated.iter().filter(|s| s.is_live()).count(), elapsed::unix_now());
+            // Losing tmux otherwise looks just like having no sessions.
+            let lost = health::lost("tmux", elapsed::unix_now());
+            if lost.is_some() != tmux_lost {
+                tmux_lost = lost.is_some();
+                match &lost {
+                    Some(error) => crate::log!("lost tmux: {error}"),
+                    None => crate::log!("reached tmux again"),
+                }
+                if config.notifications {
+                    match lost {
+                        Some(error) => notify::urgent("claudeye: cannot reach tmux", &error),
+                        None => notify::desktop("claudeye: tmux is reachable again", ""),
+                    }
+                }
+            }
+            publish(&mut subscribers, &session_events(&prev, &updated));
+            sessions = updated;
+            next_poll
+        }));
+        let next_poll = match polled {
+            Ok(next_poll) => {
+                panics = 0;
+                next_poll
+            }
+            Err(_) => {
+                panics += 1;
+                // Capture every pane afresh.
+                captured.clear();
+                health::record_restart(elapsed::unix_now());
+                let backoff = restart_backoff(panics);
+                crate::log!("polling panicked; restarting in {}s", backoff.as_secs());
+                Instant::now() + backoff
+            }
+        };
+        // Requests are answered as they come while waiting for the next
+        // poll; a poke ends the wait.
+        poked = loop {
+            match requests.recv_timeout(next_poll.saturating_duration_since(Instant::now())) {
+                Ok(Control::Poke(pane)) => break Some(pane),
+                Ok(request) => answer(request, &mut sessions, &mut subscribers),
+                Err(RecvTimeoutError::Timeout) => break None,
+                // Every handle dropped (`speak`): just wait out the interval.
+                Err(RecvTimeoutError::Disconnected) => {
+                    thread::sleep(next_poll.saturating_duration_since(Instant::now()));
+                    break None;
+                }
+            }
+        };
     });
+    Monitor { control }
+}
+
+/// How long to wait before polling again after `panics` polls in a row
+/// panicked: 1s, doubling up to a minute.
+pub fn restart_backoff(panics: u32) -> Duration {
+    Duration::from_secs((1u64 << panics.saturating_sub(1).min(6)).min(60))
+}
+
+/// An alert about `pane`, neither urgent nor snoozable.
+fn alert(pane: &PaneInfo, kind: &str, title: &str, body: String) -> Alert {
+    Alert {
+        pane: Some(pane.id.clone()),
+        project: pane.project_name.clone(),
+        kind: kind.to_string(),
+        title: title.to_string(),
+        body,
+        urgent: false,
+        snooze: false,
+    }
+}
+
+/// Show `alert`; its "Snooze" button snoozes the pane for `snooze_minutes`.
+fn show(alert: Alert, snooze_minutes: u64) {
+    match alert.pane {
+        Some(pane) if alert.snooze => notify::desktop_with_snooze(&alert.title, &alert.body, move || {
+            let mut marks = Marks::load();
+            marks.snooze(&pane, Snooze::Until(elapsed::unix_now() + snooze_minutes * 60));
+            marks.save();
+        }),
+        _ if alert.urgent => notify::urgent(&alert.title, &alert.body),
+        _ => notify::desktop(&alert.title, &alert.body),
+    }
+}
+
+/// Live sessions of `updated` that entered the Error state since `prev`.
+pub fn new_errors<'a>(prev: &[ClaudeSession], updated: &'a [ClaudeSession]) -> Vec<&'a ClaudeSession> {
+    entered(prev, updated, &ClaudeState::Error)
+}
+
+/// Live sessions of `updated` that got stuck on a command's prompt since
+/// `prev`.
+pub fn new_blocked<'a>(prev: &[ClaudeSession], updated: &'a [ClaudeSession]) -> Vec<&'a ClaudeSession> {
+    entered(prev, updated, &ClaudeState::Blocked)
+}
+
+fn entered<'a>(prev: &[ClaudeSession], updated: &'a [ClaudeSession], state: &ClaudeState) -> Vec<&'a ClaudeSession> {
+    updated
+        .iter()
+        .filter(|s| s.is_live() && s.state == *state)
+        .filter(|s| !prev.iter().any(|p| p.pane.id == s.pane.id && p.state == *state))
+        .collect()
+}
+
+fn is_snoozed(sessions: &[ClaudeSession], pane_id: &str) -> bool {
+    sessions.iter().any(|s| s.pane.id == pane_id && s.snoozed)
+}
+
+/// Capture every agent pane once, carrying `state_changed_at` over from
+/// `prev` for panes whose state did not change. Panes of `prev` that are
+/// gone are appended as tombstones while within the grace period.
+pub fn poll_once(prev: &[ClaudeSession], config: &Config) -> Vec<ClaudeSession> {
+    poll(prev, config, |_| false, None)
+}
+
+/// Like [`poll_once`], but only `pane` is captured again; the other panes
+/// of `prev` keep their sessions as they were. Panes that came or went are
+/// still picked up.
+pub fn poll_pane(prev: &[ClaudeSession], config: &Config, pane: &str) -> Vec<ClaudeSession> {
+    poll(prev, config, |id| id != pane, None)
+}
+
+/// Capture the agent panes, except that those for which `keep` holds keep
+/// their live session of `prev` as it was. The polling thread passes
+/// `rule_hits` to count rule hits in and saves what was seen to the marks;
+/// one-shot polls (`status`, `list`, the picker) change neither.
+fn poll(
+    prev: &[ClaudeSession],
+    config: &Config,
+    keep: impl Fn(&str) -> bool,
+    mut rule_hits: Option<&mut RuleStats>,
+) -> Vec<ClaudeSession> {
+    if let Some(sessions) = demo::current() {
+        return sessions;
+    }
+    let panes = agents::list_panes(&config.monitor);
+    let focused = tmux::focused_pane_ids();
+    let recording = rule_hits.is_some();
+    let mut marks = Marks::load();
+    let mut processes: Option<Vec<ProcessInfo>> = None;
+    let now = Instant::now();
+    let unix_now = elapsed::unix_now();
+    let pr_refresh = Duration::from_secs(config.pull_requests.refresh_secs);
+    let kept_session = |pane: &PaneInfo, tool: &str| {
+        keep(&pane.id).then(|| prev.iter().find(|s| s.pane.id == pane.id && s.is_live() && s.tool == tool)).flatten()
+    };
+    // Captured all at once, so that a slow pane holds up neither the others
+    // nor the time their states are stamped with.
+    let to_capture: Vec<String> = panes
+        .iter()
+        .filter(|(pane, detector)| kept_session(pane, detector.name()).is_none())
+        .map(|(pane, _)| pane.id.clone())
+        .collect();
+    let mut captures: HashMap<String, String> =
+        to_capture.iter().cloned().zip(tmux::capture_panes(&to_capture)).collect();
+    let mut sessions: Vec<ClaudeSession> = panes
+        .into_iter()
+        .map(|(pane, detector)| {
+            if let Some(kept) = kept_session(&pane, detector.name()) {
+                return kept.clone();
+            }
+            let content = captures.remove(&pane.id).unwrap_or_default();
+            let info = detector.detect(&content, config.monitor.ui_locale);
+            if let Some(hits) = rule_hits.as_deref_mut() {
+                hits.record(&pane.id, info.rule);
+            }
+            // claude started again in a pane where it had exited, or another
+            // tool took over the pane: a new session.
+            let old = prev
+                .iter()
+                .find(|s| s.pane.id == pane.id && s.is_live() && s.tool == detector.name())
+                .filter(|s| s.state != ClaudeState::NotRunning || info.state == ClaudeState::NotRunning);
+            let (state, state_changed_at, unconfirmed) = match old {
+                Some(old) => {
+                    let rule = config.monitor.debounce.for_state(&info.state);
+                    let (state, entered, candidate) =
+                        debounce(&old.state, old.unconfirmed.as_ref(), info.state.clone(), rule, now);
+                    (state, entered.unwrap_or(old.state_changed_at), candidate)
+                }
+                None => (info.state.clone(), now, None),
+            };
+            let content_hash = content_hash(&content);
+            let unread = marks.observe(&pane.id, content_hash, focused.contains(&pane.id));
+            let snoozed = marks.is_snoozed(&pane.id, status::state_key(&state), unix_now);
+            // Process start time is only looked up once, when a pane first appears.
+            let started_at = old.map(|s| s.started_at).unwrap_or_else(|| {
+                let processes = processes.get_or_insert_with(process::list_processes);
+                process::session_process_age(processes, pane.pid)
+                    .and_then(|age| now.checked_sub(age))
+                    .unwrap_or(now)
+            });
+            let (history, totals) = match old {
+                Some(old) if old.state != state => (with_history(old), old.time_in_states(state_changed_at)),
+                Some(old) => (old.history.clone(), old.totals),
+                None => (vec![], StateTotals::default()),
+            };
+            let touched_files = files::merge_touched(
+                old.map(|s| s.touched_files.as_slice()).unwrap_or_default(),
+                &files::touched_files(&content),
+            );
+            ClaudeSession {
+                tool: detector.name(),
+                unconfirmed,
+                at_risk_files: at_risk_files(info.pending.as_ref(), &pane.cwd),
+                activity: info.activity,
+                timer: info.timer,
+                tokens: info.tokens,
+                pending: info.pending,
+                error: info.error,
+                input_prompt: info.input_prompt,
+                subagents: info.subagents,
+                todo_progress: info.todo_progress,
+                permission_mode: info.permission_mode,
+                skips_permissions: info.skips_permissions || old.is_some_and(|s| s.skips_permissions),
+                model: info.model,
+                context_left: info.context_left,
+                interrupted: info.interrupted,
+                queued_messages: info.queued_messages,
+                last_turn_duration: info.last_turn,
+                content_hash,
+                unread,
+                snoozed,
+                watch_hits: watch::matching_rules(&config.watch, &content),
+                // A remote pane's directory is not on this machine.
+                branch: remote::parse_pane_id(&pane.id).is_none().then(|| git::branch(&pane.cwd)).flatten(),
+                pr: config
+                    .pull_requests
+                    .enabled
+                    .then(|| forge::cached(&pane.cwd, pr_refresh))
+                    .flatten(),
+                guardrail_hits: guardrail::check(&config.guardrail, &state, &content, &touched_files),
+                touched_files,
+                first_seen: old.map(|s| s.first_seen).unwrap_or(now),
+                started_at,
+                history,
+                totals,
+                ..ClaudeSession::new(pane, state, state_changed_at)
+            }
+        })
+        .collect();
+
+    // Panes that ran claude and are still open, but run something else now.
+    let exited: Vec<&ClaudeSession> = prev
+        .iter()
+        .filter(|p| p.is_live() && !sessions.iter().any(|s| s.pane.id == p.pane.id))
+        .collect();
+    if !exited.is_empty() {
+        let mut open = tmux::all_pane_ids();
+        if config.monitor.screen {
+            open.extend(screen::all_window_ids());
+        }
+        if config.monitor.kitty {
+            open.extend(kitty::all_window_ids());
+        }
+        open.extend(remote::all_pane_ids(&config.monitor.remote_hosts));
+        sessions.extend(
+            exited
+                .into_iter()
+                .filter(|p| open.contains(&p.pane.id))
+                .map(|p| not_running(p, now)),
+        );
+    }
+
+    if recording {
+        let live: HashSet<&str> = sessions.iter().map(|s| s.pane.id.as_str()).collect();
+        marks.retain_panes(&live);
+        marks.save();
+    }
+
+    let grace = Duration::from_secs(config.monitor.tombstone_grace_secs);
+    let ended = tombstones(prev, &sessions, grace, now);
+    sessions.extend(ended);
+    crash::record_sessions(&sessions);
+    sessions
+}
+
+/// A session whose claude process exited while its pane stayed open.
+pub fn not_running(prev: &ClaudeSession, now: Instant) -> ClaudeSession {
+    let (state_changed_at, history, totals) = match prev.state {
+        ClaudeState::NotRunning => (prev.state_changed_at, prev.history.clone(), prev.totals),
+        _ => (now, with_history(prev), prev.time_in_states(now)),
+    };
+    ClaudeSession {
+        state: ClaudeState::NotRunning,
+        state_changed_at,
+        activity: None,
+        timer: None,
+        tokens: None,
+        pending: None,
+        error: None,
+        input_prompt: None,
+        subagents: None,
+        interrupted: false,
+        queued_messages: 0,
+        last_turn_duration: None,
+        watch_hits: vec![],
+        guardrail_hits: vec![],
+        at_risk_files: None,
+        unconfirmed: None,
+        history,
+        totals,
+        ..prev.clone()
+    }
+}
+
+/// The history of `prev` with its current state added, as a session moves
+/// on from it.
+fn with_history(prev: &ClaudeSession) -> Vec<(ClaudeState, Instant)> {
+    let mut history = prev.history.clone();
+    history.push((prev.state.clone(), prev.state_changed_at));
+    if history.len() > HISTORY_LEN {
+        history.remove(0);
+    }
+    history
+}
+
+/// Dirty file count of `cwd` when `pending` is a git command that could
+/// discard those changes. The count is the cached one of
+/// [`env_facts::collect`], as panes waiting for approval are captured often.
+fn at_risk_files(pending: Option<&PendingApproval>, cwd: &str) -> Option<usize> {
+    let pending = pending.filter(|p| p.tool == "Bash")?;
+    git::discarding_operation(&pending.detail)?;
+    env_facts::collect(cwd).dirty_files.filter(|&files| files > 0)
+}
+
+/// Live sessions of `updated` that started asking for a git command that
+/// can discard uncommitted changes since `prev`.
+pub fn new_git_risks<'a>(prev: &[ClaudeSession], updated: &'a [ClaudeSession]) -> Vec<&'a ClaudeSession> {
+    updated
+        .iter()
+        .filter(|s| s.is_live() && s.at_risk_files.is_some())
+        .filter(|s| {
+            !prev
+                .iter()
+                .any(|p| p.pane.id == s.pane.id && p.at_risk_files.is_some() && p.pending == s.pending)
+        })
+        .collect()
+}
+
+/// Sessions of `prev` that no longer appear in `live`, marked as ended and
+/// kept until `grace` has passed since they disappeared.
+pub fn tombstones(
+    prev: &[ClaudeSession],
+    live: &[ClaudeSession],
+    grace: Duration,
+    now: Instant,
+) -> Vec<ClaudeSession> {
+    prev.iter()
+        .filter(|p| !live.iter().any(|s| s.pane.id == p.pane.id))
+        .filter_map(|p| {
+            let ended_at = p.ended_at.unwrap_or(now);
+            (now.saturating_duration_since(ended_at) < grace).then(|| ClaudeSession {
+                ended_at: Some(ended_at),
+                ..p.clone()
+            })
+        })
+        .collect()
+}
+
+/// FNV-1a of `content`. The hash is saved in `marks.json`, so it must not
+/// change between builds the way `DefaultHasher` may.
+pub fn content_hash(content: &str) -> u64 {
+    content
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// Panes that are new in `updated` or have disappeared since `prev` do not
+/// produce transitions; only state changes of a known pane do.
+pub fn detect_transitions(prev: &[ClaudeSession], updated: &[ClaudeSession]) -> Vec<Transition> {
+    updated
+        .iter()
+        .filter_map(|s| {
+            let old = prev.iter().find(|p| p.pane.id == s.pane.id && p.is_live())?;
+            if !s.is_live() || old.state == s.state {
+                return None;
+            }
+            Some(Transition {
+                pane: s.pane.clone(),
+                from: old.state.clone(),
+                to: s.state.clone(),
+                elapsed: s.state_changed_at.saturating_duration_since(old.state_changed_at),
+                approval: s.pending.as_ref().map(|p| p.kind).filter(|_| s.state == ClaudeState::WaitingForApproval),
+            })
+        })
+        .collect()
+}
+
+/// Fields of a transition available to the `on_transition` command, as
+/// `{name}` in its template and `CLAUDEYE_<NAME>` in its environment.
+pub fn transition_fields(transition: &Transition) -> Value {
+    json!({
+        "pane": transition.pane.id,
+        "project": transition.pane.project_name,
+        "cwd": transition.pane.cwd,
+        "from": status::state_key(&transition.from),
+        "to": status::state_key(&transition.to),
+        "elapsed": elapsed::compact(transition.elapsed.as_secs()),
+        "elapsed_secs": transition.elapsed.as_secs(),
+        "approval": transition.approval.map(|kind| kind.key()),
+    })
+}
+
+/// Transition hook implementing `on_transition`: runs the command through
+/// `sh -c` for every transition, without waiting for it to finish. Filled
+/// values are shell-quoted, since a pane title can carry any text. A
+/// template naming an unknown field is logged and disables the hook.
+pub fn command_hook(template: Option<&str>) -> Option<TransitionHook> {
+    let template = template.filter(|t| !t.trim().is_empty())?.to_string();
+    let sample = Transition {
+        pane: PaneInfo::default(),
+        from: ClaudeState::Working,
+        to: ClaudeState::Idle,
+        elapsed: Duration::ZERO,
+        approval: None,
+    };
+    if let Err(e) = format::fill(&template, &transition_fields(&sample)) {
+        crate::log!("on_transition: {e}");
+        return None;
+    }
+    Some(Box::new(move |t: &Transition| {
+        let fields = transition_fields(t);
+        let quoted: serde_json::Map<String, Value> = fields
+            .as_object()
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| (name.clone(), Value::String(remote::shell_quote(&format::cell(value)))))
+            .collect();
+        let Ok(command) = format::fill(&template, &Value::Object(quoted)) else {
+            return;
+        };
+        let mut child = Command::new("sh");
+        child.args(["-c", &command]).stdin(Stdio::null()).stdout(Stdio::null());
+        if let Some(fields) = fields.as_object() {
+            for (name, value) in fields {
+                child.env(format!("CLAUDEYE_{}", name.to_uppercase()), format::cell(value));
+            }
+        }
+        match child.spawn() {
+            Ok(mut child) => {
+                thread::spawn(move || match child.wait() {
+                    Ok(status) if !status.success() => crate::log!("on_transition exited with {status}: {command}"),
+                    Err(e) => crate::log!("on_transition: {e}"),
+                    Ok(_) => {}
+                });
+            }
+            Err(e) => crate::log!("on_transition: {e}"),
+        }
+    }))
 }
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::process::{Command, Stdio};
+use std::thread;
+
+use serde::Deserialize;
+
+use crate::redact::redact;
+
+/// Settings from the `[notify]` config table.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NotifyConfig {
+    /// An alert of the same kind for the same pane is not repeated within
+    /// this many seconds.
+    pub repeat_secs: u64,
+    /// Non-urgent alerts of a pane are at least this many seconds apart.
+    pub cooldown_secs: u64,
+    /// This many alerts of one kind in a poll are merged into one ("3
+    /// sessions: ..."); 0 never merges.
+    pub coalesce: usize,
+}
+
+impl Default for NotifyConfig {
+    fn default() -> Self {
+        Self { repeat_secs: 300, cooldown_secs: 30, coalesce: 3 }
+    }
+}
+
+/// A notification the monitor wants to show.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    /// The pane it is about; `None` for alerts about claudeye itself.
+    pub pane: Option<String>,
+    /// Names the session in a merged alert.
+    pub project: String,
+    /// What the alert is about (`error`, `watch:FAILED`, ...); repeats of
+    /// the same kind for the same pane are dropped.
+    pub kind: String,
+    pub title: String,
+    pub body: String,
+    /// Shown with [`urgent`] and exempt from the per-pane cooldown.
+    pub urgent: bool,
+    /// Offer a "Snooze" button for the pane.
+    pub snooze: bool,
+}
+
+/// Deduplicates, rate-limits, and coalesces alerts before they are shown.
+#[derive(Debug, Default)]
+pub struct Notifier {
+    config: NotifyConfig,
+    /// When each pane and kind was last alerted about.
+    sent: HashMap<(String, String), u64>,
+    /// When each pane was last alerted about.
+    last: HashMap<String, u64>,
+}
+
+impl Notifier {
+    pub fn new(config: &NotifyConfig) -> Self {
+        Self { config: config.clone(), ..Self::default() }
+    }
+
+    /// The alerts of one poll that should be shown at `now` (Unix seconds),
+    /// with bursts of the same title merged. Alerts let through are
+    /// remembered for later calls.
+    pub fn filter(&mut self, alerts: Vec<Alert>, now: u64) -> Vec<Alert> {
+        let within = |at: Option<&u64>, secs: u64| at.is_some_and(|&at| now.saturating_sub(at) < secs);
+        let mut kept: Vec<Alert> = vec![];
+        for alert in alerts {
+            if let Some(pane) = &alert.pane {
+                let key = (pane.clone(), alert.kind.clone());
+                if within(self.sent.get(&key), self.config.repeat_secs)
+                    || (!alert.urgent && within(self.last.get(pane), self.config.cooldown_secs))
+                {
+                    continue;
+                }
+                self.sent.insert(key, now);
+                self.last.insert(pane.clone(), now);
+            }
+            kept.push(alert);
+        }
+        self.coalesce(kept)
+    }
+
+    fn coalesce(&self, alerts: Vec<Alert>) -> Vec<Alert> {
+        if self.config.coalesce == 0 {
+            return alerts;
+        }
+        let mut titles: Vec<&str> = vec![];
+        for alert in &alerts {
+            if !titles.contains(&alert.title.as_str()) {
+                titles.push(&alert.title);
+            }
+        }
+        let mut out = vec![];
+        for title in titles {
+            let burst: Vec<&Alert> = alerts.iter().filter(|a| a.title == title).collect();
+            if burst.len() < self.config.coalesce.max(2) {
+                out.extend(burst.into_iter().cloned());
+                continue;
+            }
+            let names: Vec<&str> = burst.iter().map(|a| a.project.as_str()).collect();
+            out.push(Alert {
+                pane: None,
+                project: String::new(),
+                kind: title.to_string(),
+                title: title.to_string(),
+                body: format!("{} sessions: {}", burst.len(), names.join(", ")),
+                urgent: burst.iter().any(|a| a.urgent),
+                snooze: false,
+            });
+        }
+        out
+    }
+}
+
+/// Show a desktop notification via `osascript` on macOS or `notify-send`
+/// elsewhere. Runs in the background; failures are logged, never fatal.
+pub fn desktop(title: &str, body: &str) {
+    show(title, body, false);
+}
+
+/// Like [`desktop`], for problems that stop a session: critical urgency
+/// (stays on screen until dismissed) with `notify-send`, and an alert
+/// sound on macOS.
+pub fn urgent(title: &str, body: &str) {
+    show(title, body, true);
+}
+
+fn show(title: &str, body: &str, urgent: bool) {
+    let (title, body) = (&*redact(title), &*redact(body));
+    let mut command = if cfg!(target_os = "macos") {
+        let sound = if urgent { " sound name \"Basso\"" } else { "" };
+        let script = format!(
+            "display notification {} with title {}{sound}",
+            applescript_string(body),
+            applescript_string(title)
+        );
+        let mut c = Command::new("osascript");
+        c.args(["-e", &script]);
+        c
+    } else {
+        let mut c = Command::new("notify-send");
+        c.arg("--app-name=claudeye");
+        if urgent {
+            c.arg("--urgency=critical");
+        }
+        c.args([title, body]);
+        c
+    };
+    match command.spawn() {
+        Ok(mut child) => {
+            thread::spawn(move || child.wait());
+        }
+        Err(e) => crate::log!("notification failed: {e}"),
+    }
+}
+
+/// Like [`desktop`], with a "Snooze" button calling `on_snooze` when
+/// clicked. Buttons need `notify-send` 0.7.9 or later; elsewhere (including
+/// macOS) a plain notification is shown.
+pub fn desktop_with_snooze(title: &str, body: &str, on_snooze: impl FnOnce() + Send + 'static) {
+    if cfg!(target_os = "macos") {
+        return desktop(title, body);
+    }
+    let child = Command::new("notify-send")
+        .args(["--app-name=claudeye", "--action=snooze=Snooze", title, body])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn();
+    let Ok(child) = child else {
+        return desktop(title, body);
+    };
+    let (title, body) = (title.to_string(), body.to_string());
+    // notify-send blocks until the notification closes, printing the
+    // chosen action's name.
+    thread::spawn(move || match child.wait_with_output() {
+        Ok(out) if out.status.success() => {
+            if String::from_utf8_lossy(&out.stdout).trim() == "snooze" {
+                on_snooze();
+            }
+        }
+        // Older notify-send without --action support
+        _ => desktop(&title, &body),
+    });
+}
+
+fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
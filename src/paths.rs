@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+
+/// `$XDG_CONFIG_HOME/claudeye`, falling back to `~/.config/claudeye`.
+pub fn config_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// `$XDG_STATE_HOME/claudeye`, falling back to `~/.local/state/claudeye`.
+/// Holds small files shared between the overlay and the picker.
+pub fn state_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_STATE_HOME", ".local/state")
+}
+
+/// `$XDG_DATA_HOME/claudeye`, falling back to `~/.local/share/claudeye`.
+/// Holds records kept for the user rather than for claudeye itself.
+pub fn data_dir() -> Option<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", ".local/share")
+}
+
+fn xdg_dir(var: &str, home_fallback: &str) -> Option<PathBuf> {
+    let base = std::env::var_os(var)
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(home_fallback)))?;
+    Some(base.join("claudeye"))
+}
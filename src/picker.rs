@@ -1,4 +1,5 @@
 use std::io::{self, Stdout};
+use std::time::Instant;
 
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind},
@@ -7,24 +8,74 @@ use crossterm::{
 };
 use ratatui::{
     backend::CrosstermBackend,
-    style::{Color, Style},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, ListState},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
     Terminal,
 };
 
-use crate::claude_state::{detect_state, ClaudeState};
-use crate::monitor::ClaudeSession;
+use crate::actions;
+use crate::agents::EmptyReason;
+use crate::claude_state::{ApprovalKind, ClaudeState, PermissionMode};
+use crate::config::Config;
+use crate::env_facts::{self, EnvFacts};
+use crate::files;
+use crate::forge::{self, ChecksStatus, PullRequest};
+use crate::git;
+use crate::i18n::Strings;
+use crate::elapsed;
+use crate::marks::{Marks, Snooze};
+use crate::monitor::{self, ClaudeSession, NameFormat};
+use crate::status;
+use crate::text;
 use crate::tmux;
 
 pub struct PickerState {
     pub sessions: Vec<ClaudeSession>,
     pub selected: usize,
+    /// Pane ids pinned for the comparison view, oldest first (at most two).
+    pub pinned: Vec<String>,
+    /// Side-by-side snapshot of the two pinned sessions, when open.
+    pub compare: Option<[ComparePane; 2]>,
+    /// Detail panel for the selected session, when open.
+    pub detail: Option<DetailPane>,
+    pub name_format: NameFormat,
+    /// Outcome of the last approve or deny, shown under the list.
+    pub notice: Option<String>,
+    /// Pane whose interruption waits for a second `x`.
+    pub confirm_interrupt: Option<String>,
+}
+
+/// Details of one session shown below the list.
+pub struct DetailPane {
+    pub pane_id: String,
+    /// Files edited by the agent, oldest first.
+    pub touched_files: Vec<String>,
+    /// Runtime versions and git state of the session's directory.
+    pub env: EnvFacts,
+}
+
+/// One side of the comparison view.
+pub struct ComparePane {
+    pub pane_id: String,
+    pub capture: String,
+    /// `git diff --shortstat` of the session's working tree.
+    pub diff_stat: Option<String>,
 }
 
 impl PickerState {
     pub fn new(sessions: Vec<ClaudeSession>) -> Self {
-        Self { sessions, selected: 0 }
+        Self {
+            sessions,
+            selected: 0,
+            pinned: vec![],
+            compare: None,
+            detail: None,
+            name_format: NameFormat::default(),
+            notice: None,
+            confirm_interrupt: None,
+        }
     }
 
     pub fn move_up(&mut self) {
@@ -46,37 +97,106 @@ impl PickerState {
     pub fn pane_id_at(&self, idx: usize) -> Option<&str> {
         self.sessions.get(idx).map(|s| s.pane.id.as_str())
     }
+
+    /// Pin or unpin the selected session. Pinning a third session drops
+    /// the oldest pin.
+    pub fn toggle_pin(&mut self) {
+        let Some(id) = self.selected_pane_id().map(str::to_string) else {
+            return;
+        };
+        if let Some(pos) = self.pinned.iter().position(|p| *p == id) {
+            self.pinned.remove(pos);
+        } else {
+            self.pinned.push(id);
+            if self.pinned.len() > 2 {
+                self.pinned.remove(0);
+            }
+        }
+    }
+
+    pub fn is_pinned(&self, pane_id: &str) -> bool {
+        self.pinned.iter().any(|p| p == pane_id)
+    }
+
+    /// The two pinned sessions, once exactly two are pinned.
+    pub fn pinned_pair(&self) -> Option<[&ClaudeSession; 2]> {
+        let [a, b] = self.pinned.as_slice() else {
+            return None;
+        };
+        let find = |id: &String| self.sessions.iter().find(|s| s.pane.id == *id);
+        Some([find(a)?, find(b)?])
+    }
+
+    /// Flip the snoozed flag of the selected session, returning its pane
+    /// id and new flag.
+    pub fn toggle_selected_snooze(&mut self) -> Option<(String, bool)> {
+        let session = self.sessions.get_mut(self.selected)?;
+        session.snoozed = !session.snoozed;
+        Some((session.pane.id.clone(), session.snoozed))
+    }
+
+    /// Ask to interrupt the selected session: the first call arms the
+    /// confirmation and returns `None`, a second one for the same working
+    /// session returns its pane id.
+    pub fn request_interrupt(&mut self) -> Option<String> {
+        let session = self.sessions.get(self.selected).filter(|s| s.state == ClaudeState::Working)?;
+        if self.confirm_interrupt.take().as_deref() == Some(session.pane.id.as_str()) {
+            return Some(session.pane.id.clone());
+        }
+        self.confirm_interrupt = Some(session.pane.id.clone());
+        None
+    }
+
+    /// Clear the unread indicator of the selected session when it has
+    /// finished (Idle or exited), returning the pane id and content hash to
+    /// record as reviewed.
+    pub fn mark_selected_reviewed(&mut self) -> Option<(String, u64)> {
+        let session = self
+            .sessions
+            .get_mut(self.selected)
+            .filter(|s| matches!(s.state, ClaudeState::Idle | ClaudeState::NotRunning))?;
+        session.unread = false;
+        Some((session.pane.id.clone(), session.content_hash))
+    }
 }
 
-pub fn run_picker() -> io::Result<()> {
-    let panes = tmux::list_claude_panes();
-    let sessions: Vec<ClaudeSession> = panes
-        .into_iter()
-        .map(|pane| {
-            let content = tmux::capture_pane(&pane.id);
-            let state = detect_state(&content);
-            ClaudeSession { pane, state, state_changed_at: std::time::Instant::now() }
-        })
-        .collect();
+pub fn run_picker(config: &Config) -> io::Result<()> {
+    let strings = &config.strings();
+    let mut sessions = monitor::poll_once(&[], config);
+    if config.pull_requests.enabled {
+        let prs = forge::lookup_all(sessions.iter().map(|s| s.pane.cwd.as_str()));
+        for session in &mut sessions {
+            session.pr = prs.get(&session.pane.cwd).cloned();
+        }
+    }
 
     if sessions.is_empty() {
-        println!("No Claude sessions found");
+        match EmptyReason::current() {
+            Some(reason) => println!("{}", reason.label(strings)),
+            None => println!("{}", strings.no_sessions),
+        }
         return Ok(());
     }
 
     let mut picker = PickerState::new(sessions);
+    picker.name_format = config.name_format;
 
     enable_raw_mode()?;
     execute!(io::stdout(), EnterAlternateScreen)?;
     let backend = CrosstermBackend::new(io::stdout());
     let mut terminal = Terminal::new(backend)?;
 
-    let selected_pane = run_loop(&mut terminal, &mut picker);
+    let selected_pane = run_loop(&mut terminal, &mut picker, strings, config);
 
     disable_raw_mode()?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
 
     if let Some(pane_id) = selected_pane {
+        if let Some(session) = picker.sessions.iter().find(|s| s.pane.id == pane_id) {
+            let mut marks = Marks::load();
+            marks.mark_seen(&pane_id, session.content_hash);
+            marks.save();
+        }
         tmux::switch_to_pane(&pane_id);
     }
 
@@ -86,51 +206,239 @@ pub fn run_picker() -> io::Result<()> {
 fn run_loop(
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     picker: &mut PickerState,
+    strings: &Strings,
+    config: &Config,
 ) -> Option<String> {
     loop {
-        if terminal.draw(|f| render(f, picker)).is_err() {
+        if terminal.draw(|f| render(f, picker, strings)).is_err() {
             return None;
         }
 
         match event::read() {
-            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
-                KeyCode::Char('j') | KeyCode::Down => picker.move_down(),
-                KeyCode::Char('k') | KeyCode::Up => picker.move_up(),
-                KeyCode::Enter => return picker.selected_pane_id().map(|s| s.to_string()),
-                KeyCode::Char('q') | KeyCode::Esc => return None,
-                KeyCode::Char(c @ '1'..='9') => {
-                    let idx = (c as usize) - ('1' as usize);
-                    if let Some(id) = picker.pane_id_at(idx) {
-                        return Some(id.to_string());
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press && picker.compare.is_some() => {
+                if matches!(key.code, KeyCode::Char('c' | 'q') | KeyCode::Esc) {
+                    picker.compare = None;
+                }
+            }
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press && key.code == KeyCode::Char('x') => {
+                picker.notice = match picker.request_interrupt() {
+                    Some(pane_id) => Some(match actions::interrupt(&pane_id) {
+                        Ok(()) => strings.interrupt_sent_label(&pane_id),
+                        Err(e) => e,
+                    }),
+                    None => picker.confirm_interrupt.as_deref().map(|pane| strings.interrupt_confirm_label(pane)),
+                };
+            }
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => {
+                // Any other key cancels a pending interruption.
+                if picker.confirm_interrupt.take().is_some() {
+                    picker.notice = None;
+                }
+                match key.code {
+                    KeyCode::Char('j') | KeyCode::Down => picker.move_down(),
+                    KeyCode::Char('k') | KeyCode::Up => picker.move_up(),
+                    KeyCode::Enter => return picker.selected_pane_id().map(|s| s.to_string()),
+                    KeyCode::Char('r') => {
+                        if let Some((pane_id, hash)) = picker.mark_selected_reviewed() {
+                            let mut marks = Marks::load();
+                            marks.mark_seen(&pane_id, hash);
+                            marks.save();
+                        }
+                    }
+                    KeyCode::Char('z') => {
+                        if let Some((pane_id, snoozed)) = picker.toggle_selected_snooze() {
+                            let mut marks = Marks::load();
+                            if snoozed {
+                                let state = status::state_key(&picker.sessions[picker.selected].state);
+                                marks.snooze(&pane_id, Snooze::UntilChange(state.to_string()));
+                            } else {
+                                marks.unsnooze(&pane_id);
+                            }
+                            marks.save();
+                        }
                     }
+                    KeyCode::Char('Z') => {
+                        if let Some(session) = picker.sessions.get_mut(picker.selected) {
+                            session.snoozed = true;
+                            let mut marks = Marks::load();
+                            let until = elapsed::unix_now() + config.snooze_minutes * 60;
+                            marks.snooze(&session.pane.id, Snooze::Until(until));
+                            marks.save();
+                        }
+                    }
+                    KeyCode::Char(key @ ('y' | 'n')) => {
+                        if let Some(pane_id) = picker.selected_pane_id().map(str::to_string) {
+                            let result = if key == 'y' {
+                                actions::approve(&pane_id, &config.guardrail, false)
+                                    .map(|()| strings.approved_label(&pane_id))
+                            } else {
+                                actions::deny(&pane_id).map(|()| strings.denied_label(&pane_id))
+                            };
+                            picker.notice = Some(result.unwrap_or_else(|e| e));
+                        }
+                    }
+                    KeyCode::Char('p') => picker.toggle_pin(),
+                    KeyCode::Char('c') => picker.compare = picker.pinned_pair().map(|pair| pair.map(snapshot)),
+                    KeyCode::Char('i') => {
+                        picker.detail = match picker.detail {
+                            Some(_) => None,
+                            None => picker.sessions.get(picker.selected).map(detail_snapshot),
+                        };
+                    }
+                    KeyCode::Char('q') | KeyCode::Esc => return None,
+                    KeyCode::Char(c @ '1'..='9') => {
+                        let idx = (c as usize) - ('1' as usize);
+                        if let Some(id) = picker.pane_id_at(idx) {
+                            return Some(id.to_string());
+                        }
+                    }
+                    _ => {}
                 }
-                _ => {}
-            },
+            }
             Err(_) => return None,
             _ => {}
         }
+
+        // Keep an open detail panel following the selection.
+        if let (Some(detail), Some(session)) = (&picker.detail, picker.sessions.get(picker.selected))
+            && detail.pane_id != session.pane.id
+        {
+            picker.detail = Some(detail_snapshot(session));
+        }
     }
 }
 
-fn render(f: &mut ratatui::Frame, picker: &PickerState) {
+/// States, the current one included, listed in the detail panel.
+const DETAIL_TIMELINE_STATES: usize = 6;
+/// Lines of scrollback searched for edit tool calls when opening details.
+const DETAIL_HISTORY_LINES: &str = "-2000";
+
+fn detail_snapshot(session: &ClaudeSession) -> DetailPane {
+    let history = tmux::capture_pane_range(&session.pane.id, DETAIL_HISTORY_LINES, "-").unwrap_or_default();
+    DetailPane {
+        pane_id: session.pane.id.clone(),
+        touched_files: files::merge_touched(&session.touched_files, &files::touched_files(&history)),
+        env: env_facts::collect(&session.pane.cwd),
+    }
+}
+
+fn snapshot(session: &ClaudeSession) -> ComparePane {
+    ComparePane {
+        pane_id: session.pane.id.clone(),
+        capture: tmux::capture_pane(&session.pane.id),
+        diff_stat: git::diff_shortstat(&session.pane.cwd),
+    }
+}
+
+fn render(f: &mut ratatui::Frame, picker: &PickerState, strings: &Strings) {
+    if let Some(compare) = &picker.compare {
+        render_compare(f, picker, compare, strings);
+        return;
+    }
+
+    // Pane ids and names are padded to the widest one so the state labels
+    // line up, with CJK characters counted as two columns.
+    let id_width = picker.sessions.iter().map(|s| text::width(&s.pane.id)).max().unwrap_or(0);
+    let name_width = picker.sessions.iter().map(|s| text::width(&s.display_name(picker.name_format))).max().unwrap_or(0);
+    let any_pinned = picker.sessions.iter().any(|s| picker.is_pinned(&s.pane.id));
     let items: Vec<ListItem> = picker
         .sessions
         .iter()
         .enumerate()
         .map(|(i, s)| {
-            let (indicator, color, label) = state_display(&s.state);
+            let (indicator, mut color) = state_display(&s.state);
+            if s.tripped_guardrail() {
+                color = Color::LightRed;
+            }
+            let label = s.state_label(strings);
             let prefix = if i < 9 {
                 format!("{}. ", i + 1)
             } else {
                 "   ".to_string()
             };
-            ListItem::new(Line::from(Span::styled(
-                format!(
-                    "{}{} {}  {}  [{}]",
-                    prefix, indicator, s.pane.id, s.pane.project_name, label
+            let pin = match (picker.is_pinned(&s.pane.id), any_pinned) {
+                (true, _) => "◆ ",
+                (false, true) => "  ",
+                (false, false) => "",
+            };
+            let mut spans = vec![
+                Span::styled(
+                    format!(
+                        "{}{}{} {}  {}  [{}]",
+                        prefix,
+                        pin,
+                        indicator,
+                        text::pad(&s.pane.id, id_width),
+                        text::pad(&s.display_name(picker.name_format), name_width),
+                        label
+                    ),
+                    Style::default().fg(color),
+                ),
+                Span::styled(
+                    s.pending
+                        .as_ref()
+                        .map(|p| format!(" {}", p.summary(60)))
+                        .or_else(|| s.error.as_ref().map(|e| format!(" {e}")))
+                        .or_else(|| s.input_prompt.as_ref().map(|p| format!(" {p}")))
+                        .or_else(|| s.subagents_label(strings, 60).map(|label| format!(" {label}")))
+                        .unwrap_or_default(),
+                    Style::default().fg(color),
+                ),
+                Span::styled(
+                    format!("  {}", strings.age_label(s.age().as_secs())),
+                    Style::default().fg(Color::DarkGray),
                 ),
-                Style::default().fg(color),
-            )))
+            ];
+            if let Some(tokens) = s.tokens_label() {
+                spans.push(Span::styled(format!("  {tokens}"), Style::default().fg(Color::DarkGray)));
+            }
+            if let Some((done, total)) = s.todo_progress {
+                let todos = strings.todos_label(done, total);
+                spans.push(Span::styled(format!("  {todos}"), Style::default().fg(Color::DarkGray)));
+            }
+            if s.queued_messages > 0 {
+                let queued = strings.queued_label(s.queued_messages);
+                spans.push(Span::styled(format!("  {queued}"), Style::default().fg(Color::DarkGray)));
+            }
+            if let Some(last_turn) = s.last_turn_label(strings) {
+                spans.push(Span::styled(format!("  {last_turn}"), Style::default().fg(Color::DarkGray)));
+            }
+            if let Some(files) = s.at_risk_files {
+                let warning = format!("  ⚠ {}", strings.uncommitted_label(files));
+                spans.push(Span::styled(warning, Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
+            }
+            if let Some(context) = s.context_label(strings) {
+                let color = if s.context_low() { Color::Yellow } else { Color::DarkGray };
+                spans.push(Span::styled(format!("  {context}"), Style::default().fg(color)));
+            }
+            if s.skips_permissions {
+                let warning = format!("  {}", strings.skips_permissions);
+                spans.push(Span::styled(warning, Style::default().fg(Color::LightRed).add_modifier(Modifier::BOLD)));
+            }
+            if let Some(badge) = s.mode_badge(strings) {
+                spans.push(Span::styled(format!("  {badge}"), Style::default().fg(mode_color(s.permission_mode))));
+            }
+            if let Some(kind) = s.pending.as_ref().map(|p| p.kind)
+                && let Some(badge) = strings.approval_kind_badge(kind)
+            {
+                spans.push(Span::styled(format!("  {badge}"), Style::default().fg(approval_kind_color(kind))));
+            }
+            if let Some(pr) = &s.pr {
+                spans.push(Span::styled(format!("  {}", pr.label()), Style::default().fg(pr_color(pr))));
+            }
+            if s.shows_unread() {
+                spans.push(Span::styled(" •", Style::default().fg(Color::LightBlue)));
+            }
+            for hit in &s.watch_hits {
+                spans.push(Span::styled(format!("  ⚑ {hit}"), Style::default().fg(Color::Magenta)));
+            }
+            if s.snoozed {
+                spans.push(Span::styled(" zz", Style::default().fg(Color::DarkGray)));
+            }
+            for hit in &s.guardrail_hits {
+                spans.push(Span::styled(format!("  ⛔ {hit}"), Style::default().fg(Color::LightRed)));
+            }
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
@@ -138,7 +446,8 @@ fn render(f: &mut ratatui::Frame, picker: &PickerState) {
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .title("1-9: jump  j/k: move  Enter: switch  q: quit"),
+                .title(strings.picker_help.as_ref())
+                .title_bottom(picker.notice.as_deref().unwrap_or_default()),
         )
         .highlight_style(Style::default().bg(Color::DarkGray))
         .highlight_symbol("▶ ");
@@ -146,14 +455,199 @@ fn render(f: &mut ratatui::Frame, picker: &PickerState) {
     let mut list_state = ListState::default();
     list_state.select(Some(picker.selected));
 
-    f.render_stateful_widget(list, f.area(), &mut list_state);
+    let Some(detail) = &picker.detail else {
+        f.render_stateful_widget(list, f.area(), &mut list_state);
+        return;
+    };
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(f.area());
+    f.render_stateful_widget(list, rows[0], &mut list_state);
+    render_detail(f, picker, detail, rows[1], strings);
+}
+
+fn render_detail(f: &mut ratatui::Frame, picker: &PickerState, detail: &DetailPane, area: Rect, strings: &Strings) {
+    let Some(session) = picker.sessions.iter().find(|s| s.pane.id == detail.pane_id) else {
+        return;
+    };
+    let (_, color) = state_display(&session.state);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(color))
+        .title(format!("{}  {}", session.pane.id, session.display_name(picker.name_format)));
+
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut lines = vec![Line::from(Span::styled(session.pane.cwd.clone(), dim))];
+    lines.push(env_line(&detail.env, strings));
+    if let Some(pr) = &session.pr {
+        lines.push(Line::from(vec![
+            Span::styled(pr.label(), Style::default().fg(pr_color(pr))),
+            Span::styled(format!("  {}", pr.url), dim),
+        ]));
+    }
+    if !session.watch_hits.is_empty() {
+        let badges: Vec<String> = session.watch_hits.iter().map(|h| format!("⚑ {h}")).collect();
+        lines.push(Line::from(Span::styled(badges.join("  "), Style::default().fg(Color::Magenta))));
+    }
+    if let Some(pending) = &session.pending {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(format!("{}:", pending.tool), Style::default().fg(color))));
+        lines.extend(pending.detail.lines().map(|line| Line::from(format!("  {line}"))));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(strings.timeline.to_string(), Style::default().fg(Color::Cyan))));
+    for (state, lasted) in session.timeline(Instant::now()).into_iter().take(DETAIL_TIMELINE_STATES) {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<10}", strings.state_label(state)), Style::default().fg(state_display(state).1)),
+            Span::styled(elapsed::compact(lasted.as_secs()), dim),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(strings.time_in_states.to_string(), Style::default().fg(Color::Cyan))));
+    let totals = session.time_in_states(Instant::now());
+    for (state, total) in [
+        (ClaudeState::Working, totals.working),
+        (ClaudeState::WaitingForApproval, totals.approval),
+        (ClaudeState::Idle, totals.idle),
+    ] {
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<10}", strings.state_label(&state)), Style::default().fg(state_display(&state).1)),
+            Span::styled(elapsed::compact(total.as_secs()), dim),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(strings.touched_files.to_string(), Style::default().fg(Color::Cyan))));
+    if detail.touched_files.is_empty() {
+        lines.push(Line::from(Span::styled(format!("  {}", strings.no_touched_files), dim)));
+    }
+    for file in detail.touched_files.iter().rev() {
+        if files::is_outside(&session.pane.cwd, file) {
+            lines.push(Line::from(Span::styled(
+                format!("  {file}  ⚠ {}", strings.outside_cwd),
+                Style::default().fg(Color::Red),
+            )));
+        } else {
+            lines.push(Line::from(format!("  {file}")));
+        }
+    }
+    f.render_widget(Paragraph::new(lines).block(block), area);
 }
 
-fn state_display(state: &ClaudeState) -> (&'static str, Color, &'static str) {
+/// `node v20.11.0 · python 3.12.1 · 3 uncommitted files`; uncommitted
+/// changes are highlighted since destructive commands could lose them.
+fn env_line(env: &EnvFacts, strings: &Strings) -> Line<'static> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut spans = vec![];
+    if let Some(node) = &env.node {
+        spans.push(Span::styled(format!("node {node} · "), dim));
+    }
+    if let Some(python) = &env.python {
+        spans.push(Span::styled(format!("python {python} · "), dim));
+    }
+    spans.push(match env.dirty_files {
+        None => Span::styled(strings.not_a_repo.to_string(), dim),
+        Some(0) => Span::styled(strings.no_changes.to_string(), dim),
+        Some(n) => Span::styled(strings.uncommitted_label(n), Style::default().fg(Color::Yellow)),
+    });
+    Line::from(spans)
+}
+
+fn render_compare(f: &mut ratatui::Frame, picker: &PickerState, compare: &[ComparePane; 2], strings: &Strings) {
+    let outer = Block::default()
+        .borders(Borders::ALL)
+        .title(strings.compare_help.as_ref());
+    let inner = outer.inner(f.area());
+    f.render_widget(outer, f.area());
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(inner);
+    for (side, area) in compare.iter().zip(columns.iter()) {
+        render_compare_side(f, picker, side, *area, strings);
+    }
+}
+
+fn render_compare_side(
+    f: &mut ratatui::Frame,
+    picker: &PickerState,
+    side: &ComparePane,
+    area: Rect,
+    strings: &Strings,
+) {
+    let Some(session) = picker.sessions.iter().find(|s| s.pane.id == side.pane_id) else {
+        return;
+    };
+    let (_, color) = state_display(&session.state);
+    let title = format!(
+        "{}  {}  [{}]  {}",
+        session.pane.id,
+        session.display_name(picker.name_format),
+        session.state_label(strings),
+        strings.age_label(session.age().as_secs())
+    );
+    let diff = match side.diff_stat.as_deref() {
+        None => strings.not_a_repo.to_string(),
+        Some("") => strings.no_changes.to_string(),
+        Some(stat) => stat.to_string(),
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(color))
+        .title(title);
+    // Header line plus as many trailing capture lines as fit
+    let body_height = block.inner(area).height.saturating_sub(2) as usize;
+    let capture: Vec<&str> = side.capture.trim_end().lines().collect();
+    let tail = &capture[capture.len().saturating_sub(body_height)..];
+
+    let mut lines = vec![
+        Line::from(Span::styled(diff, Style::default().fg(Color::Cyan))),
+        Line::from(""),
+    ];
+    lines.extend(tail.iter().map(|l| Line::from(*l)));
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn state_display(state: &ClaudeState) -> (&'static str, Color) {
     match state {
-        ClaudeState::Working => ("●", Color::Green, "Running"),
-        ClaudeState::WaitingForApproval => ("●", Color::Yellow, "Approval"),
-        ClaudeState::Idle => ("○", Color::Gray, "Idle"),
+        ClaudeState::Working => ("●", Color::Green),
+        ClaudeState::Compacting => ("◌", Color::LightMagenta),
+        ClaudeState::WaitingForApproval => ("●", Color::Yellow),
+        ClaudeState::WaitingForAnswer => ("●", Color::Cyan),
+        ClaudeState::Blocked => ("⌨", Color::Magenta),
+        ClaudeState::Idle => ("○", Color::Gray),
+        ClaudeState::Error => ("!", Color::LightRed),
+        ClaudeState::NotRunning => ("✕", Color::Red),
+    }
+}
+
+fn mode_color(mode: PermissionMode) -> Color {
+    match mode {
+        PermissionMode::Plan => Color::Cyan,
+        PermissionMode::AcceptEdits => Color::Yellow,
+        PermissionMode::BypassPermissions => Color::LightRed,
+        PermissionMode::Default => Color::DarkGray,
+    }
+}
+
+/// Commands in gray, edits and trust decisions in yellow.
+fn approval_kind_color(kind: ApprovalKind) -> Color {
+    match kind {
+        ApprovalKind::FileEdit | ApprovalKind::TrustDirectory => Color::Yellow,
+        ApprovalKind::McpTool => Color::Magenta,
+        ApprovalKind::PlanApproval => Color::Cyan,
+        ApprovalKind::BashCommand | ApprovalKind::Other => Color::Gray,
+    }
+}
+
+fn pr_color(pr: &PullRequest) -> Color {
+    match pr.checks {
+        ChecksStatus::Passing => Color::Green,
+        ChecksStatus::Failing => Color::Red,
+        ChecksStatus::Pending => Color::Yellow,
+        ChecksStatus::None => Color::DarkGray,
     }
 }
 
@@ -163,16 +657,18 @@ mod tests {
     use crate::tmux::PaneInfo;
 
     fn make_session(id: &str) -> ClaudeSession {
-        ClaudeSession {
-            pane: PaneInfo {
+        ClaudeSession::new(
+            PaneInfo {
                 id: id.to_string(),
                 pid: 0,
                 cwd: "/tmp".to_string(),
                 project_name: "test".to_string(),
+                session_name: String::new(),
+                window_name: String::new(),
             },
-            state: ClaudeState::Idle,
-            state_changed_at: std::time::Instant::now(),
-        }
+            ClaudeState::Idle,
+            std::time::Instant::now(),
+        )
     }
 
     #[test]
@@ -232,6 +728,91 @@ mod tests {
         assert_eq!(state.pane_id_at(2), Some("gamma"));
     }
 
+    #[test]
+    fn interrupt_needs_a_second_press_on_a_working_session() {
+        let mut working = make_session("busy");
+        working.state = ClaudeState::Working;
+        let mut state = PickerState::new(vec![make_session("idle"), working]);
+        assert_eq!(state.request_interrupt(), None);
+        assert_eq!(state.confirm_interrupt, None);
+        state.move_down();
+        assert_eq!(state.request_interrupt(), None);
+        assert_eq!(state.confirm_interrupt.as_deref(), Some("busy"));
+        assert_eq!(state.request_interrupt().as_deref(), Some("busy"));
+        assert_eq!(state.confirm_interrupt, None);
+    }
+
+    #[test]
+    fn mark_selected_reviewed_clears_unread() {
+        let mut unread = make_session("pane1");
+        unread.unread = true;
+        unread.content_hash = 99;
+        let mut state = PickerState::new(vec![make_session("pane0"), unread]);
+        state.move_down();
+        assert_eq!(state.mark_selected_reviewed(), Some(("pane1".to_string(), 99)));
+        assert!(!state.sessions[1].unread);
+    }
+
+    #[test]
+    fn only_finished_sessions_can_be_reviewed() {
+        let mut working = make_session("a");
+        working.state = ClaudeState::Working;
+        working.unread = true;
+        let mut state = PickerState::new(vec![working]);
+        assert_eq!(state.mark_selected_reviewed(), None);
+        assert!(state.sessions[0].unread);
+    }
+
+    #[test]
+    fn mark_selected_reviewed_without_sessions_is_none() {
+        let mut state = PickerState::new(vec![]);
+        assert_eq!(state.mark_selected_reviewed(), None);
+    }
+
+    #[test]
+    fn toggle_selected_snooze_flips_flag() {
+        let mut state = PickerState::new(vec![make_session("a")]);
+        assert_eq!(state.toggle_selected_snooze(), Some(("a".to_string(), true)));
+        assert!(state.sessions[0].snoozed);
+        assert_eq!(state.toggle_selected_snooze(), Some(("a".to_string(), false)));
+    }
+
+    #[test]
+    fn toggle_pin_pins_and_unpins_selected() {
+        let mut state = PickerState::new(vec![make_session("a"), make_session("b")]);
+        state.toggle_pin();
+        assert!(state.is_pinned("a"));
+        state.toggle_pin();
+        assert!(!state.is_pinned("a"));
+    }
+
+    #[test]
+    fn third_pin_replaces_oldest() {
+        let mut state = PickerState::new(vec![
+            make_session("a"),
+            make_session("b"),
+            make_session("c"),
+        ]);
+        state.toggle_pin();
+        state.move_down();
+        state.toggle_pin();
+        state.move_down();
+        state.toggle_pin();
+        assert_eq!(state.pinned, vec!["b".to_string(), "c".to_string()]);
+    }
+
+    #[test]
+    fn pinned_pair_requires_two_pins() {
+        let mut state = PickerState::new(vec![make_session("a"), make_session("b")]);
+        state.toggle_pin();
+        assert!(state.pinned_pair().is_none());
+        state.move_down();
+        state.toggle_pin();
+        let [first, second] = state.pinned_pair().unwrap();
+        assert_eq!(first.pane.id, "a");
+        assert_eq!(second.pane.id, "b");
+    }
+
     #[test]
     fn pane_id_at_returns_none_for_out_of_bounds() {
         let state = PickerState::new(vec![make_session("only")]);
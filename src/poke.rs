@@ -0,0 +1,94 @@
+use std::io::ErrorKind;
+use std::os::unix::net::UnixDatagram;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Instant;
+
+use crate::paths;
+
+/// Longest pane id a poke carries.
+const MAX_PANE_ID: usize = 512;
+
+/// `$XDG_STATE_HOME/claudeye/poke`, holding one datagram socket per running
+/// monitor (the overlay, `serve`), named after its pid.
+pub fn dir() -> Option<PathBuf> {
+    Some(paths::state_dir()?.join("poke"))
+}
+
+/// Listen for pokes to this process, handing each poked pane id to the
+/// receiver. `None` when the socket cannot be bound, in which case the
+/// monitor just keeps to its poll interval.
+pub fn listen() -> Option<Receiver<String>> {
+    let dir = dir()?;
+    match listen_in(&dir) {
+        Ok(pokes) => Some(pokes),
+        Err(e) => {
+            crate::log!("cannot listen for pokes in {}: {e}", dir.display());
+            None
+        }
+    }
+}
+
+pub fn listen_in(dir: &Path) -> std::io::Result<Receiver<String>> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("{}.sock", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let socket = UnixDatagram::bind(&path)?;
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = [0u8; MAX_PANE_ID];
+        while let Ok(n) = socket.recv(&mut buf) {
+            let pane = String::from_utf8_lossy(&buf[..n]).trim().to_string();
+            if !pane.is_empty() && tx.send(pane).is_err() {
+                break;
+            }
+        }
+    });
+    Ok(rx)
+}
+
+/// Ask every running monitor to capture `pane` again right away. Returns
+/// how many were reached.
+pub fn send(pane: &str) -> usize {
+    dir().map(|dir| send_in(&dir, pane)).unwrap_or(0)
+}
+
+/// [`send`] to the sockets in `dir`, removing those whose monitor is gone.
+pub fn send_in(dir: &Path, pane: &str) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    let Ok(socket) = UnixDatagram::unbound() else {
+        return 0;
+    };
+    let mut reached = 0;
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.extension().is_none_or(|ext| ext != "sock") {
+            continue;
+        }
+        match socket.send_to(pane.as_bytes(), &path) {
+            Ok(_) => reached += 1,
+            Err(e) if matches!(e.kind(), ErrorKind::ConnectionRefused | ErrorKind::NotFound) => {
+                let _ = std::fs::remove_file(&path);
+            }
+            Err(e) => crate::log!("failed to poke {}: {e}", path.display()),
+        }
+    }
+    reached
+}
+
+/// Wait until `deadline` for a poke, returning the poked pane, or `None`
+/// once it is time for the next regular poll.
+pub fn wait(pokes: Option<&Receiver<String>>, deadline: Instant) -> Option<String> {
+    let timeout = deadline.saturating_duration_since(Instant::now());
+    match pokes.map(|pokes| pokes.recv_timeout(timeout)) {
+        Some(Ok(pane)) => Some(pane),
+        Some(Err(RecvTimeoutError::Timeout)) => None,
+        // No listener, or it stopped: just wait out the interval.
+        None | Some(Err(RecvTimeoutError::Disconnected)) => {
+            thread::sleep(deadline.saturating_duration_since(Instant::now()));
+            None
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    /// Time since the process started.
+    pub elapsed: Duration,
+    /// Executable name without its directory.
+    pub command: String,
+}
+
+pub const SHELLS: &[&str] = &["bash", "zsh", "fish", "sh", "dash", "ksh", "tcsh", "nu"];
+
+/// Commands tmux may report for a pane running claude through a runtime, a
+/// package runner (`npx @anthropic-ai/claude-code`), or a version manager
+/// shim, instead of `claude` itself.
+pub const WRAPPERS: &[&str] = &["node", "npx", "npm", "pnpm", "bun", "bunx", "deno", "mise", "asdf", "volta"];
+
+/// Snapshot of all processes via `ps`, which works the same on Linux and macOS.
+pub fn list_processes() -> Vec<ProcessInfo> {
+    let output = Command::new("ps")
+        .args(["-A", "-o", "pid=,ppid=,etime=,comm="])
+        .output();
+
+    match output {
+        Ok(out) => String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .filter_map(parse_ps_line)
+            .collect(),
+        Err(e) => {
+            crate::log!("ps failed: {e}");
+            vec![]
+        }
+    }
+}
+
+/// Parse a `<pid> <ppid> <etime> <comm>` line of `ps` output.
+pub fn parse_ps_line(line: &str) -> Option<ProcessInfo> {
+    let mut parts = line.split_whitespace();
+    let pid = parts.next()?.parse().ok()?;
+    let ppid = parts.next()?.parse().ok()?;
+    let elapsed = parse_etime(parts.next()?)?;
+    let comm = parts.collect::<Vec<_>>().join(" ");
+    let command = Path::new(&comm)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(&comm)
+        .trim_start_matches('-') // login shells are reported as "-zsh"
+        .to_string();
+    Some(ProcessInfo { pid, ppid, elapsed, command })
+}
+
+/// Parse the `ps` etime format `[[dd-]hh:]mm:ss`.
+pub fn parse_etime(s: &str) -> Option<Duration> {
+    let (days, rest) = match s.split_once('-') {
+        Some((d, rest)) => (d.parse::<u64>().ok()?, rest),
+        None => (0, s),
+    };
+    let fields: Vec<u64> = rest
+        .split(':')
+        .map(|f| f.parse().ok())
+        .collect::<Option<_>>()?;
+    let (h, m, sec) = match fields.as_slice() {
+        [m, s] => (0, *m, *s),
+        [h, m, s] => (*h, *m, *s),
+        _ => return None,
+    };
+    Some(Duration::from_secs(((days * 24 + h) * 60 + m) * 60 + sec))
+}
+
+/// How long the claude process of a pane has been running.
+///
+/// When the pane's own process is a shell, claude is its most recently
+/// started child; otherwise the pane was created running claude directly.
+pub fn session_process_age(processes: &[ProcessInfo], pane_pid: u32) -> Option<Duration> {
+    let pane = processes.iter().find(|p| p.pid == pane_pid)?;
+    if !SHELLS.contains(&pane.command.as_str()) {
+        return Some(pane.elapsed);
+    }
+    processes
+        .iter()
+        .filter(|p| p.ppid == pane_pid)
+        .map(|p| p.elapsed)
+        .min()
+}
+
+/// Processes with their parents and full command lines, to tell what runs
+/// under a pane whose foreground command is a [`WRAPPERS`] one.
+#[derive(Debug, Default)]
+pub struct ProcessTree {
+    pub processes: Vec<ProcessInfo>,
+    /// Command line (`ps -o args`) of each pid.
+    pub command_lines: HashMap<u32, String>,
+}
+
+impl ProcessTree {
+    pub fn snapshot() -> Self {
+        Self { processes: list_processes(), command_lines: list_command_lines() }
+    }
+
+    /// The first process from `pid` down (itself, then its children, then
+    /// theirs) that runs claude.
+    pub fn find_claude(&self, pid: u32) -> Option<u32> {
+        let mut level = vec![pid];
+        // Bounded, in case a stale snapshot forms a cycle of reused pids.
+        for _ in 0..16 {
+            if let Some(&found) = level.iter().find(|&&pid| self.runs_claude(pid)) {
+                return Some(found);
+            }
+            level = self.processes.iter().filter(|p| level.contains(&p.ppid)).map(|p| p.pid).collect();
+            if level.is_empty() {
+                break;
+            }
+        }
+        None
+    }
+
+    fn runs_claude(&self, pid: u32) -> bool {
+        self.processes.iter().any(|p| p.pid == pid && p.command == "claude")
+            || self.command_lines.get(&pid).is_some_and(|line| is_claude_command_line(line))
+    }
+}
+
+/// Command lines of all processes by pid, via `ps`.
+pub fn list_command_lines() -> HashMap<u32, String> {
+    match Command::new("ps").args(["-A", "-o", "pid=,args="]).output() {
+        Ok(out) => String::from_utf8_lossy(&out.stdout).lines().filter_map(parse_args_line).collect(),
+        Err(e) => {
+            crate::log!("ps failed: {e}");
+            HashMap::new()
+        }
+    }
+}
+
+/// Parse a `<pid> <command line>` line of `ps` output.
+pub fn parse_args_line(line: &str) -> Option<(u32, String)> {
+    let (pid, args) = line.trim_start().split_once(char::is_whitespace)?;
+    Some((pid.parse().ok()?, args.trim().to_string()))
+}
+
+/// Whether a command line runs Claude Code: one of its words is a program
+/// or script named `claude`, or lies in the `@anthropic-ai/claude-code`
+/// package (`node .../@anthropic-ai/claude-code/cli.js`).
+pub fn is_claude_command_line(line: &str) -> bool {
+    line.split_whitespace().any(|word| {
+        word.contains("@anthropic-ai/claude-code") || Path::new(word).file_name().is_some_and(|name| name == "claude")
+    })
+}
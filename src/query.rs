@@ -0,0 +1,254 @@
+use std::fmt;
+
+use serde_json::Value;
+
+use crate::status::SessionStatus;
+
+/// Fields a query can filter on or print: the `status.json` session keys,
+/// plus `elapsed`, the seconds since `state_since`.
+pub const FIELDS: &[&str] = &[
+    "pane",
+    "project",
+    "tool",
+    "state",
+    "unread",
+    "state_since",
+    "elapsed",
+    "watch",
+    "pending",
+    "approval",
+    "error",
+    "input_prompt",
+    "interrupted",
+    "skips_permissions",
+    "queued_messages",
+];
+
+/// A `claudeye status --query` expression: stages separated by `|`, each a
+/// filter (`state==approval`, `elapsed>300 && unread==true`), a field to
+/// print (`.project`), or `count`. A filter may end in the field to print
+/// (`project=="myapp".elapsed`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Query {
+    stages: Vec<Stage>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Stage {
+    Filter(Vec<Condition>),
+    Project(String),
+    Count,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Condition {
+    field: String,
+    op: Op,
+    value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// What a query prints.
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryOutput {
+    /// Matching sessions, one JSON object per line.
+    Sessions(Vec<Value>),
+    /// One field of each matching session, one per line.
+    Values(Vec<Value>),
+    Count(usize),
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut stages = vec![];
+        for stage in split_outside_quotes(text, "|") {
+            let stage = stage.trim();
+            if stage == "count" {
+                stages.push(Stage::Count);
+            } else if let Some(field) = stage.strip_prefix('.') {
+                stages.push(Stage::Project(known_field(field)?.to_string()));
+            } else if stage.is_empty() {
+                return Err("empty stage".to_string());
+            } else {
+                let mut conditions = vec![];
+                let mut projection = None;
+                for text in split_outside_quotes(stage, "&&") {
+                    if projection.is_some() {
+                        return Err(format!("a field to print must end the stage: {stage:?}"));
+                    }
+                    let (condition, field) = parse_condition(text.trim())?;
+                    conditions.push(condition);
+                    projection = field;
+                }
+                stages.push(Stage::Filter(conditions));
+                if let Some(field) = projection {
+                    stages.push(Stage::Project(field));
+                }
+            }
+        }
+        Ok(Self { stages })
+    }
+
+    /// Run the query over `sessions` as of Unix time `now`.
+    pub fn run(&self, sessions: &[SessionStatus], now: u64) -> Result<QueryOutput, String> {
+        let mut output = QueryOutput::Sessions(sessions.iter().map(|s| session_value(s, now)).collect());
+        for stage in &self.stages {
+            output = match (stage, output) {
+                (Stage::Filter(conditions), QueryOutput::Sessions(sessions)) => QueryOutput::Sessions(
+                    sessions
+                        .into_iter()
+                        .filter(|s| conditions.iter().all(|c| c.matches(s)))
+                        .collect(),
+                ),
+                (Stage::Project(field), QueryOutput::Sessions(sessions)) => {
+                    QueryOutput::Values(sessions.iter().map(|s| field_value(s, field)).collect())
+                }
+                (Stage::Count, QueryOutput::Sessions(items) | QueryOutput::Values(items)) => {
+                    QueryOutput::Count(items.len())
+                }
+                (_, QueryOutput::Count(_)) => return Err("nothing can follow count".to_string()),
+                (_, QueryOutput::Values(_)) => return Err("only count can follow a field".to_string()),
+            };
+        }
+        Ok(output)
+    }
+}
+
+impl fmt::Display for QueryOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryOutput::Sessions(sessions) => sessions.iter().try_for_each(|s| writeln!(f, "{s}")),
+            QueryOutput::Values(values) => values.iter().try_for_each(|v| writeln!(f, "{}", value_text(v))),
+            QueryOutput::Count(n) => writeln!(f, "{n}"),
+        }
+    }
+}
+
+impl Condition {
+    fn matches(&self, session: &Value) -> bool {
+        let value = field_value(session, &self.field);
+        let number = || Some((value.as_f64()?, self.value.parse::<f64>().ok()?));
+        match self.op {
+            // A list such as `watch` matches when any item does.
+            Op::Eq | Op::Ne => {
+                let equal = match &value {
+                    Value::Array(items) => items.iter().any(|item| value_text(item) == self.value),
+                    value => value_text(value) == self.value,
+                };
+                equal == (self.op == Op::Eq)
+            }
+            Op::Lt => number().is_some_and(|(a, b)| a < b),
+            Op::Le => number().is_some_and(|(a, b)| a <= b),
+            Op::Gt => number().is_some_and(|(a, b)| a > b),
+            Op::Ge => number().is_some_and(|(a, b)| a >= b),
+        }
+    }
+}
+
+/// A condition, and the field to print when one follows its value.
+fn parse_condition(text: &str) -> Result<(Condition, Option<String>), String> {
+    let (at, op, len) = ["==", "!=", "<=", ">=", "<", ">"]
+        .iter()
+        .filter_map(|op| text.find(op).map(|at| (at, *op, op.len())))
+        .min_by_key(|(at, _, len)| (*at, usize::MAX - len))
+        .ok_or_else(|| format!("expected a comparison such as state==approval: {text:?}"))?;
+    let field = known_field(text[..at].trim())?.to_string();
+    let op = match op {
+        "==" => Op::Eq,
+        "!=" => Op::Ne,
+        "<=" => Op::Le,
+        ">=" => Op::Ge,
+        "<" => Op::Lt,
+        _ => Op::Gt,
+    };
+    let rest = text[at + len..].trim();
+    let (value, projection) = match rest.strip_prefix('"') {
+        Some(quoted) => {
+            let end = quoted.find('"').ok_or_else(|| format!("unterminated string: {text:?}"))?;
+            let after = quoted[end + 1..].trim();
+            let projection = match after.strip_prefix('.') {
+                Some(field) => Some(known_field(field)?.to_string()),
+                None if after.is_empty() => None,
+                None => return Err(format!("unexpected {after:?} after the value")),
+            };
+            (quoted[..end].to_string(), projection)
+        }
+        // A bare value may contain dots (`12345.work/1`), so only a known
+        // field after the last one is split off.
+        None => match rest.rsplit_once('.') {
+            Some((value, field)) if FIELDS.contains(&field) => (value.to_string(), Some(field.to_string())),
+            _ => (rest.to_string(), None),
+        },
+    };
+    if value.is_empty() && !rest.starts_with('"') {
+        return Err(format!("missing value: {text:?}"));
+    }
+    if op != Op::Eq && op != Op::Ne && value.parse::<f64>().is_err() {
+        return Err(format!("{value:?} is not a number: {text:?}"));
+    }
+    Ok((Condition { field, op, value }, projection))
+}
+
+fn known_field(field: &str) -> Result<&str, String> {
+    if FIELDS.contains(&field) {
+        Ok(field)
+    } else {
+        Err(format!("unknown field {field:?}; one of {}", FIELDS.join(", ")))
+    }
+}
+
+/// `text` split at `separator`s that are not inside double quotes.
+fn split_outside_quotes<'a>(text: &'a str, separator: &str) -> Vec<&'a str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut quoted = false;
+    for (i, c) in text.char_indices() {
+        if i < start {
+            continue;
+        }
+        if c == '"' {
+            quoted = !quoted;
+        } else if !quoted && text[i..].starts_with(separator) {
+            parts.push(&text[start..i]);
+            start = i + separator.len();
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+fn session_value(session: &SessionStatus, now: u64) -> Value {
+    let mut value = serde_json::to_value(session).unwrap_or_default();
+    if let Value::Object(fields) = &mut value {
+        fields.insert("elapsed".to_string(), now.saturating_sub(session.state_since).into());
+    }
+    value
+}
+
+/// A field of a session, with the defaults `status.json` leaves out.
+fn field_value(session: &Value, field: &str) -> Value {
+    match session.get(field) {
+        Some(value) => value.clone(),
+        None if matches!(field, "interrupted" | "skips_permissions") => Value::Bool(false),
+        None if field == "queued_messages" => Value::from(0),
+        None => Value::Null,
+    }
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        Value::Array(items) => items.iter().map(value_text).collect::<Vec<_>>().join(","),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,65 @@
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+use crate::tmux;
+
+const RECORD_INTERVAL: Duration = Duration::from_secs(1);
+/// Scrollback lines compared per tick; output longer than this between two
+/// ticks is written without deduplication.
+const HISTORY_WINDOW: usize = 500;
+
+/// Continuously append a pane's output to `path` until the pane goes away.
+///
+/// Lines are written once they scroll off the visible screen into tmux
+/// history, where they no longer change (spinners and the prompt box redraw
+/// in place). The remaining visible screen is flushed when the pane closes.
+pub fn run_record(pane_id: &str, path: &Path) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    // Output already in history when recording starts is not written.
+    let mut prev: Vec<String> = history_tail(pane_id)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, format!("pane {pane_id} not found")))?;
+
+    let mut last_screen = String::new();
+    loop {
+        thread::sleep(RECORD_INTERVAL);
+        let Some(current) = history_tail(pane_id) else {
+            write_lines(&mut file, &last_screen.lines().map(str::to_string).collect::<Vec<_>>())?;
+            return Ok(());
+        };
+        write_lines(&mut file, new_lines(&prev, &current))?;
+        prev = current;
+        if let Some(screen) = tmux::capture_pane_range(pane_id, "0", "-") {
+            last_screen = screen;
+        }
+    }
+}
+
+fn history_tail(pane_id: &str) -> Option<Vec<String>> {
+    let start = format!("-{HISTORY_WINDOW}");
+    let text = tmux::capture_pane_range(pane_id, &start, "-1")?;
+    Some(text.lines().map(str::to_string).collect())
+}
+
+fn write_lines(file: &mut impl Write, lines: &[String]) -> io::Result<()> {
+    for line in lines {
+        writeln!(file, "{}", line.trim_end())?;
+    }
+    file.flush()
+}
+
+/// Lines at the end of `current` that were not already at the end of `prev`.
+///
+/// Both are tails of an append-only history, so `current` is `prev` shifted
+/// by the number of new lines. The longest suffix of `prev` that is also a
+/// prefix of `current` is the part already recorded.
+pub fn new_lines<'a>(prev: &[String], current: &'a [String]) -> &'a [String] {
+    let max = prev.len().min(current.len());
+    let overlap = (0..=max)
+        .rev()
+        .find(|&k| prev[prev.len() - k..] == current[..k])
+        .unwrap_or(0);
+    &current[overlap..]
+}
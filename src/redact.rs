@@ -0,0 +1,120 @@
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+use regex::Regex;
+use serde::Deserialize;
+
+/// Settings from the `[redaction]` config table, masking text before it
+/// leaves the process: logs, crash reports, notifications, `status.json`,
+/// the HTTP API, and webhooks.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedactionConfig {
+    /// Mask API keys and tokens, email addresses, and the home directory.
+    pub builtin: bool,
+    /// User rules, applied after the built-in ones.
+    pub rules: Vec<RedactRule>,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self { builtin: true, rules: vec![] }
+    }
+}
+
+/// A `[[redaction.rules]]` entry: every match of `pattern` is replaced with
+/// `replacement`, which may refer to capture groups (`$1`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawRedactRule")]
+pub struct RedactRule {
+    pub pattern: Regex,
+    pub replacement: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRedactRule {
+    pattern: String,
+    #[serde(default = "default_replacement")]
+    replacement: String,
+}
+
+fn default_replacement() -> String {
+    "[redacted]".to_string()
+}
+
+impl TryFrom<RawRedactRule> for RedactRule {
+    type Error = regex::Error;
+
+    fn try_from(raw: RawRedactRule) -> Result<Self, Self::Error> {
+        Ok(Self { pattern: Regex::new(&raw.pattern)?, replacement: raw.replacement })
+    }
+}
+
+/// Built-in rules: provider API keys and tokens, `key=value` secrets, and
+/// email addresses.
+fn builtin_rules() -> Vec<RedactRule> {
+    [
+        (r"\bsk-[A-Za-z0-9_-]{20,}", "[api-key]"),
+        (r"\bgh[pousr]_[A-Za-z0-9]{30,}", "[api-key]"),
+        (r"\bxox[abprs]-[A-Za-z0-9-]{10,}", "[api-key]"),
+        (r"\bAKIA[0-9A-Z]{16}\b", "[api-key]"),
+        (r"(?i)\b((?:api[_-]?key|token|secret|password)\s*[=:]\s*)[^\s'\x22]+", "${1}[redacted]"),
+        (r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b", "[email]"),
+    ]
+    .into_iter()
+    .map(|(pattern, replacement)| RedactRule {
+        pattern: Regex::new(pattern).unwrap(),
+        replacement: replacement.to_string(),
+    })
+    .collect()
+}
+
+/// The compiled redaction rules.
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    rules: Vec<RedactRule>,
+}
+
+impl Redactor {
+    /// Rules for `config`; with the built-in ones on, `home` is replaced
+    /// with `~` too.
+    pub fn new(config: &RedactionConfig, home: Option<&str>) -> Self {
+        let mut rules = vec![];
+        if config.builtin {
+            rules.extend(builtin_rules());
+            if let Some(home) = home.filter(|h| h.len() > 1) {
+                let pattern = format!(r"{}\b", regex::escape(home.trim_end_matches('/')));
+                rules.push(RedactRule { pattern: Regex::new(&pattern).unwrap(), replacement: "~".to_string() });
+            }
+        }
+        rules.extend(config.rules.iter().cloned());
+        Self { rules }
+    }
+
+    pub fn apply<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        let mut text = Cow::Borrowed(text);
+        for rule in &self.rules {
+            if let Cow::Owned(replaced) = rule.pattern.replace_all(&text, rule.replacement.as_str()) {
+                text = Cow::Owned(replaced);
+            }
+        }
+        text
+    }
+}
+
+static REDACTOR: OnceLock<Redactor> = OnceLock::new();
+
+/// Use `redactor` for [`redact`] in this process. Only the first call has
+/// an effect.
+pub fn install(redactor: Redactor) {
+    let _ = REDACTOR.set(redactor);
+}
+
+/// `text` masked with the installed rules; unchanged before [`install`].
+pub fn redact(text: &str) -> Cow<'_, str> {
+    match REDACTOR.get() {
+        Some(redactor) => redactor.apply(text),
+        None => Cow::Borrowed(text),
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::health;
+use crate::paths;
+use crate::tmux::{self, PaneInfo, PaneLabels};
+
+/// Prefix of remote pane ids, followed by the host and the pane's id on
+/// that host's tmux server (`ssh:devbox/main:0.1`).
+const ID_PREFIX: &str = "ssh:";
+
+/// How long a host that could not be reached is left alone, so that one
+/// powered-off machine does not hold up every poll for the connect timeout.
+const RETRY_AFTER: Duration = Duration::from_secs(30);
+
+/// Options for every ssh call: never prompt, give up on unreachable hosts
+/// quickly, and share one connection per host between calls, kept open for
+/// a minute after the last one.
+const SSH_OPTIONS: &[&str] = &[
+    "-o",
+    "BatchMode=yes",
+    "-o",
+    "ConnectTimeout=5",
+    "-o",
+    "ControlMaster=auto",
+    "-o",
+    "ControlPersist=60",
+];
+
+pub fn pane_id(host: &str, id: &str) -> String {
+    format!("{ID_PREFIX}{host}/{id}")
+}
+
+/// The host and its tmux pane id of a remote pane id, or `None` for a local
+/// pane.
+pub fn parse_pane_id(id: &str) -> Option<(&str, &str)> {
+    let (host, pane) = id.strip_prefix(ID_PREFIX)?.split_once('/')?;
+    (!host.is_empty() && !pane.is_empty()).then_some((host, pane))
+}
+
+/// Panes in `tmux list-panes` output from `host`, in the shape of
+/// [`tmux::list_panes_with_commands`]. Pane ids are prefixed with the host,
+/// and pids are zeroed since they mean nothing on this machine.
+pub fn parse_panes(host: &str, output: &str) -> Vec<(PaneInfo, String, PaneLabels)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (mut pane, command, labels) = tmux::parse_pane_fields(line)?;
+            pane.id = pane_id(host, &pane.id);
+            pane.pid = 0;
+            Some((pane, command.to_string(), labels))
+        })
+        .collect()
+}
+
+/// Every pane on the tmux servers of `hosts`, skipping hosts that recently
+/// failed.
+pub fn list_panes(hosts: &[String]) -> Vec<(PaneInfo, String, PaneLabels)> {
+    hosts
+        .iter()
+        .filter(|host| !backing_off(host))
+        .flat_map(|host| {
+            let output = tmux(host, &["list-panes", "-a", "-F", tmux::PANE_FORMAT]);
+            let result = match &output {
+                Ok(out) if out.status.success() => Ok(()),
+                Ok(out) => Err(String::from_utf8_lossy(&out.stderr).trim().to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+            health::record_source(&format!("ssh {host}"), result.clone());
+            if let Err(e) = result {
+                crate::log!("ssh {host} tmux list-panes failed: {e}");
+                // 255 is ssh's own failure; anything else is tmux's, such as
+                // no server running, and is worth asking again next poll.
+                if output.as_ref().map_or(true, |out| out.status.code() == Some(255)) {
+                    failures().lock().unwrap_or_else(|e| e.into_inner()).insert(host.clone(), Instant::now());
+                }
+                return vec![];
+            }
+            output.map(|out| parse_panes(host, &String::from_utf8_lossy(&out.stdout))).unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Ids of every open pane on the tmux servers of `hosts`.
+pub fn all_pane_ids(hosts: &[String]) -> HashSet<String> {
+    list_panes(hosts).into_iter().map(|(pane, _, _)| pane.id).collect()
+}
+
+fn failures() -> &'static Mutex<HashMap<String, Instant>> {
+    static FAILURES: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+    FAILURES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn backing_off(host: &str) -> bool {
+    let mut failures = failures().lock().unwrap_or_else(|e| e.into_inner());
+    match failures.get(host) {
+        Some(at) if at.elapsed() < RETRY_AFTER => true,
+        Some(_) => {
+            failures.remove(host);
+            false
+        }
+        None => false,
+    }
+}
+
+/// Lines `start`..=`end` of a remote pane, as [`tmux::capture_pane_range`].
+pub fn capture(host: &str, pane: &str, range: Option<(&str, &str)>) -> Option<String> {
+    let mut args = vec!["capture-pane", "-p", "-t", pane];
+    if let Some((start, end)) = range {
+        args.extend(["-S", start, "-E", end]);
+    }
+    match tmux(host, &args) {
+        Ok(out) if out.status.success() => Some(String::from_utf8_lossy(&out.stdout).into_owned()),
+        Ok(_) => None,
+        Err(e) => {
+            crate::log!("ssh {host} tmux capture-pane failed for {pane}: {e}");
+            None
+        }
+    }
+}
+
+pub fn send_keys(host: &str, pane: &str, keys: &[&str]) -> bool {
+    let args: Vec<&str> = ["send-keys", "-t", pane].into_iter().chain(keys.iter().copied()).collect();
+    match tmux(host, &args) {
+        Ok(out) => out.status.success(),
+        Err(e) => {
+            crate::log!("ssh {host} tmux send-keys failed for {pane}: {e}");
+            false
+        }
+    }
+}
+
+/// Switch the clients attached to the host's tmux server, such as one in
+/// another terminal's ssh session, to the pane.
+pub fn switch_to_pane(host: &str, pane: &str, zoom: bool) {
+    for command in ["select-window", "select-pane", "switch-client"] {
+        if let Err(e) = tmux(host, &[command, "-t", pane]) {
+            crate::log!("ssh {host} tmux {command} failed: {e}");
+            return;
+        }
+    }
+    if zoom
+        && let Ok(out) = tmux(host, &["display-message", "-p", "-t", pane, "#{window_zoomed_flag}"])
+        && out.status.success()
+        && String::from_utf8_lossy(&out.stdout).trim() != "1"
+        && let Err(e) = tmux(host, &["resize-pane", "-Z", "-t", pane])
+    {
+        crate::log!("ssh {host} tmux resize-pane failed: {e}");
+    }
+}
+
+/// Run tmux on `host`. ssh hands the remote shell a single command line, so
+/// every argument is quoted.
+fn tmux(host: &str, args: &[&str]) -> std::io::Result<Output> {
+    let command = std::iter::once("tmux")
+        .chain(args.iter().copied())
+        .map(shell_quote)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut ssh = Command::new("ssh");
+    ssh.args(SSH_OPTIONS);
+    if let Some(path) = control_path() {
+        ssh.arg("-o").arg(format!("ControlPath={}", path.display()));
+    }
+    ssh.args(["--", host, &command]).output()
+}
+
+/// Socket of the shared connections, `%C` being ssh's hash of the host,
+/// port, and user.
+fn control_path() -> Option<PathBuf> {
+    let dir = paths::state_dir()?;
+    std::fs::create_dir_all(&dir).ok()?;
+    Some(dir.join("ssh-%C"))
+}
+
+pub fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
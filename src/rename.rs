@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::claude_state::ClaudeState;
+use crate::monitor::ClaudeSession;
+use crate::tmux;
+
+/// What `tmux_rename` names after each session's state, so it shows in
+/// tmux's own status bar and borders without the overlay.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TmuxRename {
+    #[default]
+    Off,
+    /// The pane's window (`rename-window`).
+    Window,
+    /// The pane's title (`select-pane -T`), shown in `pane-border-format`.
+    Pane,
+}
+
+/// Mark appended to a session's name per state; `None` while claude is not
+/// running, when the original name comes back.
+pub fn state_mark(state: &ClaudeState) -> Option<&'static str> {
+    Some(match state {
+        ClaudeState::Working => "⏳",
+        ClaudeState::Compacting => "⏳",
+        ClaudeState::WaitingForApproval => "⚠",
+        ClaudeState::WaitingForAnswer => "❓",
+        ClaudeState::Blocked => "⌨",
+        ClaudeState::Error => "❌",
+        ClaudeState::Idle => "✅",
+        ClaudeState::NotRunning => return None,
+    })
+}
+
+const MARKS: &[&str] = &["⏳", "⚠", "❓", "⌨", "❌", "✅"];
+
+/// `myapp ✅`, or `None` when the session should carry no mark.
+pub fn label(session: &ClaudeSession) -> Option<String> {
+    let mark = state_mark(&session.state).filter(|_| session.is_live())?;
+    Some(format!("{} {mark}", session.pane.project_name))
+}
+
+/// `name` without a mark [`label`] appended, so that a renamed window or
+/// title is not read back as part of a session's name.
+pub fn strip_mark(name: &str) -> &str {
+    MARKS
+        .iter()
+        .find_map(|mark| name.strip_suffix(mark).map(str::trim_end))
+        .unwrap_or(name)
+}
+
+/// Keeps the windows or pane titles of tmux sessions named after their
+/// state, putting back what was there once a session ends.
+pub struct Renamer {
+    target: TmuxRename,
+    /// Label last set per pane.
+    shown: HashMap<String, String>,
+    /// What each renamed pane had before: its window's name (`None` when
+    /// tmux named it automatically) or its title.
+    originals: HashMap<String, Option<String>>,
+}
+
+impl Renamer {
+    pub fn new(target: TmuxRename) -> Option<Self> {
+        (target != TmuxRename::Off).then(|| Self { target, shown: HashMap::new(), originals: HashMap::new() })
+    }
+
+    pub fn update(&mut self, sessions: &[ClaudeSession]) {
+        let mut labels: HashMap<&str, String> = HashMap::new();
+        for session in sessions.iter().filter(|s| tmux::is_tmux_pane(&s.pane.id)) {
+            if let Some(label) = label(session) {
+                labels.insert(&session.pane.id, label);
+            }
+        }
+        let ended: Vec<String> =
+            self.shown.keys().filter(|pane| !labels.contains_key(pane.as_str())).cloned().collect();
+        for pane in ended {
+            self.restore(&pane);
+        }
+        for (pane, label) in labels {
+            if self.shown.get(pane) != Some(&label) {
+                self.apply(pane, &label);
+            }
+        }
+    }
+
+    fn apply(&mut self, pane: &str, label: &str) {
+        if !self.originals.contains_key(pane) {
+            let original = match self.target {
+                TmuxRename::Window => match tmux::display(pane, "#{?automatic-rename,,#{window_name}}") {
+                    Some(name) => (!name.is_empty()).then_some(name),
+                    None => return,
+                },
+                _ => match tmux::display(pane, "#{pane_title}") {
+                    Some(title) => Some(title),
+                    None => return,
+                },
+            };
+            self.originals.insert(pane.to_string(), original);
+        }
+        let applied = match self.target {
+            TmuxRename::Window => tmux::run(&["rename-window", "-t", pane, label]),
+            _ => tmux::run(&["select-pane", "-t", pane, "-T", label]),
+        };
+        if applied {
+            self.shown.insert(pane.to_string(), label.to_string());
+        }
+    }
+
+    fn restore(&mut self, pane: &str) {
+        self.shown.remove(pane);
+        // A pane that closed has nothing to restore; the failure is only logged.
+        match (self.target, self.originals.remove(pane).flatten()) {
+            (TmuxRename::Window, Some(name)) => tmux::run(&["rename-window", "-t", pane, &name]),
+            (TmuxRename::Window, None) => tmux::run(&["set-option", "-w", "-t", pane, "automatic-rename", "on"]),
+            (_, title) => tmux::run(&["select-pane", "-t", pane, "-T", &title.unwrap_or_default()]),
+        };
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::paths;
+
+/// How often each detection rule decided a pane's state, persisted to
+/// `$XDG_STATE_HOME/claudeye/rules.json` so `claudeye stats --rules` can
+/// read what the overlay (or `serve`) counted. One hit per pane per poll;
+/// the polling process counts in memory and adds its hits to the file now
+/// and then with [`RuleStats::add`].
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleStats {
+    /// Hits per rule since the counters were last reset.
+    pub totals: BTreeMap<String, u64>,
+    /// Hits per rule of each open pane.
+    pub sessions: BTreeMap<String, BTreeMap<String, u64>>,
+}
+
+impl RuleStats {
+    pub fn load() -> Self {
+        stats_path().map(|p| Self::load_from(&p)).unwrap_or_default()
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Some(path) = stats_path()
+            && let Err(e) = self.save_to(&path)
+        {
+            crate::log!("failed to save {}: {e}", path.display());
+        }
+    }
+
+    pub fn save_to(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string(self)?)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    /// Count one poll of `pane` decided by `rule`.
+    pub fn record(&mut self, pane: &str, rule: &str) {
+        *self.totals.entry(rule.to_string()).or_default() += 1;
+        *self.sessions.entry(pane.to_string()).or_default().entry(rule.to_string()).or_default() += 1;
+    }
+
+    /// Add the `hits` counted since the last save, forgetting the panes
+    /// that are not `live`.
+    pub fn add(&mut self, hits: &RuleStats, live: &HashSet<&str>) {
+        for (rule, n) in &hits.totals {
+            *self.totals.entry(rule.clone()).or_default() += n;
+        }
+        for (pane, counts) in &hits.sessions {
+            let session = self.sessions.entry(pane.clone()).or_default();
+            for (rule, n) in counts {
+                *session.entry(rule.clone()).or_default() += n;
+            }
+        }
+        self.retain_panes(live);
+    }
+
+    /// Forget the per-session counters of panes that are gone; totals stay.
+    pub fn retain_panes(&mut self, live: &HashSet<&str>) {
+        self.sessions.retain(|pane, _| live.contains(pane.as_str()));
+    }
+
+    /// Rules out of `known` that never decided a state.
+    pub fn unused<'a>(&self, known: &[&'a str]) -> Vec<&'a str> {
+        known.iter().copied().filter(|rule| !self.totals.contains_key(*rule)).collect()
+    }
+
+    /// Table for `claudeye stats --rules`: hits and share per rule, busiest
+    /// first, with the panes each rule fired for, then the `known` rules
+    /// that never fired.
+    pub fn render_table(&self, known: &[&str]) -> String {
+        let mut out = format!("{:<24}{:>8}{:>8}  sessions\n", "rule", "hits", "share");
+        for (rule, hits, share) in self.ranked() {
+            let panes = self.panes_of(rule).join(", ");
+            out.push_str(&format!("{rule:<24}{hits:>8}{share:>7.1}%  {panes}\n"));
+        }
+        let unused = self.unused(known);
+        if !unused.is_empty() {
+            out.push_str(&format!("never matched: {}\n", unused.join(", ")));
+        }
+        out
+    }
+
+    /// The rows of [`Self::render_table`] for `--format`: `rule`, `hits`,
+    /// `share` in percent, and `sessions`, a list of `<pane> <hits>`.
+    pub fn rows(&self) -> Vec<Value> {
+        self.ranked()
+            .into_iter()
+            .map(|(rule, hits, share)| {
+                json!({
+                    "rule": rule,
+                    "hits": hits,
+                    "share": (share * 10.0).round() / 10.0,
+                    "sessions": self.panes_of(rule),
+                })
+            })
+            .collect()
+    }
+
+    /// Rules with their hits and share in percent, busiest first.
+    fn ranked(&self) -> Vec<(&str, u64, f64)> {
+        let all: u64 = self.totals.values().sum();
+        let mut rules: Vec<(&str, u64, f64)> = self
+            .totals
+            .iter()
+            .map(|(rule, &hits)| (rule.as_str(), hits, hits as f64 * 100.0 / all.max(1) as f64))
+            .collect();
+        rules.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        rules
+    }
+
+    fn panes_of(&self, rule: &str) -> Vec<String> {
+        self.sessions
+            .iter()
+            .filter_map(|(pane, counts)| counts.get(rule).map(|n| format!("{pane} {n}")))
+            .collect()
+    }
+
+    /// Counters in the Prometheus text format, for `GET /api/metrics`.
+    pub fn prometheus(&self) -> String {
+        let mut out = String::from(
+            "# HELP claudeye_rule_hits_total Polls whose state was decided by each detection rule.\n\
+             # TYPE claudeye_rule_hits_total counter\n",
+        );
+        for (rule, hits) in &self.totals {
+            out.push_str(&format!("claudeye_rule_hits_total{{rule=\"{}\"}} {hits}\n", escape_label(rule)));
+        }
+        out.push_str(
+            "# HELP claudeye_session_rule_hits Polls of an open pane decided by each detection rule.\n\
+             # TYPE claudeye_session_rule_hits gauge\n",
+        );
+        for (pane, counts) in &self.sessions {
+            for (rule, hits) in counts {
+                out.push_str(&format!(
+                    "claudeye_session_rule_hits{{pane=\"{}\",rule=\"{}\"}} {hits}\n",
+                    escape_label(pane),
+                    escape_label(rule)
+                ));
+            }
+        }
+        out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn stats_path() -> Option<PathBuf> {
+    paths::state_dir().map(|dir| dir.join("rules.json"))
+}
@@ -0,0 +1,249 @@
+use std::collections::HashSet;
+use std::process::Command;
+use std::sync::OnceLock;
+use std::thread;
+use std::time::Duration;
+
+use regex::Regex;
+
+use crate::process::{self, ProcessInfo};
+use crate::tmux::{PaneInfo, PaneLabels};
+
+/// How long to wait for screen to write a hardcopy after `-X` returns.
+const HARDCOPY_WAIT: Duration = Duration::from_millis(300);
+const HARDCOPY_POLL: Duration = Duration::from_millis(10);
+
+/// A running screen session as listed by `screen -ls`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScreenSession {
+    /// Pid of the session's `SCREEN` server process.
+    pub pid: u32,
+    /// Name given with `-S`, or `tty.host` by default.
+    pub name: String,
+}
+
+impl ScreenSession {
+    /// `<pid>.<name>`, which `-S` accepts even when names repeat.
+    pub fn full_name(&self) -> String {
+        format!("{}.{}", self.pid, self.name)
+    }
+}
+
+/// Pane id of a screen window: `<pid>.<name>/<window>`. tmux pane ids
+/// always contain a colon and screen session ids never do.
+pub fn window_id(session: &ScreenSession, window: u32) -> String {
+    format!("{}/{window}", session.full_name())
+}
+
+/// The session and window number of a screen pane id, or `None` for a tmux
+/// pane.
+pub fn parse_window_id(id: &str) -> Option<(&str, &str)> {
+    if id.contains(':') {
+        return None;
+    }
+    let (session, window) = id.rsplit_once('/')?;
+    (!session.is_empty() && !window.is_empty() && window.chars().all(|c| c.is_ascii_digit()))
+        .then_some((session, window))
+}
+
+/// Sessions in `screen -ls` output (`\t12345.work\t(Detached)`).
+pub fn parse_ls(output: &str) -> Vec<ScreenSession> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let caps = session_line_pattern().captures(line)?;
+            Some(ScreenSession {
+                pid: caps[1].parse().ok()?,
+                name: caps[2].to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Window numbers and titles in `screen -Q windows` output
+/// (`0$ bash  1*$ claude`).
+pub fn parse_windows(output: &str) -> Vec<(u32, String)> {
+    output
+        .trim()
+        .split("  ")
+        .filter_map(|entry| {
+            let caps = window_entry_pattern().captures(entry.trim())?;
+            Some((caps[1].parse().ok()?, caps[2].trim().to_string()))
+        })
+        .collect()
+}
+
+fn session_line_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^\s+(\d+)\.(\S+)\s").unwrap())
+}
+
+/// A window number, its flags (`*` current, `-` previous, `$` logged in,
+/// `!`/`@` activity, `&` locked, `Z` zombie), and its title.
+fn window_entry_pattern() -> &'static Regex {
+    static P: OnceLock<Regex> = OnceLock::new();
+    P.get_or_init(|| Regex::new(r"^(\d+)[-*$!@&Z]*\s+(.*)$").unwrap())
+}
+
+pub fn list_sessions() -> Vec<ScreenSession> {
+    // `screen -ls` exits non-zero even when it lists sessions.
+    match Command::new("screen").arg("-ls").output() {
+        Ok(out) => parse_ls(&String::from_utf8_lossy(&out.stdout)),
+        Err(e) => {
+            crate::log!("screen -ls failed: {e}");
+            vec![]
+        }
+    }
+}
+
+fn window_titles(session: &ScreenSession) -> Vec<(u32, String)> {
+    let output = Command::new("screen")
+        .args(["-S", &session.full_name(), "-Q", "windows"])
+        .output();
+    match output {
+        Ok(out) => parse_windows(&String::from_utf8_lossy(&out.stdout)),
+        Err(e) => {
+            crate::log!("screen -Q windows failed for {}: {e}", session.name);
+            vec![]
+        }
+    }
+}
+
+/// Every screen window, with the command running in its foreground and
+/// its labels, in the shape of [`crate::tmux::list_panes_with_commands`].
+///
+/// screen does not report what runs in a window, so the window is found
+/// among the children of the session's server process by the `WINDOW`
+/// variable screen sets, which needs `/proc` (Linux).
+pub fn list_windows() -> Vec<(PaneInfo, String, PaneLabels)> {
+    let sessions = list_sessions();
+    if sessions.is_empty() {
+        return vec![];
+    }
+    let processes = process::list_processes();
+    let mut windows = vec![];
+    for session in &sessions {
+        let titles = window_titles(session);
+        for child in processes.iter().filter(|p| p.ppid == session.pid) {
+            let Some(window) = window_number(child.pid) else {
+                continue;
+            };
+            let foreground = foreground_process(&processes, child);
+            let cwd = std::fs::read_link(format!("/proc/{}/cwd", foreground.pid))
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let project_name = std::path::Path::new(&cwd)
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+            let title = titles.iter().find(|(n, _)| *n == window).map(|(_, t)| t.clone()).unwrap_or_default();
+            let pane = PaneInfo {
+                id: window_id(session, window),
+                pid: child.pid,
+                cwd,
+                project_name,
+                session_name: session.name.clone(),
+                window_name: title,
+            };
+            windows.push((pane, foreground.command.clone(), PaneLabels::default()));
+        }
+    }
+    windows
+}
+
+/// Pane ids of every open screen window.
+pub fn all_window_ids() -> HashSet<String> {
+    list_windows().into_iter().map(|(pane, _, _)| pane.id).collect()
+}
+
+/// The `WINDOW` number screen gave the process started in a window.
+fn window_number(pid: u32) -> Option<u32> {
+    let environ = std::fs::read(format!("/proc/{pid}/environ")).ok()?;
+    environ
+        .split(|b| *b == 0)
+        .find_map(|var| var.strip_prefix(b"WINDOW="))
+        .and_then(|n| std::str::from_utf8(n).ok()?.parse().ok())
+}
+
+/// The process a window shows, like tmux's `#{pane_current_command}`: the
+/// leader of its terminal's foreground process group, so a command claude
+/// runs in the background does not hide claude.
+fn foreground_process<'a>(processes: &'a [ProcessInfo], root: &'a ProcessInfo) -> &'a ProcessInfo {
+    std::fs::read_to_string(format!("/proc/{}/stat", root.pid))
+        .ok()
+        .and_then(|stat| parse_tpgid(&stat))
+        .and_then(|pgid| processes.iter().find(|p| p.pid == pgid))
+        .unwrap_or(root)
+}
+
+/// The foreground process group of the terminal in a `/proc/<pid>/stat`
+/// line, the sixth field after the parenthesized command name.
+pub fn parse_tpgid(stat: &str) -> Option<u32> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(5)?.parse().ok()
+}
+
+/// The visible content of a window, via `hardcopy` into a temporary file.
+pub fn capture(session: &str, window: &str) -> Option<String> {
+    let path = std::env::temp_dir().join(format!(
+        "claudeye-hardcopy-{}-{}-{window}",
+        std::process::id(),
+        session.replace(['/', '.'], "_")
+    ));
+    let _ = std::fs::remove_file(&path);
+    let output = Command::new("screen")
+        .args(["-S", session, "-p", window, "-X", "hardcopy"])
+        .arg(&path)
+        .output();
+    match output {
+        Ok(out) if out.status.success() => {}
+        Ok(_) => return None,
+        Err(e) => {
+            crate::log!("screen hardcopy failed for {session}/{window}: {e}");
+            return None;
+        }
+    }
+    // The session writes the file once it handles the command, which may
+    // be after `-X` returns.
+    let mut waited = Duration::ZERO;
+    while waited < HARDCOPY_WAIT {
+        if let Ok(text) = std::fs::read_to_string(&path) {
+            let _ = std::fs::remove_file(&path);
+            return Some(text);
+        }
+        thread::sleep(HARDCOPY_POLL);
+        waited += HARDCOPY_POLL;
+    }
+    None
+}
+
+/// Type `keys` (tmux key names such as `Enter`) into a window with `stuff`.
+pub fn send_keys(session: &str, window: &str, keys: &[&str]) -> bool {
+    let text: String = keys
+        .iter()
+        .map(|key| match *key {
+            "Enter" => "\r",
+            "Escape" => "\x1b",
+            other => other,
+        })
+        .collect();
+    let result = Command::new("screen")
+        .args(["-S", session, "-p", window, "-X", "stuff", &text])
+        .output();
+    match result {
+        Ok(out) => out.status.success(),
+        Err(e) => {
+            crate::log!("screen stuff failed for {session}/{window}: {e}");
+            false
+        }
+    }
+}
+
+/// Show a window in the terminals attached to its session.
+pub fn select_window(session: &str, window: &str) {
+    let result = Command::new("screen").args(["-S", session, "-X", "select", window]).output();
+    if let Err(e) = result {
+        crate::log!("screen select failed for {session}/{window}: {e}");
+    }
+}
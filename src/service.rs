@@ -0,0 +1,237 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::paths;
+
+/// systemd unit name and launchd label of the background service.
+pub const UNIT_NAME: &str = "claudeye.service";
+pub const LAUNCHD_LABEL: &str = "com.github.pi-chan.claudeye";
+
+/// Variables copied from the installing shell into the service, which
+/// otherwise starts with a bare environment: `PATH` to find tmux, claude,
+/// `gh` and `glab`, `TMUX_TMPDIR` to find tmux's socket, `KITTY_LISTEN_ON`
+/// to reach kitty, `SSH_AUTH_SOCK` to log in to `remote_hosts`, and the XDG
+/// directories to find the config and state.
+const PASSED_VARS: &[&str] = &[
+    "PATH",
+    "TMUX_TMPDIR",
+    "KITTY_LISTEN_ON",
+    "SSH_AUTH_SOCK",
+    "XDG_CONFIG_HOME",
+    "XDG_STATE_HOME",
+    "LANG",
+];
+
+/// The service manager of this OS.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Manager {
+    /// A systemd user unit, managed with `systemctl --user`.
+    Systemd,
+    /// A launchd agent, managed with `launchctl`.
+    Launchd,
+}
+
+impl Manager {
+    pub fn current() -> Self {
+        if cfg!(target_os = "macos") { Manager::Launchd } else { Manager::Systemd }
+    }
+
+    /// Where the unit or plist is installed.
+    pub fn file_path(self) -> Option<PathBuf> {
+        let home = PathBuf::from(std::env::var_os("HOME")?);
+        Some(match self {
+            Manager::Systemd => std::env::var_os("XDG_CONFIG_HOME")
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+                .unwrap_or_else(|| home.join(".config"))
+                .join("systemd/user")
+                .join(UNIT_NAME),
+            Manager::Launchd => home.join("Library/LaunchAgents").join(format!("{LAUNCHD_LABEL}.plist")),
+        })
+    }
+}
+
+/// What the service runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceSpec {
+    /// Program and arguments, e.g. `/usr/local/bin/claudeye serve`.
+    pub command: Vec<String>,
+    pub env: Vec<(String, String)>,
+    /// File receiving the output (launchd only; systemd uses the journal).
+    pub log: Option<PathBuf>,
+}
+
+impl ServiceSpec {
+    /// `claudeye serve` at `exe` under `profile`, with the environment taken
+    /// from `var`.
+    pub fn serve(exe: &str, profile: Option<&str>, var: impl Fn(&str) -> Option<String>) -> Self {
+        let mut command = vec![exe.to_string()];
+        if let Some(profile) = profile {
+            command.extend(["--profile".to_string(), profile.to_string()]);
+        }
+        command.push("serve".to_string());
+        Self {
+            command,
+            env: service_env(var),
+            log: paths::state_dir().map(|dir| dir.join("service.log")),
+        }
+    }
+
+    pub fn render(&self, manager: Manager) -> String {
+        match manager {
+            Manager::Systemd => systemd_unit(self),
+            Manager::Launchd => launchd_plist(self),
+        }
+    }
+}
+
+/// The [`PASSED_VARS`] that are set, plus the tmux socket when claudeye is
+/// installed from inside a tmux server that does not use the default one
+/// (`tmux -L work`), since tmux looks for the socket in `TMUX` first.
+pub fn service_env(var: impl Fn(&str) -> Option<String>) -> Vec<(String, String)> {
+    let mut env: Vec<(String, String)> = PASSED_VARS
+        .iter()
+        .filter_map(|name| Some((name.to_string(), var(name).filter(|v| !v.is_empty())?)))
+        .collect();
+    let socket = var("TMUX").and_then(|tmux| tmux.split(',').next().map(str::to_string));
+    if let Some(socket) = socket.filter(|s| !s.is_empty() && !s.ends_with("/default")) {
+        env.push(("TMUX".to_string(), socket));
+    }
+    env
+}
+
+pub fn systemd_unit(spec: &ServiceSpec) -> String {
+    let exec = spec.command.iter().map(|arg| systemd_quote(arg)).collect::<Vec<_>>().join(" ");
+    let mut unit = format!(
+        "[Unit]\n\
+         Description=claudeye: monitor Claude Code sessions in tmux\n\
+         \n\
+         [Service]\n\
+         ExecStart={exec}\n"
+    );
+    for (name, value) in &spec.env {
+        unit.push_str(&format!("Environment={}\n", systemd_quote(&format!("{name}={value}"))));
+    }
+    unit.push_str(
+        "Restart=on-failure\n\
+         RestartSec=5\n\
+         \n\
+         [Install]\n\
+         WantedBy=default.target\n",
+    );
+    unit
+}
+
+fn systemd_quote(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('"', "\\\"").replace('%', "%%");
+    format!("\"{escaped}\"")
+}
+
+pub fn launchd_plist(spec: &ServiceSpec) -> String {
+    let mut plist = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n\
+         <dict>\n",
+    );
+    plist.push_str(&format!("  <key>Label</key>\n  <string>{LAUNCHD_LABEL}</string>\n"));
+    plist.push_str("  <key>ProgramArguments</key>\n  <array>\n");
+    for arg in &spec.command {
+        plist.push_str(&format!("    <string>{}</string>\n", xml_escape(arg)));
+    }
+    plist.push_str("  </array>\n");
+    if !spec.env.is_empty() {
+        plist.push_str("  <key>EnvironmentVariables</key>\n  <dict>\n");
+        for (name, value) in &spec.env {
+            plist.push_str(&format!(
+                "    <key>{}</key>\n    <string>{}</string>\n",
+                xml_escape(name),
+                xml_escape(value)
+            ));
+        }
+        plist.push_str("  </dict>\n");
+    }
+    plist.push_str("  <key>RunAtLoad</key>\n  <true/>\n  <key>KeepAlive</key>\n  <true/>\n");
+    if let Some(log) = &spec.log {
+        let log = xml_escape(&log.to_string_lossy());
+        plist.push_str(&format!("  <key>StandardOutPath</key>\n  <string>{log}</string>\n"));
+        plist.push_str(&format!("  <key>StandardErrorPath</key>\n  <string>{log}</string>\n"));
+    }
+    plist.push_str("</dict>\n</plist>\n");
+    plist
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Write the service file and start it now and at every login.
+pub fn install(manager: Manager, spec: &ServiceSpec) -> Result<PathBuf, String> {
+    let path = manager.file_path().ok_or("cannot locate the service directory (HOME unset)")?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    }
+    if let Some(dir) = spec.log.as_deref().and_then(Path::parent) {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    }
+    // launchd keeps the old definition loaded until it is unloaded.
+    if manager == Manager::Launchd && path.exists() {
+        let _ = disable(manager);
+    }
+    std::fs::write(&path, spec.render(manager)).map_err(|e| format!("{}: {e}", path.display()))?;
+    if manager == Manager::Systemd {
+        run("systemctl", &["--user", "daemon-reload"])?;
+    }
+    enable(manager)?;
+    Ok(path)
+}
+
+/// Stop the service and remove its file; `None` when it was not installed.
+pub fn uninstall(manager: Manager) -> Result<Option<PathBuf>, String> {
+    let path = manager.file_path().ok_or("cannot locate the service directory (HOME unset)")?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    disable(manager)?;
+    std::fs::remove_file(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+    if manager == Manager::Systemd {
+        run("systemctl", &["--user", "daemon-reload"])?;
+    }
+    Ok(Some(path))
+}
+
+/// Start the installed service and at every login.
+pub fn enable(manager: Manager) -> Result<(), String> {
+    match manager {
+        Manager::Systemd => run("systemctl", &["--user", "enable", "--now", UNIT_NAME]),
+        Manager::Launchd => run("launchctl", &["load", "-w", &plist_path(manager)?]),
+    }
+}
+
+/// Stop the service and keep it from starting at login.
+pub fn disable(manager: Manager) -> Result<(), String> {
+    match manager {
+        Manager::Systemd => run("systemctl", &["--user", "disable", "--now", UNIT_NAME]),
+        Manager::Launchd => run("launchctl", &["unload", "-w", &plist_path(manager)?]),
+    }
+}
+
+fn plist_path(manager: Manager) -> Result<String, String> {
+    let path = manager.file_path().ok_or("cannot locate the service directory (HOME unset)")?;
+    if !path.exists() {
+        return Err(format!("{} is not installed; run `claudeye service install`", path.display()));
+    }
+    Ok(path.to_string_lossy().into_owned())
+}
+
+fn run(program: &str, args: &[&str]) -> Result<(), String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("{program}: {e}"))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("{program} {}: {}", args.join(" "), String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
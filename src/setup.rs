@@ -0,0 +1,244 @@
+use std::ffi::OsStr;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config;
+use crate::tmux;
+
+/// Positions accepted by `--position` and the `position` setting.
+pub const POSITIONS: &[&str] = &[
+    "top-left",
+    "top-center",
+    "top-right",
+    "middle-left",
+    "middle-center",
+    "middle-right",
+    "bottom-left",
+    "bottom-center",
+    "bottom-right",
+];
+
+const BLOCK_START: &str = "# >>> claudeye >>>";
+const BLOCK_END: &str = "# <<< claudeye <<<";
+
+/// Tools claudeye relies on, as found on this machine.
+#[derive(Debug, Default)]
+pub struct Environment {
+    /// `tmux -V` output, e.g. `tmux 3.4`.
+    pub tmux: Option<String>,
+    /// Path of the `claude` command.
+    pub claude: Option<String>,
+}
+
+pub fn detect() -> Environment {
+    Environment {
+        tmux: command_output(tmux::program(), &["-V"]),
+        claude: command_output("which", &["claude"]),
+    }
+}
+
+fn command_output(program: impl AsRef<OsStr>, args: &[&str]) -> Option<String> {
+    let out = Command::new(program).args(args).output().ok()?;
+    let text = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    (out.status.success() && !text.is_empty()).then_some(text)
+}
+
+/// Answers collected by `claudeye init`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Answers {
+    pub position: String,
+    pub notifications: bool,
+}
+
+/// Initial config file contents for the answers.
+pub fn render_config(answers: &Answers) -> String {
+    format!(
+        "# claudeye settings; see the README for every option.\n\
+         \n\
+         # Overlay position when --position is not given\n\
+         position = \"{}\"\n\
+         \n\
+         # Desktop notifications for watch rules, guardrails, and errors\n\
+         notifications = {}\n",
+        answers.position, answers.notifications
+    )
+}
+
+/// tmux `bind-key` line opening the picker in a popup on `prefix` + `key`.
+pub fn popup_binding(exe: &str, key: &str) -> String {
+    format!("bind-key {key} display-popup -E -w 80% -h 60% \"{exe} picker\"")
+}
+
+/// tmux line appending the `claudeye prompt` summary to the status bar.
+pub fn status_snippet(exe: &str) -> String {
+    format!("set -ag status-right \" #({exe} prompt)\"")
+}
+
+/// tmux hooks after which a pane likely changed state: it was focused, its
+/// window showed activity (with `monitor-activity on`), or keys were sent
+/// to it (such as by `claudeye approve`).
+pub const POKE_HOOKS: &[&str] = &["pane-focus-in", "alert-activity", "after-send-keys"];
+
+/// Index the poke hooks are set at, so that reloading the config replaces
+/// rather than adds them, and hooks of the user's own (at 0) stay.
+const POKE_HOOK_INDEX: u32 = 90;
+
+/// tmux lines running `claudeye poke` on the pane behind each of
+/// [`POKE_HOOKS`], so running monitors capture it at once instead of at
+/// their next poll.
+pub fn poke_hooks(exe: &str) -> Vec<String> {
+    POKE_HOOKS
+        .iter()
+        .map(|hook| {
+            format!(
+                "set-hook -g {hook}[{POKE_HOOK_INDEX}] \"run-shell -b '{exe} poke #{{q:session_name}}:#{{window_index}}.#{{pane_index}}'\""
+            )
+        })
+        .collect()
+}
+
+/// `text` with the claudeye block set to `lines`, replacing an existing
+/// block in place or appending a new one, so repeated installs never
+/// duplicate it.
+pub fn install_block(text: &str, lines: &[String]) -> String {
+    let block = format!("{BLOCK_START}\n{}\n{BLOCK_END}\n", lines.join("\n"));
+    if let Some((before, after)) = split_block(text) {
+        return format!("{before}{block}{after}");
+    }
+    let mut out = text.to_string();
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+    out.push_str(&block);
+    out
+}
+
+/// `text` without its claudeye block, if it has one.
+pub fn remove_block(text: &str) -> String {
+    match split_block(text) {
+        Some((before, after)) => format!("{before}{after}"),
+        None => text.to_string(),
+    }
+}
+
+/// The text before and after an existing claudeye block.
+fn split_block(text: &str) -> Option<(&str, &str)> {
+    let start = text.find(BLOCK_START)?;
+    let end_marker = start + text[start..].find(BLOCK_END)?;
+    let mut end = end_marker + BLOCK_END.len();
+    if text[end..].starts_with('\n') {
+        end += 1;
+    }
+    Some((&text[..start], &text[end..]))
+}
+
+/// The tmux config file in use: `~/.tmux.conf`, or the XDG location when
+/// only that exists.
+pub fn tmux_conf_path() -> Option<PathBuf> {
+    let home = PathBuf::from(std::env::var_os("HOME")?);
+    let classic = home.join(".tmux.conf");
+    let xdg = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| home.join(".config"))
+        .join("tmux/tmux.conf");
+    Some(if !classic.exists() && xdg.exists() { xdg } else { classic })
+}
+
+/// Write `lines` as the claudeye block of a tmux config file.
+pub fn write_tmux_block(path: &Path, lines: &[String]) -> io::Result<()> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e),
+    };
+    std::fs::write(path, install_block(&text, lines))
+}
+
+/// Remove the claudeye block from a tmux config file; false when there was
+/// none.
+pub fn remove_tmux_block(path: &Path) -> io::Result<bool> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+    let stripped = remove_block(&text);
+    if stripped == text {
+        return Ok(false);
+    }
+    std::fs::write(path, stripped)?;
+    Ok(true)
+}
+
+/// Ask a question on `output`, returning the trimmed answer or `default`
+/// when it is empty.
+fn ask(input: &mut impl BufRead, output: &mut impl Write, question: &str, default: &str) -> io::Result<String> {
+    write!(output, "{question} [{default}]: ")?;
+    output.flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Ok(default.to_string());
+    }
+    let answer = line.trim();
+    Ok(if answer.is_empty() { default } else { answer }.to_string())
+}
+
+fn confirm(input: &mut impl BufRead, output: &mut impl Write, question: &str, default: bool) -> io::Result<bool> {
+    let hint = if default { "Y/n" } else { "y/N" };
+    loop {
+        let answer = ask(input, output, question, hint)?;
+        match answer.to_lowercase().as_str() {
+            _ if answer == hint => return Ok(default),
+            "y" | "yes" => return Ok(true),
+            "n" | "no" => return Ok(false),
+            _ => writeln!(output, "Please answer y or n.")?,
+        }
+    }
+}
+
+/// Interactive first-run setup: check for tmux and claude, ask for the
+/// overlay position, notifications, and an optional picker keybinding,
+/// then write the config file.
+pub fn run_init(input: &mut impl BufRead, output: &mut impl Write) -> Result<(), Box<dyn std::error::Error>> {
+    let env = detect();
+    writeln!(output, "tmux:   {}", env.tmux.as_deref().unwrap_or("not found (claudeye needs tmux)"))?;
+    writeln!(output, "claude: {}", env.claude.as_deref().unwrap_or("not found on PATH"))?;
+    writeln!(output)?;
+
+    let path = config::config_path().ok_or("cannot locate the config directory (HOME unset)")?;
+    if path.exists()
+        && !confirm(input, output, &format!("{} exists. Overwrite it?", path.display()), false)?
+    {
+        writeln!(output, "Keeping the existing config.")?;
+        return Ok(());
+    }
+
+    let position = loop {
+        let answer = ask(input, output, &format!("Overlay position ({})", POSITIONS.join(", ")), "top-center")?;
+        if POSITIONS.contains(&answer.as_str()) {
+            break answer;
+        }
+        writeln!(output, "Unknown position {answer:?}.")?;
+    };
+    let notifications = confirm(input, output, "Send desktop notifications?", true)?;
+    let answers = Answers { position, notifications };
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, render_config(&answers))?;
+    writeln!(output, "Wrote {}", path.display())?;
+
+    if env.tmux.is_some()
+        && let Some(conf) = tmux_conf_path()
+        && confirm(input, output, &format!("Add a picker popup keybinding to {}?", conf.display()), true)?
+    {
+        let key = ask(input, output, "Key (pressed after the tmux prefix)", "C-g")?;
+        let exe = std::env::current_exe()?;
+        write_tmux_block(&conf, &[popup_binding(&exe.to_string_lossy(), &key)])?;
+        writeln!(output, "Added to {}; reload with: tmux source-file {}", conf.display(), conf.display())?;
+    }
+    Ok(())
+}
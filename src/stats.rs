@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::claude_state::ClaudeState;
+use crate::elapsed::{self, unix_now};
+use crate::monitor::{Transition, TransitionHook};
+use crate::paths;
+use crate::redact::redact;
+use crate::status::state_key;
+use crate::text;
+
+/// Settings from the `[stats]` config table.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StatsConfig {
+    /// Record transitions and turns in `stats.db` in the data directory,
+    /// through the `sqlite3` command.
+    pub enabled: bool,
+}
+
+impl StatsConfig {
+    /// With stats enabled, make sure a recent enough `sqlite3` is there to
+    /// record them, rather than failing on every transition.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.enabled { check_sqlite() } else { Ok(()) }
+    }
+}
+
+/// Oldest `sqlite3` release with `-json` output.
+const MIN_SQLITE: (u32, u32) = (3, 33);
+
+/// Whether `sqlite3 -version` output names a release stats can use.
+pub fn check_version(output: &str) -> Result<(), String> {
+    let mut parts = output.split_whitespace().next().unwrap_or_default().split('.').map(str::parse::<u32>);
+    match (parts.next(), parts.next()) {
+        (Some(Ok(major)), Some(Ok(minor))) if (major, minor) >= MIN_SQLITE => Ok(()),
+        (Some(Ok(major)), Some(Ok(minor))) => Err(format!(
+            "stats need sqlite3 {}.{} or newer, found {major}.{minor}",
+            MIN_SQLITE.0, MIN_SQLITE.1
+        )),
+        _ => Err(format!("unexpected sqlite3 -version output {:?}", output.trim())),
+    }
+}
+
+/// Check the `sqlite3` on `PATH` once, before stats are recorded or read.
+pub fn check_sqlite() -> Result<(), String> {
+    let output = Command::new("sqlite3")
+        .arg("-version")
+        .output()
+        .map_err(|e| format!("stats need the sqlite3 command: {e}"))?;
+    check_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Span of time `claudeye stats` reports on.
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum Period {
+    /// Since local midnight
+    #[default]
+    Today,
+    /// Since Monday, local time
+    Week,
+}
+
+impl Period {
+    pub fn key(self) -> &'static str {
+        match self {
+            Period::Today => "today",
+            Period::Week => "week",
+        }
+    }
+
+    /// SQL for the Unix time the period starts at, in local time.
+    fn start_sql(self) -> &'static str {
+        match self {
+            Period::Today => "CAST(strftime('%s', 'now', 'localtime', 'start of day', 'utc') AS INTEGER)",
+            Period::Week => "CAST(strftime('%s', 'now', 'localtime', 'start of day', '-6 days', 'weekday 1', 'utc') AS INTEGER)",
+        }
+    }
+}
+
+const SCHEMA: &str = "\
+CREATE TABLE IF NOT EXISTS transitions (
+    ts INTEGER NOT NULL,
+    pane TEXT NOT NULL,
+    project TEXT NOT NULL,
+    from_state TEXT NOT NULL,
+    to_state TEXT NOT NULL,
+    duration_secs INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS turns (
+    ts INTEGER NOT NULL,
+    pane TEXT NOT NULL,
+    project TEXT NOT NULL,
+    duration_secs INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS transitions_ts ON transitions (ts);
+CREATE INDEX IF NOT EXISTS turns_ts ON turns (ts);
+";
+
+/// `stats.db` in the data directory.
+pub fn db_path() -> Option<PathBuf> {
+    paths::data_dir().map(|dir| dir.join("stats.db"))
+}
+
+/// `text` as an SQL string literal.
+pub fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// Statement recording a transition seen at `timestamp`.
+pub fn transition_sql(transition: &Transition, timestamp: u64) -> String {
+    format!(
+        "INSERT INTO transitions VALUES ({timestamp}, {}, {}, {}, {}, {});\n",
+        quote(&transition.pane.id),
+        quote(&redact(&transition.pane.project_name)),
+        quote(state_key(&transition.from)),
+        quote(state_key(&transition.to)),
+        transition.elapsed.as_secs(),
+    )
+}
+
+/// Statement recording a turn of `secs` that ended with `transition`.
+pub fn turn_sql(transition: &Transition, secs: u64, timestamp: u64) -> String {
+    format!(
+        "INSERT INTO turns VALUES ({timestamp}, {}, {}, {secs});\n",
+        quote(&transition.pane.id),
+        quote(&redact(&transition.pane.project_name)),
+    )
+}
+
+/// Follows turns across transitions: a turn starts when an idle session
+/// starts working and ends when it is idle again, approvals and questions
+/// on the way included.
+#[derive(Debug, Default)]
+pub struct Turns {
+    started: HashMap<String, u64>,
+}
+
+impl Turns {
+    /// The length of the turn `transition` ends, if it ends one.
+    pub fn observe(&mut self, transition: &Transition, timestamp: u64) -> Option<u64> {
+        let pane = &transition.pane.id;
+        match (&transition.from, &transition.to) {
+            (ClaudeState::Idle, ClaudeState::Working) => {
+                self.started.insert(pane.clone(), timestamp);
+                None
+            }
+            (_, ClaudeState::Idle) => self.started.remove(pane).map(|started| timestamp.saturating_sub(started)),
+            (_, ClaudeState::NotRunning) => {
+                self.started.remove(pane);
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Run `sql` against the database at `db`, creating it and its tables as
+/// needed.
+pub fn execute(db: &Path, sql: &str) -> Result<(), String> {
+    if let Some(dir) = db.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    }
+    sqlite(db, &[], &format!("{SCHEMA}{sql}")).map(|_| ())
+}
+
+fn sqlite(db: &Path, args: &[&str], sql: &str) -> Result<String, String> {
+    let mut child = Command::new("sqlite3")
+        .args(["-bail", "-cmd", ".timeout 5000"])
+        .args(args)
+        .arg(db)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("sqlite3: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(sql.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!("sqlite3: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Transition hook implementing `[stats]`. Writes go through one thread so
+/// that the polling loop never waits on `sqlite3`.
+pub fn hook(config: &StatsConfig) -> Option<TransitionHook> {
+    if !config.enabled {
+        return None;
+    }
+    let db = db_path()?;
+    let (queue, statements) = mpsc::channel::<String>();
+    thread::spawn(move || {
+        for sql in statements {
+            if let Err(e) = execute(&db, &sql) {
+                crate::log!("failed to record stats in {}: {e}", db.display());
+            }
+        }
+    });
+    let mut turns = Turns::default();
+    Some(Box::new(move |t: &Transition| {
+        let timestamp = unix_now();
+        let mut sql = transition_sql(t, timestamp);
+        if let Some(secs) = turns.observe(t, timestamp) {
+            sql.push_str(&turn_sql(t, secs, timestamp));
+        }
+        let _ = queue.send(sql);
+    }))
+}
+
+/// Per-project totals since the start of `period`, busiest first:
+/// `working_secs`, `approvals` answered, `avg_wait_secs` for an approval,
+/// `turns`, and `avg_turn_secs`. Averages are null without any to average.
+pub fn report(db: &Path, period: Period) -> Result<Vec<Value>, String> {
+    let start = period.start_sql();
+    let sql = format!(
+        "SELECT project,
+            SUM(CASE WHEN from_state = 'running' THEN duration_secs ELSE 0 END) AS working_secs,
+            SUM(from_state = 'approval' AND to_state != 'not_running') AS approvals,
+            CAST(ROUND(AVG(CASE WHEN from_state = 'approval' AND to_state != 'not_running' THEN duration_secs END)) AS INTEGER) AS avg_wait_secs,
+            (SELECT COUNT(*) FROM turns u WHERE u.project = t.project AND u.ts >= {start}) AS turns,
+            (SELECT CAST(ROUND(AVG(duration_secs)) AS INTEGER) FROM turns u WHERE u.project = t.project AND u.ts >= {start}) AS avg_turn_secs
+        FROM transitions t
+        WHERE ts >= {start}
+        GROUP BY project
+        ORDER BY working_secs DESC, project;\n"
+    );
+    let out = sqlite(db, &["-json", "-readonly"], &sql)?;
+    if out.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    serde_json::from_str(&out).map_err(|e| format!("sqlite3 output: {e}"))
+}
+
+/// `claudeye stats` as a table of [`report`]'s rows.
+pub fn render_table(rows: &[Value], period: Period) -> String {
+    if rows.is_empty() {
+        return format!("nothing recorded {}\n", period_phrase(period));
+    }
+    let width = rows.iter().map(|row| text::width(row["project"].as_str().unwrap_or_default())).max().unwrap_or(0).max(7);
+    let mut out = format!("{}{:>10}{:>11}{:>10}{:>7}{:>10}\n", text::pad("project", width), "working", "approvals", "avg wait", "turns", "avg turn");
+    let secs = |value: &Value| value.as_u64().map(elapsed::compact).unwrap_or_else(|| "-".to_string());
+    for row in rows {
+        out.push_str(&format!(
+            "{}{:>10}{:>11}{:>10}{:>7}{:>10}\n",
+            text::pad(row["project"].as_str().unwrap_or_default(), width),
+            secs(&row["working_secs"]),
+            row["approvals"].as_u64().unwrap_or(0),
+            secs(&row["avg_wait_secs"]),
+            row["turns"].as_u64().unwrap_or(0),
+            secs(&row["avg_turn_secs"]),
+        ));
+    }
+    out
+}
+
+fn period_phrase(period: Period) -> &'static str {
+    match period {
+        Period::Today => "today",
+        Period::Week => "this week",
+    }
+}
+
+/// The document `claudeye stats --format json` prints.
+pub fn document(rows: &[Value], period: Period) -> Value {
+    json!({ "period": period.key(), "projects": rows })
+}
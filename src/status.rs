@@ -0,0 +1,370 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::agents;
+use crate::api::ApiConfig;
+use crate::claude_state::ClaudeState;
+use crate::config::Config;
+use crate::elapsed::unix_now;
+use crate::health::{self, Health};
+use crate::marks::Marks;
+use crate::monitor::{self, ClaudeSession, MonitorConfig, StateTotals};
+use crate::paths;
+use crate::redact::redact;
+use crate::team;
+use crate::tmux;
+
+/// How long `claudeye prompt` reuses its previous result; prompts redraw far
+/// more often than sessions change.
+const PROMPT_CACHE_TTL: Duration = Duration::from_secs(2);
+
+/// How often a [`StatusWriter`] writes `status.json` even when nothing
+/// changed, so readers can tell a running monitor from one that stopped.
+const STATUS_REFRESH: Duration = Duration::from_secs(10);
+
+/// Number of live sessions per state, plus finished ones with unread output.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateCounts {
+    pub running: usize,
+    pub compacting: usize,
+    pub approval: usize,
+    pub answer: usize,
+    pub blocked: usize,
+    pub idle: usize,
+    pub error: usize,
+    pub not_running: usize,
+    pub unread: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionStatus {
+    pub pane: String,
+    pub project: String,
+    /// Agent CLI running in the pane (`claude`, `aider`, ...).
+    pub tool: &'static str,
+    pub state: &'static str,
+    pub unread: bool,
+    /// Unix time (seconds) the session entered its current state.
+    pub state_since: u64,
+    pub watch: Vec<String>,
+    /// What a session waiting for approval asks to do (`Bash: cargo test`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending: Option<String>,
+    /// What kind of approval that is (`bash`, `file_edit`, `mcp`, `trust`,
+    /// `plan`, or `other`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval: Option<&'static str>,
+    /// Error banner of a session in the `error` state.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The prompt a session in the `blocked` state waits at.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub input_prompt: Option<String>,
+    /// An `idle` session was stopped with Esc and waits for redirection.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub interrupted: bool,
+    /// The session bypasses permission checks and never asks for approval.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub skips_permissions: bool,
+    /// Messages typed while working that Claude has not read yet.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub queued_messages: u32,
+    /// States the session was in before the current one, oldest first.
+    pub history: Vec<PastState>,
+    /// Total time spent working, waiting for approval, and idle, up to
+    /// when the snapshot was taken.
+    #[serde(flatten)]
+    pub time_in_states: TimeInStates,
+}
+
+/// Seconds a session has spent in each state over its whole life.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TimeInStates {
+    pub working_secs: u64,
+    pub approval_secs: u64,
+    pub idle_secs: u64,
+}
+
+impl From<StateTotals> for TimeInStates {
+    fn from(totals: StateTotals) -> Self {
+        Self {
+            working_secs: totals.working.as_secs(),
+            approval_secs: totals.approval.as_secs(),
+            idle_secs: totals.idle.as_secs(),
+        }
+    }
+}
+
+/// An earlier state of a session in `status.json`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PastState {
+    pub state: &'static str,
+    /// Unix time (seconds) the session entered it.
+    pub since: u64,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+/// What gets written to `status.json` for editor statuslines and prompts.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StatusSnapshot {
+    pub counts: StateCounts,
+    pub sessions: Vec<SessionStatus>,
+}
+
+/// Stable machine-readable name of a state, matching the `[labels]` keys.
+pub fn state_key(state: &ClaudeState) -> &'static str {
+    match state {
+        ClaudeState::Working => "running",
+        ClaudeState::Compacting => "compacting",
+        ClaudeState::WaitingForApproval => "approval",
+        ClaudeState::WaitingForAnswer => "answer",
+        ClaudeState::Blocked => "blocked",
+        ClaudeState::Idle => "idle",
+        ClaudeState::Error => "error",
+        ClaudeState::NotRunning => "not_running",
+    }
+}
+
+impl StatusSnapshot {
+    pub fn from_sessions(sessions: &[ClaudeSession]) -> Self {
+        let now = unix_now();
+        let mut counts = StateCounts::default();
+        let sessions = sessions
+            .iter()
+            .filter(|s| s.is_live())
+            .map(|s| {
+                match s.state {
+                    ClaudeState::Working => counts.running += 1,
+                    ClaudeState::Compacting => counts.compacting += 1,
+                    ClaudeState::WaitingForApproval => counts.approval += 1,
+                    ClaudeState::WaitingForAnswer => counts.answer += 1,
+                    ClaudeState::Blocked => counts.blocked += 1,
+                    ClaudeState::Idle => counts.idle += 1,
+                    ClaudeState::Error => counts.error += 1,
+                    ClaudeState::NotRunning => counts.not_running += 1,
+                }
+                if s.shows_unread() {
+                    counts.unread += 1;
+                }
+                SessionStatus {
+                    pane: s.pane.id.clone(),
+                    project: redact(&s.pane.project_name).into_owned(),
+                    tool: s.tool,
+                    state: state_key(&s.state),
+                    unread: s.shows_unread(),
+                    state_since: now.saturating_sub(s.state_changed_at.elapsed().as_secs()),
+                    watch: s.watch_hits.clone(),
+                    pending: s.pending.as_ref().map(|p| redact(&p.summary(120)).into_owned()),
+                    approval: s.pending.as_ref().map(|p| p.kind.key()),
+                    error: s.error.as_deref().map(|e| redact(e).into_owned()),
+                    input_prompt: s.input_prompt.as_deref().map(|p| redact(p).into_owned()),
+                    interrupted: s.interrupted && s.state == ClaudeState::Idle,
+                    skips_permissions: s.skips_permissions,
+                    queued_messages: s.queued_messages,
+                    history: s
+                        .history
+                        .iter()
+                        .map(|(state, entered)| PastState {
+                            state: state_key(state),
+                            since: now.saturating_sub(entered.elapsed().as_secs()),
+                        })
+                        .collect(),
+                    time_in_states: s.time_in_states(Instant::now()).into(),
+                }
+            })
+            .collect();
+        Self { counts, sessions }
+    }
+
+    /// Whether the two snapshots differ in anything but timestamps, which
+    /// jitter by a second between polls.
+    pub fn differs_from(&self, other: &StatusSnapshot) -> bool {
+        self.counts != other.counts
+            || self.sessions.len() != other.sessions.len()
+            || self.sessions.iter().zip(&other.sessions).any(|(a, b)| {
+                a.pane != b.pane
+                    || a.state != b.state
+                    || a.unread != b.unread
+                    || a.watch != b.watch
+                    || a.pending != b.pending
+                    || a.error != b.error
+                    || a.input_prompt != b.input_prompt
+            })
+    }
+}
+
+/// Exit status of `claudeye status`, by the most pressing state present:
+/// 3 when a session hit an error, 2 when one waits on the user (approval,
+/// an answer, or a blocked command's input), 1 when one is working or
+/// compacting, and 0 when all are idle or there are none.
+pub fn exit_code(counts: &StateCounts) -> i32 {
+    if counts.error > 0 {
+        3
+    } else if counts.approval + counts.answer + counts.blocked > 0 {
+        2
+    } else if counts.running + counts.compacting > 0 {
+        1
+    } else {
+        0
+    }
+}
+
+/// Compact one-line summary such as `⚠1 ?1 ▶2 ●1 ✕1`; empty when nothing
+/// is running, waiting, unread, or stopped.
+pub fn summary_line(counts: &StateCounts) -> String {
+    [
+        ("!", counts.error),
+        ("⌨", counts.blocked),
+        ("⚠", counts.approval),
+        ("?", counts.answer),
+        ("▶", counts.running),
+        ("◌", counts.compacting),
+        ("●", counts.unread),
+        ("✕", counts.not_running),
+    ]
+        .iter()
+        .filter(|(_, n)| *n > 0)
+        .map(|(symbol, n)| format!("{symbol}{n}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Summary line for shell prompts, served from a short-lived cache in the
+/// state directory so that frequent redraws don't each capture every pane.
+/// The counts come from a running monitor when there is one.
+pub fn prompt_summary(config: &Config) -> String {
+    let cache = paths::state_dir().map(|dir| dir.join("prompt.txt"));
+    if let Some(text) = cache.as_deref().and_then(|path| read_fresh(path, PROMPT_CACHE_TTL)) {
+        return text;
+    }
+    let counts = paths::state_dir()
+        .and_then(|dir| file_counts(&dir.join("status.json")))
+        .or_else(|| api_counts(&config.api))
+        .unwrap_or_else(|| current_counts(&config.monitor));
+    let line = summary_line(&counts);
+    if let Some(path) = cache {
+        let _ = path.parent().map(std::fs::create_dir_all);
+        let _ = write_atomic(&path, &line);
+    }
+    line
+}
+
+/// The counts in the `status.json` at `path`, unless the monitor writing it
+/// has not done so for [`health::STALE_AFTER_SECS`].
+pub fn file_counts(path: &Path) -> Option<StateCounts> {
+    let text = read_fresh(path, Duration::from_secs(health::STALE_AFTER_SECS))?;
+    serde_json::from_str::<Counts>(&text).ok().map(|snapshot| snapshot.counts)
+}
+
+/// The part of a [`StatusSnapshot`] the prompt needs.
+#[derive(Deserialize)]
+struct Counts {
+    counts: StateCounts,
+}
+
+/// The counts of a healthy `claudeye serve` at `config.bind`.
+fn api_counts(config: &ApiConfig) -> Option<StateCounts> {
+    let health: Health = serde_json::from_slice(&api_get(config, "/healthz")?).ok()?;
+    if !health.ok {
+        return None;
+    }
+    serde_json::from_slice::<Counts>(&api_get(config, "/api/status")?).ok().map(|snapshot| snapshot.counts)
+}
+
+/// Body of `GET path`, giving up quickly: prompts are waiting.
+fn api_get(config: &ApiConfig, path: &str) -> Option<Vec<u8>> {
+    let url = health::api_url(config, path).ok()?;
+    let mut child = Command::new("curl")
+        .args(["-sS", "--fail", "--max-time", "1", "--config", "-", &url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(team::curl_config(config.token.as_deref(), None).as_bytes());
+    }
+    let output = child.wait_with_output().ok()?;
+    output.status.success().then_some(output.stdout)
+}
+
+fn read_fresh(path: &Path, ttl: Duration) -> Option<String> {
+    let age = std::fs::metadata(path).ok()?.modified().ok()?.elapsed().ok()?;
+    (age < ttl).then(|| std::fs::read_to_string(path).ok()).flatten()
+}
+
+/// Count sessions with a single capture of each pane. Unlike a monitor
+/// poll this only reads the marks, so it never changes what counts as read.
+pub fn current_counts(config: &MonitorConfig) -> StateCounts {
+    let marks = Marks::load();
+    let mut counts = StateCounts::default();
+    for (pane, detector) in agents::list_panes(config) {
+        let content = tmux::capture_pane(&pane.id);
+        match detector.detect(&content, config.ui_locale).state {
+            ClaudeState::Working => counts.running += 1,
+            ClaudeState::Compacting => counts.compacting += 1,
+            ClaudeState::WaitingForApproval => counts.approval += 1,
+            ClaudeState::WaitingForAnswer => counts.answer += 1,
+            ClaudeState::Blocked => counts.blocked += 1,
+            ClaudeState::Idle => {
+                counts.idle += 1;
+                let seen = marks.get(&pane.id).and_then(|m| m.seen_hash);
+                if seen.is_some_and(|h| h != monitor::content_hash(&content)) {
+                    counts.unread += 1;
+                }
+            }
+            ClaudeState::Error => counts.error += 1,
+            ClaudeState::NotRunning => counts.not_running += 1,
+        }
+    }
+    counts
+}
+
+/// Writes `status.json` and `status.txt` to the state directory whenever
+/// the set of sessions changes.
+#[derive(Default)]
+pub struct StatusWriter {
+    last: Option<(StatusSnapshot, Instant)>,
+}
+
+impl StatusWriter {
+    pub fn update(&mut self, sessions: &[ClaudeSession]) {
+        let snapshot = StatusSnapshot::from_sessions(sessions);
+        if self
+            .last
+            .as_ref()
+            .is_some_and(|(last, at)| !snapshot.differs_from(last) && at.elapsed() < STATUS_REFRESH)
+        {
+            return;
+        }
+        let Some(dir) = paths::state_dir() else {
+            return;
+        };
+        if let Err(e) = write_snapshot(&dir, &snapshot) {
+            crate::log!("failed to write status to {}: {e}", dir.display());
+            return;
+        }
+        self.last = Some((snapshot, Instant::now()));
+    }
+}
+
+pub fn write_snapshot(dir: &Path, snapshot: &StatusSnapshot) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    write_atomic(&dir.join("status.json"), &serde_json::to_string(snapshot)?)?;
+    write_atomic(&dir.join("status.txt"), &format!("{}\n", summary_line(&snapshot.counts)))
+}
+
+/// Write to a sibling file and rename so readers never see a partial file.
+fn write_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path)
+}
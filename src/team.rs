@@ -0,0 +1,273 @@
+use std::collections::BTreeMap;
+use std::hash::BuildHasher;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::elapsed::{self, unix_now};
+use crate::monitor::ClaudeSession;
+use crate::paths;
+use crate::status::state_key;
+
+/// The `[team]` config table: opt-in reporting of anonymized state
+/// statistics to a `claudeye serve` instance shared by a team.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TeamConfig {
+    /// Base URL of the collecting instance (`https://lead-box:7890`);
+    /// reporting is off while unset.
+    pub endpoint: Option<String>,
+    /// Team token of that instance's API (its `[api] team_token`).
+    pub token: Option<String>,
+    /// Seconds between reports.
+    pub interval_secs: u64,
+}
+
+impl Default for TeamConfig {
+    fn default() -> Self {
+        Self { endpoint: None, token: None, interval_secs: 300 }
+    }
+}
+
+/// What one machine reports: counts and durations only, never pane
+/// content, project names, or paths.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Report {
+    /// Random id generated once per machine.
+    pub member: String,
+    pub timestamp: u64,
+    /// Seconds the report covers.
+    pub interval_secs: u64,
+    /// Live sessions when the report was sent.
+    pub sessions: usize,
+    /// Session-seconds spent in each state during the interval, keyed like
+    /// `status.json` states.
+    pub state_secs: BTreeMap<String, u64>,
+}
+
+/// Time spent in each state, accumulated poll by poll.
+#[derive(Debug, Default)]
+pub struct Usage {
+    state_millis: BTreeMap<&'static str, u64>,
+    last_poll: Option<Instant>,
+}
+
+impl Usage {
+    /// Credit the time since the previous poll to the state each live
+    /// session is in now.
+    pub fn record(&mut self, sessions: &[ClaudeSession], now: Instant) {
+        if let Some(last) = self.last_poll {
+            let millis = now.saturating_duration_since(last).as_millis() as u64;
+            for session in sessions.iter().filter(|s| s.is_live()) {
+                *self.state_millis.entry(state_key(&session.state)).or_default() += millis;
+            }
+        }
+        self.last_poll = Some(now);
+    }
+
+    /// The accumulated durations as a report, starting a new interval.
+    pub fn take_report(&mut self, member: &str, sessions: usize, interval: Duration, timestamp: u64) -> Report {
+        let state_secs = std::mem::take(&mut self.state_millis)
+            .into_iter()
+            .map(|(state, millis)| (state.to_string(), millis / 1000))
+            .collect();
+        Report {
+            member: member.to_string(),
+            timestamp,
+            interval_secs: interval.as_secs(),
+            sessions,
+            state_secs,
+        }
+    }
+}
+
+/// Sends a [`Report`] every `interval_secs` from the polling loop.
+pub struct TeamReporter {
+    config: TeamConfig,
+    member: String,
+    usage: Usage,
+    interval_started: Instant,
+}
+
+impl TeamReporter {
+    /// `None` unless a team endpoint is configured.
+    pub fn new(config: &TeamConfig) -> Option<Self> {
+        config.endpoint.as_ref()?;
+        Some(Self {
+            config: config.clone(),
+            member: member_id(),
+            usage: Usage::default(),
+            interval_started: Instant::now(),
+        })
+    }
+
+    pub fn update(&mut self, sessions: &[ClaudeSession]) {
+        let now = Instant::now();
+        self.usage.record(sessions, now);
+        let interval = now.duration_since(self.interval_started);
+        if interval < Duration::from_secs(self.config.interval_secs) {
+            return;
+        }
+        self.interval_started = now;
+        let live = sessions.iter().filter(|s| s.is_live()).count();
+        let report = self.usage.take_report(&self.member, live, interval, unix_now());
+        let config = self.config.clone();
+        thread::spawn(move || {
+            if let Err(e) = send(&config, &report) {
+                crate::log!("team report failed: {e}");
+            }
+        });
+    }
+}
+
+/// This machine's random team id, created on first use and kept in the
+/// state directory.
+pub fn member_id() -> String {
+    let path = paths::state_dir().map(|dir| dir.join("team-member"));
+    if let Some(id) = path.as_ref().and_then(|p| std::fs::read_to_string(p).ok()) {
+        let id = id.trim();
+        if !id.is_empty() {
+            return id.to_string();
+        }
+    }
+    let id = format!("{:016x}", std::collections::hash_map::RandomState::new().hash_one(unix_now()));
+    if let Some(path) = path {
+        let _ = path.parent().map(std::fs::create_dir_all);
+        if let Err(e) = std::fs::write(&path, &id) {
+            crate::log!("failed to save {}: {e}", path.display());
+        }
+    }
+    id
+}
+
+fn send(config: &TeamConfig, report: &Report) -> Result<(), String> {
+    let endpoint = config.endpoint.as_deref().unwrap_or_default();
+    let body = serde_json::to_string(report).map_err(|e| e.to_string())?;
+    curl(config, &format!("{}/api/team/reports", endpoint.trim_end_matches('/')), Some(&body)).map(|_| ())
+}
+
+/// A curl config with the token and, to POST, the body. It goes to curl on
+/// stdin so the token does not show up in `ps`.
+pub fn curl_config(token: Option<&str>, body: Option<&str>) -> String {
+    let quote = |value: &str| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"));
+    let mut out = String::new();
+    if let Some(token) = token.filter(|t| !t.is_empty()) {
+        out.push_str(&format!("header = {}\n", quote(&format!("Authorization: Bearer {token}"))));
+    }
+    if let Some(body) = body {
+        out.push_str("header = \"Content-Type: application/json\"\n");
+        out.push_str(&format!("data-binary = {}\n", quote(body)));
+    }
+    out
+}
+
+fn curl(config: &TeamConfig, url: &str, body: Option<&str>) -> Result<Vec<u8>, String> {
+    let mut child = Command::new("curl")
+        .args(["-sS", "--fail", "--max-time", "10", "--config", "-", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("curl: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(curl_config(config.token.as_deref(), body).as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(output.stdout)
+}
+
+/// Totals per member since the collecting instance started.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemberStats {
+    pub member: String,
+    /// Unix time of the latest report.
+    pub last_report: u64,
+    /// Live sessions in the latest report.
+    pub sessions: usize,
+    pub state_secs: BTreeMap<String, u64>,
+}
+
+impl MemberStats {
+    /// Shares of session time spent working (running, compacting),
+    /// waiting on the user (approval, answer, blocked, error), and idle
+    /// (idle, exited), in percent.
+    pub fn utilization(&self) -> (u64, u64, u64) {
+        let sum = |keys: &[&str]| keys.iter().filter_map(|k| self.state_secs.get(*k)).sum::<u64>();
+        let working = sum(&["running", "compacting"]);
+        let waiting = sum(&["approval", "answer", "blocked", "error"]);
+        let idle = sum(&["idle", "not_running"]);
+        let total = working + waiting + idle;
+        if total == 0 {
+            return (0, 0, 0);
+        }
+        (working * 100 / total, waiting * 100 / total, idle * 100 / total)
+    }
+}
+
+/// Reports received by `claudeye serve`, aggregated per member.
+#[derive(Debug, Default)]
+pub struct TeamBoard {
+    members: BTreeMap<String, MemberStats>,
+}
+
+impl TeamBoard {
+    pub fn add(&mut self, report: Report) {
+        let stats = self.members.entry(report.member.clone()).or_default();
+        stats.member = report.member;
+        stats.last_report = stats.last_report.max(report.timestamp);
+        stats.sessions = report.sessions;
+        for (state, secs) in report.state_secs {
+            *stats.state_secs.entry(state).or_default() += secs;
+        }
+    }
+
+    pub fn members(&self) -> Vec<MemberStats> {
+        self.members.values().cloned().collect()
+    }
+}
+
+/// Fetch the member totals from the configured team endpoint.
+pub fn fetch(config: &TeamConfig) -> Result<Vec<MemberStats>, String> {
+    let endpoint = config.endpoint.as_deref().ok_or("no team endpoint configured ([team] endpoint)")?;
+    let output = curl(config, &format!("{}/api/team", endpoint.trim_end_matches('/')), None)?;
+    #[derive(Deserialize)]
+    struct Body {
+        members: Vec<MemberStats>,
+    }
+    serde_json::from_slice::<Body>(&output)
+        .map(|body| body.members)
+        .map_err(|e| format!("invalid team response: {e}"))
+}
+
+/// The `claudeye team-report` table: one row per member and a fleet total.
+pub fn render_table(members: &[MemberStats], now: u64) -> String {
+    let mut out = format!(
+        "{:<18}{:>9}{:>9}{:>9}{:>7}  {}\n",
+        "member", "sessions", "working", "waiting", "idle", "last report"
+    );
+    let row = |out: &mut String, name: &str, stats: &MemberStats, last: &str| {
+        let (working, waiting, idle) = stats.utilization();
+        let line = format!("{name:<18}{:>9}{:>8}%{:>8}%{:>6}%  {last}", stats.sessions, working, waiting, idle);
+        out.push_str(line.trim_end());
+        out.push('\n');
+    };
+    let mut fleet = MemberStats::default();
+    for stats in members {
+        let ago = format!("{} ago", elapsed::compact(now.saturating_sub(stats.last_report)));
+        row(&mut out, &stats.member, stats, &ago);
+        fleet.sessions += stats.sessions;
+        for (state, secs) in &stats.state_secs {
+            *fleet.state_secs.entry(state.clone()).or_default() += secs;
+        }
+    }
+    row(&mut out, &format!("fleet ({})", members.len()), &fleet, "");
+    out
+}
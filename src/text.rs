@@ -0,0 +1,35 @@
+use std::borrow::Cow;
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Terminal columns `text` takes up: two for CJK and other wide characters,
+/// one for the rest.
+pub fn width(text: &str) -> usize {
+    text.width()
+}
+
+/// `text` shortened to at most `max_width` columns, ending in `…` when cut.
+pub fn truncate(text: &str, max_width: usize) -> Cow<'_, str> {
+    if width(text) <= max_width {
+        return Cow::Borrowed(text);
+    }
+    let budget = max_width.saturating_sub(1);
+    let mut used = 0;
+    let mut short = String::new();
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > budget {
+            break;
+        }
+        used += w;
+        short.push(c);
+    }
+    short.push('…');
+    Cow::Owned(short)
+}
+
+/// `text` followed by enough spaces to fill `width` columns.
+pub fn pad(text: &str, width: usize) -> String {
+    let fill = width.saturating_sub(self::width(text));
+    format!("{text}{}", " ".repeat(fill))
+}
@@ -1,47 +1,209 @@
 use std::collections::HashSet;
+use std::io::IsTerminal;
+use std::panic;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Mutex, OnceLock};
+use std::thread;
 use std::time::{Duration, Instant};
 
-#[derive(Debug, Clone)]
+use serde::Deserialize;
+
+use crate::claude_state::ClaudeState;
+use crate::health;
+use crate::kitty;
+use crate::monitor::{Transition, TransitionHook};
+use crate::process::SHELLS;
+use crate::remote;
+use crate::rename;
+use crate::screen;
+
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct PaneInfo {
     pub id: String,
-    #[allow(dead_code)]
     pub pid: u32,
-    #[allow(dead_code)]
     pub cwd: String,
     pub project_name: String,
+    /// `#{session_name}`, also the part of `id` before the colon.
+    pub session_name: String,
+    /// `#{window_name}`.
+    pub window_name: String,
 }
 
-pub fn list_claude_panes() -> Vec<PaneInfo> {
-    let output = Command::new("tmux")
-        .args([
-            "list-panes",
-            "-a",
-            "-F",
-            "#{session_name}:#{window_index}.#{pane_index} #{pane_pid} #{pane_current_path} #{pane_current_command}",
-        ])
-        .output();
+/// A pane's title and host, for naming panes whose directory says little
+/// (`src`, `tmp`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PaneLabels {
+    /// `#{pane_title}`, which Claude Code sets to a summary of its task.
+    pub title: String,
+    /// `#{host_short}`, tmux's default pane title.
+    pub host: String,
+}
+
+/// A source of a pane's display name, as listed in `[monitor.naming]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NameSource {
+    /// The last component of the pane's working directory.
+    Directory,
+    PaneTitle,
+    Window,
+    Session,
+}
+
+/// The `[monitor.naming]` table: where pane names come from, in order of
+/// preference. A source is skipped when it says nothing useful: a directory
+/// in `generic_dirs` or the home directory, a pane title that is just the
+/// host name, a window named after the running command, or a numbered
+/// session.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NamingConfig {
+    pub order: Vec<NameSource>,
+    pub generic_dirs: Vec<String>,
+}
+
+impl Default for NamingConfig {
+    fn default() -> Self {
+        Self {
+            order: vec![NameSource::Directory, NameSource::PaneTitle, NameSource::Window, NameSource::Session],
+            generic_dirs: ["src", "tmp", "lib", "app", "build", "dist", "code", "repo", "work", "workspace"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+impl NamingConfig {
+    /// The first useful name of `pane` in [`NamingConfig::order`], or its
+    /// directory name when none is.
+    pub fn display_name(&self, pane: &PaneInfo, labels: &PaneLabels, command: &str) -> String {
+        self.order
+            .iter()
+            .find_map(|source| self.name_from(*source, pane, labels, command))
+            .unwrap_or_else(|| pane.project_name.clone())
+    }
 
+    fn name_from(&self, source: NameSource, pane: &PaneInfo, labels: &PaneLabels, command: &str) -> Option<String> {
+        let name = match source {
+            NameSource::Directory => {
+                let generic = self.generic_dirs.iter().any(|d| d.eq_ignore_ascii_case(&pane.project_name));
+                let home = std::env::var_os("HOME").is_some_and(|home| Path::new(&pane.cwd) == Path::new(&home));
+                (!generic && !home && pane.project_name != "unknown").then_some(pane.project_name.as_str())
+            }
+            // Claude Code prefixes its title with a spinner glyph (`✳ Fix login`),
+            // and `tmux_rename` suffixes it with a state mark.
+            NameSource::PaneTitle => {
+                let title = rename::strip_mark(labels.title.trim_start_matches(|c: char| !c.is_alphanumeric()).trim());
+                (title != labels.host && title != "Claude Code").then_some(title)
+            }
+            NameSource::Window => {
+                let window = rename::strip_mark(pane.window_name.trim());
+                (window != command && !is_claude_command(window) && !SHELLS.contains(&window)).then_some(window)
+            }
+            NameSource::Session => {
+                let session = pane.session_name.trim();
+                (!session.chars().all(|c| c.is_ascii_digit())).then_some(session)
+            }
+        }?;
+        (!name.is_empty()).then(|| name.to_string())
+    }
+}
+
+pub fn list_claude_panes() -> Vec<PaneInfo> {
     let version_names = claude_version_names();
+    list_panes_with_commands()
+        .into_iter()
+        .filter(|(_, command, _)| is_claude_command_with_versions(command, &version_names))
+        .map(|(pane, _, _)| pane)
+        .collect()
+}
+
+/// tmux binary to run when `CLAUDEYE_TMUX` is unset, from `tmux_path`.
+static PROGRAM: OnceLock<PathBuf> = OnceLock::new();
+
+/// Run `path` instead of the `tmux` on `PATH`, as `[monitor] tmux_path`
+/// asks. Only the first call has an effect.
+pub fn set_program(path: PathBuf) {
+    let _ = PROGRAM.set(path);
+}
+
+/// The tmux binary: `$CLAUDEYE_TMUX` (how the integration tests swap in a
+/// fake), then [`set_program`]'s, then `tmux` from `PATH`.
+pub fn program() -> PathBuf {
+    std::env::var_os("CLAUDEYE_TMUX")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| PROGRAM.get().cloned())
+        .unwrap_or_else(|| PathBuf::from("tmux"))
+}
+
+fn command() -> Command {
+    Command::new(program())
+}
+
+/// `list-panes -F` format of the lines [`parse_pane_fields`] reads.
+pub const PANE_FORMAT: &str = "#{session_name}:#{window_index}.#{pane_index} #{pane_pid} #{pane_current_path} #{pane_current_command}\t#{pane_title}\t#{window_name}\t#{host_short}";
+
+/// Every pane, with the command running in its foreground and its labels.
+pub fn list_panes_with_commands() -> Vec<(PaneInfo, String, PaneLabels)> {
+    let output = command().args(["list-panes", "-a", "-F", PANE_FORMAT]).output();
+
     match output {
         Ok(out) => {
-            let stdout = String::from_utf8_lossy(&out.stdout);
-            stdout
+            let error = String::from_utf8_lossy(&out.stderr);
+            health::record_source("tmux", if out.status.success() { Ok(()) } else { Err(error.trim().to_string()) });
+            String::from_utf8_lossy(&out.stdout)
                 .lines()
-                .filter_map(|line| parse_pane_line_with_versions(line, &version_names))
+                .filter_map(|line| {
+                    let (pane, command, labels) = parse_pane_fields(line)?;
+                    Some((pane, command.to_string(), labels))
+                })
                 .collect()
         }
         Err(e) => {
-            eprintln!("[claudeye] tmux list-panes failed: {e}");
+            crate::log!("tmux list-panes failed: {e}");
+            health::record_source("tmux", Err(e.to_string()));
             vec![]
         }
     }
 }
 
+/// Whether a `tmux list-panes` error means no server runs, or tmux is not
+/// installed, rather than that one could not be reached.
+pub fn is_no_server(error: &str) -> bool {
+    error.contains("no server running") || error.contains("No such file or directory")
+}
+
+/// Ids of every open pane, whatever it runs.
+pub fn all_pane_ids() -> HashSet<String> {
+    let output = command()
+        .args(["list-panes", "-a", "-F", "#{session_name}:#{window_index}.#{pane_index}"])
+        .output();
+    match output {
+        Ok(out) => String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .filter(|id| !id.is_empty())
+            .collect(),
+        Err(e) => {
+            crate::log!("tmux list-panes failed: {e}");
+            HashSet::new()
+        }
+    }
+}
+
 /// Parse a tmux pane line, using the caller-provided version name set.
 fn parse_pane_line_with_versions(line: &str, version_names: &HashSet<String>) -> Option<PaneInfo> {
+    let (pane, command, _) = parse_pane_fields(line)?;
+    is_claude_command_with_versions(command, version_names).then_some(pane)
+}
+
+/// Split a `<id> <pid> <cwd> <command>` pane line, optionally followed by
+/// tab-separated title, window name, and host, into the pane, its command,
+/// and its labels.
+pub fn parse_pane_fields(line: &str) -> Option<(PaneInfo, &str, PaneLabels)> {
     let parts: Vec<&str> = line.splitn(4, ' ').collect();
     if parts.len() < 4 {
         return None;
@@ -49,11 +211,13 @@ fn parse_pane_line_with_versions(line: &str, version_names: &HashSet<String>) ->
     let id = parts[0].to_string();
     let pid: u32 = parts[1].parse().ok()?;
     let cwd = parts[2].to_string();
-    let command = parts[3].trim();
-
-    if !is_claude_command_with_versions(command, version_names) {
-        return None;
-    }
+    let mut rest = parts[3].split('\t');
+    let command = rest.next().unwrap_or_default().trim();
+    let mut label = || rest.next().unwrap_or_default().to_string();
+    let title = label();
+    let window_name = label();
+    let labels = PaneLabels { title, host: label() };
+    let session_name = id.rsplit_once(':').map(|(session, _)| session.to_string()).unwrap_or_default();
 
     let project_name = std::path::Path::new(&cwd)
         .file_name()
@@ -61,12 +225,18 @@ fn parse_pane_line_with_versions(line: &str, version_names: &HashSet<String>) ->
         .unwrap_or("unknown")
         .to_string();
 
-    Some(PaneInfo {
-        id,
-        pid,
-        cwd,
-        project_name,
-    })
+    Some((
+        PaneInfo {
+            id,
+            pid,
+            cwd,
+            project_name,
+            session_name,
+            window_name,
+        },
+        command,
+        labels,
+    ))
 }
 
 /// Public wrapper that resolves version names on each call.
@@ -76,16 +246,92 @@ pub fn parse_pane_line(line: &str) -> Option<PaneInfo> {
     parse_pane_line_with_versions(line, &version_names)
 }
 
+/// Panes currently on screen: the active pane of the active window of a
+/// session with at least one attached client.
+pub fn focused_pane_ids() -> HashSet<String> {
+    let output = command()
+        .args([
+            "list-panes",
+            "-a",
+            "-F",
+            "#{session_name}:#{window_index}.#{pane_index} #{pane_active} #{window_active} #{session_attached}",
+        ])
+        .output();
+
+    match output {
+        Ok(out) => String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .filter_map(parse_focus_line)
+            .collect(),
+        Err(e) => {
+            crate::log!("tmux list-panes failed: {e}");
+            HashSet::new()
+        }
+    }
+}
+
+/// Parse a `<pane id> <pane_active> <window_active> <session_attached>` line,
+/// returning the pane id only when the pane is focused.
+pub fn parse_focus_line(line: &str) -> Option<String> {
+    // Split from the right so session names containing spaces stay intact.
+    let mut parts = line.rsplitn(4, ' ');
+    let attached = parts.next()?.parse::<u32>().ok()? > 0;
+    let window_active = parts.next()? == "1";
+    let pane_active = parts.next()? == "1";
+    let id = parts.next()?;
+    (pane_active && window_active && attached).then(|| id.to_string())
+}
+
+/// Zoom the pane [`switch_to_pane`] brings to the front.
+static ZOOM: OnceLock<bool> = OnceLock::new();
+
+/// Zoom panes when jumping to them, as `--zoom` or `zoom_on_jump` asks.
+/// Only the first call has an effect.
+pub fn set_zoom(zoom: bool) {
+    let _ = ZOOM.set(zoom);
+}
+
+fn zooms() -> bool {
+    ZOOM.get().copied().unwrap_or(false)
+}
 
+/// Bring a pane to the front: select its window and the pane within its
+/// session (zooming it with [`set_zoom`]), then show that session in the
+/// current client, or attach to it when claudeye runs in a terminal outside
+/// tmux.
 pub fn switch_to_pane(pane_id: &str) {
-    let result = Command::new("tmux")
-        .args(["switch-client", "-t", pane_id])
-        .output();
-    if let Err(e) = result {
-        eprintln!("[claudeye] tmux switch-client failed: {e}");
+    if let Some((host, pane)) = remote::parse_pane_id(pane_id) {
+        return remote::switch_to_pane(host, pane, zooms());
+    }
+    if let Some(window) = kitty::parse_window_id(pane_id) {
+        return kitty::focus_window(window);
+    }
+    if let Some((session, window)) = screen::parse_window_id(pane_id) {
+        return screen::select_window(session, window);
+    }
+    run(&["select-window", "-t", pane_id]);
+    run(&["select-pane", "-t", pane_id]);
+    // resize-pane -Z toggles, so a window zoomed already is left as it is.
+    if zooms() && display(pane_id, "#{window_zoomed_flag}").is_some_and(|flag| flag != "1") {
+        run(&["resize-pane", "-Z", "-t", pane_id]);
+    }
+    if std::env::var_os("TMUX").is_some() || !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        run(&["switch-client", "-t", pane_id]);
+        return;
+    }
+    match command().args(["attach-session", "-t", pane_id]).status() {
+        Ok(status) if !status.success() => crate::log!("tmux attach-session failed: {status}"),
+        Ok(_) => {}
+        Err(e) => crate::log!("tmux attach-session failed: {e}"),
     }
 }
 
+/// Whether `command` is claude, by name or by the version number tmux
+/// reports for the versioned binary.
+pub fn is_claude_command(command: &str) -> bool {
+    is_claude_command_with_versions(command, &claude_version_names())
+}
+
 fn is_claude_command_with_versions(command: &str, version_names: &HashSet<String>) -> bool {
     command == "claude" || version_names.contains(command)
 }
@@ -166,16 +412,198 @@ pub fn read_version_entries(dir: &Path) -> Option<HashSet<String>> {
     Some(entries)
 }
 
+/// Lines of scrollback [`capture_pane`] takes above the visible screen.
+static CAPTURE_LINES: OnceLock<usize> = OnceLock::new();
+
+/// Capture `lines` of scrollback along with the visible screen, as
+/// `[monitor] capture_lines` asks. Only the first call has an effect.
+pub fn set_capture_lines(lines: usize) {
+    let _ = CAPTURE_LINES.set(lines);
+}
+
+/// The `-S` of a capture reaching `lines` into the scrollback, or `None`
+/// for the visible screen only.
+pub fn capture_start(lines: usize) -> Option<String> {
+    (lines > 0).then(|| format!("-{lines}"))
+}
+
+/// What a pane shows, with the scrollback `capture_lines` asks for above it.
 pub fn capture_pane(pane_id: &str) -> String {
-    let output = Command::new("tmux")
-        .args(["capture-pane", "-p", "-t", pane_id])
-        .output();
+    let start = capture_start(CAPTURE_LINES.get().copied().unwrap_or(0));
+    if let Some((host, pane)) = remote::parse_pane_id(pane_id) {
+        return remote::capture(host, pane, start.as_deref().map(|start| (start, "-"))).unwrap_or_default();
+    }
+    if let Some(window) = kitty::parse_window_id(pane_id) {
+        return kitty::capture(window).unwrap_or_default();
+    }
+    if let Some((session, window)) = screen::parse_window_id(pane_id) {
+        return screen::capture(session, window).unwrap_or_default();
+    }
+    let mut args = vec!["capture-pane", "-p", "-t", pane_id];
+    if let Some(start) = &start {
+        args.extend(["-S", start]);
+    }
+    let output = command().args(&args).output();
 
     match output {
         Ok(out) => String::from_utf8_lossy(&out.stdout).into_owned(),
         Err(e) => {
-            eprintln!("[claudeye] tmux capture-pane failed for {pane_id}: {e}");
+            crate::log!("tmux capture-pane failed for {pane_id}: {e}");
             String::new()
         }
     }
 }
+
+/// Panes [`capture_panes`] captures at the same time.
+const CAPTURE_WORKERS: usize = 8;
+
+/// [`capture_pane`] of each of `pane_ids`, in order, with up to
+/// [`CAPTURE_WORKERS`] captures running at once. A capture that panics
+/// panics here too, rather than passing its panes off as empty.
+pub fn capture_panes(pane_ids: &[String]) -> Vec<String> {
+    let next = AtomicUsize::new(0);
+    let mut captures = vec![String::new(); pane_ids.len()];
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..CAPTURE_WORKERS.min(pane_ids.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = vec![];
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(pane_id) = pane_ids.get(i) else {
+                            return done;
+                        };
+                        done.push((i, capture_pane(pane_id)));
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            for (i, content) in worker.join().unwrap_or_else(|e| panic::resume_unwind(e)) {
+                captures[i] = content;
+            }
+        }
+    });
+    captures
+}
+
+/// Capture lines `start`..=`end` of a pane (tmux line numbers: 0 is the
+/// first visible line, negative numbers are history, `-` means the very
+/// start or end). Returns `None` when the pane does not exist. Screen and
+/// kitty windows are captured as shown, whatever the range; panes on remote
+/// hosts are captured over ssh.
+pub fn capture_pane_range(pane_id: &str, start: &str, end: &str) -> Option<String> {
+    if let Some((host, pane)) = remote::parse_pane_id(pane_id) {
+        return remote::capture(host, pane, Some((start, end)));
+    }
+    if let Some(window) = kitty::parse_window_id(pane_id) {
+        return kitty::capture(window);
+    }
+    if let Some((session, window)) = screen::parse_window_id(pane_id) {
+        return screen::capture(session, window);
+    }
+    let output = command()
+        .args(["capture-pane", "-p", "-t", pane_id, "-S", start, "-E", end])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Whether a transition leaves the session waiting on the user, which
+/// rings the pane's bell with `tmux_bell` on.
+pub fn rings_bell(transition: &Transition) -> bool {
+    matches!(
+        transition.to,
+        ClaudeState::WaitingForApproval | ClaudeState::WaitingForAnswer | ClaudeState::Blocked
+    )
+}
+
+/// Transition hook implementing `tmux_bell`.
+pub fn bell_hook(enabled: bool) -> Option<TransitionHook> {
+    enabled.then(|| -> TransitionHook {
+        Box::new(|t: &Transition| {
+            if rings_bell(t) {
+                ring_bell(&t.pane.id);
+            }
+        })
+    })
+}
+
+/// Ring the bell in a pane by writing BEL to its tty. tmux then flags the
+/// pane's window (with `monitor-bell`, on by default) and its status bar
+/// entry takes `window-status-bell-style` until the window is visited.
+pub fn ring_bell(pane_id: &str) {
+    let Some(tty) = display(pane_id, "#{pane_tty}") else {
+        return;
+    };
+    let result = std::fs::OpenOptions::new()
+        .write(true)
+        .open(&tty)
+        .and_then(|mut tty| std::io::Write::write_all(&mut tty, b"\x07"));
+    if let Err(e) = result {
+        crate::log!("failed to ring the bell in {pane_id} ({tty}): {e}");
+    }
+}
+
+/// `format` expanded for a pane (`#{pane_tty}`), or `None` when the pane
+/// does not exist.
+pub fn display(pane_id: &str, format: &str) -> Option<String> {
+    match command().args(["display-message", "-p", "-t", pane_id, format]).output() {
+        Ok(out) if out.status.success() => Some(String::from_utf8_lossy(&out.stdout).trim_end_matches('\n').to_string()),
+        Ok(_) => None,
+        Err(e) => {
+            crate::log!("tmux display-message failed for {pane_id}: {e}");
+            None
+        }
+    }
+}
+
+/// Whether a pane id is one of the local tmux server's, rather than a
+/// remote, kitty, or screen one.
+pub fn is_tmux_pane(pane_id: &str) -> bool {
+    remote::parse_pane_id(pane_id).is_none()
+        && kitty::parse_window_id(pane_id).is_none()
+        && screen::parse_window_id(pane_id).is_none()
+}
+
+/// Run a tmux command that changes something, logging failures.
+pub fn run(args: &[&str]) -> bool {
+    match command().args(args).output() {
+        Ok(out) if out.status.success() => true,
+        Ok(out) => {
+            crate::log!("tmux {} failed: {}", args[0], String::from_utf8_lossy(&out.stderr).trim());
+            false
+        }
+        Err(e) => {
+            crate::log!("tmux {} failed: {e}", args[0]);
+            false
+        }
+    }
+}
+
+/// Send key names (as understood by `tmux send-keys`, e.g. `Enter`) to a pane.
+pub fn send_keys(pane_id: &str, keys: &[&str]) -> bool {
+    if let Some((host, pane)) = remote::parse_pane_id(pane_id) {
+        return remote::send_keys(host, pane, keys);
+    }
+    if let Some(window) = kitty::parse_window_id(pane_id) {
+        return kitty::send_keys(window, keys);
+    }
+    if let Some((session, window)) = screen::parse_window_id(pane_id) {
+        return screen::send_keys(session, window, keys);
+    }
+    let result = command()
+        .args(["send-keys", "-t", pane_id])
+        .args(keys)
+        .output();
+    match result {
+        Ok(out) => out.status.success(),
+        Err(e) => {
+            crate::log!("tmux send-keys failed for {pane_id}: {e}");
+            false
+        }
+    }
+}
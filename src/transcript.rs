@@ -0,0 +1,45 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+
+/// Claude's own directory: `$CLAUDE_CONFIG_DIR`, falling back to `~/.claude`.
+pub fn claude_dir() -> Option<PathBuf> {
+    std::env::var_os("CLAUDE_CONFIG_DIR")
+        .filter(|v| !v.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".claude")))
+}
+
+/// Name of the directory under `projects/` holding the transcripts of
+/// sessions started in `cwd`: the path with every character other than a
+/// letter or digit replaced by `-` (`/home/me/my.app` → `-home-me-my-app`).
+pub fn project_dir_name(cwd: &str) -> String {
+    cwd.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '-' }).collect()
+}
+
+/// The transcript (`<session id>.jsonl`) written to last among those of
+/// sessions started in `cwd`, which is the running session's.
+pub fn latest(cwd: &str) -> Option<PathBuf> {
+    latest_in(&claude_dir()?.join("projects").join(project_dir_name(cwd)))
+}
+
+pub fn latest_in(dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "jsonl"))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max()
+        .map(|(_, path)| path)
+}
+
+/// Open the latest transcript of the session in `cwd` with the desktop's
+/// default application for it.
+pub fn open(cwd: &str) -> Result<(), String> {
+    let path = latest(cwd).ok_or_else(|| format!("no Claude transcript found for {cwd}"))?;
+    let opener = if cfg!(target_os = "macos") { "open" } else { "xdg-open" };
+    let mut child =
+        Command::new(opener).arg(&path).spawn().map_err(|e| format!("failed to open {}: {e}", path.display()))?;
+    thread::spawn(move || child.wait());
+    Ok(())
+}
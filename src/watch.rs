@@ -0,0 +1,74 @@
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::monitor::ClaudeSession;
+use crate::tmux::PaneInfo;
+
+/// A user-defined `[[watch]]` rule: a regex searched for in pane content.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "RawWatchRule")]
+pub struct WatchRule {
+    pub name: String,
+    pub pattern: Regex,
+    /// Send a desktop notification when the rule starts matching.
+    pub notify: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawWatchRule {
+    name: String,
+    pattern: String,
+    #[serde(default = "default_notify")]
+    notify: bool,
+}
+
+fn default_notify() -> bool {
+    true
+}
+
+impl TryFrom<RawWatchRule> for WatchRule {
+    type Error = regex::Error;
+
+    fn try_from(raw: RawWatchRule) -> Result<Self, Self::Error> {
+        Ok(Self {
+            pattern: Regex::new(&raw.pattern)?,
+            name: raw.name,
+            notify: raw.notify,
+        })
+    }
+}
+
+/// Names of the rules whose pattern occurs in `content`, in rule order.
+pub fn matching_rules(rules: &[WatchRule], content: &str) -> Vec<String> {
+    rules
+        .iter()
+        .filter(|r| r.pattern.is_match(content))
+        .map(|r| r.name.clone())
+        .collect()
+}
+
+/// Rule matches present in `updated` that were not present for the same
+/// pane in `prev`, restricted to rules that want a notification. Panes new
+/// in `updated` are skipped so startup does not notify for old output.
+pub fn new_matches(
+    rules: &[WatchRule],
+    prev: &[ClaudeSession],
+    updated: &[ClaudeSession],
+) -> Vec<(PaneInfo, String)> {
+    let mut result = vec![];
+    for session in updated.iter().filter(|s| s.is_live()) {
+        let Some(old) = prev.iter().find(|p| p.pane.id == session.pane.id) else {
+            continue;
+        };
+        for name in &session.watch_hits {
+            if old.watch_hits.contains(name) {
+                continue;
+            }
+            if rules.iter().any(|r| r.name == *name && r.notify) {
+                result.push((session.pane.clone(), name.clone()));
+            }
+        }
+    }
+    result
+}
@@ -0,0 +1,186 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::Sha256;
+
+use crate::elapsed::{self, unix_now};
+use crate::monitor::{Transition, TransitionHook};
+use crate::paths;
+use crate::redact::redact;
+use crate::status::state_key;
+
+/// A `[[webhook]]` endpoint receiving state transitions.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Webhook {
+    pub url: String,
+    /// Signs each delivery with HMAC-SHA256 when set.
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub format: PayloadFormat,
+    /// Attempts after the first failed delivery.
+    #[serde(default = "default_retries")]
+    pub retries: u32,
+    /// Delay before the first retry; doubled for each further retry.
+    #[serde(default = "default_backoff_secs")]
+    pub backoff_secs: u64,
+}
+
+fn default_retries() -> u32 {
+    3
+}
+
+fn default_backoff_secs() -> u64 {
+    2
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PayloadFormat {
+    /// claudeye's own JSON event.
+    #[default]
+    Json,
+    /// `{"text": ...}` as accepted by Slack incoming webhooks.
+    Slack,
+}
+
+/// Request body for a transition. Transitions to `approval` also carry the
+/// approval's kind (`bash`, `file_edit`, ...).
+pub fn payload(transition: &Transition, format: PayloadFormat, timestamp: u64) -> Value {
+    match format {
+        PayloadFormat::Json => {
+            let mut body = json!({
+                "event": "transition",
+                "timestamp": timestamp,
+                "pane": transition.pane.id,
+                "project": redact(&transition.pane.project_name),
+                "cwd": redact(&transition.pane.cwd),
+                "from": state_key(&transition.from),
+                "to": state_key(&transition.to),
+                "elapsed_secs": transition.elapsed.as_secs(),
+            });
+            if let Some(kind) = transition.approval {
+                body["approval"] = json!(kind.key());
+            }
+            body
+        }
+        PayloadFormat::Slack => json!({
+            "text": format!(
+                "*{}* ({}): {} → {}{} after {}",
+                redact(&transition.pane.project_name),
+                transition.pane.id,
+                state_key(&transition.from),
+                state_key(&transition.to),
+                transition.approval.map(|kind| format!(" ({})", kind.key())).unwrap_or_default(),
+                elapsed::compact(transition.elapsed.as_secs()),
+            ),
+        }),
+    }
+}
+
+/// `sha256=<hex>` HMAC of `"{timestamp}.{body}"`, sent as
+/// `X-Claudeye-Signature`. Including the timestamp lets receivers reject
+/// replayed deliveries.
+pub fn signature(secret: &str, timestamp: u64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(format!("{timestamp}.{body}").as_bytes());
+    let hex: String = mac.finalize().into_bytes().iter().map(|b| format!("{b:02x}")).collect();
+    format!("sha256={hex}")
+}
+
+/// Delay before retry number `attempt` (1-based): `backoff`, then doubling.
+pub fn retry_delay(backoff_secs: u64, attempt: u32) -> Duration {
+    Duration::from_secs(backoff_secs.saturating_mul(1 << (attempt - 1).min(16)))
+}
+
+/// Transition hook posting every transition to each configured webhook.
+pub fn hook(webhooks: &[Webhook]) -> Option<TransitionHook> {
+    if webhooks.is_empty() {
+        return None;
+    }
+    let webhooks = webhooks.to_vec();
+    Some(Box::new(move |t: &Transition| {
+        let timestamp = unix_now();
+        for webhook in &webhooks {
+            let body = payload(t, webhook.format, timestamp).to_string();
+            let webhook = webhook.clone();
+            thread::spawn(move || deliver(&webhook, &body, timestamp));
+        }
+    }))
+}
+
+/// POST `body`, retrying with exponential backoff. A delivery that still
+/// fails is appended to the dead-letter log.
+fn deliver(webhook: &Webhook, body: &str, timestamp: u64) {
+    let mut attempt = 0;
+    loop {
+        let error = match post(webhook, body, timestamp) {
+            Ok(()) => return,
+            Err(e) => e,
+        };
+        if attempt == webhook.retries {
+            crate::log!("webhook {} failed after {} attempts: {error}", webhook.url, attempt + 1);
+            dead_letter(webhook, body, &error, attempt + 1);
+            return;
+        }
+        attempt += 1;
+        thread::sleep(retry_delay(webhook.backoff_secs, attempt));
+    }
+}
+
+fn post(webhook: &Webhook, body: &str, timestamp: u64) -> Result<(), String> {
+    let mut command = Command::new("curl");
+    command
+        .args(["-sS", "--fail", "--max-time", "10", "-X", "POST"])
+        .args(["-H", "Content-Type: application/json"])
+        .args(["-H", &format!("X-Claudeye-Timestamp: {timestamp}")]);
+    if let Some(secret) = &webhook.secret {
+        command.args(["-H", &format!("X-Claudeye-Signature: {}", signature(secret, timestamp, body))]);
+    }
+    let mut child = command
+        .args(["--data-binary", "@-", &webhook.url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("curl: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(body.as_bytes()).map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+/// Append a failed delivery to `webhook-dead-letter.jsonl` in the state
+/// directory so it can be inspected and replayed.
+fn dead_letter(webhook: &Webhook, body: &str, error: &str, attempts: u32) {
+    let Some(dir) = paths::state_dir() else {
+        return;
+    };
+    let entry = json!({
+        "failed_at": unix_now(),
+        "url": webhook.url,
+        "attempts": attempts,
+        "error": error,
+        "payload": serde_json::from_str::<Value>(body).unwrap_or(Value::Null),
+    });
+    let path = dir.join("webhook-dead-letter.jsonl");
+    let result = std::fs::create_dir_all(&dir).and_then(|_| {
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+        writeln!(file, "{entry}")
+    });
+    if let Err(e) = result {
+        crate::log!("failed to write {}: {e}", path.display());
+    }
+}
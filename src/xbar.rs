@@ -0,0 +1,70 @@
+use std::fmt::Write;
+
+use crate::claude_state::ClaudeState;
+use crate::i18n::Strings;
+use crate::monitor::{ClaudeSession, NameFormat};
+use crate::status::{summary_line, StatusSnapshot};
+
+const ALARM_COLOR: &str = "#ff3c3c";
+
+/// Menu-bar colors matching the overlay's state colors.
+fn state_color(state: &ClaudeState) -> &'static str {
+    match state {
+        ClaudeState::Working => "#50c850",
+        ClaudeState::Compacting => "#9678e6",
+        ClaudeState::WaitingForApproval => "#dcb400",
+        ClaudeState::WaitingForAnswer => "#3cc8c8",
+        ClaudeState::Blocked => "#f05aaa",
+        ClaudeState::Idle => "#a0a0a0",
+        ClaudeState::Error => "#eb5f32",
+        ClaudeState::NotRunning => "#b45050",
+    }
+}
+
+/// Render sessions in the xbar/SwiftBar plugin format: a title line, then
+/// one menu item per session. Clicking an item runs `exe approve <pane>`
+/// for sessions waiting for approval and `exe switch <pane>` otherwise.
+pub fn render(sessions: &[ClaudeSession], strings: &Strings, format: NameFormat, exe: &str) -> String {
+    let live: Vec<&ClaudeSession> = sessions.iter().filter(|s| s.is_live()).collect();
+    let counts = StatusSnapshot::from_sessions(sessions).counts;
+    let title = summary_line(&counts);
+    let mut out = String::new();
+    let _ = writeln!(out, "{}", if title.is_empty() { "◇" } else { &title });
+    let _ = writeln!(out, "---");
+    if live.is_empty() {
+        let _ = writeln!(out, "{}", strings.no_sessions);
+        return out;
+    }
+    for session in live {
+        // Sessions tripping a guardrail are never approved with one click.
+        let action = match session.state {
+            ClaudeState::WaitingForApproval if !session.tripped_guardrail() => "approve",
+            _ => "switch",
+        };
+        let unread = if session.shows_unread() { " •" } else { "" };
+        let (alarm, color) = if session.tripped_guardrail() {
+            (format!(" ⛔ {}", session.guardrail_hits.join(", ")), ALARM_COLOR)
+        } else {
+            (String::new(), state_color(&session.state))
+        };
+        let _ = writeln!(
+            out,
+            "{}  {}  [{}]{}{} | color={} {}",
+            session.pane.id,
+            session.display_name(format),
+            strings.state_label(&session.state),
+            unread,
+            alarm,
+            color,
+            command(exe, action, &session.pane.id),
+        );
+        if action == "approve" {
+            let _ = writeln!(out, "--{} | {}", session.pane.id, command(exe, "switch", &session.pane.id));
+        }
+    }
+    out
+}
+
+fn command(exe: &str, action: &str, pane_id: &str) -> String {
+    format!("bash=\"{exe}\" param1={action} param2=\"{pane_id}\" terminal=false refresh=true")
+}
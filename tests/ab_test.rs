@@ -0,0 +1,77 @@
+use std::path::Path;
+
+use claudeye::ab::{self, CandidateRules, Divergence};
+use claudeye::claude_state::UiLocale;
+use claudeye::fixtures;
+
+fn corpus() -> Vec<fixtures::Fixture> {
+    fixtures::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/states")).unwrap()
+}
+
+#[test]
+fn an_empty_candidate_agrees_with_the_current_rules() {
+    let candidate = CandidateRules::parse("").unwrap();
+    for fixture in corpus() {
+        assert_eq!(ab::compare(&candidate, &fixture.content, UiLocale::En), None, "{}", fixture.path.display());
+    }
+}
+
+#[test]
+fn disabled_rules_stop_matching() {
+    let candidate = CandidateRules::parse(r#"disabled = ["waiting_text", "selection_menu"]"#).unwrap();
+    let fixture = corpus().into_iter().find(|f| f.path.ends_with("approval/bash-dialog.txt")).unwrap();
+    let divergence = ab::compare(&candidate, &fixture.content, UiLocale::En).unwrap();
+    assert_eq!(divergence.current, "approval");
+    assert_eq!(divergence.current_rule, "waiting_text");
+    assert_eq!(divergence.candidate, "idle");
+    assert_eq!(divergence.candidate_rule, "idle_pattern");
+}
+
+#[test]
+fn candidate_rules_run_before_the_built_in_ones() {
+    let candidate = CandidateRules::parse(
+        r#"
+[[rule]]
+name = "deploy_confirm"
+state = "answer"
+pattern = 'Deploy to production\?'
+lines = 2
+"#,
+    )
+    .unwrap();
+    let divergence = ab::compare(&candidate, "Deploy to production?\n❯\n", UiLocale::En).unwrap();
+    assert_eq!(
+        divergence,
+        Divergence {
+            current: "idle",
+            current_rule: "prompt_line".to_string(),
+            candidate: "answer",
+            candidate_rule: "deploy_confirm".to_string(),
+        }
+    );
+    assert_eq!(divergence.to_string(), "current idle (prompt_line), candidate answer (deploy_confirm)");
+
+    // Only the bottom `lines` non-empty lines are searched.
+    let far = "Deploy to production?\nmore\nlines\n❯\n";
+    assert_eq!(ab::compare(&candidate, far, UiLocale::En), None);
+}
+
+#[test]
+fn invalid_candidates_are_rejected() {
+    assert!(CandidateRules::parse(r#"disabled = ["no_such_rule"]"#).unwrap_err().contains("no_such_rule"));
+    let bad_state = "[[rule]]\nname = \"x\"\nstate = \"busy\"\npattern = \"x\"\n";
+    assert!(CandidateRules::parse(bad_state).unwrap_err().contains("busy"));
+    let bad_pattern = "[[rule]]\nname = \"x\"\nstate = \"idle\"\npattern = \"(\"\n";
+    assert!(CandidateRules::parse(bad_pattern).is_err());
+}
+
+#[test]
+fn captures_are_named_after_both_states() {
+    let divergence = Divergence {
+        current: "idle",
+        current_rule: "prompt_line".to_string(),
+        candidate: "answer",
+        candidate_rule: "deploy_confirm".to_string(),
+    };
+    assert_eq!(ab::capture_name("%12", &divergence, 1700000000), "1700000000-12-idle-vs-answer.txt");
+}
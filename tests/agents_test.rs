@@ -0,0 +1,130 @@
+use claudeye::agents::{self, Aider, Codex, Detector, EmptyReason, Gemini, Listing};
+use claudeye::claude_state::{ApprovalKind, ClaudeState, UiLocale};
+use claudeye::i18n::EN;
+use claudeye::tmux::parse_pane_fields;
+
+fn state(detector: &dyn Detector, content: &str) -> ClaudeState {
+    detector.detect(content, UiLocale::Auto).state
+}
+
+#[test]
+fn panes_are_matched_by_command() {
+    let detectors = agents::enabled(&agents::builtin_names());
+    assert_eq!(agents::detector_for(&detectors, "claude").map(|d| d.name()), Some("claude"));
+    assert_eq!(agents::detector_for(&detectors, "aider").map(|d| d.name()), Some("aider"));
+    assert_eq!(agents::detector_for(&detectors, "codex").map(|d| d.name()), Some("codex"));
+    assert_eq!(agents::detector_for(&detectors, "gemini").map(|d| d.name()), Some("gemini"));
+    assert!(agents::detector_for(&detectors, "zsh").is_none());
+}
+
+#[test]
+fn only_enabled_agents_are_matched() {
+    let detectors = agents::enabled(&["aider".to_string(), "cursor".to_string()]);
+    assert_eq!(detectors.len(), 1);
+    assert!(agents::detector_for(&detectors, "claude").is_none());
+    assert!(agents::detector_for(&detectors, "aider").is_some());
+}
+
+#[test]
+fn aider_prompt_is_idle() {
+    let content = "Tokens: 4.2k sent, 310 received.\n\narchitect> \n\n";
+    assert_eq!(state(&Aider, content), ClaudeState::Idle);
+    assert_eq!(state(&Aider, "Applied edit to src/lib.rs\n> "), ClaudeState::Idle);
+}
+
+#[test]
+fn aider_question_waits_for_approval() {
+    let content = "src/main.rs\nAdd file to the chat? (Y)es/(N)o/(D)on't ask again [Yes]: ";
+    let info = Aider.detect(content, UiLocale::Auto);
+    assert_eq!(info.state, ClaudeState::WaitingForApproval);
+    let pending = info.pending.unwrap();
+    assert_eq!(pending.tool, "aider");
+    assert_eq!(pending.detail, "Add file to the chat?");
+    assert_eq!(pending.kind, ApprovalKind::Other);
+}
+
+#[test]
+fn aider_streaming_reply_is_working() {
+    let content = "> add a retry\n\nI'll wrap the request in a loop that";
+    assert_eq!(state(&Aider, content), ClaudeState::Working);
+}
+
+#[test]
+fn codex_states() {
+    let working = "• Working (12s • esc to interrupt)\n▌ \n";
+    let info = Codex.detect(working, UiLocale::Auto);
+    assert_eq!(info.state, ClaudeState::Working);
+    assert_eq!(info.activity.as_deref(), Some("Working"));
+
+    let approval = "$ cargo test\nAllow command?\n▶ Yes, proceed\n  No, and tell Codex what to do";
+    assert_eq!(state(&Codex, approval), ClaudeState::WaitingForApproval);
+    let edits = "Would you like to make the following edits?\n▶ Yes, proceed";
+    assert_eq!(Codex.detect(edits, UiLocale::Auto).pending.unwrap().kind, ApprovalKind::FileEdit);
+
+    assert_eq!(state(&Codex, "codex\nDone.\n▌ Ask Codex to do anything"), ClaudeState::Idle);
+}
+
+#[test]
+fn gemini_states() {
+    let working = "⠏ Reading the config loader (esc to cancel, 5s)\n> ";
+    assert_eq!(state(&Gemini, working), ClaudeState::Working);
+
+    let approval = "│ Shell cargo build │\n│ Allow execution? │\n│ ● Yes, allow once │";
+    let info = Gemini.detect(approval, UiLocale::Auto);
+    assert_eq!(info.state, ClaudeState::WaitingForApproval);
+    let pending = info.pending.unwrap();
+    assert_eq!(pending.detail, "Allow execution?");
+    assert_eq!(pending.kind, ApprovalKind::BashCommand);
+
+    assert_eq!(state(&Gemini, "✦ Done.\n> Type your message"), ClaudeState::Idle);
+}
+
+#[test]
+fn ansi_escapes_do_not_hide_the_prompt() {
+    assert_eq!(state(&Aider, "\x1b[32m> \x1b[0m"), ClaudeState::Idle);
+}
+
+#[test]
+fn every_detector_reports_one_of_its_rules() {
+    let cases: [(&dyn Detector, &str); 4] = [
+        (&Aider, "Add file to the chat? (Y)es/(N)o [Yes]: "),
+        (&Codex, "• Working (3s • esc to interrupt)"),
+        (&Gemini, "> "),
+        (&agents::Claude, "✢ Clauding… (esc to interrupt · 1m 45s)"),
+    ];
+    for (detector, content) in cases {
+        let rule = detector.detect(content, UiLocale::Auto).rule;
+        assert!(detector.rules().contains(&rule), "{} reported {rule}", detector.name());
+    }
+}
+
+#[test]
+fn listing_counts_sessions_and_filtered_agent_panes() {
+    let panes: Vec<_> = ["main:0.0 1 /src/api aider", "main:1.0 2 /src/web zsh", "work:0.0 3 /src/db codex"]
+        .iter()
+        .filter_map(|line| parse_pane_fields(line))
+        .map(|(pane, command, labels)| (pane, command.to_string(), labels))
+        .collect();
+    let listing = Listing::of(&panes, &agents::enabled(&["claude".to_string()]));
+    assert_eq!(listing, Listing { panes: 3, sessions: 2, filtered: 2 });
+}
+
+#[test]
+fn empty_reason_tells_no_server_from_no_sessions() {
+    let none = Listing::default();
+    let no_server = "no server running on /tmp/tmux-1000/default";
+    assert_eq!(EmptyReason::of(&none, Some(no_server)), EmptyReason::NoTmux);
+    assert_eq!(EmptyReason::of(&none, Some("No such file or directory (os error 2)")), EmptyReason::NoTmux);
+    assert_eq!(
+        EmptyReason::of(&none, Some("error connecting to /tmp/tmux-1000/default (Permission denied)")),
+        EmptyReason::TmuxFailed("error connecting to /tmp/tmux-1000/default (Permission denied)".to_string())
+    );
+    let shells = Listing { panes: 4, sessions: 3, filtered: 0 };
+    assert_eq!(EmptyReason::of(&shells, None), EmptyReason::NoAgentPanes { sessions: 3 });
+    let aider = Listing { panes: 4, sessions: 3, filtered: 1 };
+    assert_eq!(EmptyReason::of(&aider, None), EmptyReason::Filtered(1));
+    assert_eq!(
+        EmptyReason::NoAgentPanes { sessions: 3 }.label(&EN),
+        "No Claude panes in tmux (3 sessions) — run claude in a pane"
+    );
+}
@@ -0,0 +1,87 @@
+use std::time::Duration;
+
+use claudeye::announce::{announcement, Verbosity};
+use claudeye::claude_state::{ApprovalKind, ClaudeState};
+use claudeye::monitor::Transition;
+use claudeye::tmux::PaneInfo;
+
+fn transition(from: ClaudeState, to: ClaudeState, secs: u64) -> Transition {
+    Transition {
+        pane: PaneInfo {
+            id: "main:0.1".to_string(),
+            pid: 1,
+            cwd: "/home/user/myapp".to_string(),
+            project_name: "myapp".to_string(),
+            session_name: String::new(),
+            window_name: String::new(),
+        },
+        from,
+        to,
+        elapsed: Duration::from_secs(secs),
+        approval: None,
+    }
+}
+
+#[test]
+fn minimal_announces_only_approvals_without_project() {
+    let t = transition(ClaudeState::Working, ClaudeState::WaitingForApproval, 5);
+    assert_eq!(
+        announcement(&t, Verbosity::Minimal).as_deref(),
+        Some("Claude needs approval")
+    );
+    let t = transition(ClaudeState::Working, ClaudeState::Idle, 5);
+    assert_eq!(announcement(&t, Verbosity::Minimal), None);
+}
+
+#[test]
+fn normal_announces_approval_and_finish() {
+    let t = transition(ClaudeState::Working, ClaudeState::WaitingForApproval, 5);
+    assert_eq!(
+        announcement(&t, Verbosity::Normal).as_deref(),
+        Some("myapp needs approval")
+    );
+    let t = transition(ClaudeState::Working, ClaudeState::Idle, 5);
+    assert_eq!(announcement(&t, Verbosity::Normal).as_deref(), Some("myapp finished"));
+}
+
+#[test]
+fn normal_says_what_the_approval_is_for() {
+    let mut t = transition(ClaudeState::Working, ClaudeState::WaitingForApproval, 5);
+    t.approval = Some(ApprovalKind::FileEdit);
+    assert_eq!(announcement(&t, Verbosity::Normal).as_deref(), Some("myapp needs approval to edit a file"));
+    t.approval = Some(ApprovalKind::Other);
+    assert_eq!(announcement(&t, Verbosity::Normal).as_deref(), Some("myapp needs approval"));
+}
+
+#[test]
+fn normal_ignores_start_of_work() {
+    let t = transition(ClaudeState::Idle, ClaudeState::Working, 5);
+    assert_eq!(announcement(&t, Verbosity::Normal), None);
+}
+
+#[test]
+fn verbose_announces_every_transition_with_duration() {
+    let t = transition(ClaudeState::Idle, ClaudeState::Working, 1);
+    assert_eq!(
+        announcement(&t, Verbosity::Verbose).as_deref(),
+        Some("myapp: idle to running after 1 second")
+    );
+    let t = transition(ClaudeState::Working, ClaudeState::Idle, 150);
+    assert_eq!(
+        announcement(&t, Verbosity::Verbose).as_deref(),
+        Some("myapp: running to idle after 2 minutes")
+    );
+}
+
+#[test]
+fn questions_are_announced_like_approvals() {
+    let t = transition(ClaudeState::Working, ClaudeState::WaitingForAnswer, 5);
+    assert_eq!(
+        announcement(&t, Verbosity::Minimal).as_deref(),
+        Some("Claude has a question")
+    );
+    assert_eq!(
+        announcement(&t, Verbosity::Normal).as_deref(),
+        Some("myapp has a question")
+    );
+}
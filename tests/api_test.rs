@@ -0,0 +1,131 @@
+use claudeye::api::{authorized, from_browser, route, ApiConfig, Route};
+use claudeye::config::Config;
+
+#[test]
+fn api_config_defaults_to_loopback_without_token() {
+    let config = Config::parse("").unwrap();
+    assert_eq!(config.api.bind, "127.0.0.1:7890");
+    assert_eq!(config.api.token, None);
+    assert!(config.api.validate().is_ok());
+}
+
+#[test]
+fn non_loopback_bind_requires_token() {
+    let open = ApiConfig { bind: "0.0.0.0:7890".to_string(), ..ApiConfig::default() };
+    assert!(open.validate().is_err());
+    let empty = ApiConfig { token: Some(String::new()), ..open.clone() };
+    assert!(empty.validate().is_err());
+    let protected = ApiConfig { token: Some("t".to_string()), ..open };
+    assert!(protected.validate().is_ok());
+}
+
+#[test]
+fn ipv6_loopback_needs_no_token() {
+    let config = ApiConfig { bind: "[::1]:7890".to_string(), ..ApiConfig::default() };
+    assert!(config.validate().is_ok());
+}
+
+#[test]
+fn tls_needs_both_cert_and_key() {
+    let config = Config::parse("[api]\ntls_cert = \"/etc/cert.pem\"\n").unwrap();
+    assert!(config.api.validate().is_err());
+    let config =
+        Config::parse("[api]\ntls_cert = \"/etc/cert.pem\"\ntls_key = \"/etc/key.pem\"\n").unwrap();
+    assert!(config.api.validate().is_ok());
+}
+
+#[test]
+fn invalid_bind_is_rejected() {
+    let config = ApiConfig { bind: "localhost".to_string(), ..ApiConfig::default() };
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn bearer_token_is_checked() {
+    assert!(authorized(Some("s3cret"), Some("Bearer s3cret")));
+    assert!(!authorized(Some("s3cret"), Some("Bearer wrong")));
+    assert!(!authorized(Some("s3cret"), Some("s3cret")));
+    assert!(!authorized(Some("s3cret"), None));
+}
+
+#[test]
+fn team_token_only_reaches_team_endpoints() {
+    let config = ApiConfig {
+        token: Some("s3cret".to_string()),
+        team_token: Some("team".to_string()),
+        ..ApiConfig::default()
+    };
+    assert!(config.allows(&Route::TeamReport, Some("Bearer team")));
+    assert!(config.allows(&Route::Team, Some("Bearer team")));
+    assert!(!config.allows(&Route::Approve("a".to_string()), Some("Bearer team")));
+    assert!(!config.allows(&Route::Status, Some("Bearer team")));
+    assert!(config.allows(&Route::Approve("a".to_string()), Some("Bearer s3cret")));
+    assert!(config.allows(&Route::TeamReport, Some("Bearer s3cret")));
+    // An empty team token is no token.
+    let config = ApiConfig { team_token: Some(String::new()), ..config };
+    assert!(!config.allows(&Route::TeamReport, Some("Bearer ")));
+}
+
+#[test]
+fn no_token_allows_everything() {
+    assert!(authorized(None, None));
+    assert!(authorized(Some(""), None));
+}
+
+#[test]
+fn web_pages_cannot_answer_prompts() {
+    // A page POSTing with fetch, with or without a token configured.
+    assert!(from_browser(None, Some("https://evil.example"), Some("127.0.0.1:7890")));
+    assert!(from_browser(Some("s3cret"), Some("null"), Some("devbox:7890")));
+    // A rebound name resolving to loopback.
+    assert!(from_browser(None, None, Some("evil.example:7890")));
+    // Clients of the API.
+    assert!(!from_browser(None, None, Some("127.0.0.1:7890")));
+    assert!(!from_browser(None, None, Some("localhost:7890")));
+    assert!(!from_browser(None, None, Some("[::1]:7890")));
+    assert!(!from_browser(None, None, None));
+    assert!(!from_browser(Some("s3cret"), None, Some("devbox:7890")));
+}
+
+#[test]
+fn routes() {
+    assert_eq!(route("GET", "/api/status"), Route::Status);
+    assert_eq!(route("GET", "/api/status?x=1"), Route::Status);
+    assert_eq!(route("POST", "/api/status"), Route::MethodNotAllowed);
+    assert_eq!(route("POST", "/api/panes/main:1.0/approve"), Route::Approve("main:1.0".to_string()));
+    assert_eq!(route("GET", "/api/panes/main:1.0/approve"), Route::MethodNotAllowed);
+    assert_eq!(route("POST", "/api/panes//approve"), Route::NotFound);
+    assert_eq!(route("POST", "/api/panes/main:1.0/deny"), Route::Deny("main:1.0".to_string()));
+    assert_eq!(route("GET", "/api/panes/main:1.0/deny"), Route::MethodNotAllowed);
+    assert_eq!(route("GET", "/"), Route::NotFound);
+    assert_eq!(route("GET", "/healthz"), Route::Health);
+    assert_eq!(route("POST", "/healthz"), Route::MethodNotAllowed);
+    assert_eq!(route("GET", "/api/team"), Route::Team);
+    assert_eq!(route("POST", "/api/team/reports"), Route::TeamReport);
+    assert_eq!(route("GET", "/api/team/reports"), Route::MethodNotAllowed);
+    assert_eq!(route("GET", "/api/metrics"), Route::Metrics);
+    assert_eq!(route("POST", "/api/metrics"), Route::MethodNotAllowed);
+}
+
+#[test]
+fn only_answers_are_actions() {
+    assert!(route("POST", "/api/panes/a/approve").is_action());
+    assert!(route("POST", "/api/panes/a/deny").is_action());
+    assert!(!route("GET", "/api/status").is_action());
+    assert!(!route("GET", "/nope").is_action());
+    assert!(!route("POST", "/api/team/reports").is_action());
+}
+
+#[test]
+fn read_only_defaults_off() {
+    assert!(!Config::parse("").unwrap().api.read_only);
+    assert!(Config::parse("[api]\nread_only = true\n").unwrap().api.read_only);
+}
+
+#[test]
+fn pane_ids_are_percent_decoded() {
+    assert_eq!(
+        route("POST", "/api/panes/my%20work%3A1.0/approve"),
+        Route::Approve("my work:1.0".to_string())
+    );
+}
@@ -1,4 +1,9 @@
-use claudeye::claude_state::{detect_state, ClaudeState};
+use std::time::Duration;
+
+use claudeye::claude_state::{
+    detect_state, detect_state_explained, detect_state_in, detect_status, pending_approval, ApprovalKind, ClaudeState,
+    PendingApproval, PermissionMode, UiLocale, strip_ansi,
+};
 
 // Ported from tcmux status_claude_test.go
 
@@ -171,7 +176,34 @@ fn waiting_interview_mode() {
   Chat about this\n\
   Skip interview and plan immediately\n\
 Enter to select · ↑/↓ to navigate · Esc to cancel";
-    assert_eq!(detect_state(content), ClaudeState::WaitingForApproval);
+    assert_eq!(detect_state(content), ClaudeState::WaitingForAnswer);
+}
+
+#[test]
+fn waiting_for_answer_after_rejecting_a_tool_call() {
+    let content = "⏺ Bash(rm -rf build)\n\
+  ⎿  Interrupted by user\n\
+\n\
+───────────────────────────────────────\n\
+❯ What should Claude do instead?\n\
+───────────────────────────────────────";
+    assert_eq!(detect_state(content), ClaudeState::WaitingForAnswer);
+}
+
+#[test]
+fn idle_when_answered_question_scrolled_up() {
+    let content = "❯ What should Claude do instead?\n\
+use cargo clean\n\
+⏺ Bash(cargo clean)\n\
+  ⎿  Removed 120 files\n\
+⏺ Done.\n\
+\n\
+  Cleaned the build directory.\n\
+  Anything else?\n\
+───────────────────────────────────────\n\
+❯\n\
+───────────────────────────────────────";
+    assert_eq!(detect_state(content), ClaudeState::Idle);
 }
 
 #[test]
@@ -401,3 +433,827 @@ fn idle_with_vim_mode_and_stale_waiting_pattern_in_history() {
   [Opus 4.6] Context: 0%";
     assert_eq!(detect_state(content), ClaudeState::Idle);
 }
+
+#[test]
+fn not_running_after_claude_exits_to_shell() {
+    let content = "\
+⏺ Done.\n\
+\n\
+Resume this session with:\n\
+claude --resume 3f2a9c1e-8b4d-4e6f-9a1b-2c3d4e5f6a7b\n\
+user@host:~/app$ ";
+    assert_eq!(detect_state(content), ClaudeState::NotRunning);
+}
+
+#[test]
+fn resume_hint_far_above_the_prompt_is_ignored() {
+    let content = format!(
+        "claude --resume 3f2a9c1e-8b4d-4e6f-9a1b-2c3d4e5f6a7b\n{}\
+───────────────────────────────────────\n\
+❯\n\
+───────────────────────────────────────",
+        "⏺ some output\n".repeat(10)
+    );
+    assert_eq!(detect_state(&content), ClaudeState::Idle);
+}
+
+#[test]
+fn status_captures_spinner_verb() {
+    let content = "Some output\n\
+✶ Thinking… (esc to interrupt · 2m 10s · ↓ 3k tokens)";
+    let status = detect_status(content);
+    assert_eq!(status.state, ClaudeState::Working);
+    assert_eq!(status.activity.as_deref(), Some("Thinking"));
+}
+
+#[test]
+fn status_captures_custom_action_text() {
+    let content = "Some output\n\
+· Adding handler types… (esc to interrupt · ctrl+t to hide todos · 1m 32s · ↑ 3.4k tokens)\n\
+  ⎿  ☐ Wire the handlers\n\
+\n\
+───────────────────────────────────────\n\
+❯\n\
+───────────────────────────────────────";
+    assert_eq!(detect_status(content).activity.as_deref(), Some("Adding handler types"));
+}
+
+#[test]
+fn status_captures_japanese_action_text() {
+    let content = "· importパスを更新中… (esc to interrupt · 1m 32s · ↑ 3.4k tokens · thinking)";
+    assert_eq!(detect_status(content).activity.as_deref(), Some("importパスを更新中"));
+}
+
+#[test]
+fn status_uses_bottom_spinner_line() {
+    let content = "✻ Clauding… (esc to interrupt · 5s)\n\
+⏺ Ran tests\n\
+  ✻ Compacting conversation… (esc to interrupt · 12s)";
+    assert_eq!(detect_status(content).activity.as_deref(), Some("Compacting conversation"));
+}
+
+#[test]
+fn status_has_no_activity_without_ellipsis() {
+    let content = "Some output\n\
+⏺ Bash(cargo test)\n\
+  ⎿  Running… · esc to interrupt";
+    let status = detect_status(content);
+    assert_eq!(status.state, ClaudeState::Working);
+    assert_eq!(status.activity, None);
+}
+
+#[test]
+fn status_has_no_activity_when_idle() {
+    let content = "⏺ Done.\n\
+───────────────────────────────────────\n\
+❯\n\
+───────────────────────────────────────";
+    let status = detect_status(content);
+    assert_eq!(status.state, ClaudeState::Idle);
+    assert_eq!(status.activity, None);
+}
+
+#[test]
+fn status_parses_timer_and_tokens() {
+    let content = "Some output\n\
+✢ Clauding… (esc to interrupt · 1m 45s · ↓ 1.2k tokens)";
+    let status = detect_status(content);
+    assert_eq!(status.timer, Some(Duration::from_secs(105)));
+    assert_eq!(status.tokens, Some(1200));
+}
+
+#[test]
+fn status_parses_time_first_format() {
+    let content = "✢ Reticulating… (1m 52s · ↓ 11.5k tokens · thought for 7s)";
+    let status = detect_status(content);
+    assert_eq!(status.timer, Some(Duration::from_secs(112)));
+    assert_eq!(status.tokens, Some(11_500));
+}
+
+#[test]
+fn status_parses_upload_tokens_and_hours() {
+    let content = "· importパスを更新中… (esc to interrupt · ctrl+t to hide todos · 1h 2m · ↑ 340 tokens · thinking)";
+    let status = detect_status(content);
+    assert_eq!(status.timer, Some(Duration::from_secs(3720)));
+    assert_eq!(status.tokens, Some(340));
+}
+
+#[test]
+fn status_without_timer_or_tokens() {
+    let status = detect_status("✻ Thinking… (esc to interrupt)");
+    assert_eq!(status.activity.as_deref(), Some("Thinking"));
+    assert_eq!(status.timer, None);
+    assert_eq!(status.tokens, None);
+}
+
+const BASH_DIALOG: &str = "⏺ Bash(grep --help 2>/dev/null | head -10)\n\
+  ⎿  Running…\n\
+\n\
+───────────────────────────────────────\n\
+ Bash command\n\
+\n\
+   grep --help 2>/dev/null | head -10\n\
+   Check grep help\n\
+\n\
+ Do you want to proceed?\n\
+ ❯ 1. Yes\n\
+   2. Yes, and don't ask again for grep commands in /home/user/projects/myapp\n\
+   3. No\n\
+\n\
+ Esc to cancel · Tab to amend · ctrl+e to explain";
+
+#[test]
+fn pending_bash_command() {
+    let status = detect_status(BASH_DIALOG);
+    assert_eq!(status.state, ClaudeState::WaitingForApproval);
+    assert_eq!(
+        status.pending,
+        Some(PendingApproval {
+            tool: "Bash".to_string(),
+            detail: "grep --help 2>/dev/null | head -10".to_string(),
+            kind: ApprovalKind::BashCommand,
+        })
+    );
+}
+
+#[test]
+fn pending_multi_line_bash_command() {
+    let content = " Bash command\n\
+\n\
+   git add -A &&\n\
+   git commit -m wip\n\
+   Commit everything\n\
+\n\
+ Do you want to proceed?\n\
+ ❯ 1. Yes\n\
+   2. No";
+    let pending = pending_approval(content).unwrap();
+    assert_eq!(pending.detail, "git add -A &&\ngit commit -m wip");
+    assert_eq!(pending.summary(80), "Bash: git add -A &&");
+}
+
+#[test]
+fn pending_file_edit() {
+    let content = "⏺ Update(src/main.rs)\n\
+───────────────────────────────────────\n\
+ Edit file\n\
+ src/main.rs\n\
+   12 -    old();\n\
+   12 +    new();\n\
+ Do you want to make this edit to main.rs?\n\
+ ❯ 1. Yes\n\
+   2. Yes, allow all edits during this session (shift+tab)\n\
+   3. No";
+    let pending = detect_status(content).pending.unwrap();
+    assert_eq!(pending.tool, "Edit");
+    assert_eq!(pending.detail, "main.rs");
+}
+
+#[test]
+fn pending_file_create() {
+    let content = " Create file\n\
+ Do you want to create notes.md?\n\
+ ❯ 1. Yes\n\
+   2. No";
+    let pending = pending_approval(content).unwrap();
+    assert_eq!(pending.tool, "Create");
+    assert_eq!(pending.detail, "notes.md");
+}
+
+#[test]
+fn pending_falls_back_to_tool_call_line() {
+    let content = "⏺ Fetch(https://example.com/docs)\n\
+───────────────────────────────────────\n\
+ Fetch\n\
+ Do you want to allow Claude to fetch this content?\n\
+ ❯ 1. Yes\n\
+   2. No";
+    let pending = pending_approval(content).unwrap();
+    assert_eq!(pending.tool, "Fetch");
+    assert_eq!(pending.detail, "https://example.com/docs");
+    assert_eq!(pending.kind, ApprovalKind::Other);
+}
+
+#[test]
+fn pending_kind_of_bash_and_edit_dialogs() {
+    let bash = " Bash command\n\n   rm -rf target\n   Clean the build\n\n Do you want to proceed?\n ❯ 1. Yes\n   2. No";
+    assert_eq!(pending_approval(bash).unwrap().kind, ApprovalKind::BashCommand);
+    let create = " Create file\n Do you want to create notes.md?\n ❯ 1. Yes\n   2. No";
+    assert_eq!(pending_approval(create).unwrap().kind, ApprovalKind::FileEdit);
+    let write = "⏺ Write(config/prod.toml)\n Do you want to proceed?\n ❯ 1. Yes\n   2. No";
+    assert_eq!(pending_approval(write).unwrap().kind, ApprovalKind::FileEdit);
+}
+
+#[test]
+fn pending_mcp_tool_call() {
+    let content = "⏺ github - create_issue (MCP)(title: \"Flaky test\", body: \"...\")\n\
+───────────────────────────────────────\n\
+ Tool use\n\
+ Do you want to proceed?\n\
+ ❯ 1. Yes\n\
+   2. No";
+    let pending = pending_approval(content).unwrap();
+    assert_eq!(pending.kind, ApprovalKind::McpTool);
+    assert_eq!(pending.tool, "github - create_issue");
+    assert_eq!(pending.detail, "title: \"Flaky test\", body: \"...\"");
+    let legacy = "⏺ mcp__github__create_issue(title: \"x\")\n Do you want to proceed?\n ❯ 1. Yes";
+    assert_eq!(pending_approval(legacy).unwrap().kind, ApprovalKind::McpTool);
+}
+
+#[test]
+fn pending_trust_dialog_names_the_folder() {
+    let content = include_str!("fixtures/states/approval/trust-dialog.txt");
+    let pending = detect_status(content).pending.unwrap();
+    assert_eq!(pending.kind, ApprovalKind::TrustDirectory);
+    assert_eq!(pending.summary(80), "Trust: /home/user/projects/myapp");
+}
+
+#[test]
+fn pending_plan_dialog_names_the_plan() {
+    let content = include_str!("fixtures/states/approval/plan-dialog.txt");
+    let pending = detect_status(content).pending.unwrap();
+    assert_eq!(pending.kind, ApprovalKind::PlanApproval);
+    assert_eq!(pending.summary(80), "Plan: Add rate limiting to the API");
+}
+
+#[test]
+fn pending_is_only_set_while_waiting_for_approval() {
+    let content = "⏺ Bash(cargo test)\n\
+✻ Thinking… (esc to interrupt)";
+    assert_eq!(detect_status(content).pending, None);
+}
+
+#[test]
+fn pending_summary_is_shortened() {
+    let pending = PendingApproval { tool: "Bash".to_string(), detail: "a".repeat(50), kind: ApprovalKind::BashCommand };
+    let summary = pending.summary(20);
+    assert_eq!(summary.chars().count(), 20);
+    assert!(summary.starts_with("Bash: aaa"));
+    assert!(summary.ends_with('…'));
+}
+
+#[test]
+fn pending_summary_counts_wide_characters_twice() {
+    let pending = PendingApproval {
+        tool: "Bash".to_string(),
+        detail: "echo 設定ファイルを更新しました".to_string(),
+        kind: ApprovalKind::BashCommand,
+    };
+    let summary = pending.summary(20);
+    assert_eq!(summary, "Bash: echo 設定ファ…");
+    assert!(claudeye::text::width(&summary) <= 20);
+}
+
+#[test]
+fn compacting_conversation() {
+    let content = "⏺ Ran tests\n\
+\n\
+✻ Compacting conversation… (esc to interrupt · 41s · ↑ 2.1k tokens)";
+    assert_eq!(detect_state(content), ClaudeState::Compacting);
+    let status = detect_status(content);
+    assert_eq!(status.activity.as_deref(), Some("Compacting conversation"));
+    assert_eq!(status.timer, Some(Duration::from_secs(41)));
+}
+
+#[test]
+fn compacting_in_history_does_not_hide_a_new_spinner() {
+    let content = "✻ Compacting conversation… (esc to interrupt · 41s)\n\
+⏺ Compacted.\n\
+✶ Thinking… (esc to interrupt · 3s)";
+    assert_eq!(detect_state(content), ClaudeState::Working);
+}
+
+#[test]
+fn compacting_word_in_action_text_is_only_compacting_at_start() {
+    let content = "· Fixing the compacting logic… (esc to interrupt · 12s)";
+    assert_eq!(detect_state(content), ClaudeState::Working);
+}
+
+const PROMPT_BOX: &str = "───────────────────────────────────────\n\
+❯\n\
+───────────────────────────────────────\n\
+  ? for shortcuts";
+
+#[test]
+fn error_after_api_failure() {
+    let content = format!(
+        "> fix the parser\n\
+  ⎿  API Error: 529 {{\"type\":\"error\",\"error\":{{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}}}\n\
+\n{PROMPT_BOX}"
+    );
+    let status = detect_status(&content);
+    assert_eq!(status.state, ClaudeState::Error);
+    assert!(status.error.unwrap().starts_with("API Error: 529"));
+}
+
+#[test]
+fn error_on_usage_limit() {
+    let content = format!("⏺ Claude usage limit reached. Your limit will reset at 5pm (Europe/Paris).\n\n{PROMPT_BOX}");
+    assert_eq!(detect_state(&content), ClaudeState::Error);
+}
+
+#[test]
+fn error_when_offline() {
+    let content = format!("  ⎿  Unable to connect to API (ConnectionRefused)\n{PROMPT_BOX}");
+    assert_eq!(detect_state(&content), ClaudeState::Error);
+}
+
+#[test]
+fn retrying_after_api_error_is_working() {
+    let content = "  ⎿  API Error (Request timed out.) · Retrying in 5 seconds… (attempt 2/10)\n\
+✻ Thinking… (esc to interrupt · 20s)";
+    assert_eq!(detect_state(content), ClaudeState::Working);
+}
+
+#[test]
+fn old_error_scrolled_up_is_ignored() {
+    let content = format!(
+        "  ⎿  API Error: 500 Internal server error\n{}{PROMPT_BOX}",
+        "⏺ later output\n".repeat(8)
+    );
+    assert_eq!(detect_state(&content), ClaudeState::Idle);
+}
+
+#[test]
+fn error_mentioned_in_prose_is_not_an_error() {
+    let content = format!("⏺ I fixed the API Error: handling in client.rs.\n{PROMPT_BOX}");
+    assert_eq!(detect_state(&content), ClaudeState::Idle);
+}
+
+#[test]
+fn expired_login_waits_for_the_user() {
+    let content = format!(
+        "> fix the parser\n\
+  ⎿  API Error: 401 {{\"type\":\"error\",\"error\":{{\"type\":\"authentication_error\",\"message\":\"OAuth token has expired.\"}}}} · Please run /login\n\
+\n{PROMPT_BOX}"
+    );
+    let status = detect_status(&content);
+    assert_eq!(status.state, ClaudeState::WaitingForApproval);
+    let pending = status.pending.unwrap();
+    assert!(pending.is_login());
+    assert!(pending.detail.starts_with("API Error: 401"));
+    assert_eq!(status.error, None);
+}
+
+#[test]
+fn login_method_menu_waits_for_the_user() {
+    let content = " Claude Code can be used with your Claude subscription or billed based on API usage through your Console account.\n\
+\n\
+ Select login method:\n\
+\n\
+ ❯ 1. Claude account with subscription · Pro, Max, Team, or Enterprise\n\
+\n\
+   2. Anthropic Console account · API usage billing\n";
+    assert_eq!(detect_state(content), ClaudeState::WaitingForApproval);
+    assert!(pending_approval(content).unwrap().is_login());
+}
+
+#[test]
+fn oauth_url_prompt_waits_for_the_user() {
+    let content = " Browser didn't open? Use the url below to sign in:\n\
+\n\
+https://claude.ai/oauth/authorize?code=true&client_id=9d1c250a&response_type=code\n\
+\n\
+ Paste code here if prompted >\n";
+    let status = detect_status(content);
+    assert_eq!(status.state, ClaudeState::WaitingForApproval);
+    assert_eq!(status.pending.unwrap().detail, "Paste code here if prompted >");
+}
+
+#[test]
+fn tool_approval_is_not_a_login() {
+    let pending = PendingApproval { tool: "Bash".to_string(), detail: "ls".to_string(), kind: ApprovalKind::BashCommand };
+    assert!(!pending.is_login());
+}
+
+const JA_APPROVAL: &str = "⏺ Bash(cargo test)\n\
+\n\
+ 続行しますか？\n\
+ ❯ 1. はい\n\
+   2. はい、このセッションでは今後確認しない\n\
+   3. いいえ、別の方法を指示する\n";
+
+#[test]
+fn japanese_approval_prompt() {
+    assert_eq!(detect_state(JA_APPROVAL), ClaudeState::WaitingForApproval);
+    assert_eq!(detect_state_in(JA_APPROVAL, UiLocale::Ja), ClaudeState::WaitingForApproval);
+}
+
+#[test]
+fn japanese_allow_dialog() {
+    let content = "⏺ WebFetch(https://example.com)\n\
+\n\
+ はい、許可する\n\
+ いいえ\n";
+    assert_eq!(detect_state_in(content, UiLocale::Ja), ClaudeState::WaitingForApproval);
+    assert_eq!(detect_state_in(content, UiLocale::En), ClaudeState::Idle);
+}
+
+#[test]
+fn japanese_prompt_with_status_hint_is_idle() {
+    let content = "⏺ 完了しました。\n\
+───────────────────────────────────────\n\
+❯\n\
+───────────────────────────────────────\n\
+  ? でショートカット";
+    assert_eq!(detect_state_in(content, UiLocale::Ja), ClaudeState::Idle);
+    assert_eq!(detect_state(content), ClaudeState::Idle);
+}
+
+#[test]
+fn english_prompts_are_ignored_for_japanese_ui() {
+    let content = "⏺ Bash(rm -rf build)\n\
+\n\
+ Do you want to proceed?\n\
+ Yes\n\
+ No\n";
+    assert_eq!(detect_state_in(content, UiLocale::En), ClaudeState::WaitingForApproval);
+    assert_eq!(detect_state_in(content, UiLocale::Ja), ClaudeState::Idle);
+}
+
+#[test]
+fn running_subagents_are_counted() {
+    let content = [
+        "⏺ I'll explore the codebase in parallel.",
+        "",
+        "⏺ Explore(Find the config loader)",
+        "  ⎿  Done (12 tool uses · 34.5k tokens · 45s)",
+        "",
+        "⏺ Explore(Map the polling loop)",
+        "  ⎿  Read(src/monitor.rs)",
+        "     +4 more tool uses (ctrl+o to expand)",
+        "",
+        "⏺ Task(Audit error handling)",
+        "  ⎿  Search(pattern: \"eprintln\")",
+        "",
+        "✻ Exploring… (esc to interrupt · 1m 2s)",
+    ]
+    .join("\n");
+    let status = detect_status(&content);
+    assert_eq!(status.state, ClaudeState::Working);
+    let subagents = status.subagents.unwrap();
+    assert_eq!(subagents.running, 2);
+    assert_eq!(subagents.active, "Audit error handling");
+}
+
+#[test]
+fn custom_agent_is_recognised_by_nested_tool_uses() {
+    let content = [
+        "⏺ code-reviewer(Review the diff)",
+        "  ⎿  Bash(git diff --stat)",
+        "     +2 more tool uses",
+        "",
+        "✻ Reviewing… (esc to interrupt · 20s)",
+    ]
+    .join("\n");
+    let subagents = detect_status(&content).subagents.unwrap();
+    assert_eq!(subagents.running, 1);
+    assert_eq!(subagents.active, "code-reviewer: Review the diff");
+}
+
+#[test]
+fn finished_subagents_and_plain_tools_are_ignored() {
+    let content = [
+        "⏺ Explore(Find the config loader)",
+        "  ⎿  Done (12 tool uses · 34.5k tokens · 45s)",
+        "",
+        "⏺ Bash(cargo test)",
+        "  ⎿  running 12 tests",
+        "",
+        "✻ Thinking… (esc to interrupt · 5s)",
+    ]
+    .join("\n");
+    assert_eq!(detect_status(&content).subagents, None);
+}
+
+#[test]
+fn todo_progress_from_update_todos() {
+    let content = [
+        "⏺ Update Todos",
+        "  ⎿  ☒ Explore the codebase",
+        "     ☒ Add handler types",
+        "     ☐ Wire up the router",
+        "     ☐ Write tests",
+        "",
+        "✻ Wiring… (esc to interrupt · 12s)",
+    ]
+    .join("\n");
+    assert_eq!(detect_status(&content).todo_progress, Some((2, 4)));
+}
+
+#[test]
+fn todo_progress_uses_the_latest_list() {
+    let content = [
+        "  ⎿  ☒ Explore the codebase",
+        "     ☐ Add handler types",
+        "⏺ Added the handler types.",
+        "  ✔ Explore the codebase",
+        "  ✔ Add handler types",
+        "  ◼ Write tests",
+        "",
+        "❯",
+    ]
+    .join("\n");
+    assert_eq!(detect_status(&content).todo_progress, Some((2, 3)));
+}
+
+#[test]
+fn no_todo_list_means_no_progress() {
+    assert_eq!(detect_status("⏺ Done.\n❯").todo_progress, None);
+}
+
+#[test]
+fn messages_typed_while_working_are_counted_as_queued() {
+    let content = [
+        "⏺ Bash(cargo test)",
+        "  ⎿  Running…",
+        "",
+        "✻ Testing… (esc to interrupt · 12s)",
+        "  ⎿  Tip: Press up to edit queued messages",
+        "",
+        "> also update the docs",
+        "> and run clippy",
+        "────────────────────────────────────────",
+        "❯ ",
+        "────────────────────────────────────────",
+        "  ⏵⏵ accept edits on (shift+tab to cycle)",
+    ]
+    .join("\n");
+    let info = detect_status(&content);
+    assert_eq!(info.state, ClaudeState::Working);
+    assert_eq!(info.queued_messages, 2);
+}
+
+#[test]
+fn the_input_box_is_not_a_queued_message() {
+    let content = [
+        "✻ Testing… (esc to interrupt · 12s)",
+        "",
+        "╭──────────────────────────────────────╮",
+        "│ > still typing this                  │",
+        "╰──────────────────────────────────────╯",
+    ]
+    .join("\n");
+    assert_eq!(detect_status(&content).queued_messages, 0);
+    assert_eq!(detect_status("⏺ Done.\n> \n❯").queued_messages, 0);
+}
+
+fn idle_screen(footer: &str) -> String {
+    [
+        "⏺ Here is the plan.",
+        "",
+        "╭──────────────────────────────────────╮",
+        "│ >                                    │",
+        "╰──────────────────────────────────────╯",
+        footer,
+    ]
+    .join("\n")
+}
+
+#[test]
+fn plan_mode_from_footer() {
+    let info = detect_status(&idle_screen("  ⏸ plan mode on (shift+tab to cycle)"));
+    assert_eq!(info.state, ClaudeState::Idle);
+    assert_eq!(info.permission_mode, PermissionMode::Plan);
+}
+
+#[test]
+fn accept_edits_and_bypass_from_footer() {
+    let content = idle_screen("  ⏵⏵ accept edits on (shift+tab to cycle)");
+    assert_eq!(detect_status(&content).permission_mode, PermissionMode::AcceptEdits);
+    let content = idle_screen("  ⏵⏵ bypass permissions on (shift+tab to cycle)");
+    assert_eq!(detect_status(&content).permission_mode, PermissionMode::BypassPermissions);
+    assert!(detect_status(&content).skips_permissions);
+}
+
+#[test]
+fn bypass_warning_at_startup_skips_permissions() {
+    let content = format!(
+        "{}\n{}",
+        "╭────────────────────────────────────────────────────╮\n\
+│ WARNING: Claude Code running in Bypass Permissions mode │\n\
+╰────────────────────────────────────────────────────╯",
+        idle_screen("  ? for shortcuts")
+    );
+    let info = detect_status(&content);
+    assert_eq!(info.permission_mode, PermissionMode::Default);
+    assert!(info.skips_permissions);
+}
+
+#[test]
+fn talking_about_bypass_mode_does_not_skip_permissions() {
+    let content = format!("⏺ The --dangerously-skip-permissions flag enables bypass permissions mode.\n{}", idle_screen("  ? for shortcuts"));
+    assert!(!detect_status(&content).skips_permissions);
+}
+
+#[test]
+fn no_mode_line_means_default_mode() {
+    let content = idle_screen("  ? for shortcuts");
+    assert_eq!(detect_status(&content).permission_mode, PermissionMode::Default);
+}
+
+#[test]
+fn model_and_context_from_footer() {
+    let info = detect_status(&idle_screen("  [Opus 4.6] Context: 37%"));
+    assert_eq!(info.state, ClaudeState::Idle);
+    assert_eq!(info.model.as_deref(), Some("Opus 4.6"));
+    assert_eq!(info.context_left, Some(37));
+}
+
+#[test]
+fn context_from_auto_compact_warning() {
+    let content = idle_screen("  ⏵⏵ accept edits on (shift+tab to cycle)    Context left until auto-compact: 8%");
+    let info = detect_status(&content);
+    assert_eq!(info.model, None);
+    assert_eq!(info.context_left, Some(8));
+    assert_eq!(info.permission_mode, PermissionMode::AcceptEdits);
+}
+
+#[test]
+fn no_footer_means_no_context() {
+    let info = detect_status(&idle_screen("  ? for shortcuts"));
+    assert_eq!((info.model, info.context_left), (None, None));
+}
+
+#[test]
+fn strip_ansi_removes_colors_and_osc_sequences() {
+    assert_eq!(strip_ansi("\x1b[1;38;5;174m✻\x1b[0m Thinking…"), "✻ Thinking…");
+    assert_eq!(strip_ansi("\x1b]0;claude\x07\x1b]8;;https://x.dev\x1b\\link\x1b]8;;\x1b\\"), "link");
+    assert_eq!(strip_ansi("\x1b(B\x1b[?25lplain\x1b[K"), "plain");
+    assert_eq!(strip_ansi("no escapes"), "no escapes");
+}
+
+#[test]
+fn colored_status_line_is_working() {
+    let content = "Some output\n\x1b[38;5;174m✢\x1b[39m \x1b[38;5;174mClauding…\x1b[39m \x1b[2m(esc to interrupt · 1m 45s · ↓ 1.2k tokens)\x1b[22m";
+    let info = detect_status(content);
+    assert_eq!(info.state, ClaudeState::Working);
+    assert_eq!(info.activity.as_deref(), Some("Clauding"));
+    assert_eq!(info.timer, Some(Duration::from_secs(105)));
+}
+
+#[test]
+fn colored_prompt_is_idle() {
+    let content = "⏺ Done.\n\x1b[2m───────────────────────\x1b[22m\n\x1b[1m❯\x1b[22m \n\x1b[2m───────────────────────\x1b[22m";
+    assert_eq!(detect_state(content), ClaudeState::Idle);
+}
+
+#[test]
+fn colored_approval_dialog_is_parsed() {
+    let colored = BASH_DIALOG
+        .replace("Bash command", "\x1b[1mBash command\x1b[22m")
+        .replace("❯ 1. Yes", "\x1b[38;5;153m❯ 1. Yes\x1b[39m");
+    let status = detect_status(&colored);
+    assert_eq!(status.state, ClaudeState::WaitingForApproval);
+    assert_eq!(status.pending, detect_status(BASH_DIALOG).pending);
+    assert_eq!(pending_approval(&colored), status.pending);
+}
+
+#[test]
+fn sudo_password_prompt_blocks_the_session() {
+    let content = [
+        "⏺ Bash(sudo apt-get install -y jq)",
+        "  ⎿  [sudo] password for me:",
+        "",
+        "✻ Running… (esc to interrupt · 42s)",
+        "",
+        "╭──────────────────────────────────────╮",
+        "│ >                                    │",
+        "╰──────────────────────────────────────╯",
+        "  ? for shortcuts",
+    ]
+    .join("\n");
+    let info = detect_status(&content);
+    assert_eq!(info.state, ClaudeState::Blocked);
+    assert_eq!(info.input_prompt.as_deref(), Some("[sudo] password for me:"));
+}
+
+#[test]
+fn ssh_host_key_confirmation_blocks_the_session() {
+    let content = [
+        "⏺ Bash(git push origin main)",
+        "  ⎿  The authenticity of host 'git.example.com (10.0.0.5)' can't be established.",
+        "     ED25519 key fingerprint is SHA256:abc.",
+        "     Are you sure you want to continue connecting (yes/no/[fingerprint])?",
+        "",
+        "✻ Running… (esc to interrupt · 12s)",
+    ]
+    .join("\n");
+    assert_eq!(detect_state(&content), ClaudeState::Blocked);
+}
+
+#[test]
+fn answered_sub_prompt_no_longer_blocks() {
+    let content = [
+        "⏺ Bash(sudo systemctl restart nginx)",
+        "  ⎿  [sudo] password for me:",
+        "     sudo: a terminal is required to read the password",
+        "⏺ sudo needs a password here; please restart nginx yourself.",
+        "",
+        "❯ ",
+    ]
+    .join("\n");
+    assert_eq!(detect_state(&content), ClaudeState::Idle);
+}
+
+#[test]
+fn password_mentioned_in_prose_does_not_block() {
+    let content = "⏺ The script asks for Password: and then exits.\n\n❯ ";
+    assert_eq!(detect_state(content), ClaudeState::Idle);
+}
+
+#[test]
+fn explained_detection_names_the_deciding_rule() {
+    let content = "Some output\n✢ Clauding… (esc to interrupt · 1m 45s · ↓ 1.2k tokens)";
+    let (state, trace) = detect_state_explained(content);
+    assert_eq!(state, ClaudeState::Working);
+    assert_eq!(trace.rule, "running");
+    assert_eq!(trace.matched.as_deref(), Some("✢ Clauding… (esc to interrupt · 1m 45s"));
+    assert_eq!(trace.skipped, vec!["exit_hint", "input_prompt"]);
+    assert!(!trace.stripped_ansi);
+}
+
+#[test]
+fn explained_detection_reports_prompt_line_and_fallback() {
+    let (state, trace) = detect_state_explained("⏺ Done.\n\x1b[1m❯\x1b[22m ");
+    assert_eq!(state, ClaudeState::Idle);
+    assert_eq!(trace.rule, "prompt_line");
+    assert_eq!(trace.matched.as_deref(), Some("❯"));
+    assert!(trace.stripped_ansi);
+
+    let (state, trace) = detect_state_explained("plain shell output");
+    assert_eq!(state, ClaudeState::Idle);
+    assert_eq!(trace.rule, "fallback");
+    assert_eq!(trace.matched, None);
+    assert!(trace.render().starts_with("rule: fallback\nchecked first: exit_hint, input_prompt, running,"));
+}
+
+#[test]
+fn explained_detection_notes_compacting() {
+    let content = "⏺ Output\n✻ Compacting conversation… (esc to interrupt · 41s)";
+    let (state, trace) = detect_state_explained(content);
+    assert_eq!(state, ClaudeState::Compacting);
+    assert!(trace.compacting);
+    assert!(trace.render().contains("Working became Compacting"));
+}
+
+#[test]
+fn esc_mid_turn_is_an_interrupted_idle_session() {
+    let content = [
+        "> refactor the parser",
+        "",
+        "⏺ Update(src/parser.rs)",
+        "  ⎿  Interrupted · What should Claude do instead?",
+        "",
+        "───────────────────────────────────────",
+        "❯ ",
+        "───────────────────────────────────────",
+        "  ? for shortcuts",
+    ]
+    .join("\n");
+    let info = detect_status(&content);
+    assert_eq!(info.state, ClaudeState::Idle);
+    assert!(info.interrupted);
+}
+
+#[test]
+fn reply_after_an_interruption_is_a_plain_idle_session() {
+    let content = [
+        "  ⎿  Interrupted · What should Claude do instead?",
+        "",
+        "> never mind, just run the tests",
+        "",
+        "⏺ All 42 tests pass.",
+        "",
+        "───────────────────────────────────────",
+        "❯ ",
+        "───────────────────────────────────────",
+    ]
+    .join("\n");
+    let info = detect_status(&content);
+    assert_eq!(info.state, ClaudeState::Idle);
+    assert!(!info.interrupted);
+    assert!(!detect_status(PROMPT_BOX).interrupted);
+}
+
+#[test]
+fn status_names_the_deciding_rule() {
+    assert_eq!(detect_status(PROMPT_BOX).rule, "prompt_line");
+    assert_eq!(detect_status("✢ Clauding… (esc to interrupt · 1m 45s)").rule, "running");
+    assert_eq!(detect_status("").rule, "fallback");
+}
+
+#[test]
+fn finished_turn_duration_is_read_from_the_summary() {
+    let content = "⏺ Done.\n\n✻ Cooked for 43s\n\n────────\n❯\n────────";
+    assert_eq!(detect_status(content).last_turn, Some(Duration::from_secs(43)));
+    let content = std::fs::read_to_string("tests/fixtures/states/idle/file-changes-status-line.txt").unwrap();
+    assert_eq!(detect_status(&content).last_turn, Some(Duration::from_secs(185)));
+}
+
+#[test]
+fn summary_of_an_earlier_turn_is_ignored() {
+    let content = "✻ Cooked for 43s\n\n> next task\n\n⏺ Done.\n\n────────\n❯\n────────";
+    assert_eq!(detect_status(content).state, ClaudeState::Idle);
+    assert_eq!(detect_status(content).last_turn, None);
+}
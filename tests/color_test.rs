@@ -0,0 +1,38 @@
+use claudeye::color::{enabled, state, summary, tmux_summary, ColorChoice};
+
+fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<String> + 'a {
+    move |name| vars.iter().find(|(n, _)| *n == name).map(|(_, v)| v.to_string())
+}
+
+#[test]
+fn auto_colors_terminals_only() {
+    assert!(enabled(ColorChoice::Auto, true, env(&[("TERM", "xterm-256color")])));
+    assert!(!enabled(ColorChoice::Auto, false, env(&[])));
+}
+
+#[test]
+fn no_color_and_dumb_terminals_turn_auto_off() {
+    assert!(!enabled(ColorChoice::Auto, true, env(&[("NO_COLOR", "1")])));
+    assert!(enabled(ColorChoice::Auto, true, env(&[("NO_COLOR", "")])));
+    assert!(!enabled(ColorChoice::Auto, true, env(&[("TERM", "dumb")])));
+}
+
+#[test]
+fn explicit_choices_win() {
+    assert!(enabled(ColorChoice::Always, false, env(&[("NO_COLOR", "1")])));
+    assert!(!enabled(ColorChoice::Never, true, env(&[])));
+}
+
+#[test]
+fn states_and_summaries_are_painted() {
+    assert_eq!(state("approval", "approval"), "\x1b[33mapproval\x1b[0m");
+    assert_eq!(state("idle", "idle"), "idle");
+    assert_eq!(summary("⚠1 ▶2"), "\x1b[33m⚠1\x1b[0m \x1b[32m▶2\x1b[0m");
+}
+
+#[test]
+fn tmux_summaries_use_style_codes() {
+    assert_eq!(tmux_summary("⚠1 ●2"), "#[fg=yellow]⚠1#[fg=default] #[fg=blue]●2#[fg=default]");
+    assert_eq!(tmux_summary("✕3"), "#[fg=colour244]✕3#[fg=default]");
+    assert_eq!(tmux_summary(""), "");
+}
@@ -0,0 +1,12 @@
+use claudeye::tmux::PaneInfo;
+
+/// A local pane running in `/tmp/<project>`.
+pub fn pane(id: &str, project: &str) -> PaneInfo {
+    PaneInfo {
+        id: id.to_string(),
+        pid: 1,
+        cwd: format!("/tmp/{project}"),
+        project_name: project.to_string(),
+        ..PaneInfo::default()
+    }
+}
@@ -0,0 +1,340 @@
+use claudeye::actions::{ClickConfig, RowAction};
+use claudeye::claude_state::{ClaudeState, UiLocale};
+use claudeye::config::{Config, ConfigError};
+use claudeye::i18n::Language;
+use claudeye::monitor::{Debounce, NameFormat};
+use claudeye::rename::TmuxRename;
+use claudeye::tmux::NameSource;
+
+#[test]
+fn empty_config_uses_defaults() {
+    let config = Config::parse("").unwrap();
+    assert_eq!(config.language, Language::En);
+}
+
+#[test]
+fn language_selects_japanese_bundle() {
+    let config = Config::parse("language = \"ja\"").unwrap();
+    assert_eq!(config.language, Language::Ja);
+    let strings = config.language.strings();
+    assert_eq!(strings.state_label(&ClaudeState::WaitingForApproval), "承認待ち");
+}
+
+#[test]
+fn english_bundle_keeps_existing_labels() {
+    let strings = Language::En.strings();
+    assert_eq!(strings.state_label(&ClaudeState::Working), "Running");
+    assert_eq!(strings.state_label(&ClaudeState::WaitingForApproval), "Approval");
+    assert_eq!(strings.state_label(&ClaudeState::Idle), "Idle");
+    assert_eq!(strings.no_sessions, "No Claude sessions found");
+}
+
+#[test]
+fn unknown_language_is_rejected() {
+    assert!(Config::parse("language = \"fr\"").is_err());
+}
+
+#[test]
+fn unknown_key_is_rejected() {
+    assert!(Config::parse("langauge = \"ja\"").is_err());
+}
+
+#[test]
+fn label_overrides_replace_only_configured_states() {
+    let config = Config::parse(
+        r#"
+[labels]
+running = "🟢"
+approval = "🟡 NEEDS YOU"
+"#,
+    )
+    .unwrap();
+    let strings = config.strings();
+    assert_eq!(strings.state_label(&ClaudeState::Working), "🟢");
+    assert_eq!(strings.state_label(&ClaudeState::WaitingForApproval), "🟡 NEEDS YOU");
+    assert_eq!(strings.state_label(&ClaudeState::Idle), "Idle");
+}
+
+#[test]
+fn label_overrides_apply_on_top_of_language() {
+    let config = Config::parse(
+        r#"
+language = "ja"
+
+[labels]
+idle = "…"
+"#,
+    )
+    .unwrap();
+    let strings = config.strings();
+    assert_eq!(strings.state_label(&ClaudeState::Working), "実行中");
+    assert_eq!(strings.state_label(&ClaudeState::Idle), "…");
+}
+
+#[test]
+fn ended_label_fills_in_durations() {
+    let strings = Language::En.strings();
+    assert_eq!(strings.ended_label(30, 42 * 60), "ended 30s ago · ran 42m");
+}
+
+#[test]
+fn todos_label_fills_in_counts() {
+    assert_eq!(Language::En.strings().todos_label(3, 6), "3/6 tasks");
+    assert_eq!(Language::Ja.strings().todos_label(3, 6), "タスク 3/6");
+}
+
+#[test]
+fn monitor_table_sets_tombstone_grace() {
+    assert_eq!(Config::parse("").unwrap().monitor.tombstone_grace_secs, 60);
+    let config = Config::parse("[monitor]\ntombstone_grace_secs = 0").unwrap();
+    assert_eq!(config.monitor.tombstone_grace_secs, 0);
+}
+
+#[test]
+fn monitor_table_sets_ui_locale() {
+    assert_eq!(Config::parse("").unwrap().monitor.ui_locale, UiLocale::Auto);
+    let config = Config::parse("[monitor]\nui_locale = \"ja\"").unwrap();
+    assert_eq!(config.monitor.ui_locale, UiLocale::Ja);
+    assert!(Config::parse("[monitor]\nui_locale = \"fr\"").is_err());
+}
+
+#[test]
+fn monitor_table_selects_agents() {
+    let defaults = Config::parse("").unwrap().monitor.agents;
+    assert_eq!(defaults, ["claude", "aider", "codex", "gemini"]);
+    let config = Config::parse("[monitor]\nagents = [\"claude\", \"aider\"]").unwrap();
+    assert_eq!(config.monitor.agents, ["claude", "aider"]);
+}
+
+#[test]
+fn debounce_is_configured_per_state() {
+    let defaults = Config::parse("").unwrap().monitor.debounce;
+    assert_eq!(defaults.for_state(&ClaudeState::Idle), Debounce { polls: 2, min_secs: 0 });
+    assert_eq!(defaults.for_state(&ClaudeState::Working), Debounce { polls: 1, min_secs: 0 });
+
+    let config = Config::parse("[monitor.debounce]\nidle = { polls = 3 }\nerror = { min_secs = 5 }").unwrap();
+    assert_eq!(config.monitor.debounce.idle, Debounce { polls: 3, min_secs: 0 });
+    assert_eq!(config.monitor.debounce.error, Debounce { polls: 1, min_secs: 5 });
+    assert!(Config::parse("[monitor.debounce]\nidle = { frames = 3 }").is_err());
+}
+
+#[test]
+fn screen_backend_is_opt_in() {
+    assert!(!Config::default().monitor.screen);
+    assert!(Config::parse("[monitor]\nscreen = true\n").unwrap().monitor.screen);
+}
+
+#[test]
+fn kitty_backend_is_opt_in() {
+    assert!(!Config::default().monitor.kitty);
+    assert!(Config::parse("[monitor]\nkitty = true\n").unwrap().monitor.kitty);
+}
+
+#[test]
+fn remote_hosts_are_read() {
+    assert!(Config::default().monitor.remote_hosts.is_empty());
+    let config = Config::parse("[monitor]\nremote_hosts = [\"devbox\", \"me@gpu1\"]\n").unwrap();
+    assert_eq!(config.monitor.remote_hosts, ["devbox", "me@gpu1"]);
+}
+
+#[test]
+fn tmux_rename_is_opt_in() {
+    assert_eq!(Config::default().tmux_rename, TmuxRename::Off);
+    assert_eq!(Config::parse("tmux_rename = \"window\"\n").unwrap().tmux_rename, TmuxRename::Window);
+    assert!(Config::parse("tmux_rename = \"session\"\n").is_err());
+}
+
+#[test]
+fn tmux_path_is_read() {
+    assert!(Config::default().monitor.tmux_path.is_none());
+    let config = Config::parse("[monitor]\ntmux_path = \"/opt/tmux/bin/tmux\"\n").unwrap();
+    assert_eq!(config.monitor.tmux_path, Some("/opt/tmux/bin/tmux".into()));
+}
+
+#[test]
+fn naming_order_and_generic_dirs_are_read() {
+    let config = Config::parse("[monitor.naming]\norder = [\"window\", \"directory\"]\ngeneric_dirs = [\"web\"]\n").unwrap();
+    assert_eq!(config.monitor.naming.order, vec![NameSource::Window, NameSource::Directory]);
+    assert_eq!(config.monitor.naming.generic_dirs, vec!["web".to_string()]);
+    assert!(Config::parse("[monitor.naming]\norder = [\"hostname\"]\n").is_err());
+}
+
+#[test]
+fn name_format_is_read() {
+    assert_eq!(Config::default().name_format, NameFormat::Project);
+    assert_eq!(Config::parse("name_format = \"tmux\"\n").unwrap().name_format, NameFormat::Tmux);
+    assert!(Config::parse("name_format = \"window\"\n").is_err());
+}
+
+#[test]
+fn watch_rules_are_parsed_in_order() {
+    let config = Config::parse(
+        r#"
+[[watch]]
+name = "tests passed"
+pattern = "test result: ok"
+
+[[watch]]
+name = "panic"
+pattern = "panicked at"
+notify = false
+"#,
+    )
+    .unwrap();
+    let names: Vec<_> = config.watch.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, ["tests passed", "panic"]);
+    assert!(config.watch[0].notify);
+    assert!(!config.watch[1].notify);
+}
+
+#[test]
+fn invalid_watch_pattern_is_rejected() {
+    assert!(Config::parse("[[watch]]\nname = \"bad\"\npattern = \"(\"").is_err());
+}
+
+#[test]
+fn answer_label_can_be_overridden() {
+    let strings = Language::En.strings();
+    assert_eq!(strings.state_label(&ClaudeState::WaitingForAnswer), "Question");
+    let config = Config::parse("[labels]\nanswer = \"✍\"").unwrap();
+    assert_eq!(config.strings().state_label(&ClaudeState::WaitingForAnswer), "✍");
+}
+
+#[test]
+fn queued_label_fills_in_the_count() {
+    assert_eq!(Language::En.strings().queued_label(2), "2 queued");
+    assert_eq!(Language::Ja.strings().queued_label(2), "送信待ち 2件");
+}
+
+#[test]
+fn interrupted_label_can_be_overridden() {
+    assert_eq!(Language::En.strings().interrupted, "Interrupted");
+    assert_eq!(Language::Ja.strings().interrupted, "中断");
+    let config = Config::parse("[labels]\ninterrupted = \"✋\"\n").unwrap();
+    assert_eq!(config.strings().interrupted, "✋");
+}
+
+#[test]
+fn compacting_label_can_be_overridden() {
+    let config = Config::parse("[labels]\ncompacting = \"🗜\"\n").unwrap();
+    assert_eq!(config.strings().state_label(&ClaudeState::Compacting), "🗜");
+    let ja = Config::parse("language = \"ja\"\n").unwrap();
+    assert_eq!(ja.strings().state_label(&ClaudeState::Compacting), "圧縮中");
+}
+
+#[test]
+fn light_mode_is_off_by_default() {
+    assert!(!Config::default().light);
+    assert!(Config::parse("light = true\n").unwrap().light);
+}
+
+#[test]
+fn strip_edge_is_read() {
+    assert_eq!(Config::default().strip, None);
+    let config = Config::parse("strip = \"right\"\n").unwrap();
+    assert_eq!(config.strip.as_deref(), Some("right"));
+}
+
+const PROFILES: &str = r#"
+language = "ja"
+position = "top-center"
+
+[elapsed]
+hide_below_secs = 5
+minutes_from_secs = 60
+
+[[watch]]
+name = "FAILED"
+pattern = "FAILED"
+
+[profiles.demo]
+position = "bottom-right"
+notifications = false
+watch = []
+
+[profiles.demo.elapsed]
+hide_below_secs = 30
+
+[profiles.quiet]
+show_age = true
+"#;
+
+#[test]
+fn no_profile_uses_base_settings() {
+    let config = Config::parse_with_profile(PROFILES, None).unwrap();
+    assert_eq!(config.position.as_deref(), Some("top-center"));
+    assert!(config.notifications);
+    assert_eq!(config.watch.len(), 1);
+    assert_eq!(config.profiles.len(), 2);
+}
+
+#[test]
+fn profile_overrides_keys_and_merges_tables() {
+    let config = Config::parse_with_profile(PROFILES, Some("demo")).unwrap();
+    assert_eq!(config.language, Language::Ja);
+    assert_eq!(config.position.as_deref(), Some("bottom-right"));
+    assert!(!config.notifications);
+    assert!(config.watch.is_empty());
+    assert_eq!(config.elapsed.hide_below_secs, 30);
+    assert_eq!(config.elapsed.minutes_from_secs, Some(60));
+}
+
+#[test]
+fn unknown_profile_is_an_error() {
+    let err = Config::parse_with_profile(PROFILES, Some("work")).unwrap_err();
+    assert!(matches!(err, ConfigError::UnknownProfile(ref name) if name == "work"));
+}
+
+#[test]
+fn invalid_profile_setting_is_rejected() {
+    let text = "[profiles.bad]\nshow_age = \"yes\"\n";
+    assert!(Config::parse_with_profile(text, None).is_ok());
+    assert!(matches!(Config::parse_with_profile(text, Some("bad")), Err(ConfigError::Invalid(_))));
+}
+
+#[test]
+fn clicks_are_unbound_by_default() {
+    assert_eq!(Config::default().clicks, ClickConfig::default());
+    let config = Config::parse("[clicks]\ndouble = \"switch\"\nmiddle = \"transcript\"\n").unwrap();
+    assert_eq!(config.clicks.double, Some(RowAction::Switch));
+    assert_eq!(config.clicks.middle, Some(RowAction::Transcript));
+    assert!(Config::parse("[clicks]\nright = \"snooze\"\n").is_err());
+    assert!(Config::parse("[clicks]\ndouble = \"kill\"\n").is_err());
+}
+
+#[test]
+fn pane_rules_are_read() {
+    let config = Config::parse("[[monitor.exclude]]\nsession = \"scratch*\"\n\n[[monitor.include]]\npath = \"~/src\"\n").unwrap();
+    assert_eq!(config.monitor.exclude[0].session.as_deref(), Some("scratch*"));
+    assert_eq!(config.monitor.include[0].path.as_deref(), Some("~/src"));
+    assert!(Config::parse("[[monitor.exclude]]\nwindow = \"x\"\n").is_err());
+}
+
+#[test]
+fn capture_and_detection_depths_are_read() {
+    assert_eq!(Config::default().monitor.capture_lines, 0);
+    assert_eq!(Config::default().monitor.detection_lines, 30);
+    let config = Config::parse("[monitor]\ncapture_lines = 200\ndetection_lines = 60\n").unwrap();
+    assert_eq!((config.monitor.capture_lines, config.monitor.detection_lines), (200, 60));
+}
+
+#[test]
+fn zoom_on_jump_is_opt_in() {
+    assert!(!Config::default().zoom_on_jump);
+    assert!(Config::parse("zoom_on_jump = true\n").unwrap().zoom_on_jump);
+}
+
+#[test]
+fn on_transition_command_is_read() {
+    assert_eq!(Config::default().on_transition, None);
+    let config = Config::parse("on_transition = \"notify-send '{project}' '{from} -> {to}'\"\n").unwrap();
+    assert_eq!(config.on_transition.as_deref(), Some("notify-send '{project}' '{from} -> {to}'"));
+}
+
+#[test]
+fn poll_intervals_are_read_per_state() {
+    let config = Config::parse("[monitor.poll_intervals]\nidle = 10000\n").unwrap();
+    assert_eq!(config.monitor.poll_intervals.for_state(&ClaudeState::Idle), std::time::Duration::from_secs(10));
+    assert_eq!(config.monitor.poll_intervals.running, 500);
+    assert!(Config::parse("[monitor.poll_intervals]\nworking = 100\n").is_err());
+}
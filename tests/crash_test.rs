@@ -0,0 +1,59 @@
+use std::time::Instant;
+
+use claudeye::claude_state::ClaudeState;
+use claudeye::crash::{recent_log, redact_home, redact_path, render_report, session_line, LOG_CAPACITY};
+use claudeye::monitor::ClaudeSession;
+use claudeye::tmux::PaneInfo;
+
+#[test]
+fn redact_path_keeps_only_the_last_component() {
+    assert_eq!(redact_path("/home/me/src/api"), "…/api");
+    assert_eq!(redact_path("/home/me/src/api/"), "…/api");
+    assert_eq!(redact_path("api"), "api");
+}
+
+#[test]
+fn redact_home_replaces_the_home_directory() {
+    assert_eq!(redact_home("failed to save /home/me/.local/state/x", "/home/me"), "failed to save ~/.local/state/x");
+    assert_eq!(redact_home("/etc/x", "/"), "/etc/x");
+    assert_eq!(redact_home("/etc/x", ""), "/etc/x");
+}
+
+#[test]
+fn session_line_omits_full_paths() {
+    let pane = PaneInfo {
+        id: "%3".to_string(),
+        pid: 1,
+        cwd: "/home/me/src/api".to_string(),
+        project_name: "api".to_string(),
+        session_name: String::new(),
+        window_name: String::new(),
+    };
+    let session = ClaudeSession::new(pane, ClaudeState::WaitingForApproval, Instant::now());
+    assert_eq!(session_line(&session), "%3\tapproval\t…/api");
+}
+
+#[test]
+fn report_has_every_section() {
+    let report = render_report(
+        "thread 'main' panicked at src/main.rs:1:1:\nboom",
+        "0: main",
+        &["1760000000 tmux list-panes failed".to_string()],
+        &["%3\tapproval\t…/api".to_string()],
+    );
+    assert!(report.starts_with("claudeye "));
+    assert!(report.contains("boom\n\nbacktrace:\n0: main\n"));
+    assert!(report.contains("recent log:\n1760000000 tmux list-panes failed\n"));
+    assert!(report.ends_with("sessions:\n%3\tapproval\t…/api\n"));
+}
+
+#[test]
+fn log_keeps_only_the_latest_lines() {
+    for i in 0..LOG_CAPACITY + 5 {
+        claudeye::log!("message {i}");
+    }
+    let log = recent_log();
+    assert_eq!(log.len(), LOG_CAPACITY);
+    assert!(log[0].ends_with(" message 5"));
+    assert!(log[LOG_CAPACITY - 1].ends_with(&format!(" message {}", LOG_CAPACITY + 4)));
+}
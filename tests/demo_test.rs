@@ -0,0 +1,50 @@
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use claudeye::claude_state::ClaudeState;
+use claudeye::demo::sessions;
+use claudeye::status::state_key;
+
+#[test]
+fn sessions_get_distinct_panes_and_projects() {
+    let start = Instant::now();
+    let demo = sessions(10, start, start);
+    assert_eq!(demo.len(), 10);
+    let panes: HashSet<&str> = demo.iter().map(|s| s.pane.id.as_str()).collect();
+    let projects: HashSet<&str> = demo.iter().map(|s| s.pane.project_name.as_str()).collect();
+    assert_eq!((panes.len(), projects.len()), (10, 10));
+    assert_eq!(demo[0].pane.id, "demo:1.0");
+}
+
+#[test]
+fn every_state_comes_up_over_a_few_minutes() {
+    let start = Instant::now();
+    let mut seen = HashSet::new();
+    for second in 0..600 {
+        for session in sessions(5, start, start + Duration::from_secs(second)) {
+            seen.insert(state_key(&session.state));
+            match session.state {
+                ClaudeState::WaitingForApproval => assert!(session.pending.is_some()),
+                ClaudeState::Error => assert!(session.error.is_some()),
+                ClaudeState::Blocked => assert!(session.input_prompt.is_some()),
+                _ => {}
+            }
+        }
+    }
+    for key in ["running", "compacting", "approval", "answer", "blocked", "idle", "error"] {
+        assert!(seen.contains(key), "{key} never came up");
+    }
+}
+
+#[test]
+fn states_hold_until_their_step_ends() {
+    let start = Instant::now();
+    let now = start + Duration::from_secs(95);
+    let a = sessions(3, start, now);
+    let b = sessions(3, start, now);
+    for (a, b) in a.iter().zip(&b) {
+        assert_eq!(a.state, b.state);
+        assert_eq!(a.state_changed_at, b.state_changed_at);
+        assert!(a.state_changed_at <= now && a.state_changed_at >= start);
+    }
+}
@@ -0,0 +1,54 @@
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+use claudeye::api::ApiConfig;
+use claudeye::config::Config;
+use claudeye::discovery::{txt_properties, Instance};
+
+fn instance(addresses: &[&str], tls: bool) -> Instance {
+    Instance {
+        name: "laptop".to_string(),
+        addresses: addresses.iter().map(|a| a.parse::<IpAddr>().unwrap()).collect(),
+        port: 7890,
+        tls,
+        read_only: false,
+    }
+}
+
+#[test]
+fn url_prefers_ipv4() {
+    let i = instance(&["fe80::1", "192.168.1.5"], false);
+    assert_eq!(i.url().as_deref(), Some("http://192.168.1.5:7890"));
+}
+
+#[test]
+fn url_uses_https_with_tls_and_brackets_ipv6() {
+    let i = instance(&["fd00::5"], true);
+    assert_eq!(i.url().as_deref(), Some("https://[fd00::5]:7890"));
+}
+
+#[test]
+fn url_needs_an_address() {
+    assert_eq!(instance(&[], false).url(), None);
+}
+
+#[test]
+fn txt_record_flags_tls_and_read_only_but_not_token() {
+    let config = ApiConfig {
+        token: Some("secret".to_string()),
+        tls_cert: Some(PathBuf::from("/c.pem")),
+        tls_key: Some(PathBuf::from("/k.pem")),
+        read_only: true,
+        ..ApiConfig::default()
+    };
+    let txt = txt_properties(&config);
+    assert!(txt.contains(&("tls", "1".to_string())));
+    assert!(txt.contains(&("ro", "1".to_string())));
+    assert!(txt.iter().all(|(_, value)| value != "secret"));
+}
+
+#[test]
+fn advertise_defaults_on() {
+    assert!(Config::parse("").unwrap().api.advertise);
+    assert!(!Config::parse("[api]\nadvertise = false\n").unwrap().api.advertise);
+}
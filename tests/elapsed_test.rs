@@ -0,0 +1,62 @@
+use claudeye::config::Config;
+use claudeye::elapsed::{compact, ElapsedFormat};
+
+#[test]
+fn default_always_shows_seconds() {
+    let format = ElapsedFormat::default();
+    assert_eq!(format.format(0).as_deref(), Some("0s"));
+    assert_eq!(format.format(4000).as_deref(), Some("4000s"));
+}
+
+#[test]
+fn hides_elapsed_below_threshold() {
+    let format = ElapsedFormat { hide_below_secs: 5, ..Default::default() };
+    assert_eq!(format.format(4), None);
+    assert_eq!(format.format(5).as_deref(), Some("5s"));
+}
+
+#[test]
+fn switches_to_minutes_and_hours() {
+    let format = ElapsedFormat {
+        minutes_from_secs: Some(60),
+        hours_from_secs: Some(3600),
+        ..Default::default()
+    };
+    assert_eq!(format.format(59).as_deref(), Some("59s"));
+    assert_eq!(format.format(125).as_deref(), Some("2m"));
+    assert_eq!(format.format(3 * 3600 + 12 * 60 + 5).as_deref(), Some("3h 12m"));
+}
+
+#[test]
+fn ticks_every_second_only_while_seconds_are_visible() {
+    let format = ElapsedFormat {
+        hide_below_secs: 5,
+        minutes_from_secs: Some(60),
+        ..Default::default()
+    };
+    assert!(!format.ticks_every_second(2));
+    assert!(format.ticks_every_second(30));
+    assert!(!format.ticks_every_second(90));
+}
+
+#[test]
+fn parses_elapsed_table_from_config() {
+    let config = Config::parse(
+        r#"
+[elapsed]
+hide_below_secs = 5
+minutes_from_secs = 120
+"#,
+    )
+    .unwrap();
+    assert_eq!(config.elapsed.hide_below_secs, 5);
+    assert_eq!(config.elapsed.minutes_from_secs, Some(120));
+    assert_eq!(config.elapsed.hours_from_secs, None);
+}
+
+#[test]
+fn compact_duration_picks_largest_unit() {
+    assert_eq!(compact(42), "42s");
+    assert_eq!(compact(42 * 60 + 10), "42m");
+    assert_eq!(compact(3 * 3600 + 5 * 60), "3h 5m");
+}
@@ -0,0 +1,79 @@
+use std::process::Command;
+
+use claudeye::env_facts::{collect, parse_version, runtimes, EnvFacts};
+use claudeye::git::porcelain_count;
+
+#[test]
+fn runtimes_follow_marker_files() {
+    let dir = std::env::temp_dir().join(format!("claudeye_test_runtimes_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    assert_eq!(runtimes(&dir), (false, false));
+    std::fs::write(dir.join("package.json"), "{}").unwrap();
+    assert_eq!(runtimes(&dir), (true, false));
+    std::fs::write(dir.join("pyproject.toml"), "").unwrap();
+    assert_eq!(runtimes(&dir), (true, true));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn versions_are_parsed() {
+    assert_eq!(parse_version("v20.11.0\n").as_deref(), Some("v20.11.0"));
+    assert_eq!(parse_version("Python 3.12.1\n").as_deref(), Some("3.12.1"));
+    assert_eq!(parse_version("  \n"), None);
+}
+
+#[test]
+fn porcelain_entries_are_counted() {
+    assert_eq!(porcelain_count(""), 0);
+    assert_eq!(porcelain_count(" M src/main.rs\n?? notes.txt\n"), 2);
+}
+
+#[test]
+fn uncommitted_changes_need_a_dirty_repo() {
+    assert!(!EnvFacts::default().has_uncommitted_changes());
+    assert!(!EnvFacts { dirty_files: Some(0), ..Default::default() }.has_uncommitted_changes());
+    assert!(EnvFacts { dirty_files: Some(2), ..Default::default() }.has_uncommitted_changes());
+}
+
+#[test]
+fn collect_counts_dirty_files() {
+    let dir = std::env::temp_dir().join(format!("claudeye_test_env_facts_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let cwd = dir.to_str().unwrap();
+    assert_eq!(collect(cwd).dirty_files, None);
+
+    let repo = dir.join("repo");
+    std::fs::create_dir_all(&repo).unwrap();
+    let git_ok = Command::new("git").args(["init", "-q"]).current_dir(&repo).status().is_ok_and(|s| s.success());
+    if git_ok {
+        std::fs::write(repo.join("a.txt"), "a").unwrap();
+        assert_eq!(collect(repo.to_str().unwrap()).dirty_files, Some(1));
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn discarding_git_operations_are_recognized() {
+    use claudeye::git::discarding_operation;
+    assert_eq!(discarding_operation("git checkout main"), Some("checkout"));
+    assert_eq!(discarding_operation("git -C ../api reset --hard HEAD~1"), Some("reset"));
+    assert_eq!(discarding_operation("cargo fmt && git rebase -i origin/main"), Some("rebase"));
+    assert_eq!(discarding_operation("git status"), None);
+    assert_eq!(discarding_operation("git log --grep reset"), None);
+}
+
+#[test]
+fn branches_are_read_from_head() {
+    use claudeye::git::{branch, parse_head};
+    assert_eq!(parse_head("ref: refs/heads/feature/search\n").as_deref(), Some("feature/search"));
+    assert_eq!(parse_head("3f2c9e1d0b7a6c5e4f3a2b1c0d9e8f7a6b5c4d3e\n").as_deref(), Some("3f2c9e1"));
+    assert_eq!(parse_head(""), None);
+
+    let dir = std::env::temp_dir().join(format!("claudeye_test_branch_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join(".git")).unwrap();
+    std::fs::create_dir_all(dir.join("src/handlers")).unwrap();
+    std::fs::write(dir.join(".git/HEAD"), "ref: refs/heads/main\n").unwrap();
+    let nested = dir.join("src/handlers");
+    assert_eq!(branch(&nested.to_string_lossy()).as_deref(), Some("main"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
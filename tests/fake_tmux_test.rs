@@ -0,0 +1,228 @@
+//! End-to-end runs of the claudeye binary against `fixtures/fake-tmux`, a
+//! shell script standing in for a tmux server.
+
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::time::{Duration, Instant};
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// A scratch home with a fake tmux server in it, removed on drop.
+struct FakeTmux {
+    dir: PathBuf,
+}
+
+impl FakeTmux {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("claudeye_test_fake_tmux_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("server/capture")).unwrap();
+        std::fs::create_dir_all(dir.join("config/claudeye")).unwrap();
+        FakeTmux { dir }
+    }
+
+    fn server(&self) -> PathBuf {
+        self.dir.join("server")
+    }
+
+    /// Open a pane running claude in `project`, showing the state fixture
+    /// `capture` (`approval/bash-dialog`).
+    fn pane(&self, id: &str, project: &str, capture: &str) {
+        let cwd = self.dir.join(project);
+        std::fs::create_dir_all(&cwd).unwrap();
+        let line = format!("{id} 0 {} claude\t\tzsh\tfakehost\n", cwd.display());
+        let panes = self.server().join("panes");
+        let mut text = std::fs::read_to_string(&panes).unwrap_or_default();
+        text.push_str(&line);
+        std::fs::write(&panes, text).unwrap();
+        self.show(id, capture);
+    }
+
+    fn show(&self, id: &str, capture: &str) {
+        let path = fixtures().join("states").join(format!("{capture}.txt"));
+        std::fs::copy(path, self.server().join("capture").join(id)).unwrap();
+    }
+
+    fn config(&self, text: &str) {
+        std::fs::write(self.dir.join("config/claudeye/config.toml"), text).unwrap();
+    }
+
+    /// `claudeye` with its home, config, and state inside the scratch
+    /// directory, talking to the fake server.
+    fn command(&self, args: &[&str]) -> Command {
+        let mut command = Command::new(env!("CARGO_BIN_EXE_claudeye"));
+        command
+            .args(args)
+            .env("CLAUDEYE_TMUX", fixtures().join("fake-tmux/tmux"))
+            .env("FAKE_TMUX_DIR", self.server())
+            .env("HOME", &self.dir)
+            .env("XDG_CONFIG_HOME", self.dir.join("config"))
+            .env("XDG_STATE_HOME", self.dir.join("state"))
+            .env("NO_COLOR", "1");
+        command
+    }
+
+    fn run(&self, args: &[&str]) -> Output {
+        self.command(args).output().unwrap()
+    }
+
+    fn log(&self) -> String {
+        std::fs::read_to_string(self.server().join("log")).unwrap_or_default()
+    }
+}
+
+impl Drop for FakeTmux {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+#[test]
+fn panes_are_listed_and_captured() {
+    let tmux = FakeTmux::new("list");
+    tmux.pane("main:0.1", "api", "approval/bash-dialog");
+    tmux.pane("main:0.2", "web", "running/clauding");
+    let out = tmux.run(&["list", "--format", "{pane} {project} {state}"]);
+    assert!(out.status.success(), "{out:?}");
+    assert_eq!(stdout(&out), "main:0.1 api approval\nmain:0.2 web running\n");
+}
+
+#[test]
+fn many_panes_are_captured_together_and_kept_apart() {
+    let tmux = FakeTmux::new("many");
+    let mut expected = String::new();
+    for i in 0..12 {
+        let (capture, state) = if i % 3 == 0 { ("approval/bash-dialog", "approval") } else { ("running/clauding", "running") };
+        tmux.pane(&format!("main:{i}.0"), &format!("p{i}"), capture);
+        expected.push_str(&format!("main:{i}.0 {state}\n"));
+    }
+    let out = tmux.run(&["list", "--format", "{pane} {state}"]);
+    assert!(out.status.success(), "{out:?}");
+    assert_eq!(stdout(&out), expected);
+}
+
+#[test]
+fn excluded_panes_are_not_listed() {
+    let tmux = FakeTmux::new("exclude");
+    tmux.pane("main:0.1", "api", "approval/bash-dialog");
+    tmux.pane("main:0.2", "web", "running/clauding");
+    tmux.pane("scratch1:0.0", "tmp", "idle/prompt-only");
+    tmux.config("[[monitor.exclude]]\nsession = \"scratch*\"\n\n[[monitor.exclude]]\npath = \"~/web\"\n");
+    let out = tmux.run(&["list", "--format", "{pane}"]);
+    assert!(out.status.success(), "{out:?}");
+    assert_eq!(stdout(&out), "main:0.1\n");
+}
+
+#[test]
+fn status_exits_with_the_most_pressing_state() {
+    let tmux = FakeTmux::new("status");
+    tmux.pane("main:0.1", "api", "running/clauding");
+    assert_eq!(tmux.run(&["status", "-q"]).status.code(), Some(1));
+    tmux.pane("main:0.2", "web", "approval/bash-dialog");
+    assert_eq!(tmux.run(&["status", "-q"]).status.code(), Some(2));
+}
+
+#[test]
+fn jumps_zoom_the_pane_when_asked() {
+    let tmux = FakeTmux::new("zoom");
+    tmux.pane("main:0.1", "api", "running/clauding");
+    assert!(tmux.run(&["--zoom", "switch", "main:0.1"]).status.success());
+    tmux.config("zoom_on_jump = true\n");
+    assert!(tmux.run(&["switch", "main:0.1"]).status.success());
+    assert_eq!(tmux.log().matches("resize-pane -Z -t main:0.1\n").count(), 2);
+}
+
+#[test]
+fn no_server_means_no_sessions() {
+    let tmux = FakeTmux::new("no_server");
+    let out = tmux.run(&["status", "--query", "count"]);
+    assert!(out.status.success(), "{out:?}");
+    assert_eq!(stdout(&out), "0\n");
+}
+
+#[test]
+fn actions_send_keys_and_switch_clients() {
+    let tmux = FakeTmux::new("actions");
+    tmux.pane("main:0.1", "api", "approval/bash-dialog");
+    tmux.pane("main:0.2", "web", "running/clauding");
+    assert!(tmux.run(&["approve", "main:0.1"]).status.success());
+    assert!(!tmux.run(&["approve", "main:0.2"]).status.success());
+    assert!(tmux.run(&["interrupt", "--yes", "main:0.2"]).status.success());
+    assert!(tmux.run(&["switch", "main:0.2"]).status.success());
+    assert!(!tmux.run(&["switch", "main:9.9"]).status.success());
+    assert_eq!(
+        tmux.log(),
+        "send-keys -t main:0.1 Enter\nsend-keys -t main:0.2 Escape\n\
+         select-window -t main:0.2\nselect-pane -t main:0.2\nswitch-client -t main:0.2\n"
+    );
+}
+
+/// Wait up to 20 seconds for `done`.
+fn eventually(mut done: impl FnMut() -> bool) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(20);
+    while Instant::now() < deadline {
+        if done() {
+            return true;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    false
+}
+
+fn free_port() -> u16 {
+    std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+#[test]
+fn windows_are_renamed_after_the_state_and_restored() {
+    let tmux = FakeTmux::new("rename");
+    tmux.pane("main:0.1", "api", "running/clauding");
+    tmux.config("tmux_rename = \"window\"\n");
+    let mut serve = tmux
+        .command(&["serve", "--bind", &format!("127.0.0.1:{}", free_port())])
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let renamed = eventually(|| tmux.log().contains("rename-window -t main:0.1 api ⏳\n"));
+    std::fs::write(tmux.server().join("panes"), "").unwrap();
+    let restored = renamed && eventually(|| tmux.log().contains("set-option -w -t main:0.1 automatic-rename on\n"));
+    let _ = serve.kill();
+    let _ = serve.wait();
+    assert!(renamed, "{}", tmux.log());
+    assert!(restored, "{}", tmux.log());
+}
+
+#[test]
+fn monitor_loop_follows_state_changes() {
+    let tmux = FakeTmux::new("monitor");
+    tmux.pane("main:0.1", "api", "running/clauding");
+    // Through the config this time, as a user with tmux off PATH would.
+    tmux.config(&format!(
+        "status_file = true\n[monitor]\ntmux_path = \"{}\"\n",
+        fixtures().join("fake-tmux/tmux").display()
+    ));
+    let mut serve = tmux
+        .command(&["serve", "--bind", &format!("127.0.0.1:{}", free_port())])
+        .env_remove("CLAUDEYE_TMUX")
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let status = tmux.dir.join("state/claudeye/status.json");
+    let wait_for = |state: &str| {
+        let needle = format!("\"state\":\"{state}\"");
+        eventually(|| std::fs::read_to_string(&status).is_ok_and(|text| text.contains(&needle)))
+    };
+    let running = wait_for("running");
+    tmux.show("main:0.1", "approval/bash-dialog");
+    let approval = running && wait_for("approval");
+    let _ = serve.kill();
+    let _ = serve.wait();
+    assert!(running, "never saw the running session");
+    assert!(approval, "never saw the session wait for approval");
+}
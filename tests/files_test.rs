@@ -0,0 +1,43 @@
+use claudeye::files::{is_outside, merge_touched, touched_files, MAX_TOUCHED_FILES};
+
+#[test]
+fn touched_files_extracts_edit_and_write_calls() {
+    let content = "\
+⏺ Read(src/lib.rs)
+  ⎿  Read 120 lines
+⏺ Update(src/main.rs)
+  ⎿  Updated src/main.rs with 3 additions
+⏺ Write(tests/new_test.rs)
+● Edit(README.md)
+⏺ Bash(cargo test)
+";
+    assert_eq!(touched_files(content), ["src/main.rs", "tests/new_test.rs", "README.md"]);
+}
+
+#[test]
+fn touched_files_lists_repeated_file_at_latest_position() {
+    let content = "⏺ Update(a.rs)\n⏺ Update(b.rs)\n⏺ Update(a.rs)\n";
+    assert_eq!(touched_files(content), ["b.rs", "a.rs"]);
+}
+
+#[test]
+fn merge_touched_moves_seen_files_to_the_end_and_caps() {
+    let prev = vec!["a.rs".to_string(), "b.rs".to_string()];
+    assert_eq!(merge_touched(&prev, &["a.rs".to_string()]), ["b.rs", "a.rs"]);
+
+    let many: Vec<String> = (0..MAX_TOUCHED_FILES + 5).map(|i| format!("{i}.rs")).collect();
+    let merged = merge_touched(&prev, &many);
+    assert_eq!(merged.len(), MAX_TOUCHED_FILES);
+    assert_eq!(merged.last().unwrap(), &format!("{}.rs", MAX_TOUCHED_FILES + 4));
+}
+
+#[test]
+fn is_outside_detects_paths_leaving_the_project() {
+    let cwd = "/home/me/app";
+    assert!(!is_outside(cwd, "src/main.rs"));
+    assert!(!is_outside(cwd, "/home/me/app/src/main.rs"));
+    assert!(!is_outside(cwd, "./docs/../README.md"));
+    assert!(is_outside(cwd, "../other/lib.rs"));
+    assert!(is_outside(cwd, "/etc/hosts"));
+    assert!(is_outside(cwd, "/home/me/application/x"));
+}
@@ -0,0 +1,46 @@
+use claudeye::filter::{glob_match, is_monitored, path_matches, PaneRule};
+use claudeye::tmux::PaneInfo;
+
+fn pane(id: &str, cwd: &str) -> PaneInfo {
+    PaneInfo {
+        id: id.to_string(),
+        pid: 0,
+        cwd: cwd.to_string(),
+        project_name: String::new(),
+        session_name: id.split(':').next().unwrap().to_string(),
+        window_name: String::new(),
+    }
+}
+
+#[test]
+fn globs_keep_single_stars_within_a_segment() {
+    assert!(glob_match("scratch*", "scratch-2"));
+    assert!(glob_match("main:?.*", "main:0.1"));
+    assert!(!glob_match("/src/*", "/src/api/web"));
+    assert!(glob_match("/src/**", "/src/api/web"));
+    assert!(glob_match("/src/*/web", "/src/api/web"));
+    assert!(!glob_match("scratch", "scratch-2"));
+}
+
+#[test]
+fn paths_cover_the_directories_below() {
+    assert!(path_matches("/src/noisy", "/src/noisy"));
+    assert!(path_matches("/src/noisy/", "/src/noisy/sub"));
+    assert!(!path_matches("/src/noisy", "/src/noisy-other"));
+    assert!(path_matches("/src/*-tmp", "/src/api-tmp/x"));
+}
+
+#[test]
+fn include_narrows_and_exclude_wins() {
+    let api = pane("main:0.1", "/src/api");
+    let scratch = pane("scratch:0.0", "/src/api");
+    let exclude = [PaneRule { session: Some("scratch*".to_string()), ..PaneRule::default() }];
+    assert!(is_monitored(&api, &[], &exclude));
+    assert!(!is_monitored(&scratch, &[], &exclude));
+
+    let include = [PaneRule { path: Some("/src/web".to_string()), ..PaneRule::default() }];
+    assert!(!is_monitored(&api, &include, &[]));
+    let both = [PaneRule { path: Some("/src/*".to_string()), pane: Some("main:*".to_string()), ..PaneRule::default() }];
+    assert!(is_monitored(&api, &both, &[]));
+    assert!(!is_monitored(&scratch, &both, &[]));
+}
@@ -0,0 +1,32 @@
+use std::path::{Path, PathBuf};
+
+use claudeye::fixtures;
+
+fn corpus() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/states")
+}
+
+// Drop a capture (`tmux capture-pane -p -t <pane> > <state>/<name>.txt`)
+// into the corpus to turn a misdetection into a regression test.
+#[test]
+fn captured_panes_are_detected_as_their_directory_says() {
+    let fixtures = fixtures::load(&corpus()).unwrap();
+    assert!(fixtures.len() >= 10);
+    let mismatches: Vec<String> = fixtures.iter().filter_map(fixtures::check).map(|m| m.to_string()).collect();
+    assert!(mismatches.is_empty(), "\n{}", mismatches.join("\n\n"));
+}
+
+#[test]
+fn mismatches_name_the_file_and_the_deciding_rule() {
+    let dir = std::env::temp_dir().join(format!("claudeye_test_fixtures_{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("approval")).unwrap();
+    std::fs::write(dir.join("approval/prompt.txt"), "Some output\n❯\n").unwrap();
+    std::fs::write(dir.join("approval/notes.md"), "not a capture").unwrap();
+
+    let mismatches = fixtures::check_dir(&dir).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(mismatches.len(), 1);
+    let report = mismatches[0].to_string();
+    assert!(report.contains("prompt.txt: expected approval, detected idle"), "{report}");
+    assert!(report.contains("rule: "), "{report}");
+}
@@ -0,0 +1,131 @@
+use claudeye::config::Config;
+use std::time::Duration;
+
+use claudeye::claude_state::ClaudeState;
+use claudeye::forge::{finish_alert, finish_comment, parse_gh_pr, parse_glab_mr, ChecksStatus, OnFinish, PullRequest};
+use claudeye::monitor::Transition;
+use claudeye::tmux::PaneInfo;
+
+#[test]
+fn gh_pr_with_passing_checks() {
+    let json = r#"{"number":42,"url":"https://github.com/o/r/pull/42","state":"OPEN",
+        "statusCheckRollup":[
+            {"__typename":"CheckRun","status":"COMPLETED","conclusion":"SUCCESS"},
+            {"__typename":"StatusContext","state":"SUCCESS"}]}"#;
+    let pr = parse_gh_pr(json).unwrap();
+    assert_eq!(pr.number, 42);
+    assert_eq!(pr.url, "https://github.com/o/r/pull/42");
+    assert_eq!(pr.checks, ChecksStatus::Passing);
+    assert_eq!(pr.label(), "#42 ✓");
+}
+
+#[test]
+fn gh_pr_failure_outranks_pending() {
+    let json = r#"{"number":7,"url":"u","state":"OPEN","statusCheckRollup":[
+        {"status":"IN_PROGRESS","conclusion":""},
+        {"status":"COMPLETED","conclusion":"FAILURE"}]}"#;
+    assert_eq!(parse_gh_pr(json).unwrap().checks, ChecksStatus::Failing);
+}
+
+#[test]
+fn gh_pr_in_progress_check_is_pending() {
+    let json = r#"{"number":7,"url":"u","state":"OPEN","statusCheckRollup":[
+        {"status":"COMPLETED","conclusion":"SUCCESS"},
+        {"status":"QUEUED","conclusion":""}]}"#;
+    assert_eq!(parse_gh_pr(json).unwrap().checks, ChecksStatus::Pending);
+}
+
+#[test]
+fn gh_pr_without_checks_has_plain_label() {
+    let json = r#"{"number":7,"url":"u","state":"OPEN","statusCheckRollup":[]}"#;
+    let pr = parse_gh_pr(json).unwrap();
+    assert_eq!(pr.checks, ChecksStatus::None);
+    assert_eq!(pr.label(), "#7");
+}
+
+#[test]
+fn merged_pr_is_ignored() {
+    let json = r#"{"number":7,"url":"u","state":"MERGED","statusCheckRollup":[]}"#;
+    assert_eq!(parse_gh_pr(json), None);
+    assert_eq!(parse_gh_pr("no pull requests found"), None);
+}
+
+#[test]
+fn glab_mr_uses_head_pipeline_status() {
+    let json = r#"{"iid":15,"web_url":"https://gitlab.com/o/r/-/merge_requests/15",
+        "state":"opened","head_pipeline":{"status":"running"}}"#;
+    assert_eq!(
+        parse_glab_mr(json),
+        Some(PullRequest {
+            number: 15,
+            url: "https://gitlab.com/o/r/-/merge_requests/15".to_string(),
+            checks: ChecksStatus::Pending,
+        })
+    );
+}
+
+#[test]
+fn glab_mr_without_pipeline_and_closed_mr() {
+    let open = r#"{"iid":3,"web_url":"u","state":"opened","head_pipeline":null,"pipeline":{"status":"failed"}}"#;
+    assert_eq!(parse_glab_mr(open).unwrap().checks, ChecksStatus::Failing);
+    let closed = r#"{"iid":3,"web_url":"u","state":"merged"}"#;
+    assert_eq!(parse_glab_mr(closed), None);
+}
+
+#[test]
+fn pull_requests_are_disabled_by_default() {
+    let config = Config::parse("").unwrap();
+    assert!(!config.pull_requests.enabled);
+    assert_eq!(config.pull_requests.refresh_secs, 120);
+    let config = Config::parse("[pull_requests]\nenabled = true\nrefresh_secs = 30").unwrap();
+    assert!(config.pull_requests.enabled);
+    assert_eq!(config.pull_requests.refresh_secs, 30);
+}
+
+#[test]
+fn on_finish_defaults_to_off() {
+    assert_eq!(Config::parse("").unwrap().pull_requests.on_finish, OnFinish::Off);
+    let config = Config::parse("[pull_requests]\non_finish = \"comment\"").unwrap();
+    assert_eq!(config.pull_requests.on_finish, OnFinish::Comment);
+    assert!(Config::parse("[pull_requests]\non_finish = \"email\"").is_err());
+}
+
+#[test]
+fn finish_comment_names_project_and_duration() {
+    let transition = Transition {
+        pane: PaneInfo {
+            id: "main:0.1".to_string(),
+            pid: 1,
+            cwd: "/tmp/api".to_string(),
+            project_name: "api".to_string(),
+            session_name: String::new(),
+            window_name: String::new(),
+        },
+        from: ClaudeState::Working,
+        to: ClaudeState::Idle,
+        elapsed: Duration::from_secs(12 * 60),
+        approval: None,
+    };
+    assert_eq!(
+        finish_comment(&transition),
+        "Claude session in `api` finished after 12m (posted by claudeye)."
+    );
+}
+
+#[test]
+fn finish_alert_names_the_pane_and_pull_request() {
+    let transition = Transition {
+        pane: PaneInfo { id: "main:0.1".to_string(), project_name: "api".to_string(), ..PaneInfo::default() },
+        from: ClaudeState::Working,
+        to: ClaudeState::Idle,
+        elapsed: Duration::from_secs(60),
+        approval: None,
+    };
+    let pr = PullRequest { number: 7, url: "https://github.com/o/r/pull/7".to_string(), checks: ChecksStatus::Passing };
+    let alert = finish_alert(&transition, &pr);
+    assert_eq!(alert.pane.as_deref(), Some("main:0.1"));
+    assert_eq!(alert.kind, "pr_finished");
+    assert_eq!(alert.title, "api finished");
+    assert_eq!(alert.body, "#7 ✓ https://github.com/o/r/pull/7");
+    assert!(!alert.urgent);
+}
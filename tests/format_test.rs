@@ -0,0 +1,59 @@
+use claudeye::format::{fill, table, yaml, Format};
+use serde_json::json;
+
+fn rows() -> Vec<serde_json::Value> {
+    vec![
+        json!({"pane": "main:0.1", "project": "api", "state": "approval", "elapsed": 5}),
+        json!({"pane": "%12", "project": "web", "state": "running", "elapsed": 120}),
+    ]
+}
+
+#[test]
+fn formats_parse_by_name_or_as_templates() {
+    assert_eq!("yaml".parse(), Ok(Format::Yaml));
+    assert_eq!("{pane}".parse(), Ok(Format::Template("{pane}".to_string())));
+    assert!("xml".parse::<Format>().is_err());
+}
+
+#[test]
+fn yaml_nests_blocks_and_quotes_ambiguous_strings() {
+    let doc = json!({
+        "counts": {"running": 1},
+        "sessions": [{"pane": "%12", "watch": ["deploy"], "error": null, "unread": "yes"}],
+        "empty": [],
+    });
+    assert_eq!(
+        yaml(&doc),
+        "counts:\n  running: 1\nsessions:\n  - pane: \"%12\"\n    watch:\n      - deploy\n    error: null\n    unread: \"yes\"\nempty: []\n"
+    );
+    assert_eq!(yaml(&json!("main:0.1")), "main:0.1\n");
+    assert_eq!(yaml(&json!("a: b")), "\"a: b\"\n");
+    assert_eq!(yaml(&json!([[1, 2]])), "- - 1\n  - 2\n");
+}
+
+#[test]
+fn tables_align_columns_and_right_align_numbers() {
+    assert_eq!(
+        table(&rows()),
+        "pane      project  state     elapsed\n\
+         main:0.1  api      approval        5\n\
+         %12       web      running       120\n"
+    );
+    assert_eq!(table(&[]), "");
+}
+
+#[test]
+fn templates_fill_fields_and_reject_unknown_ones() {
+    assert_eq!(fill("{pane}\t{state} {{x}}", &rows()[0]).unwrap(), "main:0.1\tapproval {x}");
+    assert_eq!(fill("{watch}", &json!({"watch": ["a", "b"], "x": null})).unwrap(), "a, b");
+    let err = fill("{status}", &rows()[0]).unwrap_err();
+    assert!(err.contains("`status`") && err.contains("pane, project"), "{err}");
+}
+
+#[test]
+fn plain_is_left_to_the_subcommand() {
+    let out = Format::Plain.render(&json!([]), &rows(), || "own\n".to_string()).unwrap();
+    assert_eq!(out, "own\n");
+    let out = Format::Template("{project}".to_string()).render(&json!([]), &rows(), String::new).unwrap();
+    assert_eq!(out, "api\nweb\n");
+}
@@ -0,0 +1,88 @@
+use std::time::Instant;
+
+use claudeye::claude_state::ClaudeState;
+use claudeye::config::Config;
+use claudeye::guardrail::{check, new_hits, pending_prompt, Guardrail, Target};
+use claudeye::monitor::ClaudeSession;
+use claudeye::tmux::PaneInfo;
+
+fn rules() -> Vec<Guardrail> {
+    Config::parse(
+        r#"
+[[guardrail]]
+name = "rm -rf"
+pattern = "rm -rf"
+target = "command"
+
+[[guardrail]]
+name = "ssh keys"
+pattern = "\\.ssh/"
+"#,
+    )
+    .unwrap()
+    .guardrail
+}
+
+const PROMPT: &str = "\
+⏺ Bash(rm -rf build)
+ Do you want to proceed?
+ ❯ 1. Yes
+   2. No
+";
+
+#[test]
+fn target_defaults_to_any() {
+    let rules = rules();
+    assert_eq!(rules[0].target, Target::Command);
+    assert_eq!(rules[1].target, Target::Any);
+}
+
+#[test]
+fn command_rule_matches_pending_prompt() {
+    assert_eq!(check(&rules(), &ClaudeState::WaitingForApproval, PROMPT, &[]), ["rm -rf"]);
+}
+
+#[test]
+fn command_rule_ignored_when_not_waiting_for_approval() {
+    assert!(check(&rules(), &ClaudeState::Idle, PROMPT, &[]).is_empty());
+}
+
+#[test]
+fn path_rule_matches_touched_files_in_any_state() {
+    let touched = vec!["src/main.rs".to_string(), "/home/me/.ssh/config".to_string()];
+    assert_eq!(check(&rules(), &ClaudeState::Working, "", &touched), ["ssh keys"]);
+}
+
+#[test]
+fn command_only_rule_ignores_paths() {
+    let touched = vec!["scripts/rm -rf.sh".to_string()];
+    assert!(check(&rules(), &ClaudeState::Working, "", &touched).is_empty());
+}
+
+#[test]
+fn pending_prompt_keeps_only_the_bottom_of_the_pane() {
+    let content = format!("rm -rf old\n{}{PROMPT}", "filler\n".repeat(40));
+    assert!(!pending_prompt(&content).contains("rm -rf old"));
+    assert!(pending_prompt(&content).contains("Bash(rm -rf build)"));
+}
+
+#[test]
+fn new_hits_reports_hits_not_seen_before() {
+    let pane = PaneInfo {
+        id: "main:0.1".to_string(),
+        pid: 1,
+        cwd: "/tmp/app".to_string(),
+        project_name: "app".to_string(),
+        session_name: String::new(),
+        window_name: String::new(),
+    };
+    let prev = ClaudeSession::new(pane, ClaudeState::WaitingForApproval, Instant::now());
+    let updated = ClaudeSession {
+        guardrail_hits: vec!["rm -rf".to_string()],
+        ..prev.clone()
+    };
+    let hits = new_hits(std::slice::from_ref(&prev), std::slice::from_ref(&updated));
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].1, "rm -rf");
+    assert!(new_hits(std::slice::from_ref(&updated), std::slice::from_ref(&updated)).is_empty());
+}
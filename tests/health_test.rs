@@ -0,0 +1,83 @@
+use std::collections::BTreeMap;
+
+use claudeye::api::ApiConfig;
+use claudeye::elapsed::unix_now;
+use claudeye::health::{healthz_url, lost, parse_response, record_restart, record_source, restarted_recently, Health, SourceStatus, LOST_AFTER_SECS, RESTART_NOTICE_SECS, STALE_AFTER_SECS};
+
+fn tmux(error: Option<&str>) -> BTreeMap<String, SourceStatus> {
+    BTreeMap::from([(
+        "tmux".to_string(),
+        SourceStatus { ok: error.is_none(), error: error.map(String::from) },
+    )])
+}
+
+#[test]
+fn fresh_poll_with_answering_sources_is_healthy() {
+    let health = Health::new(Some(1000), 3, tmux(None), 1002);
+    assert!(health.ok);
+    assert_eq!(health.poll_age_secs, Some(2));
+    assert_eq!(health.summary(), "ok: polled 2s ago, 3 sessions, tmux ok");
+}
+
+#[test]
+fn stale_or_missing_polls_are_unhealthy() {
+    assert!(!Health::new(Some(1000), 0, tmux(None), 1000 + STALE_AFTER_SECS + 1).ok);
+    let starting = Health::new(None, 0, BTreeMap::new(), 1000);
+    assert!(!starting.ok);
+    assert_eq!(starting.summary(), "unhealthy: not polled yet, 0 sessions");
+}
+
+#[test]
+fn failing_source_is_unhealthy() {
+    let health = Health::new(Some(1000), 0, tmux(Some("no server running")), 1000);
+    assert!(!health.ok);
+    assert_eq!(health.summary(), "unhealthy: polled 0s ago, 0 sessions, tmux: no server running");
+}
+
+#[test]
+fn restarts_are_noted_for_a_while() {
+    let now = unix_now();
+    record_restart(now);
+    assert!(restarted_recently(now + 5));
+    assert!(!restarted_recently(now + RESTART_NOTICE_SECS));
+    assert!(Health::current().restarts >= 1);
+    let health = Health { restarts: 2, ..Health::new(Some(1000), 1, tmux(None), 1002) };
+    assert_eq!(health.summary(), "ok: polled 2s ago, 1 sessions, restarted 2 times, tmux ok");
+}
+
+#[test]
+fn response_round_trips_and_errors_surface() {
+    let health = Health::new(Some(1000), 1, tmux(None), 1005);
+    let body = serde_json::to_vec(&health).unwrap();
+    assert_eq!(parse_response(&body), Ok(health));
+    assert_eq!(parse_response(br#"{"error":"unauthorized"}"#), Err("unauthorized".to_string()));
+    assert!(parse_response(b"<html>").is_err());
+}
+
+#[test]
+fn healthz_url_follows_the_api_config() {
+    assert_eq!(healthz_url(&ApiConfig::default()).unwrap(), "http://127.0.0.1:7890/healthz");
+    let config = ApiConfig {
+        bind: "0.0.0.0:8443".to_string(),
+        tls_cert: Some("cert.pem".into()),
+        ..ApiConfig::default()
+    };
+    assert_eq!(healthz_url(&config).unwrap(), "https://127.0.0.1:8443/healthz");
+}
+
+#[test]
+fn source_is_lost_only_after_failing_for_a_while() {
+    let source = "watchdog-test";
+    let now = unix_now();
+    record_source(source, Err("no server running on /tmp/tmux-1000/default".to_string()));
+    assert_eq!(lost(source, now), None);
+    assert_eq!(
+        lost(source, now + LOST_AFTER_SECS).as_deref(),
+        Some("no server running on /tmp/tmux-1000/default")
+    );
+    // Another failure keeps the streak; one success ends it.
+    record_source(source, Err("still failing".to_string()));
+    assert_eq!(lost(source, now + LOST_AFTER_SECS).as_deref(), Some("still failing"));
+    record_source(source, Ok(()));
+    assert_eq!(lost(source, now + LOST_AFTER_SECS), None);
+}
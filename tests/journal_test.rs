@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use claudeye::claude_state::{ApprovalKind, ClaudeState};
+use claudeye::config::Config;
+use claudeye::journal::{append, entry};
+use claudeye::monitor::Transition;
+use claudeye::tmux::PaneInfo;
+
+fn transition() -> Transition {
+    Transition {
+        pane: PaneInfo {
+            id: "main:1.0".to_string(),
+            pid: 1,
+            cwd: "/src/api".to_string(),
+            project_name: "api".to_string(),
+            session_name: String::new(),
+            window_name: String::new(),
+        },
+        from: ClaudeState::Working,
+        to: ClaudeState::WaitingForApproval,
+        elapsed: Duration::from_secs(42),
+        approval: Some(ApprovalKind::BashCommand),
+    }
+}
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("claudeye_test_journal_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+#[test]
+fn entries_describe_the_transition() {
+    let entry = entry(&transition(), 1_760_000_000);
+    assert_eq!(entry["timestamp"], 1_760_000_000);
+    assert_eq!(entry["pane"], "main:1.0");
+    assert_eq!(entry["project"], "api");
+    assert_eq!(entry["from"], "running");
+    assert_eq!(entry["to"], "approval");
+    assert_eq!(entry["duration_secs"], 42);
+    assert_eq!(entry["approval"], "bash");
+}
+
+#[test]
+fn appends_one_line_per_transition() {
+    let dir = scratch("append");
+    let path = dir.join("transitions.jsonl");
+    append(&path, "{\"n\":1}", 1024, 2).unwrap();
+    append(&path, "{\"n\":2}", 1024, 2).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"n\":1}\n{\"n\":2}\n");
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn full_logs_are_rotated_keeping_the_newest() {
+    let dir = scratch("rotate");
+    let path = dir.join("transitions.jsonl");
+    for n in 1..=4 {
+        append(&path, &format!("line {n}"), 5, 2).unwrap();
+    }
+    let read = |name: &str| std::fs::read_to_string(dir.join(name)).unwrap();
+    assert_eq!(read("transitions.jsonl"), "line 4\n");
+    assert_eq!(read("transitions.jsonl.1"), "line 3\n");
+    assert_eq!(read("transitions.jsonl.2"), "line 2\n");
+    assert!(!dir.join("transitions.jsonl.3").exists());
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn transition_log_is_opt_in() {
+    assert!(!Config::default().transition_log.enabled);
+    let config = Config::parse("[transition_log]\nenabled = true\nkeep = 1\n").unwrap();
+    assert!(config.transition_log.enabled);
+    assert_eq!(config.transition_log.keep, 1);
+    assert_eq!(config.transition_log.max_mb, 10);
+}
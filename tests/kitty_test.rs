@@ -0,0 +1,66 @@
+use claudeye::kitty::{parse_ls, parse_window_id, window_id};
+
+const LS: &str = r#"[
+  {
+    "id": 1,
+    "is_focused": true,
+    "tabs": [
+      {
+        "id": 1,
+        "title": "backend",
+        "windows": [
+          {
+            "id": 3,
+            "title": "✳ Fix login redirect",
+            "pid": 4100,
+            "cwd": "/home/me/api",
+            "is_focused": true,
+            "foreground_processes": [
+              { "pid": 4242, "cmdline": ["/home/me/.local/bin/claude", "--resume"], "cwd": "/home/me/api/src" }
+            ]
+          },
+          {
+            "id": 4,
+            "title": "zsh",
+            "pid": 4300,
+            "cwd": "/home/me/web",
+            "foreground_processes": [{ "pid": 4300, "cmdline": ["-zsh"], "cwd": "" }]
+          }
+        ]
+      }
+    ]
+  }
+]"#;
+
+#[test]
+fn windows_are_read_from_kitty_ls() {
+    let windows = parse_ls(LS).unwrap();
+    assert_eq!(windows.len(), 2);
+    let (pane, command, labels) = &windows[0];
+    assert_eq!(pane.id, "kitty:3");
+    assert_eq!(pane.pid, 4100);
+    assert_eq!(command, "claude");
+    // The foreground process's directory wins over the window's.
+    assert_eq!(pane.cwd, "/home/me/api/src");
+    assert_eq!(pane.project_name, "src");
+    assert_eq!((pane.session_name.as_str(), pane.window_name.as_str()), ("kitty", "backend"));
+    assert_eq!(labels.title, "✳ Fix login redirect");
+    let (pane, command, _) = &windows[1];
+    assert_eq!(command, "zsh");
+    assert_eq!(pane.cwd, "/home/me/web");
+}
+
+#[test]
+fn malformed_output_is_an_error() {
+    assert!(parse_ls("Remote control is disabled").is_err());
+    assert_eq!(parse_ls("[]").unwrap().len(), 0);
+}
+
+#[test]
+fn kitty_ids_are_told_apart_from_tmux_ids() {
+    assert_eq!(window_id(12), "kitty:12");
+    assert_eq!(parse_window_id("kitty:12"), Some("12"));
+    assert_eq!(parse_window_id("kitty:1.0"), None);
+    assert_eq!(parse_window_id("main:0.1"), None);
+    assert_eq!(parse_window_id("kitty:"), None);
+}
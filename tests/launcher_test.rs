@@ -0,0 +1,59 @@
+use std::time::Instant;
+
+use claudeye::claude_state::ClaudeState;
+use claudeye::i18n::Language;
+use claudeye::launcher::{alfred, plain, raycast};
+use claudeye::monitor::ClaudeSession;
+
+mod common;
+
+fn session(id: &str, project: &str, state: ClaudeState) -> ClaudeSession {
+    ClaudeSession::new(common::pane(id, project), state, Instant::now())
+}
+
+fn sessions() -> Vec<ClaudeSession> {
+    let ended = ClaudeSession {
+        ended_at: Some(Instant::now()),
+        ..session("main:0.3", "old", ClaudeState::Idle)
+    };
+    vec![
+        session("main:0.1", "api", ClaudeState::WaitingForApproval),
+        session("main:0.2", "web", ClaudeState::Idle),
+        ended,
+    ]
+}
+
+#[test]
+fn plain_lists_live_sessions_tab_separated() {
+    assert_eq!(plain(&sessions(), false), "main:0.1\tapi\tapproval\t0\nmain:0.2\tweb\tidle\t0\n");
+}
+
+#[test]
+fn alfred_items_approve_pending_sessions_and_switch_others() {
+    let json = alfred(&sessions(), Language::En.strings());
+    let items = json["items"].as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0]["title"], "api");
+    assert_eq!(items[0]["subtitle"], "main:0.1 · Approval 0s");
+    assert_eq!(items[0]["variables"]["action"], "approve");
+    assert_eq!(items[0]["mods"]["cmd"]["variables"]["action"], "switch");
+    assert_eq!(items[1]["variables"]["action"], "switch");
+    assert_eq!(items[1]["variables"]["pane"], "main:0.2");
+}
+
+#[test]
+fn raycast_items_put_primary_action_first() {
+    let json = raycast(&sessions(), Language::En.strings(), "/bin/claudeye");
+    let items = json["items"].as_array().unwrap();
+    assert_eq!(items[0]["state"], "approval");
+    assert_eq!(items[0]["actions"][0]["command"], serde_json::json!(["/bin/claudeye", "approve", "main:0.1"]));
+    assert_eq!(items[0]["actions"][1]["title"], "Switch");
+    assert_eq!(items[1]["actions"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn plain_colors_states_on_request() {
+    let lines = plain(&sessions(), true);
+    assert!(lines.starts_with("main:0.1\tapi\t\x1b[33mapproval\x1b[0m\t0\n"));
+    assert!(lines.ends_with("\tidle\t0\n"));
+}
@@ -0,0 +1,121 @@
+use std::collections::HashSet;
+
+use claudeye::marks::{Marks, Snooze};
+
+#[test]
+fn first_observation_counts_as_read() {
+    let mut marks = Marks::default();
+    assert!(!marks.observe("main:0.1", 42, false));
+}
+
+#[test]
+fn new_content_while_unfocused_is_unread() {
+    let mut marks = Marks::default();
+    marks.observe("main:0.1", 1, false);
+    assert!(marks.observe("main:0.1", 2, false));
+    // Stays unread until the pane is focused
+    assert!(marks.observe("main:0.1", 2, false));
+}
+
+#[test]
+fn focusing_the_pane_marks_it_read() {
+    let mut marks = Marks::default();
+    marks.observe("main:0.1", 1, false);
+    assert!(!marks.observe("main:0.1", 2, true));
+    assert!(!marks.observe("main:0.1", 2, false));
+}
+
+#[test]
+fn mark_seen_clears_unread() {
+    let mut marks = Marks::default();
+    marks.observe("main:0.1", 1, false);
+    marks.mark_seen("main:0.1", 2);
+    assert!(!marks.observe("main:0.1", 2, false));
+}
+
+#[test]
+fn retain_panes_forgets_vanished_panes() {
+    let mut marks = Marks::default();
+    marks.observe("main:0.1", 1, false);
+    marks.observe("main:0.2", 1, false);
+    marks.retain_panes(&HashSet::from(["main:0.2"]));
+    assert!(marks.get("main:0.1").is_none());
+    assert!(marks.get("main:0.2").is_some());
+}
+
+#[test]
+fn marks_round_trip_through_file() {
+    let path = std::env::temp_dir().join(format!("claudeye_test_marks_{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut marks = Marks::default();
+    marks.observe("main:0.1", 7, false);
+    marks.save_to(&path).unwrap();
+
+    let loaded = Marks::load_from(&path);
+    assert_eq!(loaded.get("main:0.1").and_then(|m| m.seen_hash), Some(7));
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn snooze_until_change_ends_when_state_changes() {
+    let mut marks = Marks::default();
+    marks.snooze("main:0.1", Snooze::UntilChange("approval".to_string()));
+    assert!(marks.is_snoozed("main:0.1", "approval", 0));
+    assert!(!marks.is_snoozed("main:0.1", "running", 0));
+    // Cleared for good, even when the state comes back
+    assert!(!marks.is_snoozed("main:0.1", "approval", 0));
+}
+
+#[test]
+fn timed_snooze_ends_after_deadline() {
+    let mut marks = Marks::default();
+    marks.snooze("main:0.1", Snooze::Until(1000));
+    assert!(marks.is_snoozed("main:0.1", "approval", 999));
+    assert!(marks.is_snoozed("main:0.1", "idle", 999));
+    assert!(!marks.is_snoozed("main:0.1", "idle", 1000));
+}
+
+#[test]
+fn unsnooze_clears_snooze() {
+    let mut marks = Marks::default();
+    marks.snooze("main:0.1", Snooze::UntilChange("approval".to_string()));
+    marks.unsnooze("main:0.1");
+    assert!(!marks.is_snoozed("main:0.1", "approval", 0));
+}
+
+#[test]
+fn snooze_survives_save_and_load() {
+    let path = std::env::temp_dir().join(format!("claudeye_test_snooze_{}.json", std::process::id()));
+    let mut marks = Marks::default();
+    marks.snooze("main:0.1", Snooze::Until(1000));
+    marks.save_to(&path).unwrap();
+    let mut loaded = Marks::load_from(&path);
+    assert!(loaded.is_snoozed("main:0.1", "idle", 10));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn saving_keeps_what_another_process_saved_meanwhile() {
+    let path = std::env::temp_dir().join(format!("claudeye_test_marks_merge_{}.json", std::process::id()));
+    let mut marks = Marks::default();
+    marks.observe("main:0.1", 1, false);
+    marks.observe("main:0.2", 1, false);
+    marks.save_to(&path).unwrap();
+
+    // The overlay polls while the picker snoozes a pane.
+    let mut overlay = Marks::load_from(&path);
+    let mut picker = Marks::load_from(&path);
+    picker.snooze("main:0.1", Snooze::Until(1000));
+    picker.mark_seen("main:0.2", 5);
+    picker.save_to(&path).unwrap();
+    overlay.observe("main:0.1", 2, true);
+    overlay.save_to(&path).unwrap();
+
+    let mut loaded = Marks::load_from(&path);
+    assert_eq!(loaded.get("main:0.1").and_then(|m| m.seen_hash), Some(2));
+    assert!(loaded.is_snoozed("main:0.1", "idle", 10));
+    assert_eq!(loaded.get("main:0.2").and_then(|m| m.seen_hash), Some(5));
+    let _ = std::fs::remove_file(&path);
+}
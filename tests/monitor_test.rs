@@ -0,0 +1,626 @@
+use std::time::{Duration, Instant};
+
+use claudeye::claude_state::{ApprovalKind, ClaudeState, PendingApproval, PermissionMode, Subagents};
+use claudeye::i18n::Language;
+use claudeye::monitor::{command_hook, content_hash, restart_backoff, debounce, transition_fields, Candidate, Debounce, PollIntervals, detect_transitions, StateTotals, new_blocked, new_errors, new_git_risks, not_running, tombstones, session_events, ClaudeSession, Hooks, Monitor, NameFormat, SessionEvent, SessionList, Transition, TransitionHook};
+use claudeye::tmux::PaneInfo;
+
+mod common;
+
+fn session(id: &str, state: ClaudeState, changed_at: Instant) -> ClaudeSession {
+    let pane = PaneInfo { cwd: "/tmp".to_string(), ..common::pane(id, "test") };
+    ClaudeSession::new(pane, state, changed_at)
+}
+
+#[test]
+fn transition_reported_for_state_change() {
+    let start = Instant::now();
+    let later = start + Duration::from_secs(30);
+    let prev = vec![session("a", ClaudeState::Working, start)];
+    let updated = vec![session("a", ClaudeState::Idle, later)];
+
+    let transitions = detect_transitions(&prev, &updated);
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].from, ClaudeState::Working);
+    assert_eq!(transitions[0].to, ClaudeState::Idle);
+    assert_eq!(transitions[0].elapsed, Duration::from_secs(30));
+}
+
+#[test]
+fn no_transition_when_state_unchanged() {
+    let now = Instant::now();
+    let prev = vec![session("a", ClaudeState::Idle, now)];
+    let updated = vec![session("a", ClaudeState::Idle, now)];
+    assert!(detect_transitions(&prev, &updated).is_empty());
+}
+
+#[test]
+fn new_and_vanished_panes_produce_no_transition() {
+    let now = Instant::now();
+    let prev = vec![session("gone", ClaudeState::Working, now)];
+    let updated = vec![session("new", ClaudeState::Idle, now)];
+    assert!(detect_transitions(&prev, &updated).is_empty());
+}
+
+#[test]
+fn idle_session_without_unread_output_is_reviewed() {
+    let mut s = session("a", ClaudeState::Idle, Instant::now());
+    assert!(s.is_reviewed());
+    s.unread = true;
+    assert!(!s.is_reviewed());
+    assert!(s.shows_unread());
+}
+
+#[test]
+fn working_session_is_never_reviewed_or_unread() {
+    let mut s = session("a", ClaudeState::Working, Instant::now());
+    s.unread = true;
+    assert!(!s.is_reviewed());
+    assert!(!s.shows_unread());
+}
+
+#[test]
+fn vanished_pane_becomes_tombstone() {
+    let now = Instant::now();
+    let prev = vec![session("gone", ClaudeState::Working, now)];
+    let ended = tombstones(&prev, &[], Duration::from_secs(60), now);
+    assert_eq!(ended.len(), 1);
+    assert_eq!(ended[0].ended_at, Some(now));
+    assert!(!ended[0].is_live());
+}
+
+#[test]
+fn tombstone_keeps_original_end_time_and_expires_after_grace() {
+    let start = Instant::now();
+    let mut gone = session("gone", ClaudeState::Idle, start);
+    gone.ended_at = Some(start);
+
+    let later = start + Duration::from_secs(30);
+    let kept = tombstones(&[gone.clone()], &[], Duration::from_secs(60), later);
+    assert_eq!(kept[0].ended_at, Some(start));
+
+    let expired = start + Duration::from_secs(61);
+    assert!(tombstones(&[gone], &[], Duration::from_secs(60), expired).is_empty());
+}
+
+#[test]
+fn zero_grace_disables_tombstones() {
+    let now = Instant::now();
+    let prev = vec![session("gone", ClaudeState::Working, now)];
+    assert!(tombstones(&prev, &[], Duration::ZERO, now).is_empty());
+}
+
+#[test]
+fn live_pane_is_not_tombstoned() {
+    let now = Instant::now();
+    let prev = vec![session("a", ClaudeState::Working, now)];
+    let live = vec![session("a", ClaudeState::Idle, now)];
+    assert!(tombstones(&prev, &live, Duration::from_secs(60), now).is_empty());
+}
+
+#[test]
+fn tombstone_produces_no_transition() {
+    let now = Instant::now();
+    let prev = vec![session("a", ClaudeState::Working, now)];
+    let mut ended = session("a", ClaudeState::Working, now);
+    ended.ended_at = Some(now);
+    assert!(detect_transitions(&prev, &[ended]).is_empty());
+}
+
+#[test]
+fn exited_claude_becomes_not_running_and_reports_transition() {
+    let start = Instant::now();
+    let later = start + Duration::from_secs(30);
+    let mut prev = session("a", ClaudeState::Working, start);
+    prev.guardrail_hits = vec!["rm -rf".to_string()];
+
+    let stopped = not_running(&prev, later);
+    assert_eq!(stopped.state, ClaudeState::NotRunning);
+    assert_eq!(stopped.state_changed_at, later);
+    assert!(stopped.is_live());
+    assert!(stopped.guardrail_hits.is_empty());
+
+    let transitions = detect_transitions(&[prev], std::slice::from_ref(&stopped));
+    assert_eq!(transitions.len(), 1);
+    assert_eq!(transitions[0].to, ClaudeState::NotRunning);
+
+    // Staying stopped keeps the original time
+    let still = not_running(&stopped, later + Duration::from_secs(10));
+    assert_eq!(still.state_changed_at, later);
+}
+
+#[test]
+fn working_elapsed_uses_claude_timer() {
+    let mut s = session("a", ClaudeState::Working, Instant::now());
+    assert_eq!(s.elapsed_secs(), 0);
+    s.timer = Some(Duration::from_secs(105));
+    assert_eq!(s.elapsed_secs(), 105);
+    s.state = ClaudeState::Idle;
+    assert_eq!(s.elapsed_secs(), 0);
+}
+
+#[test]
+fn tokens_label_includes_throughput() {
+    let mut s = session("a", ClaudeState::Working, Instant::now());
+    assert_eq!(s.tokens_label(), None);
+    s.tokens = Some(500);
+    assert_eq!(s.tokens_label().as_deref(), Some("500 tok"));
+    s.tokens = Some(1200);
+    s.timer = Some(Duration::from_secs(100));
+    assert_eq!(s.tokens_label().as_deref(), Some("1.2k tok · 12/s"));
+}
+
+#[test]
+fn subagents_label_counts_and_shortens() {
+    let strings = Language::En.strings();
+    let mut s = session("a", ClaudeState::Working, Instant::now());
+    assert_eq!(s.subagents_label(strings, 20), None);
+    s.subagents = Some(Subagents { running: 2, active: "Explore: Find the config loader".to_string() });
+    assert_eq!(s.subagents_label(strings, 20).as_deref(), Some("subagents: 2 · Explore: Find the c…"));
+    assert_eq!(
+        s.subagents_label(strings, 40).as_deref(),
+        Some("subagents: 2 · Explore: Find the config loader")
+    );
+}
+
+#[test]
+fn context_label_shows_model_and_context_left() {
+    let strings = Language::En.strings();
+    let mut s = session("a", ClaudeState::Idle, Instant::now());
+    assert_eq!(s.context_label(strings), None);
+    s.model = Some("Opus 4.6".to_string());
+    assert_eq!(s.context_label(strings).as_deref(), Some("Opus 4.6"));
+    s.context_left = Some(37);
+    assert_eq!(s.context_label(strings).as_deref(), Some("Opus 4.6 · 37% context left"));
+    assert!(!s.context_low());
+    s.context_left = Some(12);
+    assert!(s.context_low());
+}
+
+#[test]
+fn last_turn_is_shown_only_while_idle() {
+    let strings = Language::En.strings();
+    let mut s = session("a", ClaudeState::Idle, Instant::now());
+    assert_eq!(s.last_turn_label(strings), None);
+    s.last_turn_duration = Some(Duration::from_secs(125));
+    assert_eq!(s.last_turn_label(strings).as_deref(), Some("took 2m"));
+    s.state = ClaudeState::Working;
+    assert_eq!(s.last_turn_label(strings), None);
+}
+
+#[test]
+fn new_blocked_reports_only_entering_sessions() {
+    let now = Instant::now();
+    let prev = vec![session("a", ClaudeState::Working, now), session("b", ClaudeState::Blocked, now)];
+    let updated = vec![session("a", ClaudeState::Blocked, now), session("b", ClaudeState::Blocked, now)];
+    let blocked = new_blocked(&prev, &updated);
+    assert_eq!(blocked.len(), 1);
+    assert_eq!(blocked[0].pane.id, "a");
+}
+
+#[test]
+fn new_errors_reports_only_entering_sessions() {
+    let now = Instant::now();
+    let prev = vec![session("a", ClaudeState::Working, now), session("b", ClaudeState::Error, now)];
+    let updated = vec![session("a", ClaudeState::Error, now), session("b", ClaudeState::Error, now)];
+    let errors = new_errors(&prev, &updated);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].pane.id, "a");
+}
+
+#[test]
+fn new_git_risks_reports_each_risky_command_once() {
+    let now = Instant::now();
+    let mut risky = session("a", ClaudeState::WaitingForApproval, now);
+    risky.pending = Some(PendingApproval { tool: "Bash".to_string(), detail: "git reset --hard".to_string(), kind: ApprovalKind::BashCommand });
+    risky.at_risk_files = Some(3);
+    let prev = vec![session("a", ClaudeState::Working, now)];
+    assert_eq!(new_git_risks(&prev, std::slice::from_ref(&risky)).len(), 1);
+    assert!(new_git_risks(std::slice::from_ref(&risky), std::slice::from_ref(&risky)).is_empty());
+
+    let mut next = risky.clone();
+    next.pending = Some(PendingApproval { tool: "Bash".to_string(), detail: "git checkout main".to_string(), kind: ApprovalKind::BashCommand });
+    assert_eq!(new_git_risks(&[risky], &[next]).len(), 1);
+}
+
+#[test]
+fn debounce_waits_for_consecutive_polls() {
+    let t0 = Instant::now();
+    let rule = Debounce { polls: 2, min_secs: 0 };
+    let (state, entered, candidate) = debounce(&ClaudeState::Working, None, ClaudeState::Idle, rule, t0);
+    assert_eq!((state, entered), (ClaudeState::Working, None));
+    let candidate = candidate.unwrap();
+    assert_eq!(candidate.polls, 1);
+
+    let t1 = t0 + Duration::from_secs(2);
+    let (state, entered, candidate) = debounce(&ClaudeState::Working, Some(&candidate), ClaudeState::Idle, rule, t1);
+    assert_eq!(state, ClaudeState::Idle);
+    assert_eq!(entered, Some(t0));
+    assert_eq!(candidate, None);
+}
+
+#[test]
+fn debounce_drops_a_flicker() {
+    let t0 = Instant::now();
+    let rule = Debounce { polls: 2, min_secs: 0 };
+    let (_, _, candidate) = debounce(&ClaudeState::Working, None, ClaudeState::Idle, rule, t0);
+    let (state, entered, candidate) =
+        debounce(&ClaudeState::Working, candidate.as_ref(), ClaudeState::Working, rule, t0);
+    assert_eq!((state, entered, candidate), (ClaudeState::Working, None, None));
+}
+
+#[test]
+fn debounce_honours_minimum_dwell_time() {
+    let t0 = Instant::now();
+    let rule = Debounce { polls: 1, min_secs: 3 };
+    let (state, _, candidate) = debounce(&ClaudeState::Working, None, ClaudeState::Error, rule, t0);
+    assert_eq!(state, ClaudeState::Working);
+    let t1 = t0 + Duration::from_secs(3);
+    let (state, entered, _) = debounce(&ClaudeState::Working, candidate.as_ref(), ClaudeState::Error, rule, t1);
+    assert_eq!((state, entered), (ClaudeState::Error, Some(t0)));
+}
+
+#[test]
+fn debounce_commits_immediately_by_default() {
+    let now = Instant::now();
+    let (state, entered, candidate) =
+        debounce(&ClaudeState::Idle, None, ClaudeState::Working, Debounce::default(), now);
+    assert_eq!((state, entered, candidate), (ClaudeState::Working, Some(now), None));
+}
+
+#[test]
+fn other_agents_are_tagged_with_their_tool() {
+    let mut s = session("a", ClaudeState::Idle, Instant::now());
+    assert_eq!(s.display_name(NameFormat::Project), "test");
+    s.tool = "aider";
+    assert_eq!(s.display_name(NameFormat::Project), "test (aider)");
+}
+
+#[test]
+fn tmux_name_format_leads_with_session_and_window() {
+    let mut s = session("work:1.0", ClaudeState::Idle, Instant::now());
+    s.pane.session_name = "work".to_string();
+    s.pane.window_name = "api".to_string();
+    assert_eq!(s.display_name(NameFormat::Tmux), "work/api ▸ test");
+    s.tool = "aider";
+    assert_eq!(s.display_name(NameFormat::Tmux), "work/api ▸ test (aider)");
+}
+
+#[test]
+fn interrupted_idle_session_is_labelled_as_such() {
+    let strings = Language::En.strings();
+    let mut s = session("a", ClaudeState::Idle, Instant::now());
+    assert_eq!(s.state_label(strings), "Idle");
+    s.interrupted = true;
+    assert_eq!(s.state_label(strings), "Interrupted");
+    s.state = ClaudeState::Working;
+    assert_eq!(s.state_label(strings), "Running");
+}
+
+#[test]
+fn bypass_warning_replaces_the_bypass_mode_badge() {
+    let strings = Language::En.strings();
+    let mut s = session("a", ClaudeState::Idle, Instant::now());
+    s.permission_mode = PermissionMode::BypassPermissions;
+    assert_eq!(s.mode_badge(strings), Some("⏵⏵ bypass"));
+    s.skips_permissions = true;
+    assert_eq!(s.mode_badge(strings), None);
+    // Cycled to plan mode, the session still skips checks when it cycles back.
+    s.permission_mode = PermissionMode::Plan;
+    assert_eq!(s.mode_badge(strings), Some("⏸ plan"));
+}
+
+#[test]
+fn approval_prompts_answerable_from_outside_the_pane() {
+    let now = Instant::now();
+    let pending = |tool: &str, kind| PendingApproval { tool: tool.to_string(), detail: String::new(), kind };
+    let mut bash = session("a", ClaudeState::WaitingForApproval, now);
+    bash.pending = Some(pending("Bash", ApprovalKind::BashCommand));
+    assert!(bash.approvable() && bash.deniable());
+
+    let mut guarded = bash.clone();
+    guarded.guardrail_hits = vec!["rm -rf".to_string()];
+    assert!(!guarded.approvable() && guarded.deniable());
+
+    // Esc on a folder trust prompt quits Claude.
+    let mut trust = bash.clone();
+    trust.pending = Some(pending("Trust", ApprovalKind::TrustDirectory));
+    assert!(trust.approvable() && !trust.deniable());
+
+    let working = session("b", ClaudeState::Working, now);
+    assert!(!working.approvable() && !working.deniable());
+    let mut ended = bash.clone();
+    ended.ended_at = Some(now);
+    assert!(!ended.approvable() && !ended.deniable());
+}
+
+#[test]
+fn state_changes_are_kept_as_history() {
+    let start = Instant::now();
+    let working = session("a", ClaudeState::Working, start);
+    let stopped = not_running(&working, start + Duration::from_secs(90));
+    assert_eq!(stopped.history, vec![(ClaudeState::Working, start)]);
+    // Staying stopped adds nothing.
+    let still = not_running(&stopped, start + Duration::from_secs(100));
+    assert_eq!(still.history.len(), 1);
+}
+
+#[test]
+fn detail_lines_show_path_branch_states_and_pending_command() {
+    let start = Instant::now();
+    let mut s = session("main:0.1", ClaudeState::WaitingForApproval, start + Duration::from_secs(120));
+    s.history = vec![(ClaudeState::Idle, start), (ClaudeState::Working, start + Duration::from_secs(60))];
+    s.pending = Some(PendingApproval {
+        tool: "Bash".to_string(),
+        detail: "cargo test --workspace".to_string(),
+        kind: ApprovalKind::BashCommand,
+    });
+    let strings = Language::En.strings();
+    let lines = s.detail_lines(strings, Some("main"), start + Duration::from_secs(150));
+    assert_eq!(
+        lines,
+        [
+            "/tmp",
+            "main:0.1 · ⎇ main",
+            "Approval 30s ← Running 1m ← Idle 1m",
+            "Bash: cargo test --workspace",
+        ]
+    );
+    assert_eq!(s.detail_lines(strings, None, start + Duration::from_secs(150))[1], "main:0.1");
+}
+
+#[test]
+fn panes_needing_attention_soon_are_polled_more_often() {
+    let intervals = PollIntervals::default();
+    let at = Instant::now();
+    let running = session("a", ClaudeState::Working, at);
+    let mut idle = session("b", ClaudeState::Idle, at);
+    assert_eq!(intervals.due_at(&running, at), at + Duration::from_millis(500));
+    assert_eq!(intervals.due_at(&idle, at), at + Duration::from_secs(5));
+    // An idle session that may be working again is confirmed quickly.
+    idle.unconfirmed = Some(Candidate { state: ClaudeState::Working, polls: 1, since: at });
+    assert_eq!(intervals.due_at(&idle, at), at + Duration::from_millis(500));
+}
+
+#[test]
+fn timelines_list_states_latest_first_with_their_lengths() {
+    let start = Instant::now();
+    let mut s = session("a", ClaudeState::Idle, start + Duration::from_secs(100));
+    s.history = vec![(ClaudeState::Working, start), (ClaudeState::WaitingForApproval, start + Duration::from_secs(40))];
+    assert_eq!(
+        s.timeline(start + Duration::from_secs(130)),
+        [
+            (&ClaudeState::Idle, Duration::from_secs(30)),
+            (&ClaudeState::WaitingForApproval, Duration::from_secs(60)),
+            (&ClaudeState::Working, Duration::from_secs(40)),
+        ]
+    );
+}
+
+#[test]
+fn history_keeps_the_last_twenty_states() {
+    let start = Instant::now();
+    let mut s = session("a", ClaudeState::Working, start + Duration::from_secs(20));
+    s.history = (0..20).map(|i| (ClaudeState::Idle, start + Duration::from_secs(i))).collect();
+    let stopped = not_running(&s, start + Duration::from_secs(30));
+    assert_eq!(stopped.history.len(), 20);
+    assert_eq!(stopped.history[0].1, start + Duration::from_secs(1));
+    assert_eq!(stopped.history[19], (ClaudeState::Working, start + Duration::from_secs(20)));
+}
+
+fn approval_transition() -> claudeye::monitor::Transition {
+    let start = Instant::now();
+    let prev = vec![session("main:1.0", ClaudeState::Working, start)];
+    let updated = vec![session("main:1.0", ClaudeState::WaitingForApproval, start + Duration::from_secs(75))];
+    detect_transitions(&prev, &updated).remove(0)
+}
+
+#[test]
+fn transition_fields_fill_the_command_template() {
+    let fields = transition_fields(&approval_transition());
+    let command = claudeye::format::fill("notify-send 'claude {project}' '{from} -> {to} after {elapsed}'", &fields);
+    assert_eq!(command.unwrap(), "notify-send 'claude test' 'running -> approval after 1m'");
+    assert_eq!(fields["pane"], "main:1.0");
+    assert_eq!(fields["elapsed_secs"], 75);
+}
+
+#[test]
+fn command_hook_is_off_without_a_valid_template() {
+    assert!(command_hook(None).is_none());
+    assert!(command_hook(Some("  ")).is_none());
+    assert!(command_hook(Some("echo {state}")).is_none());
+    assert!(command_hook(Some("echo {to}")).is_some());
+}
+
+#[test]
+fn command_hook_runs_the_command_with_fields_in_its_environment() {
+    let dir = std::env::temp_dir().join(format!("claudeye_test_on_transition_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let out = dir.join("out");
+    let template = format!("echo {{to}} \"$CLAUDEYE_PROJECT $CLAUDEYE_ELAPSED_SECS\" > {}", out.display());
+    let mut hook = command_hook(Some(&template)).unwrap();
+    hook(&approval_transition());
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !std::fs::read_to_string(&out).is_ok_and(|s| s.ends_with('\n')) && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), "approval test 75\n");
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn command_hook_quotes_filled_values() {
+    let dir = std::env::temp_dir().join(format!("claudeye_test_on_transition_quoting_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let out = dir.join("out");
+    let project = "it's $(touch x)";
+    let template = format!("cd {} && printf '%s\\n' {{project}} > out", dir.display());
+    let mut hook = command_hook(Some(&template)).unwrap();
+    let mut transition = approval_transition();
+    transition.pane.project_name = project.to_string();
+    hook(&transition);
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !std::fs::read_to_string(&out).is_ok_and(|s| s.ends_with('\n')) && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    assert_eq!(std::fs::read_to_string(&out).unwrap(), format!("{project}\n"));
+    assert!(!dir.join("x").exists());
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn time_in_states_adds_up_every_stretch() {
+    let start = Instant::now();
+    let mut s = session("a", ClaudeState::Working, start + Duration::from_secs(100));
+    s.totals = StateTotals::default()
+        .with(&ClaudeState::Working, Duration::from_secs(40))
+        .with(&ClaudeState::WaitingForApproval, Duration::from_secs(20))
+        .with(&ClaudeState::Compacting, Duration::from_secs(10))
+        .with(&ClaudeState::Working, Duration::from_secs(30));
+    let totals = s.time_in_states(start + Duration::from_secs(125));
+    assert_eq!(totals.working, Duration::from_secs(95));
+    assert_eq!(totals.approval, Duration::from_secs(20));
+    assert_eq!(totals.idle, Duration::ZERO);
+}
+
+#[test]
+fn exiting_counts_the_last_stretch() {
+    let start = Instant::now();
+    let s = session("a", ClaudeState::Idle, start);
+    let stopped = not_running(&s, start + Duration::from_secs(90));
+    assert_eq!(stopped.totals.idle, Duration::from_secs(90));
+    // Staying stopped adds nothing more.
+    let still = not_running(&stopped, start + Duration::from_secs(200));
+    assert_eq!(still.time_in_states(start + Duration::from_secs(200)), stopped.totals);
+}
+
+#[test]
+fn restarts_back_off_up_to_a_minute() {
+    let secs: Vec<u64> = [1, 2, 3, 6, 7, 50].into_iter().map(|n| restart_backoff(n).as_secs()).collect();
+    assert_eq!(secs, [1, 2, 4, 32, 60, 60]);
+}
+
+fn mirror(prev: &[ClaudeSession], updated: &[ClaudeSession]) -> Vec<ClaudeSession> {
+    let mut list = SessionList::default();
+    for event in session_events(&[], prev).into_iter().chain(session_events(prev, updated)) {
+        list.apply(event);
+    }
+    list.sessions().to_vec()
+}
+
+#[test]
+fn session_events_describe_each_kind_of_change() {
+    let now = Instant::now();
+    let prev = vec![session("a", ClaudeState::Idle, now), session("b", ClaudeState::Idle, now), session("c", ClaudeState::Idle, now)];
+    let mut renamed = session("b", ClaudeState::Idle, now);
+    renamed.pane.window_name = "docs".to_string();
+    let updated = vec![session("a", ClaudeState::Working, now), renamed.clone(), session("d", ClaudeState::Idle, now)];
+    let events = session_events(&prev, &updated);
+    assert_eq!(
+        events,
+        vec![
+            SessionEvent::Removed { pane: "c".to_string() },
+            SessionEvent::StateChanged { index: 0, from: ClaudeState::Idle, session: updated[0].clone() },
+            SessionEvent::Updated { index: 1, session: renamed },
+            SessionEvent::Added { index: 2, session: updated[2].clone() },
+        ]
+    );
+    assert_eq!(mirror(&prev, &updated), updated);
+}
+
+#[test]
+fn unchanged_sessions_produce_no_events() {
+    let now = Instant::now();
+    let sessions = vec![session("a", ClaudeState::Idle, now), session("b", ClaudeState::Working, now)];
+    assert!(session_events(&sessions, &sessions).is_empty());
+}
+
+#[test]
+fn session_list_follows_reordering() {
+    let now = Instant::now();
+    let prev: Vec<_> = ["a", "b", "c", "d"].iter().map(|id| session(id, ClaudeState::Idle, now)).collect();
+    let updated: Vec<_> = ["d", "x", "b", "a"].iter().map(|id| session(id, ClaudeState::Idle, now)).collect();
+    assert_eq!(mirror(&prev, &updated), updated);
+    assert_eq!(mirror(&updated, &prev), prev);
+}
+
+#[test]
+fn session_list_catches_up_and_dismisses_only_ended_sessions() {
+    let now = Instant::now();
+    let (send, events) = std::sync::mpsc::channel();
+    let ended = tombstones(&[session("a", ClaudeState::Idle, now)], &[], Duration::from_secs(60), now);
+    for event in session_events(&[], &[ended[0].clone(), session("b", ClaudeState::Idle, now)]) {
+        send.send(event).unwrap();
+    }
+    let mut list = SessionList::default();
+    assert!(list.catch_up(&events));
+    assert!(!list.catch_up(&events));
+    list.dismiss("a");
+    list.dismiss("b");
+    let panes: Vec<_> = list.sessions().iter().map(|s| s.pane.id.as_str()).collect();
+    assert_eq!(panes, ["b"]);
+}
+
+#[test]
+fn detached_monitor_sends_nothing() {
+    let events = Monitor::default().subscribe();
+    assert!(events.try_recv().is_err());
+}
+
+#[test]
+fn snoozing_silences_only_alerting_hooks() {
+    use std::sync::{Arc, Mutex};
+    let seen = Arc::new(Mutex::new(vec![]));
+    let hook = |name: &'static str| {
+        let seen = Arc::clone(&seen);
+        Box::new(move |_: &Transition| seen.lock().unwrap().push(name)) as TransitionHook
+    };
+    let mut hooks = Hooks::default();
+    hooks.alerting.push(hook("bell"));
+    hooks.recording.push(hook("journal"));
+    let now = Instant::now();
+    let transition = detect_transitions(&[session("a", ClaudeState::Working, now)], &[session("a", ClaudeState::Idle, now)]).remove(0);
+    hooks.run(&transition, true);
+    assert_eq!(*seen.lock().unwrap(), ["journal"]);
+    hooks.run(&transition, false);
+    assert_eq!(*seen.lock().unwrap(), ["journal", "journal", "bell"]);
+}
+
+#[test]
+fn queued_events_do_not_bring_back_a_dismissed_row() {
+    let now = Instant::now();
+    let alive = session("a", ClaudeState::Idle, now);
+    let ended = tombstones(std::slice::from_ref(&alive), &[], Duration::from_secs(60), now).remove(0);
+    let mut list = SessionList::default();
+    list.apply(SessionEvent::Added { index: 0, session: ended.clone() });
+    list.dismiss("a");
+    // A poll before the monitor heard of the dismissal updated the row.
+    let moved = ClaudeSession { unread: true, ..ended.clone() };
+    list.apply(SessionEvent::Updated { index: 0, session: moved });
+    assert!(list.sessions().is_empty());
+    // The monitor's removal, then the pane running claude again.
+    list.apply(SessionEvent::Removed { pane: "a".to_string() });
+    list.apply(SessionEvent::Added { index: 0, session: alive.clone() });
+    assert_eq!(list.sessions(), [alive]);
+}
+
+#[test]
+fn a_panicking_hook_does_not_stop_the_others() {
+    use std::sync::{Arc, Mutex};
+    let seen = Arc::new(Mutex::new(0));
+    let counted = Arc::clone(&seen);
+    let mut hooks = Hooks::default();
+    hooks.recording.push(Box::new(|_: &Transition| panic!("hook failed")));
+    hooks.recording.push(Box::new(move |_: &Transition| *counted.lock().unwrap() += 1));
+    let now = Instant::now();
+    let transition = detect_transitions(&[session("a", ClaudeState::Working, now)], &[session("a", ClaudeState::Idle, now)]).remove(0);
+    hooks.run(&transition, false);
+    assert_eq!(*seen.lock().unwrap(), 1);
+}
+
+#[test]
+fn content_hash_is_the_same_in_every_build() {
+    assert_eq!(content_hash(""), 0xcbf2_9ce4_8422_2325);
+    assert_eq!(content_hash("a"), 0xaf63_dc4c_8601_ec8c);
+    assert_eq!(content_hash("foobar"), 0x8594_4171_f739_67e8);
+}
@@ -0,0 +1,69 @@
+use claudeye::config::Config;
+use claudeye::notify::{Alert, Notifier, NotifyConfig};
+
+fn alert(pane: &str, kind: &str) -> Alert {
+    Alert {
+        pane: Some(pane.to_string()),
+        project: format!("app-{pane}"),
+        kind: kind.to_string(),
+        title: format!("claudeye: {kind}"),
+        body: format!("app-{pane} ({pane})"),
+        urgent: false,
+        snooze: false,
+    }
+}
+
+fn urgent(pane: &str, kind: &str) -> Alert {
+    Alert { urgent: true, ..alert(pane, kind) }
+}
+
+#[test]
+fn repeats_for_the_same_pane_and_kind_are_dropped() {
+    let mut notifier = Notifier::new(&NotifyConfig::default());
+    assert_eq!(notifier.filter(vec![urgent("1", "error")], 1000).len(), 1);
+    assert!(notifier.filter(vec![urgent("1", "error")], 1100).is_empty());
+    // Another pane, or the same one once the repeat window has passed.
+    assert_eq!(notifier.filter(vec![urgent("2", "error")], 1100).len(), 1);
+    assert_eq!(notifier.filter(vec![urgent("1", "error")], 1300).len(), 1);
+}
+
+#[test]
+fn non_urgent_alerts_wait_out_the_pane_cooldown() {
+    let mut notifier = Notifier::new(&NotifyConfig::default());
+    notifier.filter(vec![alert("1", "watch:FAILED")], 1000);
+    assert!(notifier.filter(vec![alert("1", "guardrail:rm")], 1010).is_empty());
+    assert_eq!(notifier.filter(vec![urgent("1", "blocked")], 1010).len(), 1);
+    assert_eq!(notifier.filter(vec![alert("1", "guardrail:rm")], 1040).len(), 1);
+}
+
+#[test]
+fn bursts_of_one_kind_are_merged() {
+    let mut notifier = Notifier::new(&NotifyConfig::default());
+    let shown = notifier.filter(
+        vec![urgent("1", "error"), alert("4", "guardrail"), urgent("2", "error"), urgent("3", "error")],
+        1000,
+    );
+    assert_eq!(shown.len(), 2);
+    assert_eq!(shown[0].title, "claudeye: error");
+    assert_eq!(shown[0].body, "3 sessions: app-1, app-2, app-3");
+    assert_eq!(shown[0].pane, None);
+    assert!(shown[0].urgent);
+    assert_eq!(shown[1], alert("4", "guardrail"));
+    // Merged alerts still count as sent for each pane.
+    assert!(notifier.filter(vec![urgent("2", "error")], 1001).is_empty());
+}
+
+#[test]
+fn merging_can_be_turned_off() {
+    let mut notifier = Notifier::new(&NotifyConfig { coalesce: 0, ..NotifyConfig::default() });
+    let shown = notifier.filter(vec![urgent("1", "error"), urgent("2", "error"), urgent("3", "error")], 1000);
+    assert_eq!(shown.len(), 3);
+}
+
+#[test]
+fn notify_settings_are_read() {
+    let config = Config::parse("[notify]\nrepeat_secs = 0\ncooldown_secs = 5\n").unwrap();
+    assert_eq!(config.notify.repeat_secs, 0);
+    assert_eq!(config.notify.cooldown_secs, 5);
+    assert_eq!(config.notify.coalesce, 3);
+}
@@ -0,0 +1,27 @@
+use std::time::{Duration, Instant};
+
+use claudeye::poke::{listen_in, send_in, wait};
+
+#[test]
+fn pokes_reach_listeners_and_stale_sockets_are_removed() {
+    let dir = std::env::temp_dir().join(format!("claudeye_test_poke_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let pokes = listen_in(&dir).unwrap();
+    // A monitor that exited without removing its socket.
+    let stale = dir.join("1.sock");
+    drop(std::os::unix::net::UnixDatagram::bind(&stale).unwrap());
+
+    assert_eq!(send_in(&dir, "main:0.1"), 1);
+    assert!(!stale.exists());
+    let deadline = Instant::now() + Duration::from_secs(5);
+    assert_eq!(wait(Some(&pokes), deadline).as_deref(), Some("main:0.1"));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn waiting_without_pokes_returns_at_the_deadline() {
+    let start = Instant::now();
+    assert_eq!(wait(None, start + Duration::from_millis(50)), None);
+    assert!(start.elapsed() >= Duration::from_millis(50));
+    assert_eq!(send_in(&std::env::temp_dir().join("claudeye_test_poke_missing"), "main:0.1"), 0);
+}
@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use claudeye::process::{
+    is_claude_command_line, parse_args_line, parse_etime, parse_ps_line, session_process_age, ProcessInfo, ProcessTree,
+};
+
+#[test]
+fn parse_etime_minutes_and_seconds() {
+    assert_eq!(parse_etime("05:07"), Some(Duration::from_secs(5 * 60 + 7)));
+}
+
+#[test]
+fn parse_etime_hours() {
+    assert_eq!(parse_etime("02:05:07"), Some(Duration::from_secs(2 * 3600 + 5 * 60 + 7)));
+}
+
+#[test]
+fn parse_etime_days() {
+    assert_eq!(
+        parse_etime("3-02:05:07"),
+        Some(Duration::from_secs(3 * 86400 + 2 * 3600 + 5 * 60 + 7))
+    );
+}
+
+#[test]
+fn parse_etime_rejects_garbage() {
+    assert_eq!(parse_etime("abc"), None);
+    assert_eq!(parse_etime("12"), None);
+}
+
+#[test]
+fn parse_ps_line_strips_path_and_login_dash() {
+    let p = parse_ps_line("  4321     1    01:00 /bin/-zsh").unwrap();
+    assert_eq!(p.pid, 4321);
+    assert_eq!(p.ppid, 1);
+    assert_eq!(p.elapsed, Duration::from_secs(60));
+    assert_eq!(p.command, "zsh");
+}
+
+fn proc(pid: u32, ppid: u32, secs: u64, command: &str) -> ProcessInfo {
+    ProcessInfo {
+        pid,
+        ppid,
+        elapsed: Duration::from_secs(secs),
+        command: command.to_string(),
+    }
+}
+
+#[test]
+fn age_of_claude_started_from_shell_is_child_age() {
+    let processes = vec![
+        proc(100, 1, 7200, "zsh"),
+        proc(200, 100, 600, "claude"),
+        proc(300, 200, 30, "node"),
+    ];
+    assert_eq!(session_process_age(&processes, 100), Some(Duration::from_secs(600)));
+}
+
+#[test]
+fn age_of_pane_running_claude_directly_is_pane_age() {
+    let processes = vec![proc(100, 1, 900, "claude"), proc(300, 100, 30, "node")];
+    assert_eq!(session_process_age(&processes, 100), Some(Duration::from_secs(900)));
+}
+
+#[test]
+fn age_of_unknown_pane_is_none() {
+    assert_eq!(session_process_age(&[], 100), None);
+}
+
+#[test]
+fn claude_is_found_under_wrappers() {
+    let tree = ProcessTree {
+        processes: vec![
+            proc(100, 1, 60, "node"),
+            proc(101, 100, 60, "sh"),
+            proc(102, 101, 60, "node"),
+            proc(200, 1, 60, "node"),
+            proc(201, 200, 60, "node"),
+        ],
+        command_lines: [
+            (100, "npm exec @anthropic-ai/claude-code"),
+            (101, "sh -c claude-code"),
+            (102, "node /home/me/.npm/_npx/1f2e/node_modules/@anthropic-ai/claude-code/cli.js"),
+            (200, "node server.js"),
+            (201, "node /usr/lib/node_modules/vite/bin/vite.js"),
+        ]
+        .into_iter()
+        .map(|(pid, line)| (pid, line.to_string()))
+        .collect(),
+    };
+    assert_eq!(tree.find_claude(100), Some(100));
+    assert_eq!(tree.find_claude(101), Some(102));
+    assert_eq!(tree.find_claude(200), None);
+    assert_eq!(tree.find_claude(999), None);
+}
+
+#[test]
+fn claude_command_lines() {
+    assert!(is_claude_command_line("bun /home/me/.bun/bin/claude --resume"));
+    assert!(is_claude_command_line("/home/me/.local/share/mise/shims/claude"));
+    assert!(!is_claude_command_line("vim claude.md"));
+    assert!(!is_claude_command_line("node server.js"));
+    assert_eq!(parse_args_line("  4321 node /x/cli.js --flag"), Some((4321, "node /x/cli.js --flag".to_string())));
+}
@@ -0,0 +1,84 @@
+use claudeye::query::{Query, QueryOutput};
+use claudeye::status::{SessionStatus, TimeInStates};
+use serde_json::json;
+
+const NOW: u64 = 10_000;
+
+fn session(pane: &str, project: &str, state: &'static str, since: u64) -> SessionStatus {
+    SessionStatus {
+        pane: pane.to_string(),
+        project: project.to_string(),
+        tool: "claude",
+        state,
+        unread: false,
+        state_since: since,
+        watch: vec![],
+        pending: None,
+        approval: None,
+        error: None,
+        input_prompt: None,
+        interrupted: false,
+        skips_permissions: false,
+        queued_messages: 0,
+        history: vec![],
+        time_in_states: TimeInStates { working_secs: 0, approval_secs: 0, idle_secs: 0 },
+    }
+}
+
+fn sessions() -> Vec<SessionStatus> {
+    let mut api = session("work:1.0", "api", "approval", NOW - 90);
+    api.watch = vec!["deploy".to_string()];
+    vec![
+        api,
+        session("work:2.0", "myapp", "running", NOW - 400),
+        session("misc:0.0", "my app", "approval", NOW - 5),
+    ]
+}
+
+fn run(query: &str) -> QueryOutput {
+    Query::parse(query).unwrap().run(&sessions(), NOW).unwrap()
+}
+
+#[test]
+fn filters_and_counts() {
+    assert_eq!(run("state==approval | count"), QueryOutput::Count(2));
+    assert_eq!(run("state!=approval | count"), QueryOutput::Count(1));
+    assert_eq!(run("state==approval && elapsed>60 | count"), QueryOutput::Count(1));
+    assert_eq!(run("state==error | count").to_string(), "0\n");
+}
+
+#[test]
+fn fields_are_printed_one_per_line() {
+    assert_eq!(run("project==\"myapp\".elapsed").to_string(), "400\n");
+    assert_eq!(run("project==\"my app\".pane").to_string(), "misc:0.0\n");
+    assert_eq!(run("state==approval.project").to_string(), "api\nmy app\n");
+    assert_eq!(run("state==approval | .pane").to_string(), "work:1.0\nmisc:0.0\n");
+    // Fields left out of status.json read as their defaults.
+    assert_eq!(run("pane==work:2.0.queued_messages").to_string(), "0\n");
+}
+
+#[test]
+fn lists_match_any_item() {
+    assert_eq!(run("watch==deploy.project").to_string(), "api\n");
+}
+
+#[test]
+fn without_a_projection_sessions_are_printed_as_json_lines() {
+    let QueryOutput::Sessions(matched) = run("elapsed<10") else {
+        panic!("expected sessions");
+    };
+    assert_eq!(matched.len(), 1);
+    assert_eq!(matched[0]["project"], json!("my app"));
+    assert_eq!(matched[0]["elapsed"], json!(5));
+}
+
+#[test]
+fn mistakes_are_reported() {
+    assert!(Query::parse("status==approval").unwrap_err().contains("unknown field"));
+    assert!(Query::parse("state approval").is_err());
+    assert!(Query::parse("elapsed>soon").is_err());
+    assert!(Query::parse("project==\"myapp").is_err());
+    assert!(Query::parse("state==approval |").is_err());
+    let count_then_field = Query::parse("count | .pane").unwrap();
+    assert!(count_then_field.run(&sessions(), NOW).is_err());
+}
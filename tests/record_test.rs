@@ -0,0 +1,38 @@
+use claudeye::record::new_lines;
+
+fn lines(s: &[&str]) -> Vec<String> {
+    s.iter().map(|l| l.to_string()).collect()
+}
+
+#[test]
+fn unchanged_history_has_no_new_lines() {
+    let prev = lines(&["a", "b", "c"]);
+    assert!(new_lines(&prev, &prev.clone()).is_empty());
+}
+
+#[test]
+fn scrolled_history_yields_only_appended_lines() {
+    let prev = lines(&["a", "b", "c"]);
+    let current = lines(&["b", "c", "d"]);
+    assert_eq!(new_lines(&prev, &current), lines(&["d"]).as_slice());
+}
+
+#[test]
+fn growing_history_below_window_yields_appended_lines() {
+    let prev = lines(&["a", "b"]);
+    let current = lines(&["a", "b", "c", "d"]);
+    assert_eq!(new_lines(&prev, &current), lines(&["c", "d"]).as_slice());
+}
+
+#[test]
+fn no_overlap_yields_everything() {
+    let prev = lines(&["a", "b"]);
+    let current = lines(&["x", "y"]);
+    assert_eq!(new_lines(&prev, &current), current.as_slice());
+}
+
+#[test]
+fn empty_previous_yields_everything() {
+    let current = lines(&["x"]);
+    assert_eq!(new_lines(&[], &current), current.as_slice());
+}
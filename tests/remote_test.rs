@@ -0,0 +1,32 @@
+use claudeye::remote::{pane_id, parse_pane_id, parse_panes, shell_quote};
+
+#[test]
+fn remote_ids_carry_the_host() {
+    assert_eq!(pane_id("devbox", "main:0.1"), "ssh:devbox/main:0.1");
+    assert_eq!(parse_pane_id("ssh:devbox/main:0.1"), Some(("devbox", "main:0.1")));
+    assert_eq!(parse_pane_id("ssh:me@gpu1/a/b:1.0"), Some(("me@gpu1", "a/b:1.0")));
+    assert_eq!(parse_pane_id("main:0.1"), None);
+    assert_eq!(parse_pane_id("kitty:3"), None);
+    assert_eq!(parse_pane_id("ssh:devbox/"), None);
+}
+
+#[test]
+fn remote_panes_are_read_from_list_panes() {
+    let output = "work:1.0 4242 /home/me/api claude\t✳ Fix login\tapi\tdevbox\nwork:2.0 4300 /home/me zsh\t\tzsh\tdevbox\n";
+    let panes = parse_panes("devbox", output);
+    assert_eq!(panes.len(), 2);
+    let (pane, command, labels) = &panes[0];
+    assert_eq!(pane.id, "ssh:devbox/work:1.0");
+    // Remote pids mean nothing on this machine.
+    assert_eq!(pane.pid, 0);
+    assert_eq!(pane.cwd, "/home/me/api");
+    assert_eq!(pane.session_name, "work");
+    assert_eq!(command, "claude");
+    assert_eq!(labels.title, "✳ Fix login");
+}
+
+#[test]
+fn remote_arguments_are_quoted_for_the_shell() {
+    assert_eq!(shell_quote("#{pane_pid} x"), "'#{pane_pid} x'");
+    assert_eq!(shell_quote("it's"), r"'it'\''s'");
+}
@@ -0,0 +1,39 @@
+use std::time::Instant;
+
+use claudeye::claude_state::ClaudeState;
+use claudeye::monitor::ClaudeSession;
+use claudeye::rename::{label, strip_mark};
+use claudeye::tmux::PaneInfo;
+
+mod common;
+
+fn session(state: ClaudeState) -> ClaudeSession {
+    let pane = PaneInfo {
+        session_name: "main".to_string(),
+        window_name: "claude".to_string(),
+        ..common::pane("main:0.1", "myapp")
+    };
+    ClaudeSession::new(pane, state, Instant::now())
+}
+
+#[test]
+fn labels_carry_the_state_mark() {
+    assert_eq!(label(&session(ClaudeState::Working)).as_deref(), Some("myapp ⏳"));
+    assert_eq!(label(&session(ClaudeState::Idle)).as_deref(), Some("myapp ✅"));
+    assert_eq!(label(&session(ClaudeState::WaitingForApproval)).as_deref(), Some("myapp ⚠"));
+    assert_eq!(label(&session(ClaudeState::NotRunning)), None);
+}
+
+#[test]
+fn ended_sessions_lose_their_label() {
+    let mut ended = session(ClaudeState::Idle);
+    ended.ended_at = Some(Instant::now());
+    assert_eq!(label(&ended), None);
+}
+
+#[test]
+fn marks_are_stripped_from_names() {
+    assert_eq!(strip_mark("myapp ⏳"), "myapp");
+    assert_eq!(strip_mark("myapp"), "myapp");
+    assert_eq!(strip_mark("v1 ✅ notes"), "v1 ✅ notes");
+}
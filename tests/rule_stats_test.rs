@@ -0,0 +1,85 @@
+use std::collections::HashSet;
+
+use claudeye::rule_stats::RuleStats;
+
+fn sample() -> RuleStats {
+    let mut stats = RuleStats::default();
+    for _ in 0..3 {
+        stats.record("main:0.1", "running");
+    }
+    stats.record("main:0.2", "running");
+    stats.record("main:0.2", "prompt_line");
+    stats
+}
+
+#[test]
+fn hits_are_counted_per_rule_and_per_session() {
+    let stats = sample();
+    assert_eq!(stats.totals["running"], 4);
+    assert_eq!(stats.totals["prompt_line"], 1);
+    assert_eq!(stats.sessions["main:0.1"]["running"], 3);
+    assert_eq!(stats.sessions["main:0.2"]["prompt_line"], 1);
+}
+
+#[test]
+fn hits_add_up_across_saves() {
+    let mut saved = sample();
+    let mut hits = RuleStats::default();
+    hits.record("main:0.2", "running");
+    hits.record("main:0.3", "spinner");
+    saved.add(&hits, &HashSet::from(["main:0.2", "main:0.3"]));
+    assert_eq!(saved.totals["running"], 5);
+    assert_eq!(saved.totals["spinner"], 1);
+    assert_eq!(saved.sessions["main:0.2"]["running"], 2);
+    assert!(!saved.sessions.contains_key("main:0.1"));
+    // After `stats --reset` the next save starts from zero.
+    let mut reset = RuleStats::default();
+    reset.add(&hits, &HashSet::from(["main:0.2"]));
+    assert_eq!(reset.totals["running"], 1);
+}
+
+#[test]
+fn closed_panes_drop_out_but_totals_stay() {
+    let mut stats = sample();
+    stats.retain_panes(&HashSet::from(["main:0.2"]));
+    assert!(!stats.sessions.contains_key("main:0.1"));
+    assert_eq!(stats.totals["running"], 4);
+}
+
+#[test]
+fn table_lists_busiest_rules_first_and_unused_ones() {
+    let table = sample().render_table(&["running", "prompt_line", "login", "error"]);
+    let lines: Vec<&str> = table.lines().collect();
+    assert!(lines[1].starts_with("running"), "{table}");
+    assert!(lines[1].contains("80.0%") && lines[1].contains("main:0.1 3, main:0.2 1"), "{table}");
+    assert!(lines[2].starts_with("prompt_line"), "{table}");
+    assert_eq!(lines[3], "never matched: login, error");
+}
+
+#[test]
+fn prometheus_output_has_totals_and_session_series() {
+    let text = sample().prometheus();
+    assert!(text.contains("# TYPE claudeye_rule_hits_total counter\n"));
+    assert!(text.contains("claudeye_rule_hits_total{rule=\"running\"} 4\n"));
+    assert!(text.contains("claudeye_session_rule_hits{pane=\"main:0.1\",rule=\"running\"} 3\n"));
+}
+
+#[test]
+fn counters_survive_a_round_trip() {
+    let path = std::env::temp_dir().join(format!("claudeye_test_rules_{}.json", std::process::id()));
+    let stats = sample();
+    stats.save_to(&path).unwrap();
+    let loaded = RuleStats::load_from(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded, stats);
+}
+
+#[test]
+fn rows_follow_the_table() {
+    let rows = sample().rows();
+    assert_eq!(rows[0]["rule"], "running");
+    assert_eq!(rows[0]["hits"], 4);
+    assert_eq!(rows[0]["share"], 80.0);
+    assert_eq!(rows[0]["sessions"], serde_json::json!(["main:0.1 3", "main:0.2 1"]));
+    assert_eq!(rows[1]["rule"], "prompt_line");
+}
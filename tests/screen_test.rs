@@ -0,0 +1,44 @@
+use claudeye::screen::{parse_ls, parse_tpgid, parse_window_id, parse_windows, window_id, ScreenSession};
+
+#[test]
+fn sessions_are_read_from_screen_ls() {
+    let output = "There are screens on:\n\
+\t12345.work\t(10/15/2026 09:12:01 AM)\t(Attached)\n\
+\t2301.pts-3.build-host\t(Detached)\n\
+2 Sockets in /run/screen/S-me.\n";
+    assert_eq!(
+        parse_ls(output),
+        vec![
+            ScreenSession { pid: 12345, name: "work".to_string() },
+            ScreenSession { pid: 2301, name: "pts-3.build-host".to_string() },
+        ]
+    );
+    assert_eq!(parse_ls("No Sockets found in /run/screen/S-me.\n"), vec![]);
+}
+
+#[test]
+fn windows_are_read_with_their_titles() {
+    assert_eq!(
+        parse_windows("0$ bash  1*$ claude  2-$ api server\n"),
+        vec![(0, "bash".to_string()), (1, "claude".to_string()), (2, "api server".to_string())]
+    );
+}
+
+#[test]
+fn screen_ids_are_told_apart_from_tmux_ids() {
+    let session = ScreenSession { pid: 12345, name: "work".to_string() };
+    let id = window_id(&session, 3);
+    assert_eq!(id, "12345.work/3");
+    assert_eq!(parse_window_id(&id), Some(("12345.work", "3")));
+    assert_eq!(parse_window_id("main:0.1"), None);
+    assert_eq!(parse_window_id("a/b:1.0"), None);
+    assert_eq!(parse_window_id("12345.work/"), None);
+}
+
+#[test]
+fn foreground_group_is_read_from_proc_stat() {
+    let stat = "4242 (claude (v2)) S 4100 4242 4100 34817 4242 4194560 1 0 0 0";
+    assert_eq!(parse_tpgid(stat), Some(4242));
+    assert_eq!(parse_tpgid("4100 (bash) S 12345 4100 4100 34817 4242 0"), Some(4242));
+    assert_eq!(parse_tpgid("garbage"), None);
+}
@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use claudeye::service::{launchd_plist, service_env, systemd_unit, ServiceSpec};
+
+fn vars(pairs: &'static [(&'static str, &'static str)]) -> impl Fn(&str) -> Option<String> {
+    move |name| pairs.iter().find(|(n, _)| *n == name).map(|(_, v)| v.to_string())
+}
+
+#[test]
+fn environment_keeps_path_and_a_custom_tmux_socket() {
+    let env = service_env(vars(&[
+        ("PATH", "/opt/homebrew/bin:/usr/bin"),
+        ("TMUX_TMPDIR", ""),
+        ("TMUX", "/tmp/tmux-501/work,4242,0"),
+        ("HOME", "/Users/me"),
+    ]));
+    assert_eq!(
+        env,
+        vec![
+            ("PATH".to_string(), "/opt/homebrew/bin:/usr/bin".to_string()),
+            ("TMUX".to_string(), "/tmp/tmux-501/work".to_string()),
+        ]
+    );
+    // The default socket is found without help.
+    let env = service_env(vars(&[("TMUX", "/tmp/tmux-501/default,4242,0")]));
+    assert_eq!(env, vec![]);
+}
+
+#[test]
+fn serve_spec_forwards_the_profile() {
+    let spec = ServiceSpec::serve("/usr/local/bin/claudeye", Some("work"), vars(&[]));
+    assert_eq!(spec.command, ["/usr/local/bin/claudeye", "--profile", "work", "serve"]);
+    let spec = ServiceSpec::serve("/usr/local/bin/claudeye", None, vars(&[]));
+    assert_eq!(spec.command, ["/usr/local/bin/claudeye", "serve"]);
+}
+
+fn spec() -> ServiceSpec {
+    ServiceSpec {
+        command: vec!["/home/me/my bin/claudeye".to_string(), "serve".to_string()],
+        env: vec![("PATH".to_string(), "/home/me/.local/bin:/usr/bin".to_string())],
+        log: Some(PathBuf::from("/home/me/.local/state/claudeye/service.log")),
+    }
+}
+
+#[test]
+fn systemd_unit_quotes_arguments_and_environment() {
+    let unit = systemd_unit(&spec());
+    assert!(unit.contains("ExecStart=\"/home/me/my bin/claudeye\" \"serve\"\n"));
+    assert!(unit.contains("Environment=\"PATH=/home/me/.local/bin:/usr/bin\"\n"));
+    assert!(unit.contains("Restart=on-failure\n"));
+    assert!(unit.contains("WantedBy=default.target\n"));
+}
+
+#[test]
+fn launchd_plist_lists_arguments_environment_and_log() {
+    let mut spec = spec();
+    spec.command[1] = "<serve & more>".to_string();
+    let plist = launchd_plist(&spec);
+    assert!(plist.contains("<string>com.github.pi-chan.claudeye</string>"));
+    assert!(plist.contains("    <string>/home/me/my bin/claudeye</string>\n    <string>&lt;serve &amp; more&gt;</string>\n"));
+    assert!(plist.contains("    <key>PATH</key>\n    <string>/home/me/.local/bin:/usr/bin</string>\n"));
+    assert!(plist.contains("<key>StandardErrorPath</key>\n  <string>/home/me/.local/state/claudeye/service.log</string>"));
+    assert!(plist.contains("<key>KeepAlive</key>\n  <true/>"));
+}
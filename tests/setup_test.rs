@@ -0,0 +1,69 @@
+use claudeye::config::Config;
+use claudeye::setup::{
+    Answers, POKE_HOOKS, POSITIONS, install_block, poke_hooks, popup_binding, remove_block, render_config, status_snippet,
+};
+
+#[test]
+fn rendered_config_parses_back() {
+    let answers = Answers { position: "bottom-right".into(), notifications: false };
+    let config = Config::parse(&render_config(&answers)).unwrap();
+    assert_eq!(config.position.as_deref(), Some("bottom-right"));
+    assert!(!config.notifications);
+}
+
+#[test]
+fn positions_cover_the_grid() {
+    assert_eq!(POSITIONS.len(), 9);
+    assert!(POSITIONS.contains(&"top-center"));
+}
+
+#[test]
+fn popup_binding_runs_the_picker() {
+    assert_eq!(
+        popup_binding("/usr/bin/claudeye", "C-g"),
+        "bind-key C-g display-popup -E -w 80% -h 60% \"/usr/bin/claudeye picker\""
+    );
+}
+
+#[test]
+fn poke_hooks_name_the_pane_behind_each_hook() {
+    let lines = poke_hooks("/usr/bin/claudeye");
+    assert_eq!(lines.len(), POKE_HOOKS.len());
+    assert_eq!(
+        lines[0],
+        "set-hook -g pane-focus-in[90] \"run-shell -b '/usr/bin/claudeye poke #{q:session_name}:#{window_index}.#{pane_index}'\""
+    );
+    assert!(lines[2].starts_with("set-hook -g after-send-keys[90] "));
+}
+
+#[test]
+fn install_block_appends_to_existing_config() {
+    let text = "set -g mouse on";
+    let out = install_block(text, &["bind-key C-g foo".into()]);
+    assert_eq!(out, "set -g mouse on\n# >>> claudeye >>>\nbind-key C-g foo\n# <<< claudeye <<<\n");
+}
+
+#[test]
+fn install_block_replaces_existing_block() {
+    let first = install_block("set -g mouse on\n", &["bind-key C-g foo".into()]);
+    let text = format!("{first}set -g status on\n");
+    let out = install_block(&text, &["bind-key C-h bar".into()]);
+    assert_eq!(
+        out,
+        "set -g mouse on\n# >>> claudeye >>>\nbind-key C-h bar\n# <<< claudeye <<<\nset -g status on\n"
+    );
+    assert_eq!(install_block(&out, &["bind-key C-h bar".into()]), out);
+}
+
+#[test]
+fn status_snippet_appends_the_prompt_summary() {
+    assert_eq!(status_snippet("claudeye"), "set -ag status-right \" #(claudeye prompt)\"");
+}
+
+#[test]
+fn remove_block_restores_the_surrounding_config() {
+    let text = "set -g mouse on\nset -g status on\n";
+    let installed = install_block("set -g mouse on\n", &["bind-key C-g foo".into()]) + "set -g status on\n";
+    assert_eq!(remove_block(&installed), text);
+    assert_eq!(remove_block(text), text);
+}
@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+use claudeye::claude_state::ClaudeState;
+use claudeye::config::Config;
+use claudeye::elapsed::unix_now;
+use claudeye::monitor::Transition;
+use claudeye::stats::{check_version, execute, quote, render_table, report, transition_sql, turn_sql, Period, Turns};
+use claudeye::tmux::PaneInfo;
+
+fn transition(project: &str, from: ClaudeState, to: ClaudeState, secs: u64) -> Transition {
+    Transition {
+        pane: PaneInfo {
+            id: format!("main:{project}"),
+            pid: 1,
+            cwd: format!("/src/{project}"),
+            project_name: project.to_string(),
+            session_name: String::new(),
+            window_name: String::new(),
+        },
+        from,
+        to,
+        elapsed: Duration::from_secs(secs),
+        approval: None,
+    }
+}
+
+fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("claudeye_test_stats_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn has_sqlite() -> bool {
+    Command::new("sqlite3").arg("-version").output().is_ok_and(|out| out.status.success())
+}
+
+#[test]
+fn text_is_quoted_for_sql() {
+    assert_eq!(quote("api"), "'api'");
+    assert_eq!(quote("bob's app"), "'bob''s app'");
+}
+
+#[test]
+fn sqlite_needs_json_output() {
+    assert!(check_version("3.51.2 2026-01-09 17:27:48 b270f8 (64-bit)\n").is_ok());
+    assert!(check_version("3.33.0 2020-08-14").is_ok());
+    assert_eq!(check_version("3.31.1 2020-01-27").unwrap_err(), "stats need sqlite3 3.33 or newer, found 3.31");
+    assert!(check_version("").is_err());
+}
+
+#[test]
+fn transitions_become_inserts() {
+    let sql = transition_sql(&transition("api", ClaudeState::Working, ClaudeState::Idle, 42), 1_760_000_000);
+    assert_eq!(sql, "INSERT INTO transitions VALUES (1760000000, 'main:api', 'api', 'running', 'idle', 42);\n");
+}
+
+#[test]
+fn turns_run_from_idle_to_idle() {
+    let mut turns = Turns::default();
+    assert_eq!(turns.observe(&transition("api", ClaudeState::Idle, ClaudeState::Working, 5), 100), None);
+    assert_eq!(turns.observe(&transition("api", ClaudeState::Working, ClaudeState::WaitingForApproval, 20), 120), None);
+    assert_eq!(turns.observe(&transition("api", ClaudeState::WaitingForApproval, ClaudeState::Working, 30), 150), None);
+    assert_eq!(turns.observe(&transition("api", ClaudeState::Working, ClaudeState::Idle, 40), 190), Some(90));
+    // Idle again without a start seen: no turn.
+    assert_eq!(turns.observe(&transition("api", ClaudeState::Error, ClaudeState::Idle, 1), 200), None);
+}
+
+#[test]
+fn exiting_abandons_a_turn() {
+    let mut turns = Turns::default();
+    turns.observe(&transition("api", ClaudeState::Idle, ClaudeState::Working, 5), 100);
+    turns.observe(&transition("api", ClaudeState::Working, ClaudeState::NotRunning, 5), 110);
+    assert_eq!(turns.observe(&transition("api", ClaudeState::NotRunning, ClaudeState::Idle, 5), 120), None);
+}
+
+#[test]
+fn stats_are_opt_in() {
+    assert!(!Config::default().stats.enabled);
+    assert!(Config::parse("[stats]\nenabled = true\n").unwrap().stats.enabled);
+}
+
+#[test]
+fn reports_total_each_project() {
+    if !has_sqlite() {
+        return;
+    }
+    let dir = scratch("report");
+    let db = dir.join("stats.db");
+    let now = unix_now();
+    let api = |from, to, secs| transition_sql(&transition("api", from, to, secs), now);
+    let mut sql = [
+        api(ClaudeState::Working, ClaudeState::WaitingForApproval, 600),
+        api(ClaudeState::WaitingForApproval, ClaudeState::Working, 30),
+        api(ClaudeState::Working, ClaudeState::WaitingForApproval, 60),
+        api(ClaudeState::WaitingForApproval, ClaudeState::Working, 11),
+        api(ClaudeState::Working, ClaudeState::Idle, 100),
+        transition_sql(&transition("web", ClaudeState::Working, ClaudeState::Idle, 90), now),
+        // Last week's work is not today's.
+        transition_sql(&transition("web", ClaudeState::Working, ClaudeState::Idle, 5000), now - 8 * 86_400),
+    ]
+    .concat();
+    sql.push_str(&turn_sql(&transition("api", ClaudeState::Working, ClaudeState::Idle, 0), 900, now));
+    execute(&db, &sql).unwrap();
+
+    let rows = report(&db, Period::Today).unwrap();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0]["project"], "api");
+    assert_eq!(rows[0]["working_secs"], 760);
+    assert_eq!(rows[0]["approvals"], 2);
+    assert_eq!(rows[0]["avg_wait_secs"], 21);
+    assert_eq!(rows[0]["turns"], 1);
+    assert_eq!(rows[0]["avg_turn_secs"], 900);
+    assert_eq!(rows[1]["project"], "web");
+    assert_eq!(rows[1]["working_secs"], 90);
+    assert!(rows[1]["avg_wait_secs"].is_null());
+
+    let table = render_table(&rows, Period::Today);
+    assert!(table.starts_with("project"));
+    assert!(table.contains("api           12m          2       21s      1       15m\n"), "{table}");
+    std::fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn empty_reports_say_so() {
+    assert_eq!(render_table(&[], Period::Week), "nothing recorded this week\n");
+}
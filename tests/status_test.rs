@@ -0,0 +1,147 @@
+use std::time::{Duration, Instant};
+
+use claudeye::claude_state::ClaudeState;
+use claudeye::monitor::{ClaudeSession, StateTotals};
+use claudeye::status::{exit_code, file_counts, summary_line, write_snapshot, StateCounts, StatusSnapshot};
+
+mod common;
+
+fn session(id: &str, state: ClaudeState, unread: bool) -> ClaudeSession {
+    let pane = common::pane(id, "app");
+    ClaudeSession {
+        unread,
+        ..ClaudeSession::new(pane, state, Instant::now())
+    }
+}
+
+#[test]
+fn snapshot_counts_live_sessions_by_state() {
+    let ended = ClaudeSession {
+        ended_at: Some(Instant::now()),
+        ..session("main:0.4", ClaudeState::Working, false)
+    };
+    let sessions = [
+        session("main:0.1", ClaudeState::Working, false),
+        session("main:0.2", ClaudeState::WaitingForApproval, false),
+        session("main:0.3", ClaudeState::Idle, true),
+        ended,
+    ];
+    let snapshot = StatusSnapshot::from_sessions(&sessions);
+    assert_eq!(
+        snapshot.counts,
+        StateCounts { running: 1, approval: 1, idle: 1, unread: 1, ..Default::default() }
+    );
+    assert_eq!(snapshot.sessions.len(), 3);
+    assert_eq!(snapshot.sessions[1].state, "approval");
+    assert!(snapshot.sessions[2].unread);
+}
+
+#[test]
+fn summary_line_omits_zero_counts() {
+    let counts = StateCounts { running: 2, approval: 1, answer: 1, idle: 4, ..Default::default() };
+    assert_eq!(summary_line(&counts), "⚠1 ?1 ▶2");
+    assert_eq!(summary_line(&StateCounts::default()), "");
+}
+
+#[test]
+fn summary_line_puts_blocked_sessions_after_errors() {
+    let counts = StateCounts { error: 1, blocked: 2, running: 1, ..Default::default() };
+    assert_eq!(summary_line(&counts), "!1 ⌨2 ▶1");
+}
+
+#[test]
+fn exit_code_reports_the_most_pressing_state() {
+    assert_eq!(exit_code(&StateCounts::default()), 0);
+    assert_eq!(exit_code(&StateCounts { idle: 3, not_running: 1, unread: 2, ..Default::default() }), 0);
+    assert_eq!(exit_code(&StateCounts { idle: 3, compacting: 1, ..Default::default() }), 1);
+    assert_eq!(exit_code(&StateCounts { running: 2, answer: 1, ..Default::default() }), 2);
+    assert_eq!(exit_code(&StateCounts { blocked: 1, ..Default::default() }), 2);
+    assert_eq!(exit_code(&StateCounts { running: 1, approval: 1, error: 1, ..Default::default() }), 3);
+}
+
+#[test]
+fn differs_from_ignores_timestamps() {
+    let a = StatusSnapshot::from_sessions(&[session("main:0.1", ClaudeState::Idle, false)]);
+    let mut b = a.clone();
+    b.sessions[0].state_since += 1;
+    assert!(!a.differs_from(&b));
+    b.sessions[0].unread = true;
+    assert!(a.differs_from(&b));
+}
+
+#[test]
+fn write_snapshot_writes_json_and_text() {
+    let dir = std::env::temp_dir().join(format!("claudeye_test_status_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let snapshot =
+        StatusSnapshot::from_sessions(&[session("main:0.1", ClaudeState::WaitingForApproval, false)]);
+
+    write_snapshot(&dir, &snapshot).unwrap();
+
+    let json: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.join("status.json")).unwrap()).unwrap();
+    assert_eq!(json["counts"]["approval"], 1);
+    assert_eq!(json["sessions"][0]["pane"], "main:0.1");
+    assert_eq!(json["sessions"][0]["tool"], "claude");
+    assert_eq!(std::fs::read_to_string(dir.join("status.txt")).unwrap(), "⚠1\n");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn prompt_reads_counts_of_a_running_monitor() {
+    let dir = std::env::temp_dir().join(format!("claudeye_test_status_counts_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let path = dir.join("status.json");
+    assert_eq!(file_counts(&path), None);
+
+    let snapshot = StatusSnapshot::from_sessions(&[session("main:0.1", ClaudeState::Working, false)]);
+    write_snapshot(&dir, &snapshot).unwrap();
+    assert_eq!(file_counts(&path), Some(snapshot.counts));
+
+    // Not rewritten for a minute: the monitor is gone.
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(std::time::SystemTime::now() - Duration::from_secs(60)).unwrap();
+    assert_eq!(file_counts(&path), None);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn compacting_sessions_are_counted_separately() {
+    let sessions = [
+        session("main:0.1", ClaudeState::Working, false),
+        session("main:0.2", ClaudeState::Compacting, false),
+    ];
+    let snapshot = StatusSnapshot::from_sessions(&sessions);
+    assert_eq!(snapshot.counts, StateCounts { running: 1, compacting: 1, ..Default::default() });
+    assert_eq!(snapshot.sessions[1].state, "compacting");
+    assert_eq!(summary_line(&snapshot.counts), "▶1 ◌1");
+}
+
+#[test]
+fn errors_lead_the_summary() {
+    let counts = StateCounts { error: 1, approval: 1, running: 1, ..Default::default() };
+    assert_eq!(summary_line(&counts), "!1 ⚠1 ▶1");
+}
+
+#[test]
+fn snapshots_carry_each_session_history() {
+    let mut working = session("main:0.1", ClaudeState::Working, false);
+    working.history = vec![(ClaudeState::Idle, Instant::now()), (ClaudeState::WaitingForApproval, Instant::now())];
+    let snapshot = StatusSnapshot::from_sessions(&[working]);
+    let states: Vec<&str> = snapshot.sessions[0].history.iter().map(|past| past.state).collect();
+    assert_eq!(states, ["idle", "approval"]);
+    assert!(snapshot.sessions[0].history[0].since > 0);
+}
+
+#[test]
+fn snapshots_carry_time_in_each_state() {
+    let mut idle = session("main:0.1", ClaudeState::Idle, false);
+    idle.totals = StateTotals { working: Duration::from_secs(600), approval: Duration::from_secs(45), idle: Duration::from_secs(30) };
+    let snapshot = StatusSnapshot::from_sessions(&[idle]);
+    let row = serde_json::to_value(&snapshot.sessions[0]).unwrap();
+    assert_eq!(row["working_secs"], 600);
+    assert_eq!(row["approval_secs"], 45);
+    assert_eq!(row["idle_secs"], 30);
+}
@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use claudeye::claude_state::ClaudeState;
+use claudeye::config::Config;
+use claudeye::monitor::ClaudeSession;
+use claudeye::team::{curl_config, render_table, MemberStats, Report, TeamBoard, Usage};
+
+mod common;
+
+fn session(id: &str, state: ClaudeState) -> ClaudeSession {
+    ClaudeSession::new(common::pane(id, "secret-project"), state, Instant::now())
+}
+
+fn secs(pairs: &[(&str, u64)]) -> BTreeMap<String, u64> {
+    pairs.iter().map(|(k, v)| (k.to_string(), *v)).collect()
+}
+
+#[test]
+fn team_reporting_is_off_by_default() {
+    let config = Config::parse("").unwrap();
+    assert_eq!(config.team.endpoint, None);
+    assert_eq!(config.team.interval_secs, 300);
+}
+
+#[test]
+fn usage_credits_time_between_polls() {
+    let start = Instant::now();
+    let mut usage = Usage::default();
+    let sessions = vec![session("a", ClaudeState::Working), session("b", ClaudeState::WaitingForApproval)];
+    usage.record(&sessions, start);
+    usage.record(&sessions, start + Duration::from_secs(2));
+    usage.record(&[session("a", ClaudeState::Working)], start + Duration::from_secs(5));
+
+    let report = usage.take_report("m1", 1, Duration::from_secs(5), 1_760_000_000);
+    assert_eq!(report.state_secs, secs(&[("approval", 2), ("running", 5)]));
+    assert_eq!(report.sessions, 1);
+
+    let next = usage.take_report("m1", 1, Duration::from_secs(5), 1_760_000_005);
+    assert!(next.state_secs.is_empty());
+}
+
+#[test]
+fn report_carries_no_content_or_names() {
+    let mut usage = Usage::default();
+    let start = Instant::now();
+    usage.record(&[session("main:1.0", ClaudeState::Working)], start);
+    usage.record(&[session("main:1.0", ClaudeState::Working)], start + Duration::from_secs(1));
+    let json = serde_json::to_string(&usage.take_report("m1", 1, Duration::from_secs(1), 0)).unwrap();
+    assert!(!json.contains("secret-project"));
+    assert!(!json.contains("main:1.0"));
+}
+
+#[test]
+fn board_sums_reports_per_member() {
+    let mut board = TeamBoard::default();
+    let report = |member: &str, timestamp, state_secs| Report {
+        member: member.to_string(),
+        timestamp,
+        interval_secs: 300,
+        sessions: 2,
+        state_secs,
+    };
+    board.add(report("m1", 100, secs(&[("running", 200), ("idle", 100)])));
+    board.add(report("m2", 150, secs(&[("approval", 300)])));
+    board.add(report("m1", 400, secs(&[("running", 100)])));
+
+    let members = board.members();
+    assert_eq!(members.len(), 2);
+    assert_eq!(members[0].member, "m1");
+    assert_eq!(members[0].last_report, 400);
+    assert_eq!(members[0].state_secs, secs(&[("idle", 100), ("running", 300)]));
+    assert_eq!(members[0].utilization(), (75, 0, 25));
+}
+
+#[test]
+fn table_lists_members_and_fleet() {
+    let members = vec![
+        MemberStats {
+            member: "m1".to_string(),
+            last_report: 1000,
+            sessions: 2,
+            state_secs: secs(&[("running", 50), ("approval", 50)]),
+        },
+        MemberStats {
+            member: "m2".to_string(),
+            last_report: 940,
+            sessions: 1,
+            state_secs: secs(&[("idle", 100)]),
+        },
+    ];
+    let table = render_table(&members, 1000);
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[1].starts_with("m1") && lines[1].contains("50%") && lines[1].ends_with("0s ago"));
+    assert!(lines[2].ends_with("1m ago"));
+    assert!(lines[3].starts_with("fleet (2)") && lines[3].ends_with("25%      25%    50%"));
+}
+
+#[test]
+fn token_and_body_go_to_curl_on_stdin() {
+    assert_eq!(curl_config(None, None), "");
+    assert_eq!(curl_config(Some("t0k"), None), "header = \"Authorization: Bearer t0k\"\n");
+    assert_eq!(
+        curl_config(Some("t0k"), Some(r#"{"member":"a\\b"}"#)),
+        concat!(
+            "header = \"Authorization: Bearer t0k\"\n",
+            "header = \"Content-Type: application/json\"\n",
+            "data-binary = \"{\\\"member\\\":\\\"a\\\\\\\\b\\\"}\"\n",
+        )
+    );
+}
@@ -0,0 +1,27 @@
+use claudeye::text;
+
+#[test]
+fn cjk_characters_are_two_columns_wide() {
+    assert_eq!(text::width("api"), 3);
+    assert_eq!(text::width("設定画面"), 8);
+    assert_eq!(text::width("web-管理"), 8);
+}
+
+#[test]
+fn truncate_counts_columns_not_characters() {
+    assert_eq!(text::truncate("api-server", 20), "api-server");
+    assert_eq!(text::truncate("api-server", 6), "api-s…");
+    // Five wide characters are ten columns; a wide character that would
+    // cross the limit is dropped rather than split.
+    assert_eq!(text::truncate("設定画面の改修", 10), "設定画面…");
+    assert_eq!(text::truncate("設定画面の改修", 9), "設定画面…");
+    assert!(text::width(&text::truncate("設定画面の改修", 9)) <= 9);
+}
+
+#[test]
+fn pad_fills_to_the_display_width() {
+    assert_eq!(text::pad("api", 6), "api   ");
+    assert_eq!(text::pad("設定", 6), "設定  ");
+    assert_eq!(text::pad("設定画面", 6), "設定画面");
+    assert_eq!(text::width(&text::pad("設定", 6)), text::width(&text::pad("api", 6)));
+}
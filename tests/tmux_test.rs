@@ -1,4 +1,11 @@
-use claudeye::tmux::{parse_pane_line, read_version_entries, refresh_version_cache};
+use std::time::Duration;
+
+use claudeye::claude_state::ClaudeState;
+use claudeye::monitor::Transition;
+use claudeye::tmux::{
+    bell_hook, capture_start, parse_focus_line, parse_pane_fields, parse_pane_line, read_version_entries, refresh_version_cache, rings_bell,
+    NameSource, NamingConfig, PaneInfo, PaneLabels,
+};
 
 #[test]
 fn parse_valid_pane_line_claude() {
@@ -42,6 +49,64 @@ fn project_name_is_basename_of_cwd() {
     assert_eq!(pane.project_name, "claudeye");
 }
 
+#[test]
+fn pane_labels_follow_the_command() {
+    let line = "work:2.3 54321 /home/user/api/src claude\t✳ Fix login redirect\tapi\tlaptop";
+    let (pane, command, labels) = parse_pane_fields(line).unwrap();
+    assert_eq!(pane.project_name, "src");
+    assert_eq!((pane.session_name.as_str(), pane.window_name.as_str()), ("work", "api"));
+    assert_eq!(command, "claude");
+    assert_eq!(
+        labels,
+        PaneLabels {
+            title: "✳ Fix login redirect".to_string(),
+            host: "laptop".to_string(),
+        }
+    );
+    // Lines without labels still parse.
+    let (pane, command, labels) = parse_pane_fields("main:0.1 1 /tmp claude").unwrap();
+    assert_eq!(command, "claude");
+    assert_eq!(pane.session_name, "main");
+    assert_eq!(pane.window_name, "");
+    assert_eq!(labels.title, "");
+}
+
+fn named(line: &str, naming: &NamingConfig) -> String {
+    let (pane, command, labels) = parse_pane_fields(line).unwrap();
+    naming.display_name(&pane, &labels, command)
+}
+
+#[test]
+fn generic_directories_fall_back_to_the_pane_title() {
+    let naming = NamingConfig::default();
+    assert_eq!(named("w:0.0 1 /home/user/api claude\t✳ Fix login\tzsh\tlaptop", &naming), "api");
+    assert_eq!(named("w:0.0 1 /home/user/api/src claude\t✳ Fix login\tzsh\tlaptop", &naming), "Fix login");
+}
+
+#[test]
+fn unhelpful_labels_are_skipped() {
+    let naming = NamingConfig::default();
+    // Title is the host name, window is named after the command: the session is used.
+    assert_eq!(named("billing:0.0 1 /tmp claude\tlaptop\tclaude\tlaptop", &naming), "billing");
+    assert_eq!(named("3:0.0 1 /tmp claude\tlaptop\tdocs\tlaptop", &naming), "docs");
+    // Nothing useful anywhere: the directory after all.
+    assert_eq!(named("3:0.0 1 /tmp claude\tlaptop\tzsh\tlaptop", &naming), "tmp");
+}
+
+#[test]
+fn state_marks_of_tmux_rename_are_not_read_back() {
+    let naming = NamingConfig::default();
+    assert_eq!(named("3:0.0 1 /tmp claude\tlaptop\tdocs ⏳\tlaptop", &naming), "docs");
+    assert_eq!(named("w:0.0 1 /home/user/api/src claude\tapi ✅\tzsh\tlaptop", &naming), "api");
+}
+
+#[test]
+fn naming_order_is_configurable() {
+    let naming = NamingConfig { order: vec![NameSource::Session, NameSource::Directory], ..NamingConfig::default() };
+    assert_eq!(named("billing:0.0 1 /home/user/api claude\t✳ Fix\tapi\tlaptop", &naming), "billing");
+    assert_eq!(named("7:0.0 1 /home/user/api claude\t✳ Fix\tapi\tlaptop", &naming), "api");
+}
+
 /// On macOS, tmux reports the resolved symlink target name (e.g. "2.1.50")
 /// instead of "claude". Multiple versions may coexist in the versions
 /// directory, so all of them should be detected.
@@ -142,3 +207,57 @@ fn refresh_version_cache_detects_installed_versions() {
     }
 }
 
+#[test]
+fn parse_focus_line_returns_active_pane_of_attached_session() {
+    assert_eq!(parse_focus_line("main:0.1 1 1 1").as_deref(), Some("main:0.1"));
+}
+
+#[test]
+fn parse_focus_line_ignores_unfocused_panes() {
+    assert_eq!(parse_focus_line("main:0.1 0 1 1"), None);
+    assert_eq!(parse_focus_line("main:0.1 1 0 1"), None);
+    assert_eq!(parse_focus_line("main:0.1 1 1 0"), None);
+}
+
+#[test]
+fn parse_focus_line_keeps_session_names_with_spaces() {
+    assert_eq!(parse_focus_line("my work:0.1 1 1 2").as_deref(), Some("my work:0.1"));
+}
+
+fn transition(from: ClaudeState, to: ClaudeState) -> Transition {
+    Transition {
+        pane: PaneInfo {
+            id: "main:0.1".to_string(),
+            pid: 1,
+            cwd: "/tmp".to_string(),
+            project_name: "test".to_string(),
+            session_name: String::new(),
+            window_name: String::new(),
+        },
+        from,
+        to,
+        elapsed: Duration::from_secs(5),
+        approval: None,
+    }
+}
+
+#[test]
+fn bell_rings_when_a_session_starts_waiting_on_the_user() {
+    assert!(rings_bell(&transition(ClaudeState::Working, ClaudeState::WaitingForApproval)));
+    assert!(rings_bell(&transition(ClaudeState::Working, ClaudeState::WaitingForAnswer)));
+    assert!(rings_bell(&transition(ClaudeState::Working, ClaudeState::Blocked)));
+    assert!(!rings_bell(&transition(ClaudeState::Working, ClaudeState::Idle)));
+    assert!(!rings_bell(&transition(ClaudeState::WaitingForApproval, ClaudeState::Working)));
+}
+
+#[test]
+fn bell_hook_only_when_enabled() {
+    assert!(bell_hook(false).is_none());
+    assert!(bell_hook(true).is_some());
+}
+
+#[test]
+fn captures_reach_into_the_scrollback_when_asked() {
+    assert_eq!(capture_start(0), None);
+    assert_eq!(capture_start(200).as_deref(), Some("-200"));
+}
@@ -0,0 +1,25 @@
+use std::time::{Duration, SystemTime};
+
+use claudeye::transcript::{latest_in, project_dir_name};
+
+#[test]
+fn project_dirs_replace_everything_but_letters_and_digits() {
+    assert_eq!(project_dir_name("/home/me/my.app"), "-home-me-my-app");
+    assert_eq!(project_dir_name("/src/api_v2"), "-src-api-v2");
+}
+
+#[test]
+fn the_latest_transcript_is_the_last_written() {
+    let dir = std::env::temp_dir().join(format!("claudeye_test_transcript_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    assert_eq!(latest_in(&dir), None);
+    let now = SystemTime::now();
+    for (name, age) in [("old.jsonl", 60), ("new.jsonl", 0), ("notes.txt", 0)] {
+        let file = std::fs::File::create(dir.join(name)).unwrap();
+        file.set_modified(now - Duration::from_secs(age)).unwrap();
+    }
+    assert_eq!(latest_in(&dir), Some(dir.join("new.jsonl")));
+    assert_eq!(latest_in(&dir.join("missing")), None);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
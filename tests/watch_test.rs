@@ -0,0 +1,71 @@
+use std::time::Instant;
+
+use claudeye::claude_state::ClaudeState;
+use claudeye::config::Config;
+use claudeye::monitor::ClaudeSession;
+use claudeye::watch::{matching_rules, new_matches, WatchRule};
+
+fn rules() -> Vec<WatchRule> {
+    Config::parse(
+        r#"
+[[watch]]
+name = "FAILED"
+pattern = "FAILED|error\\["
+
+[[watch]]
+name = "passed"
+pattern = "test result: ok"
+
+[[watch]]
+name = "quiet"
+pattern = "quiet"
+notify = false
+"#,
+    )
+    .unwrap()
+    .watch
+}
+
+mod common;
+
+fn session(id: &str, hits: &[&str]) -> ClaudeSession {
+    let pane = common::pane(id, "app");
+    ClaudeSession {
+        watch_hits: hits.iter().map(|h| h.to_string()).collect(),
+        ..ClaudeSession::new(pane, ClaudeState::Idle, Instant::now())
+    }
+}
+
+#[test]
+fn matching_rules_returns_names_in_rule_order() {
+    let content = "test result: ok. 3 passed\nerror[E0308]: mismatched types";
+    assert_eq!(matching_rules(&rules(), content), ["FAILED", "passed"]);
+}
+
+#[test]
+fn matching_rules_is_empty_without_a_match() {
+    assert!(matching_rules(&rules(), "nothing here").is_empty());
+}
+
+#[test]
+fn new_matches_reports_only_newly_matching_rules() {
+    let prev = [session("main:0.1", &["passed"])];
+    let updated = [session("main:0.1", &["passed", "FAILED"])];
+    let hits = new_matches(&rules(), &prev, &updated);
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].0.id, "main:0.1");
+    assert_eq!(hits[0].1, "FAILED");
+}
+
+#[test]
+fn new_matches_skips_rules_without_notify() {
+    let prev = [session("main:0.1", &[])];
+    let updated = [session("main:0.1", &["quiet"])];
+    assert!(new_matches(&rules(), &prev, &updated).is_empty());
+}
+
+#[test]
+fn new_matches_skips_panes_seen_for_the_first_time() {
+    let updated = [session("main:0.1", &["FAILED"])];
+    assert!(new_matches(&rules(), &[], &updated).is_empty());
+}
@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use claudeye::claude_state::{ApprovalKind, ClaudeState};
+use claudeye::config::Config;
+use claudeye::monitor::Transition;
+use claudeye::tmux::PaneInfo;
+use claudeye::webhook::{payload, retry_delay, signature, PayloadFormat};
+
+fn transition() -> Transition {
+    Transition {
+        pane: PaneInfo {
+            id: "main:1.0".to_string(),
+            pid: 1,
+            cwd: "/src/api".to_string(),
+            project_name: "api".to_string(),
+            session_name: String::new(),
+            window_name: String::new(),
+        },
+        from: ClaudeState::Working,
+        to: ClaudeState::WaitingForApproval,
+        elapsed: Duration::from_secs(42),
+        approval: None,
+    }
+}
+
+#[test]
+fn json_payload_describes_transition() {
+    let body = payload(&transition(), PayloadFormat::Json, 1_760_000_000);
+    assert_eq!(body["event"], "transition");
+    assert_eq!(body["timestamp"], 1_760_000_000);
+    assert_eq!(body["pane"], "main:1.0");
+    assert_eq!(body["project"], "api");
+    assert_eq!(body["cwd"], "/src/api");
+    assert_eq!(body["from"], "running");
+    assert_eq!(body["to"], "approval");
+    assert_eq!(body["elapsed_secs"], 42);
+    assert!(body.get("approval").is_none());
+}
+
+#[test]
+fn approval_kind_is_included() {
+    let transition = Transition { approval: Some(ApprovalKind::FileEdit), ..transition() };
+    assert_eq!(payload(&transition, PayloadFormat::Json, 0)["approval"], "file_edit");
+    let slack = payload(&transition, PayloadFormat::Slack, 0);
+    assert_eq!(slack["text"], "*api* (main:1.0): running → approval (file_edit) after 42s");
+}
+
+#[test]
+fn slack_payload_is_text_only() {
+    let body = payload(&transition(), PayloadFormat::Slack, 0);
+    assert_eq!(body["text"], "*api* (main:1.0): running → approval after 42s");
+    assert_eq!(body.as_object().unwrap().len(), 1);
+}
+
+#[test]
+fn signature_is_hmac_sha256_of_timestamp_and_body() {
+    // printf '1700000000.{"a":1}' | openssl dgst -sha256 -hmac secret
+    assert_eq!(
+        signature("secret", 1_700_000_000, r#"{"a":1}"#),
+        "sha256=49f24e537407743fa4a0242bb63b94b9a47ee99cbbe071ccd8a22550ae411686"
+    );
+}
+
+#[test]
+fn signature_depends_on_timestamp() {
+    assert_ne!(signature("secret", 1, "{}"), signature("secret", 2, "{}"));
+}
+
+#[test]
+fn retry_delay_doubles() {
+    assert_eq!(retry_delay(2, 1), Duration::from_secs(2));
+    assert_eq!(retry_delay(2, 2), Duration::from_secs(4));
+    assert_eq!(retry_delay(2, 3), Duration::from_secs(8));
+}
+
+#[test]
+fn webhook_config_defaults() {
+    let config = Config::parse("[[webhook]]\nurl = \"https://example.com\"\n").unwrap();
+    let webhook = &config.webhook[0];
+    assert_eq!(webhook.url, "https://example.com");
+    assert_eq!(webhook.secret, None);
+    assert_eq!(webhook.format, PayloadFormat::Json);
+    assert_eq!(webhook.retries, 3);
+    assert_eq!(webhook.backoff_secs, 2);
+}
+
+#[test]
+fn webhook_config_slack_format() {
+    let config = Config::parse(
+        "[[webhook]]\nurl = \"u\"\nsecret = \"k\"\nformat = \"slack\"\nretries = 0\n",
+    )
+    .unwrap();
+    assert_eq!(config.webhook[0].format, PayloadFormat::Slack);
+    assert_eq!(config.webhook[0].secret.as_deref(), Some("k"));
+    assert_eq!(config.webhook[0].retries, 0);
+}
@@ -0,0 +1,50 @@
+use std::time::Instant;
+
+use claudeye::claude_state::ClaudeState;
+use claudeye::i18n::Language;
+use claudeye::monitor::{ClaudeSession, NameFormat};
+use claudeye::xbar::render;
+
+mod common;
+
+fn session(id: &str, project: &str, state: ClaudeState) -> ClaudeSession {
+    ClaudeSession::new(common::pane(id, project), state, Instant::now())
+}
+
+#[test]
+fn render_lists_sessions_with_click_actions() {
+    let sessions = [
+        session("main:0.1", "api", ClaudeState::WaitingForApproval),
+        session("main:0.2", "web", ClaudeState::Working),
+    ];
+    let out = render(&sessions, Language::En.strings(), NameFormat::Project, "/usr/bin/claudeye");
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines[0], "⚠1 ▶1");
+    assert_eq!(lines[1], "---");
+    assert!(lines[2].starts_with("main:0.1  api  [Approval] | color=#dcb400"));
+    assert!(lines[2].contains("bash=\"/usr/bin/claudeye\" param1=approve param2=\"main:0.1\""));
+    assert!(lines[3].starts_with("--main:0.1 | "));
+    assert!(lines[3].contains("param1=switch"));
+    assert!(lines[4].contains("[Running]"));
+    assert!(lines[4].contains("param1=switch param2=\"main:0.2\""));
+    assert_eq!(lines.len(), 5);
+}
+
+#[test]
+fn render_without_sessions_shows_placeholder() {
+    let out = render(&[], Language::En.strings(), NameFormat::Project, "claudeye");
+    assert_eq!(out, "◇\n---\nNo Claude sessions found\n");
+}
+
+#[test]
+fn render_never_approves_sessions_tripping_a_guardrail() {
+    let session = ClaudeSession {
+        guardrail_hits: vec!["rm -rf".to_string()],
+        ..session("main:0.1", "api", ClaudeState::WaitingForApproval)
+    };
+    let out = render(&[session], Language::En.strings(), NameFormat::Project, "claudeye");
+    let line = out.lines().nth(2).unwrap();
+    assert!(line.contains("⛔ rm -rf | color=#ff3c3c"));
+    assert!(line.contains("param1=switch"));
+    assert!(!out.contains("param1=approve"));
+}